@@ -93,6 +93,30 @@ pub(super) fn collect_composed_directives<'a>(
         }
     }
 
+    // @cost: the most expensive weight across subgraphs wins.
+    if let Some(weight) = sites.clone().filter_map(|directives| directives.cost()).max() {
+        push_directive(ctx, federated::Directive::Cost { weight });
+    }
+
+    // @listSize
+    if let Some(list_size) = sites.clone().find_map(|directives| directives.list_size()) {
+        let directive = federated::Directive::ListSize(federated::ListSize {
+            assumed_size: list_size.assumed_size,
+            slicing_arguments: list_size
+                .slicing_arguments
+                .iter()
+                .map(|argument| ctx.insert_string(*argument))
+                .collect(),
+            sized_fields: list_size
+                .sized_fields
+                .iter()
+                .map(|field| ctx.insert_string(*field))
+                .collect(),
+            require_one_slicing_argument: list_size.require_one_slicing_argument,
+        });
+        push_directive(ctx, directive);
+    }
+
     for tag in tags {
         let name = ctx.insert_string(tag);
         let directive = federated::Directive::Other {
@@ -157,6 +157,70 @@ pub(super) fn ingest_directives(
             }
         }
 
+        if directive_matcher.is_cost(directive_name) {
+            let weight = directive
+                .node
+                .get_argument("weight")
+                .and_then(|v| match &v.node {
+                    ConstValue::Number(n) => n.as_i64(),
+                    _ => None,
+                })
+                .and_then(|weight| i32::try_from(weight).ok());
+
+            match weight {
+                Some(weight) => subgraphs.insert_cost(directive_site_id, weight),
+                None => {
+                    let location = location(subgraphs);
+                    subgraphs.push_ingestion_diagnostic(
+                        subgraph,
+                        format!("Error validating the @cost directive at {location}: missing or invalid weight"),
+                    );
+                }
+            }
+        }
+
+        if directive_matcher.is_list_size(directive_name) {
+            let strings = |subgraphs: &mut Subgraphs, argument: &str| -> Vec<subgraphs::StringId> {
+                directive
+                    .node
+                    .get_argument(argument)
+                    .into_iter()
+                    .flat_map(|value| match &value.node {
+                        ConstValue::List(list) => Some(list),
+                        _ => None,
+                    })
+                    .flatten()
+                    .filter_map(|value| match value {
+                        ConstValue::String(string) => Some(subgraphs.strings.intern(string.as_str())),
+                        _ => None,
+                    })
+                    .collect()
+            };
+
+            let list_size = subgraphs::ListSizeDirective {
+                assumed_size: directive
+                    .node
+                    .get_argument("assumedSize")
+                    .and_then(|v| match &v.node {
+                        ConstValue::Number(n) => n.as_u64(),
+                        _ => None,
+                    })
+                    .and_then(|size| u32::try_from(size).ok()),
+                slicing_arguments: strings(subgraphs, "slicingArguments"),
+                sized_fields: strings(subgraphs, "sizedFields"),
+                require_one_slicing_argument: directive
+                    .node
+                    .get_argument("requireOneSlicingArgument")
+                    .and_then(|v| match v.node {
+                        ConstValue::Boolean(b) => Some(b),
+                        _ => None,
+                    })
+                    .unwrap_or(true),
+            };
+
+            subgraphs.insert_list_size(directive_site_id, list_size);
+        }
+
         if directive_name == "deprecated" {
             let reason = directive.node.get_argument("reason").and_then(|v| match &v.node {
                 async_graphql_value::ConstValue::String(s) => Some(s.as_str()),
@@ -283,6 +347,8 @@ pub(crate) struct DirectiveMatcher<'a> {
     requires_scopes: Cow<'a, str>,
     authenticated: Cow<'a, str>,
     policy: Cow<'a, str>,
+    cost: Cow<'a, str>,
+    list_size: Cow<'a, str>,
     tag: Cow<'a, str>,
 
     /// directive name -> is repeatable
@@ -299,10 +365,12 @@ impl Default for DirectiveMatcher<'_> {
             authenticated: Cow::Borrowed(AUTHENTICATED),
             compose_directive: Cow::Borrowed(COMPOSE_DIRECTIVE),
             composed_directives: BTreeSet::new(),
+            cost: Cow::Borrowed(COST),
             external: Cow::Borrowed(EXTERNAL),
             inaccessible: Cow::Borrowed(INACCESSIBLE),
             interface_object: Cow::Borrowed(INTERFACE_OBJECT),
             key: Cow::Borrowed(KEY),
+            list_size: Cow::Borrowed(LIST_SIZE),
             policy: Cow::Borrowed(POLICY),
             provides: Cow::Borrowed(PROVIDES),
             r#override: Cow::Borrowed(OVERRIDE),
@@ -357,10 +425,12 @@ impl<'a> DirectiveMatcher<'a> {
             authenticated: final_name(AUTHENTICATED),
             compose_directive: final_name(COMPOSE_DIRECTIVE),
             composed_directives: BTreeSet::new(),
+            cost: final_name(COST),
             external: final_name(EXTERNAL),
             inaccessible: final_name(INACCESSIBLE),
             interface_object: final_name(INTERFACE_OBJECT),
             key: final_name(KEY),
+            list_size: final_name(LIST_SIZE),
             policy: final_name(POLICY),
             provides: final_name(PROVIDES),
             r#override: final_name(OVERRIDE),
@@ -424,6 +494,14 @@ impl<'a> DirectiveMatcher<'a> {
         self.authenticated == directive_name
     }
 
+    pub(crate) fn is_cost(&self, directive_name: &str) -> bool {
+        self.cost == directive_name
+    }
+
+    pub(crate) fn is_list_size(&self, directive_name: &str) -> bool {
+        self.list_size == directive_name
+    }
+
     pub(crate) fn is_policy(&self, directive_name: &str) -> bool {
         self.policy == directive_name
     }
@@ -1,10 +1,12 @@
 pub(super) const AUTHENTICATED: &str = "authenticated";
 pub(super) const AUTHORIZED: &str = "authorized";
+pub(super) const COST: &str = "cost";
 pub(super) const COMPOSE_DIRECTIVE: &str = "composeDirective";
 pub(super) const EXTERNAL: &str = "external";
 pub(super) const INACCESSIBLE: &str = "inaccessible";
 pub(super) const INTERFACE_OBJECT: &str = "interfaceObject";
 pub(super) const KEY: &str = "key";
+pub(super) const LIST_SIZE: &str = "listSize";
 pub(super) const OVERRIDE: &str = "override";
 pub(super) const POLICY: &str = "policy";
 pub(super) const PROVIDES: &str = "provides";
@@ -25,6 +25,8 @@ pub(super) struct Directives {
     provides: BTreeMap<DirectiveSiteId, Vec<Selection>>,
     requires: BTreeMap<DirectiveSiteId, Vec<Selection>>,
     authorized: BTreeMap<DirectiveSiteId, AuthorizedDirective>,
+    cost: BTreeMap<DirectiveSiteId, i32>,
+    list_size: BTreeMap<DirectiveSiteId, ListSizeDirective>,
 
     requires_scopes: BTreeSet<(DirectiveSiteId, Vec<StringId>)>,
     policies: BTreeSet<(DirectiveSiteId, Vec<StringId>)>,
@@ -73,6 +75,14 @@ impl Subgraphs {
             .push((id, directive_name, arguments));
     }
 
+    pub(crate) fn insert_cost(&mut self, id: DirectiveSiteId, weight: i32) {
+        self.directives.cost.insert(id, weight);
+    }
+
+    pub(crate) fn insert_list_size(&mut self, id: DirectiveSiteId, directive: ListSizeDirective) {
+        self.directives.list_size.insert(id, directive);
+    }
+
     pub(crate) fn insert_deprecated(&mut self, id: DirectiveSiteId, reason: Option<&str>) {
         let reason = reason.map(|reason| self.strings.intern(reason));
         self.directives.deprecated.insert(id, Deprecated { reason });
@@ -141,6 +151,16 @@ impl<'a> DirectiveSiteWalker<'a> {
         self.subgraphs.directives.authorized.get(&self.id)
     }
 
+    /// ```graphql,ignore
+    /// type Query {
+    ///   search(text: String!): [Result!]! @cost(weight: 10)
+    ///                                     ^^^^^^^^^^^^^^^^^
+    /// }
+    /// ```
+    pub(crate) fn cost(self) -> Option<i32> {
+        self.subgraphs.directives.cost.get(&self.id).copied()
+    }
+
     pub(crate) fn deprecated(self) -> Option<DeprecatedWalker<'a>> {
         self.subgraphs
             .directives
@@ -180,6 +200,16 @@ impl<'a> DirectiveSiteWalker<'a> {
         self.subgraphs.directives.r#override.get(&self.id)
     }
 
+    /// ```graphql,ignore
+    /// type Query {
+    ///   products(first: Int): [Product!]! @listSize(slicingArguments: ["first"], assumedSize: 50)
+    ///                                     ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+    /// }
+    /// ```
+    pub(crate) fn list_size(self) -> Option<&'a ListSizeDirective> {
+        self.subgraphs.directives.list_size.get(&self.id)
+    }
+
     pub(crate) fn policies(self) -> impl Iterator<Item = &'a [StringId]> {
         self.subgraphs
             .directives
@@ -257,6 +287,14 @@ pub(crate) struct OverrideDirective {
     pub(crate) label: Option<StringId>,
 }
 
+#[derive(Debug)]
+pub(crate) struct ListSizeDirective {
+    pub(crate) assumed_size: Option<u32>,
+    pub(crate) slicing_arguments: Vec<StringId>,
+    pub(crate) sized_fields: Vec<StringId>,
+    pub(crate) require_one_slicing_argument: bool,
+}
+
 #[derive(Debug)]
 pub(crate) struct AuthorizedDirective {
     pub(crate) arguments: Option<Vec<Selection>>,
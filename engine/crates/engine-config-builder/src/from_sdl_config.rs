@@ -4,16 +4,19 @@ use std::collections::BTreeMap;
 use std::time::Duration;
 
 use config::{
-    AuthConfig, AuthProviderConfig, CacheConfig, CacheConfigTarget, CacheConfigs, EntityCaching, HeaderForward,
-    HeaderInsert, HeaderRemove, HeaderRenameDuplicate, HeaderRule, HeaderRuleId, NameOrPattern, OperationLimits,
-    SubgraphConfig,
+    AuthConfig, AuthProviderConfig, CacheConfig, CacheConfigTarget, CacheConfigs, DemandControlConfig,
+    DemandControlMode, EntityCaching, ErrorMaskingConfig, ErrorMaskingMode, HeaderForward, HeaderInsert,
+    HeaderMergeStrategy, HeaderRemove, HeaderRenameDuplicate, HeaderRule, HeaderRuleId, NameOrPattern, OperationLimits,
+    ProgressiveOverrideConfig, PublicErrorCode, QueryPlanConfig, ResponseCachingConfig, ResponseHeaderForward,
+    ResponseHeaderInsert, ResponseHeaderRename, ResponseHeaderRule, ResponseHeaderRuleId, SubgraphConfig,
+    SubscriptionProtocol,
 };
 use engine_v2_config::{
     latest::{self as config},
     VersionedConfig,
 };
 use federated_graph::{FederatedGraph, FederatedGraphV3, FieldId, ObjectId, SubgraphId};
-use parser_sdl::federation::header::SubgraphHeaderRule;
+use parser_sdl::federation::header::{SubgraphHeaderRule, SubgraphResponseHeaderRule};
 use parser_sdl::federation::{EntityCachingConfig, FederatedGraphConfig};
 use parser_sdl::{AuthV2Provider, GlobalCacheTarget};
 
@@ -36,6 +39,7 @@ pub fn build_with_sdl_config(config: &FederatedGraphConfig, graph: FederatedGrap
         paths: context.paths.into_vec(),
         header_rules: context.header_rules,
         default_header_rules,
+        response_header_rules: context.response_header_rules,
         subgraph_configs: context.subgraph_configs,
         cache: context.cache,
         auth: build_auth_config(config),
@@ -47,9 +51,66 @@ pub fn build_with_sdl_config(config: &FederatedGraphConfig, graph: FederatedGrap
             EntityCachingConfig::Enabled { ttl, .. } => EntityCaching::Enabled { ttl },
             _ => EntityCaching::Disabled,
         },
+        query_plan: QueryPlanConfig {
+            enabled: config.query_plan.enabled,
+            always_include: config.query_plan.always_include,
+        },
+        response_caching: ResponseCachingConfig {
+            enabled: config.response_caching.enabled,
+            vary_headers: config.response_caching.vary_headers.clone(),
+            vary_claims: config.response_caching.vary_claims.clone(),
+        },
+        error_masking: build_error_masking(&config.error_masking),
+        demand_control: config.demand_control.map(build_demand_control),
+        progressive_override: ProgressiveOverrideConfig {
+            enabled_labels: config.progressive_override.enabled_labels.clone(),
+        },
     })
 }
 
+fn build_demand_control(config: parser_sdl::federation::DemandControlConfig) -> DemandControlConfig {
+    use parser_sdl::federation::DemandControlMode as Mode;
+
+    DemandControlConfig {
+        mode: match config.mode {
+            Mode::Enforce => DemandControlMode::Enforce,
+            Mode::Measure => DemandControlMode::Measure,
+        },
+        max_cost: config.max_cost,
+        default_list_size: config.default_list_size,
+    }
+}
+
+fn build_error_masking(config: &parser_sdl::federation::ErrorMaskingConfig) -> ErrorMaskingConfig {
+    use parser_sdl::federation::{ErrorMaskingMode as Mode, PublicErrorCode as Code};
+
+    ErrorMaskingConfig {
+        mode: match config.mode {
+            Mode::PassThrough => ErrorMaskingMode::PassThrough,
+            Mode::Mask => ErrorMaskingMode::Mask,
+            Mode::Allowlist => ErrorMaskingMode::Allowlist,
+        },
+        message: config.message.clone(),
+        allowed_codes: config.allowed_codes.clone(),
+        code_mappings: config
+            .code_mappings
+            .iter()
+            .map(|(code, public_code)| {
+                let public_code = match public_code {
+                    Code::BadRequest => PublicErrorCode::BadRequest,
+                    Code::Unauthenticated => PublicErrorCode::Unauthenticated,
+                    Code::Unauthorized => PublicErrorCode::Unauthorized,
+                    Code::RateLimited => PublicErrorCode::RateLimited,
+                    Code::GatewayTimeout => PublicErrorCode::GatewayTimeout,
+                    Code::InternalServerError => PublicErrorCode::InternalServerError,
+                    Code::SubgraphError => PublicErrorCode::SubgraphError,
+                };
+                (code.clone(), public_code)
+            })
+            .collect(),
+    }
+}
+
 fn build_operation_limits(config: &FederatedGraphConfig) -> OperationLimits {
     let parsed_operation_limits = &config.operation_limits;
     OperationLimits {
@@ -91,6 +152,7 @@ struct BuildContext<'a> {
     strings: crate::strings::Strings<'a>,
     paths: crate::paths::Paths<'a>,
     header_rules: Vec<HeaderRule>,
+    response_header_rules: Vec<ResponseHeaderRule>,
     rate_limit: Option<config::RateLimitConfig>,
     subgraph_configs: BTreeMap<SubgraphId, SubgraphConfig>,
     cache: CacheConfigs,
@@ -170,14 +232,29 @@ impl<'a> BuildContext<'a> {
 
             let parser_sdl::federation::SubgraphConfig {
                 websocket_url,
+                subscription_protocol,
+                subscription_heartbeat_timeout,
+                subscription_max_reconnect_attempts,
+                deduplicate_subscriptions,
+                deduplicate_requests,
                 header_rules,
+                response_header_rules,
                 rate_limit,
                 timeout,
                 entity_caching,
+                max_entities_per_request,
+                error_masking,
+                circuit_breaker,
+                load_balancing,
+                traffic_split,
+                shadow,
+                compression,
+                connection_pool,
                 ..
             } = config;
 
             let headers = self.insert_headers(header_rules.iter());
+            let response_headers = self.insert_response_headers(response_header_rules.iter());
             let websocket_url = websocket_url.as_ref().map(|url| self.strings.intern(url));
             let subgraph_name = self.strings.intern(name);
 
@@ -192,11 +269,13 @@ impl<'a> BuildContext<'a> {
                      ttl,
                      retry_percent,
                      retry_mutations,
+                     retry_on_status,
                  }| config::RetryConfig {
                     min_per_second: *min_per_second,
                     ttl: *ttl,
                     retry_percent: *retry_percent,
                     retry_mutations: *retry_mutations,
+                    retry_on_status: retry_on_status.clone(),
                 },
             );
 
@@ -205,7 +284,19 @@ impl<'a> BuildContext<'a> {
                 config::SubgraphConfig {
                     name: subgraph_name,
                     headers,
+                    response_headers,
                     websocket_url,
+                    subscription_protocol: match subscription_protocol {
+                        parser_sdl::federation::SubscriptionProtocol::Websocket => SubscriptionProtocol::Websocket,
+                        parser_sdl::federation::SubscriptionProtocol::ServerSentEvents => {
+                            SubscriptionProtocol::ServerSentEvents
+                        }
+                        parser_sdl::federation::SubscriptionProtocol::Multipart => SubscriptionProtocol::Multipart,
+                    },
+                    subscription_heartbeat_timeout: *subscription_heartbeat_timeout,
+                    subscription_max_reconnect_attempts: *subscription_max_reconnect_attempts,
+                    deduplicate_subscriptions: *deduplicate_subscriptions,
+                    deduplicate_requests: *deduplicate_requests,
                     rate_limit,
                     timeout: *timeout,
                     retry,
@@ -213,11 +304,113 @@ impl<'a> BuildContext<'a> {
                         EntityCachingConfig::Disabled => EntityCaching::Disabled,
                         EntityCachingConfig::Enabled { ttl, .. } => EntityCaching::Enabled { ttl: *ttl },
                     }),
+                    max_entities_per_request: *max_entities_per_request,
+                    error_masking: error_masking.as_ref().map(build_error_masking),
+                    circuit_breaker: circuit_breaker.as_ref().map(
+                        |parser_sdl::federation::CircuitBreakerConfig {
+                             consecutive_failures,
+                             failure_rate,
+                             window,
+                             minimum_requests,
+                             cooldown,
+                         }| config::CircuitBreakerConfig {
+                            consecutive_failures: *consecutive_failures,
+                            failure_rate: *failure_rate,
+                            window: *window,
+                            minimum_requests: *minimum_requests,
+                            cooldown: *cooldown,
+                        },
+                    ),
+                    load_balancing: load_balancing.as_ref().map(|config| self.insert_load_balancing(config)),
+                    traffic_split: traffic_split.as_ref().map(|config| self.insert_traffic_split(config)),
+                    shadow: shadow.as_ref().map(|config| config::ShadowConfig {
+                        url: self.strings.intern(&config.url),
+                        sampling: config.sampling,
+                    }),
+                    compression: compression.map(|config| config::CompressionConfig {
+                        request: config.request.map(|algorithm| match algorithm {
+                            parser_sdl::federation::CompressionAlgorithm::Gzip => config::CompressionAlgorithm::Gzip,
+                            parser_sdl::federation::CompressionAlgorithm::Brotli => {
+                                config::CompressionAlgorithm::Brotli
+                            }
+                            parser_sdl::federation::CompressionAlgorithm::Zstd => config::CompressionAlgorithm::Zstd,
+                        }),
+                        response: config.response,
+                    }),
+                    connection_pool: connection_pool.map(
+                        |parser_sdl::federation::ConnectionPoolConfig {
+                             max_idle_per_host,
+                             idle_timeout,
+                             tcp_keepalive,
+                             http2_prior_knowledge,
+                             connect_timeout,
+                         }| config::ConnectionPoolConfig {
+                            max_idle_per_host,
+                            idle_timeout,
+                            tcp_keepalive,
+                            http2_prior_knowledge,
+                            connect_timeout,
+                        },
+                    ),
                 },
             );
         }
     }
 
+    fn insert_load_balancing(
+        &mut self,
+        config: &'a parser_sdl::federation::LoadBalancingConfig,
+    ) -> config::LoadBalancingConfig {
+        use parser_sdl::federation::LoadBalancingStrategy as Strategy;
+
+        config::LoadBalancingConfig {
+            strategy: match config.strategy {
+                Strategy::RoundRobin => config::LoadBalancingStrategy::RoundRobin,
+                Strategy::LeastOutstanding => config::LoadBalancingStrategy::LeastOutstanding,
+                Strategy::Weighted => config::LoadBalancingStrategy::Weighted,
+            },
+            upstreams: config
+                .urls
+                .iter()
+                .map(|upstream| config::UpstreamConfig {
+                    url: self.strings.intern(&upstream.url),
+                    websocket_url: upstream.websocket_url.as_ref().map(|url| self.strings.intern(url)),
+                    weight: upstream.weight,
+                })
+                .collect(),
+            outlier_ejection: config::OutlierEjectionConfig {
+                consecutive_failures: config.outlier_ejection_consecutive_failures,
+                duration: config.outlier_ejection_duration,
+            },
+        }
+    }
+
+    fn insert_traffic_split(
+        &mut self,
+        config: &'a parser_sdl::federation::TrafficSplitConfig,
+    ) -> config::TrafficSplitConfig {
+        config::TrafficSplitConfig {
+            sticky_header: config.sticky_header.as_ref().map(|name| self.strings.intern(name)),
+            variants: config
+                .variants
+                .iter()
+                .map(|variant| config::TrafficVariantConfig {
+                    name: self.strings.intern(&variant.name),
+                    url: self.strings.intern(&variant.url),
+                    percentage: variant.percentage,
+                    header: variant.header.as_ref().map(|(name, value)| config::TrafficMatch {
+                        name: self.strings.intern(name),
+                        value: self.strings.intern(value),
+                    }),
+                    claim: variant.claim.as_ref().map(|(name, value)| config::TrafficMatch {
+                        name: self.strings.intern(name),
+                        value: self.strings.intern(value),
+                    }),
+                })
+                .collect(),
+        }
+    }
+
     fn insert_headers(&mut self, header_rules: impl IntoIterator<Item = &'a SubgraphHeaderRule>) -> Vec<HeaderRuleId> {
         header_rules.into_iter().map(|rule| self.insert_header(rule)).collect()
     }
@@ -251,6 +444,46 @@ impl<'a> BuildContext<'a> {
         id
     }
 
+    fn insert_response_headers(
+        &mut self,
+        rules: impl IntoIterator<Item = &'a SubgraphResponseHeaderRule>,
+    ) -> Vec<ResponseHeaderRuleId> {
+        rules
+            .into_iter()
+            .map(|rule| self.insert_response_header(rule))
+            .collect()
+    }
+
+    fn insert_response_header(&mut self, rule: &'a SubgraphResponseHeaderRule) -> ResponseHeaderRuleId {
+        let rule = match rule {
+            SubgraphResponseHeaderRule::Forward(ref rule) => ResponseHeaderRule::Forward(ResponseHeaderForward {
+                name: self.intern_header_name(&rule.name),
+                default: rule.default.as_ref().map(|default| self.strings.intern(default)),
+                rename: rule.rename.as_ref().map(|rename| self.strings.intern(rename)),
+                merge: merge_strategy(rule.merge),
+            }),
+            SubgraphResponseHeaderRule::Insert(ref rule) => ResponseHeaderRule::Insert(ResponseHeaderInsert {
+                name: self.strings.intern(&rule.name),
+                value: self.strings.intern(&rule.value),
+                merge: merge_strategy(rule.merge),
+            }),
+            SubgraphResponseHeaderRule::Remove(ref rule) => ResponseHeaderRule::Remove(HeaderRemove {
+                name: self.intern_header_name(&rule.name),
+            }),
+            SubgraphResponseHeaderRule::Rename(ref rule) => ResponseHeaderRule::Rename(ResponseHeaderRename {
+                name: self.strings.intern(&rule.name),
+                default: rule.default.as_ref().map(|default| self.strings.intern(default)),
+                rename: self.strings.intern(&rule.rename),
+                merge: merge_strategy(rule.merge),
+            }),
+        };
+
+        let id = ResponseHeaderRuleId(self.response_header_rules.len());
+        self.response_header_rules.push(rule);
+
+        id
+    }
+
     fn intern_header_name(&mut self, name: &'a parser_sdl::federation::header::NameOrPattern) -> NameOrPattern {
         match name {
             parser_sdl::federation::header::NameOrPattern::Pattern(ref pattern) => {
@@ -263,6 +496,17 @@ impl<'a> BuildContext<'a> {
     }
 }
 
+fn merge_strategy(strategy: parser_sdl::federation::header::HeaderMergeStrategy) -> HeaderMergeStrategy {
+    match strategy {
+        parser_sdl::federation::header::HeaderMergeStrategy::First => HeaderMergeStrategy::First,
+        parser_sdl::federation::header::HeaderMergeStrategy::Last => HeaderMergeStrategy::Last,
+        parser_sdl::federation::header::HeaderMergeStrategy::Append => HeaderMergeStrategy::Append,
+        parser_sdl::federation::header::HeaderMergeStrategy::MostRestrictiveCacheControl => {
+            HeaderMergeStrategy::MostRestrictiveCacheControl
+        }
+    }
+}
+
 trait FederatedGraphExt {
     fn find_subgraph(&self, name: &str) -> Option<SubgraphId>;
     fn find_object(&self, name: &str) -> Option<ObjectId>;
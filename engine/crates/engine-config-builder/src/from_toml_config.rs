@@ -1,7 +1,10 @@
 use engine_v2_config::VersionedConfig;
 use federated_graph::FederatedGraph;
-use gateway_config::{Config, RetryConfig};
-use parser_sdl::federation::{header::SubgraphHeaderRule, FederatedGraphConfig};
+use gateway_config::{Config, RetryConfig, RetryStatus};
+use parser_sdl::federation::{
+    header::{SubgraphHeaderRule, SubgraphResponseHeaderRule},
+    FederatedGraphConfig,
+};
 
 use crate::build_with_sdl_config;
 
@@ -28,6 +31,11 @@ pub fn build_with_toml_config(config: &Config, graph: FederatedGraph) -> Version
 
     graph_config.rate_limit = config.gateway.rate_limit.clone().map(Into::into);
     graph_config.entity_caching = config.entity_caching.clone().into();
+    graph_config.query_plan = config.query_plan.into();
+    graph_config.response_caching = config.response_caching.clone().into();
+    graph_config.error_masking = config.error_masking.clone().into();
+    graph_config.demand_control = config.demand_control.map(Into::into);
+    graph_config.progressive_override = config.progressive_override.clone().into();
 
     graph_config.subgraphs = config
         .subgraphs
@@ -40,15 +48,35 @@ pub fn build_with_toml_config(config: &Config, graph: FederatedGraph) -> Version
                 .map(SubgraphHeaderRule::from)
                 .collect();
 
+            let response_header_rules = subgraph_config
+                .response_headers
+                .into_iter()
+                .map(SubgraphResponseHeaderRule::from)
+                .collect();
+
             let config = parser_sdl::federation::SubgraphConfig {
                 name: name.clone(),
                 websocket_url: subgraph_config.websocket_url.map(|url| url.to_string()),
+                subscription_protocol: subgraph_config.subscription_protocol.into(),
+                subscription_heartbeat_timeout: subgraph_config.subscription_heartbeat_timeout,
+                subscription_max_reconnect_attempts: subgraph_config.subscription_max_reconnect_attempts,
+                deduplicate_subscriptions: subgraph_config.deduplicate_subscriptions,
+                deduplicate_requests: subgraph_config.deduplicate_requests,
                 header_rules,
+                response_header_rules,
                 development_url: None,
                 rate_limit: subgraph_config.rate_limit.map(Into::into),
                 timeout: subgraph_config.timeout.or(config.gateway.subgraph_timeout),
                 entity_caching: subgraph_config.entity_caching.map(Into::into),
-                retry: retry_config(subgraph_config.retry, config.gateway.retry),
+                max_entities_per_request: subgraph_config.max_entities_per_request.map(|max| max.get()),
+                error_masking: subgraph_config.error_masking.map(Into::into),
+                circuit_breaker: subgraph_config.circuit_breaker.map(Into::into),
+                load_balancing: subgraph_config.load_balancing.map(Into::into),
+                traffic_split: subgraph_config.traffic_split.map(Into::into),
+                shadow: subgraph_config.shadow.map(Into::into),
+                compression: subgraph_config.compression.map(Into::into),
+                connection_pool: subgraph_config.connection_pool.map(Into::into),
+                retry: retry_config(subgraph_config.retry, config.gateway.retry.clone()),
             };
 
             (name, config)
@@ -73,6 +101,9 @@ fn retry_config(
         ttl: retry.ttl,
         retry_percent: retry.retry_percent,
         retry_mutations: retry.retry_mutations,
+        retry_on_status: retry
+            .retry_on_status
+            .map(|statuses| statuses.into_iter().flat_map(RetryStatus::codes).collect()),
     })
 }
 
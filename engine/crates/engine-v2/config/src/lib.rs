@@ -133,6 +133,7 @@ impl VersionedConfig {
                     paths: Vec::new(),
                     header_rules,
                     default_header_rules,
+                    response_header_rules: Vec::new(),
                     subgraph_configs,
                     cache,
                     auth,
@@ -141,6 +142,11 @@ impl VersionedConfig {
                     rate_limit: Default::default(),
                     timeout: None,
                     entity_caching: Default::default(),
+                    query_plan: Default::default(),
+                    response_caching: Default::default(),
+                    error_masking: Default::default(),
+                    demand_control: None,
+                    progressive_override: Default::default(),
                 }
             }
             VersionedConfig::V5(latest) => latest,
@@ -7,7 +7,7 @@ pub use cache_config::{CacheConfig, CacheConfigTarget, CacheConfigs};
 use federated_graph::{FederatedGraphV1, SubgraphId};
 pub use gateway_auth_config::v2::*;
 
-use crate::v5::HeaderRuleId;
+use crate::v5::{HeaderRuleId, ResponseHeaderRuleId};
 
 #[derive(Default, serde::Deserialize, serde::Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -47,8 +47,20 @@ pub struct Config {
 pub struct SubgraphConfig {
     pub name: StringId,
     pub websocket_url: Option<StringId>,
+    #[serde(default)]
+    pub subscription_protocol: SubscriptionProtocol,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subscription_heartbeat_timeout: Option<Duration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subscription_max_reconnect_attempts: Option<u32>,
+    #[serde(default)]
+    pub deduplicate_subscriptions: bool,
+    #[serde(default)]
+    pub deduplicate_requests: bool,
     pub headers: Vec<HeaderRuleId>,
     #[serde(default)]
+    pub response_headers: Vec<ResponseHeaderRuleId>,
+    #[serde(default)]
     pub rate_limit: Option<GraphRateLimit>,
     #[serde(default)]
     pub timeout: Option<Duration>,
@@ -56,6 +68,34 @@ pub struct SubgraphConfig {
     pub retry: Option<RetryConfig>,
     #[serde(default)]
     pub entity_caching: Option<EntityCaching>,
+    #[serde(default)]
+    pub max_entities_per_request: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_masking: Option<ErrorMaskingConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub load_balancing: Option<LoadBalancingConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub traffic_split: Option<TrafficSplitConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shadow: Option<ShadowConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compression: Option<CompressionConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection_pool: Option<ConnectionPoolConfig>,
+}
+
+/// Transport used for subscriptions to a subgraph.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SubscriptionProtocol {
+    /// `graphql-transport-ws` over websockets
+    #[default]
+    Websocket,
+    /// GraphQL over server-sent events
+    ServerSentEvents,
+    /// Multipart HTTP subscriptions
+    Multipart,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, Copy)]
@@ -78,6 +118,166 @@ impl EntityCaching {
     }
 }
 
+/// Multiple URLs of a subgraph, replacing the one defined in the federated graph.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct LoadBalancingConfig {
+    #[serde(default)]
+    pub strategy: LoadBalancingStrategy,
+    pub upstreams: Vec<UpstreamConfig>,
+    #[serde(default)]
+    pub outlier_ejection: OutlierEjectionConfig,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LoadBalancingStrategy {
+    #[default]
+    RoundRobin,
+    LeastOutstanding,
+    Weighted,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy)]
+pub struct UpstreamConfig {
+    pub url: StringId,
+    pub websocket_url: Option<StringId>,
+    pub weight: u32,
+}
+
+/// Alternative versions of a subgraph receiving part of the requests.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct TrafficSplitConfig {
+    pub sticky_header: Option<StringId>,
+    pub variants: Vec<TrafficVariantConfig>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy)]
+pub struct TrafficVariantConfig {
+    pub name: StringId,
+    pub url: StringId,
+    pub percentage: f32,
+    pub header: Option<TrafficMatch>,
+    pub claim: Option<TrafficMatch>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy)]
+pub struct TrafficMatch {
+    pub name: StringId,
+    pub value: StringId,
+}
+
+/// Candidate subgraph receiving a copy of a sample of the queries.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy)]
+pub struct ShadowConfig {
+    pub url: StringId,
+    pub sampling: f64,
+}
+
+/// Compression of the requests sent to a subgraph and of its responses.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CompressionConfig {
+    pub request: Option<CompressionAlgorithm>,
+    pub response: bool,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompressionAlgorithm {
+    Gzip,
+    Brotli,
+    Zstd,
+}
+
+/// Connection pool of the HTTP client of a subgraph.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConnectionPoolConfig {
+    pub max_idle_per_host: Option<usize>,
+    pub idle_timeout: Option<Duration>,
+    pub tcp_keepalive: Option<Duration>,
+    pub http2_prior_knowledge: bool,
+    pub connect_timeout: Option<Duration>,
+}
+
+/// URLs failing too many times in a row are ejected for a while.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, Copy)]
+pub struct OutlierEjectionConfig {
+    pub consecutive_failures: Option<u32>,
+    pub duration: Option<Duration>,
+}
+
+/// Failure thresholds opening the circuit breaker of a subgraph, and for how long.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, Copy)]
+pub struct CircuitBreakerConfig {
+    /// Opens the circuit after this many consecutive failures.
+    pub consecutive_failures: Option<u32>,
+    /// Opens the circuit once the fraction of failed requests within the window reaches it.
+    pub failure_rate: Option<f32>,
+    /// Window over which the failure rate is computed.
+    pub window: Option<Duration>,
+    /// Minimum number of requests within the window for the failure rate to apply.
+    pub minimum_requests: Option<u32>,
+    /// How long the circuit stays open before a probe request is let through.
+    pub cooldown: Option<Duration>,
+}
+
+/// How errors returned by a subgraph are exposed to clients.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+pub struct ErrorMaskingConfig {
+    #[serde(default)]
+    pub mode: ErrorMaskingMode,
+    /// Message replacing the one of masked errors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Error codes, from the `code` extension, passed through in the allowlist mode.
+    #[serde(default)]
+    pub allowed_codes: Vec<String>,
+    /// Error codes, from the `code` extension, mapped to the ones sent to the client.
+    #[serde(default)]
+    pub code_mappings: BTreeMap<String, PublicErrorCode>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorMaskingMode {
+    /// Errors are sent as returned by the subgraph.
+    #[default]
+    PassThrough,
+    /// Errors are replaced with a generic message and a correlation id.
+    Mask,
+    /// Errors with an allowed code are passed through, the others are masked.
+    Allowlist,
+}
+
+/// Error codes subgraph errors can be mapped to.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublicErrorCode {
+    BadRequest,
+    Unauthenticated,
+    Unauthorized,
+    RateLimited,
+    GatewayTimeout,
+    InternalServerError,
+    SubgraphError,
+}
+
+const DEFAULT_MASKED_ERROR_MESSAGE: &str = "Internal subgraph error";
+
+impl ErrorMaskingConfig {
+    /// Whether an error with the given `code` extension must be masked.
+    pub fn should_mask(&self, code: Option<&str>) -> bool {
+        match self.mode {
+            ErrorMaskingMode::PassThrough => false,
+            ErrorMaskingMode::Mask => true,
+            ErrorMaskingMode::Allowlist => !code.is_some_and(|code| self.allowed_codes.iter().any(|c| c == code)),
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        self.message.as_deref().unwrap_or(DEFAULT_MASKED_ERROR_MESSAGE)
+    }
+
+    pub fn public_code(&self, code: &str) -> Option<PublicErrorCode> {
+        self.code_mappings.get(code).copied()
+    }
+}
+
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct RetryConfig {
     /// How many retries are available per second, at a minimum.
@@ -89,6 +289,9 @@ pub struct RetryConfig {
     /// Whether mutations should be retried at all. False by default.
     #[serde(default)]
     pub retry_mutations: bool,
+    /// HTTP status codes of subgraph responses to retry. Defaults to 502, 503 and 504.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_on_status: Option<Vec<u16>>,
 }
 
 /// A header that should be sent to a subgraph
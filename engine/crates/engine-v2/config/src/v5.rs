@@ -11,13 +11,20 @@ use federated_graph::{FederatedGraphV3, SubgraphId};
 
 use self::rate_limit::{RateLimitConfigRef, RateLimitRedisConfigRef, RateLimitRedisTlsConfigRef};
 
-pub use super::v2::EntityCaching;
+pub use super::v2::{
+    CircuitBreakerConfig, CompressionAlgorithm, CompressionConfig, ConnectionPoolConfig, EntityCaching,
+    ErrorMaskingConfig, ErrorMaskingMode, LoadBalancingConfig, LoadBalancingStrategy, OutlierEjectionConfig,
+    PublicErrorCode, ShadowConfig, SubscriptionProtocol, TrafficMatch, TrafficSplitConfig, TrafficVariantConfig,
+    UpstreamConfig,
+};
 pub use super::v4::{
     AuthConfig, AuthProviderConfig, CacheConfig, CacheConfigTarget, CacheConfigs, Header, HeaderId, HeaderValue,
     JwksConfig, JwtConfig, OperationLimits, RetryConfig, StringId, SubgraphConfig,
 };
 pub use header::{
-    HeaderForward, HeaderInsert, HeaderRemove, HeaderRenameDuplicate, HeaderRule, HeaderRuleId, NameOrPattern,
+    HeaderForward, HeaderInsert, HeaderMergeStrategy, HeaderRemove, HeaderRenameDuplicate, HeaderRule, HeaderRuleId,
+    NameOrPattern, ResponseHeaderForward, ResponseHeaderInsert, ResponseHeaderRename, ResponseHeaderRule,
+    ResponseHeaderRuleId,
 };
 pub use rate_limit::{
     GraphRateLimit, RateLimitConfig, RateLimitRedisConfig, RateLimitRedisTlsConfig, RateLimitStorage,
@@ -32,6 +39,8 @@ pub struct Config {
     pub paths: Vec<PathBuf>,
     pub header_rules: Vec<HeaderRule>,
     pub default_header_rules: Vec<HeaderRuleId>,
+    #[serde(default)]
+    pub response_header_rules: Vec<ResponseHeaderRule>,
 
     /// Additional configuration for our subgraphs
     pub subgraph_configs: BTreeMap<SubgraphId, SubgraphConfig>,
@@ -56,6 +65,68 @@ pub struct Config {
 
     #[serde(default)]
     pub entity_caching: EntityCaching,
+
+    #[serde(default)]
+    pub query_plan: QueryPlanConfig,
+
+    #[serde(default)]
+    pub response_caching: ResponseCachingConfig,
+
+    /// Default error masking of the subgraphs.
+    #[serde(default)]
+    pub error_masking: ErrorMaskingConfig,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub demand_control: Option<DemandControlConfig>,
+
+    #[serde(default)]
+    pub progressive_override: ProgressiveOverrideConfig,
+}
+
+/// Whether the query plan can be exposed in the response extensions.
+#[derive(Default, Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+pub struct QueryPlanConfig {
+    /// Clients may request the query plan with the `x-grafbase-query-plan` header.
+    pub enabled: bool,
+    /// The query plan is added to every response.
+    pub always_include: bool,
+}
+
+/// Estimation of the cost of operations from the `@cost` and `@listSize` directives.
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+pub struct DemandControlConfig {
+    pub mode: DemandControlMode,
+    /// Operations with a higher estimated cost are rejected in the enforce mode.
+    pub max_cost: Option<u64>,
+    /// Size of the lists without any `@listSize` directive or slicing argument.
+    pub default_list_size: u32,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum DemandControlMode {
+    /// Operations above the maximum cost are rejected.
+    #[default]
+    Enforce,
+    /// The cost is only measured.
+    Measure,
+}
+
+/// Progressive `@override` migrations.
+#[derive(Default, Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ProgressiveOverrideConfig {
+    /// Custom override labels active for all requests.
+    pub enabled_labels: Vec<String>,
+}
+
+/// Caching of whole query responses, driven by the `@cacheControl` directives.
+#[derive(Default, Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ResponseCachingConfig {
+    pub enabled: bool,
+    /// Request headers whose values are part of the cache key. Headers forwarded to subgraphs
+    /// must be listed for the response to be cached.
+    pub vary_headers: Vec<String>,
+    /// JWT claims, as dot-separated paths, whose values are part of the cache key.
+    pub vary_claims: Vec<String>,
 }
 
 impl Config {
@@ -66,6 +137,7 @@ impl Config {
             paths: Vec::new(),
             header_rules: Vec::new(),
             default_header_rules: Default::default(),
+            response_header_rules: Vec::new(),
             subgraph_configs: Default::default(),
             cache: Default::default(),
             auth: Default::default(),
@@ -74,6 +146,11 @@ impl Config {
             rate_limit: Default::default(),
             timeout: None,
             entity_caching: EntityCaching::Disabled,
+            query_plan: Default::default(),
+            response_caching: Default::default(),
+            error_masking: Default::default(),
+            demand_control: None,
+            progressive_override: Default::default(),
         }
     }
 
@@ -182,6 +259,7 @@ mod tests {
             paths: Vec::new(),
             header_rules: vec![],
             default_header_rules: Vec::new(),
+            response_header_rules: Vec::new(),
             subgraph_configs: Default::default(),
             cache: CacheConfigs { rules: cache_config },
             auth: None,
@@ -190,6 +268,11 @@ mod tests {
             rate_limit: Default::default(),
             timeout: None,
             entity_caching: Default::default(),
+            query_plan: Default::default(),
+            response_caching: Default::default(),
+            error_masking: Default::default(),
+            demand_control: None,
+            progressive_override: Default::default(),
         };
 
         insta::with_settings!({sort_maps => true}, {
@@ -213,6 +296,11 @@ mod tests {
               "default_header_rules": [],
               "disable_introspection": false,
               "entity_caching": "Disabled",
+              "error_masking": {
+                "allowed_codes": [],
+                "code_mappings": {},
+                "mode": "PassThrough"
+              },
               "graph": {
                 "authorized_directives": [],
                 "directives": [],
@@ -245,7 +333,20 @@ mod tests {
                 "rootFields": null
               },
               "paths": [],
+              "progressive_override": {
+                "enabled_labels": []
+              },
+              "query_plan": {
+                "always_include": false,
+                "enabled": false
+              },
               "rate_limit": null,
+              "response_caching": {
+                "enabled": false,
+                "vary_claims": [],
+                "vary_headers": []
+              },
+              "response_header_rules": [],
               "strings": [],
               "subgraph_configs": {}
             }
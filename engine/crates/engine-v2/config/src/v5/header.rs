@@ -93,3 +93,84 @@ impl std::ops::Index<HeaderRuleId> for super::Config {
         &self.header_rules[index.0]
     }
 }
+
+/// Defines a rule applied to the headers of a subgraph response, the result is forwarded
+/// to the client.
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[serde(tag = "rule")]
+pub enum ResponseHeaderRule {
+    /// Forward the header to the client.
+    #[serde(rename = "forward")]
+    Forward(ResponseHeaderForward),
+    /// Insert a new static header.
+    #[serde(rename = "insert")]
+    Insert(ResponseHeaderInsert),
+    /// Remove a header added by a previous rule.
+    #[serde(rename = "remove")]
+    Remove(HeaderRemove),
+    /// Forward the header to the client with a new name.
+    #[serde(rename = "rename")]
+    Rename(ResponseHeaderRename),
+}
+
+/// Response header forwarding rules.
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+pub struct ResponseHeaderForward {
+    /// Name or pattern of the header to be forwarded.
+    #[serde(flatten)]
+    pub name: NameOrPattern,
+    /// If header is not present, insert this value.
+    pub default: Option<StringId>,
+    /// Use this name instead of the original when forwarding.
+    pub rename: Option<StringId>,
+    /// How to merge the header if multiple subgraphs set it.
+    #[serde(default)]
+    pub merge: HeaderMergeStrategy,
+}
+
+/// Response header insertion rules.
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+pub struct ResponseHeaderInsert {
+    /// The name of the header.
+    pub name: StringId,
+    /// The value of the header.
+    pub value: StringId,
+    /// How to merge the header if multiple subgraphs set it.
+    #[serde(default)]
+    pub merge: HeaderMergeStrategy,
+}
+
+/// Response header renaming rules.
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+pub struct ResponseHeaderRename {
+    /// The name of the header in the subgraph response.
+    pub name: StringId,
+    /// If header is not present, insert this value.
+    pub default: Option<StringId>,
+    /// The name of the header sent to the client.
+    pub rename: StringId,
+    /// How to merge the header if multiple subgraphs set it.
+    #[serde(default)]
+    pub merge: HeaderMergeStrategy,
+}
+
+/// Decides the value of a response header set by multiple subgraphs.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderMergeStrategy {
+    First,
+    #[default]
+    Last,
+    Append,
+    MostRestrictiveCacheControl,
+}
+
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ResponseHeaderRuleId(pub usize);
+
+impl std::ops::Index<ResponseHeaderRuleId> for super::Config {
+    type Output = ResponseHeaderRule;
+
+    fn index(&self, index: ResponseHeaderRuleId) -> &Self::Output {
+        &self.response_header_rules[index.0]
+    }
+}
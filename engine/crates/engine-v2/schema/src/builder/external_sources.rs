@@ -4,7 +4,10 @@ use config::latest::Config;
 
 use super::{
     sources::{
-        graphql::{GraphqlEndpoint, RetryConfig},
+        graphql::{
+            GraphqlEndpoint, LoadBalancing, RetryConfig, Shadow, TrafficSplit, TrafficVariant, Upstream,
+            DEFAULT_RETRY_ON_STATUS,
+        },
         GraphqlEndpoints,
     },
     BuildContext,
@@ -28,10 +31,24 @@ impl ExternalDataSources {
                 match config.subgraph_configs.remove(&federated_graph::SubgraphId(index)) {
                     Some(config::latest::SubgraphConfig {
                         websocket_url,
+                        subscription_protocol,
+                        subscription_heartbeat_timeout,
+                        subscription_max_reconnect_attempts,
+                        deduplicate_subscriptions,
+                        deduplicate_requests,
                         headers,
+                        response_headers,
                         timeout,
                         retry,
                         entity_caching,
+                        max_entities_per_request,
+                        error_masking,
+                        circuit_breaker,
+                        load_balancing,
+                        traffic_split,
+                        shadow,
+                        compression,
+                        connection_pool,
                         ..
                     }) => GraphqlEndpoint {
                         subgraph_name: name,
@@ -39,7 +56,13 @@ impl ExternalDataSources {
                         url,
                         websocket_url: websocket_url
                             .map(|url| ctx.urls.insert(url::Url::parse(&config[url]).expect("valid url"))),
+                        subscription_protocol,
+                        subscription_heartbeat_timeout,
+                        subscription_max_reconnect_attempts,
+                        deduplicate_subscriptions,
+                        deduplicate_requests,
                         header_rules: headers.into_iter().map(Into::into).collect(),
+                        response_header_rules: response_headers.into_iter().map(Into::into).collect(),
                         timeout: timeout.unwrap_or(DEFAULT_SUBGRAPH_TIMEOUT),
                         retry: retry.map(
                             |config::latest::RetryConfig {
@@ -47,14 +70,88 @@ impl ExternalDataSources {
                                  ttl,
                                  retry_percent,
                                  retry_mutations,
-                             }| RetryConfig {
-                                min_per_second,
-                                ttl,
-                                retry_percent,
-                                retry_mutations,
+                                 retry_on_status,
+                             }| {
+                                let mut retry_on_status =
+                                    retry_on_status.unwrap_or_else(|| DEFAULT_RETRY_ON_STATUS.to_vec());
+                                retry_on_status.sort_unstable();
+                                retry_on_status.dedup();
+                                RetryConfig {
+                                    min_per_second,
+                                    ttl,
+                                    retry_percent,
+                                    retry_mutations,
+                                    retry_on_status,
+                                }
                             },
                         ),
                         entity_cache_ttl: entity_caching.as_ref().unwrap_or(&config.entity_caching).ttl(),
+                        max_entities_per_request: max_entities_per_request.filter(|max| *max > 0),
+                        error_masking: error_masking.unwrap_or_else(|| config.error_masking.clone()),
+                        circuit_breaker,
+                        load_balancing: load_balancing.filter(|config| !config.upstreams.is_empty()).map(
+                            |config::latest::LoadBalancingConfig {
+                                 strategy,
+                                 upstreams,
+                                 outlier_ejection,
+                             }| LoadBalancing {
+                                strategy,
+                                upstreams: upstreams
+                                    .into_iter()
+                                    .map(|upstream| Upstream {
+                                        url: ctx
+                                            .urls
+                                            .insert(url::Url::parse(&config[upstream.url]).expect("valid url")),
+                                        websocket_url: upstream.websocket_url.map(|url| {
+                                            ctx.urls.insert(url::Url::parse(&config[url]).expect("valid url"))
+                                        }),
+                                        weight: upstream.weight.max(1),
+                                    })
+                                    .collect(),
+                                outlier_ejection_consecutive_failures: outlier_ejection
+                                    .consecutive_failures
+                                    .unwrap_or(DEFAULT_OUTLIER_EJECTION_CONSECUTIVE_FAILURES),
+                                outlier_ejection_duration: outlier_ejection
+                                    .duration
+                                    .unwrap_or(DEFAULT_OUTLIER_EJECTION_DURATION),
+                            },
+                        ),
+                        traffic_split: traffic_split.filter(|config| !config.variants.is_empty()).map(|split| {
+                            TrafficSplit {
+                                sticky_header: split.sticky_header.map(|name| ctx.strings.get_or_new(&config[name])),
+                                variants: split
+                                    .variants
+                                    .into_iter()
+                                    .map(|variant| TrafficVariant {
+                                        name: ctx.strings.get_or_new(&config[variant.name]),
+                                        url: ctx
+                                            .urls
+                                            .insert(url::Url::parse(&config[variant.url]).expect("valid url")),
+                                        percentage: variant.percentage.clamp(0.0, 100.0),
+                                        header: variant.header.map(|header| {
+                                            (
+                                                ctx.strings.get_or_new(&config[header.name]),
+                                                ctx.strings.get_or_new(&config[header.value]),
+                                            )
+                                        }),
+                                        claim: variant.claim.map(|claim| {
+                                            (
+                                                ctx.strings.get_or_new(&config[claim.name]),
+                                                ctx.strings.get_or_new(&config[claim.value]),
+                                            )
+                                        }),
+                                    })
+                                    .collect(),
+                            }
+                        }),
+                        shadow: shadow.map(|shadow| Shadow {
+                            url: ctx
+                                .urls
+                                .insert(url::Url::parse(&config[shadow.url]).expect("valid url")),
+                            sampling: shadow.sampling.clamp(0.0, 1.0),
+                        }),
+                        compression: compression.unwrap_or_default(),
+                        connection_pool: connection_pool.unwrap_or_default(),
                     },
 
                     None => GraphqlEndpoint {
@@ -62,10 +159,24 @@ impl ExternalDataSources {
                         subgraph_id,
                         url,
                         websocket_url: None,
+                        subscription_protocol: Default::default(),
+                        subscription_heartbeat_timeout: None,
+                        subscription_max_reconnect_attempts: None,
+                        deduplicate_subscriptions: false,
+                        deduplicate_requests: false,
                         header_rules: Vec::new(),
+                        response_header_rules: Vec::new(),
                         timeout: DEFAULT_SUBGRAPH_TIMEOUT,
                         retry: None,
                         entity_cache_ttl: config.entity_caching.ttl(),
+                        max_entities_per_request: None,
+                        error_masking: config.error_masking.clone(),
+                        circuit_breaker: None,
+                        load_balancing: None,
+                        traffic_split: None,
+                        shadow: None,
+                        compression: Default::default(),
+                        connection_pool: Default::default(),
                     },
                 }
             })
@@ -77,3 +188,5 @@ impl ExternalDataSources {
 }
 
 const DEFAULT_SUBGRAPH_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_OUTLIER_EJECTION_CONSECUTIVE_FAILURES: u32 = 5;
+const DEFAULT_OUTLIER_EJECTION_DURATION: Duration = Duration::from_secs(30);
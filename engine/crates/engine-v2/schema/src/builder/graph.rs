@@ -25,6 +25,7 @@ pub(crate) struct GraphBuilder<'a> {
     required_field_sets_buffer: RequiredFieldSetBuffer,
     cache_control: Interner<CacheControl, CacheControlId>,
     required_scopes: Interner<RequiredScopes, RequiredScopesId>,
+    policies: Interner<Policy, PolicyId>,
     graph: Graph,
 }
 
@@ -40,6 +41,7 @@ impl<'a> GraphBuilder<'a> {
             required_field_sets_buffer: Default::default(),
             cache_control: Default::default(),
             required_scopes: Default::default(),
+            policies: Default::default(),
             graph: Graph {
                 description: None,
                 root_operation_types: RootOperationTypes {
@@ -64,7 +66,9 @@ impl<'a> GraphBuilder<'a> {
                 cache_control: Vec::new(),
                 input_values: Default::default(),
                 required_scopes: Vec::new(),
+                policies: Vec::new(),
                 authorized_directives: Vec::new(),
+                override_labels: Vec::new(),
             },
         };
         builder.ingest_config(config);
@@ -390,10 +394,22 @@ impl<'a> GraphBuilder<'a> {
             for r#override in &field.overrides {
                 only_resolvable_in.insert(r#override.graph.into());
             }
+            let mut progressive_overrides = Vec::new();
             for r#override in field.overrides {
                 match r#override.from {
                     federated_graph::OverrideSource::Subgraph(id) => {
-                        only_resolvable_in.remove(&id.into());
+                        // With a label, the overridden subgraph keeps resolving the field and the
+                        // planner chooses between both for each request.
+                        if let Some(label_id) = self.insert_override_label(config, r#override.label) {
+                            progressive_overrides.push(ProgressiveOverride {
+                                label_id,
+                                subgraph_id: self.sources.graphql[GraphqlEndpointId::from(r#override.graph)]
+                                    .subgraph_id,
+                                from_subgraph_id: self.sources.graphql[GraphqlEndpointId::from(id)].subgraph_id,
+                            });
+                        } else {
+                            only_resolvable_in.remove(&id.into());
+                        }
                     }
                     federated_graph::OverrideSource::Missing(_) => (),
                 };
@@ -483,6 +499,7 @@ impl<'a> GraphBuilder<'a> {
                     .into_iter()
                     .map(|endpoint_id| self.sources.graphql[endpoint_id].subgraph_id)
                     .collect(),
+                progressive_overrides,
                 resolvers,
                 provides: field
                     .provides
@@ -511,18 +528,44 @@ impl<'a> GraphBuilder<'a> {
         }
     }
 
+    fn insert_override_label(
+        &mut self,
+        config: &Config,
+        label: federated_graph::OverrideLabel,
+    ) -> Option<OverrideLabelId> {
+        let label = match label {
+            federated_graph::OverrideLabel::Percent(percent) => OverrideLabel::Percent(percent),
+            federated_graph::OverrideLabel::Custom(name) => OverrideLabel::Custom {
+                enabled: config.progressive_override.enabled_labels.contains(&name),
+                name: self.ctx.strings.get_or_new(&name),
+            },
+            federated_graph::OverrideLabel::Unknown => return None,
+        };
+        let labels = &mut self.graph.override_labels;
+        let id = labels
+            .iter()
+            .position(|existing| *existing == label)
+            .unwrap_or_else(|| {
+                labels.push(label);
+                labels.len() - 1
+            });
+        Some(id.into())
+    }
+
     fn finalize(self) -> Result<(Graph, IntrospectionMetadata), BuildError> {
         let Self {
             ctx,
             required_field_sets_buffer,
             cache_control,
             required_scopes,
+            policies,
             mut graph,
             sources: _,
         } = self;
 
         graph.cache_control = cache_control.into();
         graph.required_scopes = required_scopes.into();
+        graph.policies = policies.into();
         required_field_sets_buffer.try_insert_into(ctx, &mut graph)?;
 
         let introspection = IntrospectionBuilder::create_data_source_and_insert_fields(ctx, &mut graph);
@@ -636,6 +679,36 @@ impl<'a> GraphBuilder<'a> {
         }
     }
 
+    /// `@cacheControl(maxAge: Int, staleWhileRevalidate: Int, scope: CacheControlScope)`, durations
+    /// in seconds.
+    fn convert_cache_control_directive(
+        &self,
+        arguments: &[(federated_graph::StringId, federated_graph::Value)],
+    ) -> Option<CacheControl> {
+        let mut cache_control = CacheControl::default();
+        let mut has_max_age = false;
+
+        for (name, value) in arguments {
+            match (&self.ctx.strings[(*name).into()], value) {
+                ("maxAge", federated_graph::Value::Int(seconds)) => {
+                    cache_control.max_age = std::time::Duration::from_secs((*seconds).max(0) as u64);
+                    has_max_age = true;
+                }
+                ("staleWhileRevalidate", federated_graph::Value::Int(seconds)) => {
+                    cache_control.stale_while_revalidate = std::time::Duration::from_secs((*seconds).max(0) as u64)
+                }
+                ("scope", federated_graph::Value::EnumValue(scope))
+                    if &self.ctx.strings[(*scope).into()] == "PRIVATE" =>
+                {
+                    cache_control.scope = CacheControlScope::Private
+                }
+                _ => {}
+            }
+        }
+
+        has_max_age.then_some(cache_control)
+    }
+
     fn push_resolver(&mut self, resolver: ResolverDefinition) -> ResolverDefinitionId {
         let resolver_id = ResolverDefinitionId::from(self.graph.resolver_definitions.len());
         self.graph.resolver_definitions.push(resolver);
@@ -644,9 +717,19 @@ impl<'a> GraphBuilder<'a> {
 
     fn push_directives(&mut self, config: &Config, directives: Directives) -> IdRange<TypeSystemDirectiveId> {
         let start = self.graph.type_system_directives.len();
+        let mut cache_control = None;
 
         for directive in &config.graph[directives.federated] {
             let directive = match directive {
+                federated_graph::Directive::Other { name, arguments }
+                    if &self.ctx.strings[(*name).into()] == "cacheControl" =>
+                {
+                    cache_control = CacheControl::union_opt(
+                        cache_control.as_ref(),
+                        self.convert_cache_control_directive(arguments).as_ref(),
+                    );
+                    continue;
+                }
                 federated_graph::Directive::Authenticated => TypeSystemDirective::Authenticated,
                 federated_graph::Directive::RequiresScopes(federated_scopes) => {
                     let id = self.required_scopes.get_or_insert(RequiredScopes::new(
@@ -657,26 +740,45 @@ impl<'a> GraphBuilder<'a> {
                     ));
                     TypeSystemDirective::RequiresScopes(id)
                 }
+                federated_graph::Directive::Policy(federated_policies) => {
+                    let id = self.policies.get_or_insert(Policy::new(
+                        federated_policies
+                            .iter()
+                            .map(|policies| policies.iter().copied().map(Into::into).collect())
+                            .collect(),
+                    ));
+                    TypeSystemDirective::Policy(id)
+                }
                 federated_graph::Directive::Deprecated { reason } => {
                     TypeSystemDirective::Deprecated(crate::Deprecated {
                         reason: reason.map(Into::into),
                     })
                 }
-                federated_graph::Directive::Other { .. }
-                | federated_graph::Directive::Inaccessible
-                | federated_graph::Directive::Policy(_) => continue,
+                federated_graph::Directive::Cost { weight } => {
+                    TypeSystemDirective::Cost(crate::Cost { weight: *weight })
+                }
+                federated_graph::Directive::ListSize(list_size) => TypeSystemDirective::ListSize(crate::ListSize {
+                    assumed_size: list_size.assumed_size,
+                    slicing_arguments: list_size.slicing_arguments.iter().copied().map(Into::into).collect(),
+                    sized_fields: list_size.sized_fields.iter().copied().map(Into::into).collect(),
+                    require_one_slicing_argument: list_size.require_one_slicing_argument,
+                }),
+                federated_graph::Directive::Other { .. } | federated_graph::Directive::Inaccessible => continue,
             };
             self.graph.type_system_directives.push(directive);
         }
 
-        if let Some(config) = directives
+        let cache_config = directives
             .cache_config_target
             .and_then(|target| config.cache.rule(target))
-        {
-            let cache_control_id = self.cache_control.get_or_insert(CacheControl {
+            .map(|config| CacheControl {
                 max_age: config.max_age,
                 stale_while_revalidate: config.stale_while_revalidate,
+                ..Default::default()
             });
+
+        if let Some(cache_control) = CacheControl::union_opt(cache_control.as_ref(), cache_config.as_ref()) {
+            let cache_control_id = self.cache_control.get_or_insert(cache_control);
             self.graph
                 .type_system_directives
                 .push(TypeSystemDirective::CacheControl(cache_control_id));
@@ -90,6 +90,7 @@ impl BuildContext {
                     },
                     resolvers: Default::default(),
                     only_resolvable_in: Default::default(),
+                    progressive_overrides: Default::default(),
                     requires: Default::default(),
                     provides: Default::default(),
                     argument_ids: Default::default(),
@@ -106,6 +107,7 @@ impl BuildContext {
                     },
                     resolvers: Default::default(),
                     only_resolvable_in: Default::default(),
+                    progressive_overrides: Default::default(),
                     requires: Default::default(),
                     provides: Default::default(),
                     argument_ids: Default::default(),
@@ -125,7 +127,9 @@ impl BuildContext {
             cache_control: Vec::new(),
             input_values: Default::default(),
             required_scopes: Vec::new(),
+            policies: Vec::new(),
             authorized_directives: Vec::new(),
+            override_labels: Vec::new(),
         };
 
         let out = build(&mut ctx, &mut graph);
@@ -141,6 +145,7 @@ impl BuildContext {
             regexps: Default::default(),
             urls: Default::default(),
             header_rules: Default::default(),
+            response_header_rules: Default::default(),
             settings: Default::default(),
         };
 
@@ -169,14 +174,7 @@ impl BuildContext {
             .map(|rule| -> HeaderRule {
                 match rule {
                     config::latest::HeaderRule::Forward(rule) => {
-                        let name = match rule.name {
-                            config::latest::NameOrPattern::Pattern(regex) => {
-                                NameOrPattern::Pattern(self.regexps.get_or_insert(regex))
-                            }
-                            config::latest::NameOrPattern::Name(name) => {
-                                NameOrPattern::Name(self.strings.get_or_new(&config[name]))
-                            }
-                        };
+                        let name = self.insert_name_or_pattern(&config, rule.name);
 
                         let default = rule.default.map(|id| self.strings.get_or_new(&config[id]));
                         let rename = rule.rename.map(|id| self.strings.get_or_new(&config[id]));
@@ -190,14 +188,7 @@ impl BuildContext {
                         HeaderRule::Insert { name, value }
                     }
                     config::latest::HeaderRule::Remove(rule) => {
-                        let name = match rule.name {
-                            config::latest::NameOrPattern::Pattern(regex) => {
-                                NameOrPattern::Pattern(self.regexps.get_or_insert(regex))
-                            }
-                            config::latest::NameOrPattern::Name(name) => {
-                                NameOrPattern::Name(self.strings.get_or_new(&config[name]))
-                            }
-                        };
+                        let name = self.insert_name_or_pattern(&config, rule.name);
 
                         HeaderRule::Remove { name }
                     }
@@ -210,6 +201,34 @@ impl BuildContext {
             })
             .collect();
 
+        let response_header_rules: Vec<_> = take(&mut config.response_header_rules)
+            .into_iter()
+            .map(|rule| -> ResponseHeaderRule {
+                match rule {
+                    config::latest::ResponseHeaderRule::Forward(rule) => ResponseHeaderRule::Forward {
+                        name: self.insert_name_or_pattern(&config, rule.name),
+                        default: rule.default.map(|id| self.strings.get_or_new(&config[id])),
+                        rename: rule.rename.map(|id| self.strings.get_or_new(&config[id])),
+                        merge: rule.merge,
+                    },
+                    config::latest::ResponseHeaderRule::Insert(rule) => ResponseHeaderRule::Insert {
+                        name: self.strings.get_or_new(&config[rule.name]),
+                        value: self.strings.get_or_new(&config[rule.value]),
+                        merge: rule.merge,
+                    },
+                    config::latest::ResponseHeaderRule::Remove(rule) => ResponseHeaderRule::Remove {
+                        name: self.insert_name_or_pattern(&config, rule.name),
+                    },
+                    config::latest::ResponseHeaderRule::Rename(rule) => ResponseHeaderRule::Rename {
+                        name: self.strings.get_or_new(&config[rule.name]),
+                        default: rule.default.map(|id| self.strings.get_or_new(&config[id])),
+                        rename: self.strings.get_or_new(&config[rule.rename]),
+                        merge: rule.merge,
+                    },
+                }
+            })
+            .collect();
+
         let default_header_rules = config
             .default_header_rules
             .into_iter()
@@ -223,15 +242,26 @@ impl BuildContext {
             regexps: self.regexps.into(),
             urls: self.urls.into(),
             header_rules,
+            response_header_rules,
             settings: Settings {
                 timeout: config.timeout.unwrap_or(DEFAULT_GATEWAY_TIMEOUT),
                 default_header_rules,
                 auth_config: take(&mut config.auth),
                 operation_limits: take(&mut config.operation_limits),
                 disable_introspection: config.disable_introspection,
+                query_plan: config.query_plan,
+                response_caching: take(&mut config.response_caching),
+                demand_control: config.demand_control,
             },
         })
     }
+
+    fn insert_name_or_pattern(&mut self, config: &Config, name: config::latest::NameOrPattern) -> NameOrPattern {
+        match name {
+            config::latest::NameOrPattern::Pattern(regex) => NameOrPattern::Pattern(self.regexps.get_or_insert(regex)),
+            config::latest::NameOrPattern::Name(name) => NameOrPattern::Name(self.strings.get_or_new(&config[name])),
+        }
+    }
 }
 
 macro_rules! from_id_newtypes {
@@ -258,6 +288,7 @@ from_id_newtypes! {
     federated_graph::SubgraphId => GraphqlEndpointId,
     federated_graph::UnionId => UnionDefinitionId,
     config::latest::HeaderRuleId => HeaderRuleId,
+    config::latest::ResponseHeaderRuleId => ResponseHeaderRuleId,
 }
 
 const DEFAULT_GATEWAY_TIMEOUT: Duration = Duration::from_secs(30);
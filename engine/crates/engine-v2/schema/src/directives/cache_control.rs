@@ -4,6 +4,15 @@ use std::time::Duration;
 pub struct CacheControl {
     pub max_age: Duration,
     pub stale_while_revalidate: Duration,
+    pub scope: CacheControlScope,
+}
+
+/// `@cacheControl(scope:)`, private data must never be shared between users.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+pub enum CacheControlScope {
+    #[default]
+    Public,
+    Private,
 }
 
 impl CacheControl {
@@ -11,6 +20,7 @@ impl CacheControl {
         CacheControl {
             max_age: self.max_age.min(other.max_age),
             stale_while_revalidate: self.stale_while_revalidate.min(other.stale_while_revalidate),
+            scope: self.scope.max(other.scope),
         }
     }
 
@@ -33,26 +43,53 @@ mod test {
         let left = CacheControl {
             max_age: Duration::from_secs(1),
             stale_while_revalidate: Duration::from_secs(1),
+            ..Default::default()
         };
 
         let right = CacheControl {
             max_age: Duration::from_secs(2),
             stale_while_revalidate: Duration::from_secs(2),
+            ..Default::default()
         };
 
         assert_eq!(left, left.union(right));
     }
 
+    #[test]
+    fn test_merge_private_scope() {
+        let left = CacheControl {
+            max_age: Duration::from_secs(1),
+            ..Default::default()
+        };
+
+        let right = CacheControl {
+            max_age: Duration::from_secs(2),
+            scope: CacheControlScope::Private,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            CacheControl {
+                max_age: Duration::from_secs(1),
+                scope: CacheControlScope::Private,
+                ..Default::default()
+            },
+            left.union(right)
+        );
+    }
+
     #[test]
     fn test_merge_optional() {
         let left = Some(CacheControl {
             max_age: Duration::from_secs(1),
             stale_while_revalidate: Duration::from_secs(1),
+            ..Default::default()
         });
 
         let right = Some(CacheControl {
             max_age: Duration::from_secs(2),
             stale_while_revalidate: Duration::from_secs(2),
+            ..Default::default()
         });
 
         assert_eq!(left, CacheControl::union_opt(left.as_ref(), right.as_ref()));
@@ -1,23 +1,43 @@
 mod authorized;
 mod cache_control;
+mod policy;
 mod requires_scopes;
 
 pub use authorized::*;
 pub use cache_control::*;
+pub use policy::*;
 pub use requires_scopes::*;
 
-use crate::{AuthorizedDirectiveId, CacheControlId, RequiredScopesId, StringId};
+use crate::{AuthorizedDirectiveId, CacheControlId, PolicyId, RequiredScopesId, StringId};
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub enum TypeSystemDirective {
     Deprecated(Deprecated),
     Authenticated,
     RequiresScopes(RequiredScopesId),
+    Policy(PolicyId),
     CacheControl(CacheControlId),
     Authorized(AuthorizedDirectiveId),
+    Cost(Cost),
+    ListSize(ListSize),
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct Deprecated {
     pub reason: Option<StringId>,
 }
+
+/// `@cost(weight:)`, the estimated cost of resolving a field or a type.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct Cost {
+    pub weight: i32,
+}
+
+/// `@listSize`, how to estimate the number of items returned by a list field.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ListSize {
+    pub assumed_size: Option<u32>,
+    pub slicing_arguments: Vec<StringId>,
+    pub sized_fields: Vec<StringId>,
+    pub require_one_slicing_argument: bool,
+}
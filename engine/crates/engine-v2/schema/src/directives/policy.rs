@@ -0,0 +1,33 @@
+use crate::{PolicyId, SchemaWalker, StringId};
+
+/// `@policy(policies: [[...]])`, access is granted if all policies of any of the groups are.
+#[derive(Debug, Hash, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Policy(Vec<Vec<StringId>>);
+
+impl Policy {
+    pub fn new(mut policies: Vec<Vec<StringId>>) -> Self {
+        for policies in &mut policies {
+            policies.sort_unstable();
+        }
+        policies.sort_unstable();
+        Self(policies)
+    }
+}
+
+pub type PolicyWalker<'a> = SchemaWalker<'a, PolicyId>;
+
+impl<'a> PolicyWalker<'a> {
+    /// All the policies referenced by the directive.
+    pub fn policies(&self) -> impl Iterator<Item = &'a str> + 'a {
+        let schema = self.schema;
+        self.as_ref().0.iter().flatten().map(move |id| schema[*id].as_str())
+    }
+
+    pub fn matches(&self, granted_policies: &[String]) -> bool {
+        self.as_ref().0.iter().any(|all_of| {
+            all_of
+                .iter()
+                .all(|id| granted_policies.iter().any(|granted| *granted == self.schema[*id]))
+        })
+    }
+}
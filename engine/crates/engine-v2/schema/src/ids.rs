@@ -2,9 +2,9 @@
 /// They can only be created by From<usize>
 use crate::{
     AuthorizedDirective, CacheControl, Definition, EnumDefinition, EnumValue, FieldDefinition, Graph, HeaderRule,
-    InputObjectDefinition, InputValueDefinition, InterfaceDefinition, ObjectDefinition, RequiredField,
-    RequiredFieldSet, RequiredScopes, ResolverDefinition, ScalarDefinition, Schema, TypeSystemDirective,
-    UnionDefinition,
+    InputObjectDefinition, InputValueDefinition, InterfaceDefinition, ObjectDefinition, OverrideLabel, Policy,
+    RequiredField, RequiredFieldSet, RequiredScopes, ResolverDefinition, ResponseHeaderRule, ScalarDefinition, Schema,
+    TypeSystemDirective, UnionDefinition,
 };
 use regex::Regex;
 use url::Url;
@@ -31,8 +31,11 @@ id_newtypes::NonZeroU32! {
     Graph.required_fields[RequiredFieldId] => RequiredField | max(MAX_ID) | proxy(Schema.graph),
     Graph.cache_control[CacheControlId] => CacheControl | max(MAX_ID) | proxy(Schema.graph),
     Graph.required_scopes[RequiredScopesId] => RequiredScopes | max(MAX_ID) | proxy(Schema.graph),
+    Graph.policies[PolicyId] => Policy | max(MAX_ID) | proxy(Schema.graph),
     Graph.authorized_directives[AuthorizedDirectiveId] => AuthorizedDirective | max(MAX_ID) | proxy(Schema.graph),
+    Graph.override_labels[OverrideLabelId] => OverrideLabel | max(MAX_ID) | proxy(Schema.graph),
     Schema.header_rules[HeaderRuleId] => HeaderRule | max(MAX_ID),
+    Schema.response_header_rules[ResponseHeaderRuleId] => ResponseHeaderRule | max(MAX_ID),
     Schema.urls[UrlId] => Url | max(MAX_ID),
     Schema.strings[StringId] => String | max(MAX_ID),
     Schema.regexps[RegexId] => Regex | max(MAX_ID),
@@ -60,6 +60,8 @@ pub struct Schema {
     urls: Vec<url::Url>,
     /// Headers we might want to send to a subgraph
     header_rules: Vec<HeaderRule>,
+    /// Subgraph response headers we might want to send back to the client
+    response_header_rules: Vec<ResponseHeaderRule>,
 
     pub settings: Settings,
 }
@@ -72,6 +74,9 @@ pub struct Settings {
     pub auth_config: Option<config::latest::AuthConfig>,
     pub operation_limits: config::latest::OperationLimits,
     pub disable_introspection: bool,
+    pub query_plan: config::latest::QueryPlanConfig,
+    pub response_caching: config::latest::ResponseCachingConfig,
+    pub demand_control: Option<config::latest::DemandControlConfig>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -100,7 +105,10 @@ pub struct Graph {
     input_values: SchemaInputValues,
     cache_control: Vec<CacheControl>,
     required_scopes: Vec<RequiredScopes>,
+    policies: Vec<Policy>,
     authorized_directives: Vec<AuthorizedDirective>,
+    /// Labels of the progressive overrides, deduplicated.
+    override_labels: Vec<OverrideLabel>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -110,6 +118,14 @@ pub struct DataSources {
 }
 
 impl Schema {
+    pub fn override_labels(&self) -> impl ExactSizeIterator<Item = (OverrideLabelId, &OverrideLabel)> + '_ {
+        self.graph
+            .override_labels
+            .iter()
+            .enumerate()
+            .map(|(id, label)| (OverrideLabelId::from(id), label))
+    }
+
     pub fn definition_by_name(&self, name: &str) -> Option<Definition> {
         self.graph
             .type_definitions
@@ -184,6 +200,9 @@ pub struct FieldDefinition {
     /// It's up to the composition to ensure it. If this field is specific to some subgraphs, they
     /// will be specified in this Vec.
     pub only_resolvable_in: Vec<SubgraphId>,
+    /// Overrides with a label, the field being resolvable by both subgraphs until the migration
+    /// is complete.
+    pub progressive_overrides: Vec<ProgressiveOverride>,
     pub requires: Vec<FieldRequires>,
     pub provides: Vec<FieldProvides>,
     /// The arguments referenced by this range are sorted by their name (string)
@@ -191,6 +210,23 @@ pub struct FieldDefinition {
     pub directives: IdRange<TypeSystemDirectiveId>,
 }
 
+/// A field migrated from one subgraph to another with `@override(label: ...)`. The overriding
+/// subgraph resolves it only if the label is active for the request, the overridden one otherwise.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub struct ProgressiveOverride {
+    pub label_id: OverrideLabelId,
+    pub subgraph_id: SubgraphId,
+    pub from_subgraph_id: SubgraphId,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum OverrideLabel {
+    /// Active for the given percentage of the requests.
+    Percent(u8),
+    /// Active for all requests if enabled in the gateway configuration, never otherwise.
+    Custom { name: StringId, enabled: bool },
+}
+
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct FieldProvides {
     subgraph_id: SubgraphId,
@@ -452,3 +488,33 @@ pub enum HeaderRule {
         rename: StringId,
     },
 }
+
+pub use config::latest::{
+    CircuitBreakerConfig, CompressionAlgorithm, CompressionConfig, ConnectionPoolConfig, DemandControlConfig,
+    DemandControlMode, ErrorMaskingConfig, ErrorMaskingMode, HeaderMergeStrategy, LoadBalancingStrategy,
+    PublicErrorCode, SubscriptionProtocol,
+};
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum ResponseHeaderRule {
+    Forward {
+        name: NameOrPattern,
+        default: Option<StringId>,
+        rename: Option<StringId>,
+        merge: HeaderMergeStrategy,
+    },
+    Insert {
+        name: StringId,
+        value: StringId,
+        merge: HeaderMergeStrategy,
+    },
+    Remove {
+        name: NameOrPattern,
+    },
+    Rename {
+        name: StringId,
+        default: Option<StringId>,
+        rename: StringId,
+        merge: HeaderMergeStrategy,
+    },
+}
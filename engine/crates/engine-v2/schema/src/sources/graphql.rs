@@ -2,7 +2,9 @@ use std::time::Duration;
 use url::Url;
 
 use crate::{
-    HeaderRuleId, HeaderRuleWalker, RequiredFieldSet, RequiredFieldSetId, SchemaWalker, StringId, SubgraphId, UrlId,
+    CircuitBreakerConfig, CompressionConfig, ConnectionPoolConfig, ErrorMaskingConfig, HeaderRuleId, HeaderRuleWalker,
+    LoadBalancingStrategy, RequiredFieldSet, RequiredFieldSetId, ResponseHeaderRuleId, ResponseHeaderRuleWalker,
+    SchemaWalker, StringId, SubgraphId, SubscriptionProtocol, UrlId,
 };
 
 #[derive(Default, serde::Serialize, serde::Deserialize)]
@@ -16,12 +18,108 @@ pub struct GraphqlEndpoint {
     pub(crate) subgraph_name: StringId,
     pub(crate) url: UrlId,
     pub(crate) websocket_url: Option<UrlId>,
+    pub(crate) subscription_protocol: SubscriptionProtocol,
+    // If None, SSE and multipart subscriptions are never considered dead for lack of heartbeats.
+    pub(crate) subscription_heartbeat_timeout: Option<Duration>,
+    // If None, the fetcher default is used.
+    pub(crate) subscription_max_reconnect_attempts: Option<u32>,
+    pub(crate) deduplicate_subscriptions: bool,
+    pub(crate) deduplicate_requests: bool,
     pub(crate) header_rules: Vec<HeaderRuleId>,
+    pub(crate) response_header_rules: Vec<ResponseHeaderRuleId>,
     pub(crate) timeout: Duration,
     pub(crate) retry: Option<RetryConfig>,
     // The ttl to use for caching for this subgraph.
     // If None then caching is disabled for this subgraph
     pub(crate) entity_cache_ttl: Option<Duration>,
+    pub(crate) max_entities_per_request: Option<usize>,
+    // Either the subgraph specific or the global error masking.
+    pub(crate) error_masking: ErrorMaskingConfig,
+    pub(crate) circuit_breaker: Option<CircuitBreakerConfig>,
+    // If present, requests are sent to those upstreams rather than `url`.
+    pub(crate) load_balancing: Option<LoadBalancing>,
+    pub(crate) traffic_split: Option<TrafficSplit>,
+    pub(crate) shadow: Option<Shadow>,
+    pub(crate) compression: CompressionConfig,
+    pub(crate) connection_pool: ConnectionPoolConfig,
+}
+
+/// Candidate version of the subgraph receiving a copy of a sample of the queries.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct Shadow {
+    pub(crate) url: UrlId,
+    /// Fraction of the queries mirrored, between 0 and 1.
+    pub sampling: f64,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct LoadBalancing {
+    pub strategy: LoadBalancingStrategy,
+    pub(crate) upstreams: Vec<Upstream>,
+    /// Number of consecutive failures ejecting an upstream.
+    pub outlier_ejection_consecutive_failures: u32,
+    /// How long an upstream stays ejected.
+    pub outlier_ejection_duration: Duration,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct Upstream {
+    pub(crate) url: UrlId,
+    pub(crate) websocket_url: Option<UrlId>,
+    pub weight: u32,
+}
+
+impl LoadBalancing {
+    pub fn upstreams(&self) -> &[Upstream] {
+        &self.upstreams
+    }
+}
+
+/// Alternative versions of the subgraph, each receiving the requests it matches.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TrafficSplit {
+    pub(crate) sticky_header: Option<StringId>,
+    pub(crate) variants: Vec<TrafficVariant>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TrafficVariant {
+    pub(crate) name: StringId,
+    pub(crate) url: UrlId,
+    /// Share of the requests, between 0 and 100.
+    pub percentage: f32,
+    /// Header name and value matching the variant.
+    pub(crate) header: Option<(StringId, StringId)>,
+    /// Claim path and value matching the variant.
+    pub(crate) claim: Option<(StringId, StringId)>,
+}
+
+pub type TrafficVariantWalker<'a> = SchemaWalker<'a, &'a TrafficVariant>;
+
+impl<'a> TrafficVariantWalker<'a> {
+    pub fn name(&self) -> &'a str {
+        &self.schema[self.item.name]
+    }
+
+    pub fn url(&self) -> &'a Url {
+        &self.schema[self.item.url]
+    }
+
+    pub fn percentage(&self) -> f32 {
+        self.item.percentage
+    }
+
+    pub fn header(&self) -> Option<(&'a str, &'a str)> {
+        self.item
+            .header
+            .map(|(name, value)| (&self.schema[name], &self.schema[value]))
+    }
+
+    pub fn claim(&self) -> Option<(&'a str, &'a str)> {
+        self.item
+            .claim
+            .map(|(path, value)| (&self.schema[path], &self.schema[value]))
+    }
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -34,6 +132,16 @@ pub struct RetryConfig {
     pub retry_percent: Option<f32>,
     /// Whether mutations should be retried at all. False by default.
     pub retry_mutations: bool,
+    /// HTTP status codes of subgraph responses to retry, sorted.
+    pub retry_on_status: Vec<u16>,
+}
+
+pub const DEFAULT_RETRY_ON_STATUS: [u16; 3] = [502, 503, 504];
+
+impl RetryConfig {
+    pub fn should_retry_status(&self, status: u16) -> bool {
+        self.retry_on_status.binary_search(&status).is_ok()
+    }
 }
 
 id_newtypes::U8! {
@@ -164,14 +272,117 @@ impl<'a> GraphqlEndpointWalker<'a> {
         }
     }
 
+    pub fn load_balancing(self) -> Option<&'a LoadBalancing> {
+        self.as_ref().load_balancing.as_ref()
+    }
+
+    /// URL of the upstream at `index` if load balancing is configured, otherwise `url`.
+    pub fn upstream_url(self, index: usize) -> &'a Url {
+        match self.load_balancing() {
+            Some(load_balancing) => &self.schema[load_balancing.upstreams[index].url],
+            None => self.url(),
+        }
+    }
+
+    /// Websocket URL of the upstream at `index`, which defaults to its URL.
+    pub fn upstream_websocket_url(self, index: usize) -> &'a Url {
+        match self.load_balancing() {
+            Some(load_balancing) => {
+                let upstream = &load_balancing.upstreams[index];
+                &self.schema[upstream.websocket_url.unwrap_or(upstream.url)]
+            }
+            None => self.websocket_url(),
+        }
+    }
+
+    /// Whether requests may be routed to alternative versions of the subgraph.
+    pub fn has_traffic_split(self) -> bool {
+        self.as_ref().traffic_split.is_some()
+    }
+
+    /// Header keying the percentage based assignment of the traffic variants.
+    pub fn traffic_split_sticky_header(self) -> Option<&'a str> {
+        self.as_ref()
+            .traffic_split
+            .as_ref()
+            .and_then(|split| split.sticky_header)
+            .map(|id| &self.schema[id])
+    }
+
+    pub fn traffic_variants(self) -> impl ExactSizeIterator<Item = TrafficVariantWalker<'a>> + 'a {
+        self.as_ref()
+            .traffic_split
+            .as_ref()
+            .map(|split| split.variants.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(move |variant| self.walk(variant))
+    }
+
+    pub fn shadow_url(self) -> Option<&'a Url> {
+        self.as_ref().shadow.as_ref().map(|shadow| &self.schema[shadow.url])
+    }
+
+    pub fn shadow_sampling(self) -> f64 {
+        self.as_ref()
+            .shadow
+            .as_ref()
+            .map(|shadow| shadow.sampling)
+            .unwrap_or_default()
+    }
+
+    pub fn compression(self) -> CompressionConfig {
+        self.as_ref().compression
+    }
+
+    pub fn connection_pool(self) -> ConnectionPoolConfig {
+        self.as_ref().connection_pool
+    }
+
+    pub fn subscription_protocol(&self) -> SubscriptionProtocol {
+        self.as_ref().subscription_protocol
+    }
+
+    pub fn subscription_heartbeat_timeout(&self) -> Option<Duration> {
+        self.as_ref().subscription_heartbeat_timeout
+    }
+
+    pub fn subscription_max_reconnect_attempts(&self) -> Option<u32> {
+        self.as_ref().subscription_max_reconnect_attempts
+    }
+
+    pub fn deduplicate_subscriptions(&self) -> bool {
+        self.as_ref().deduplicate_subscriptions
+    }
+
+    pub fn deduplicate_requests(&self) -> bool {
+        self.as_ref().deduplicate_requests
+    }
+
     pub fn header_rules(self) -> impl Iterator<Item = HeaderRuleWalker<'a>> {
         self.as_ref().header_rules.iter().map(move |id| self.walk(*id))
     }
 
+    pub fn response_header_rules(self) -> impl ExactSizeIterator<Item = ResponseHeaderRuleWalker<'a>> {
+        self.as_ref().response_header_rules.iter().map(move |id| self.walk(*id))
+    }
+
     pub fn entity_cache_ttl(self) -> Option<Duration> {
         self.as_ref().entity_cache_ttl
     }
 
+    pub fn max_entities_per_request(self) -> Option<usize> {
+        self.as_ref().max_entities_per_request
+    }
+
+    pub fn error_masking(self) -> &'a ErrorMaskingConfig {
+        &self.as_ref().error_masking
+    }
+
+    pub fn circuit_breaker_config(self) -> Option<&'a CircuitBreakerConfig> {
+        self.as_ref().circuit_breaker.as_ref()
+    }
+
     pub fn retry_config(self) -> Option<&'a RetryConfig> {
         self.as_ref().retry.as_ref()
     }
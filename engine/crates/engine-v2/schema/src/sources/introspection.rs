@@ -698,6 +698,7 @@ impl<'a> IntrospectionBuilder<'a> {
                 ty: r#type,
                 parent_entity: EntityId::Object(object_id),
                 only_resolvable_in: vec![subgraph_id],
+                progressive_overrides: Vec::new(),
                 requires: Vec::new(),
                 provides: Vec::new(),
                 directives: IdRange::empty(),
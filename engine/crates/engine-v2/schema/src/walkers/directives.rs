@@ -1,8 +1,8 @@
 use id_newtypes::IdRange;
 
 use crate::{
-    AuthorizedDirectiveId, CacheControl, Deprecated, InputValueSet, RequiredFieldSet, RequiredScopesWalker,
-    SchemaInputValueWalker, SchemaWalker, TypeSystemDirective, TypeSystemDirectiveId,
+    AuthorizedDirectiveId, CacheControl, Cost, Deprecated, InputValueSet, ListSize, PolicyWalker, RequiredFieldSet,
+    RequiredScopesWalker, SchemaInputValueWalker, SchemaWalker, TypeSystemDirective, TypeSystemDirectiveId,
 };
 
 pub type TypeSystemDirectivesWalker<'a> = SchemaWalker<'a, IdRange<TypeSystemDirectiveId>>;
@@ -15,6 +15,20 @@ impl<'a> TypeSystemDirectivesWalker<'a> {
         })
     }
 
+    pub fn cost(&self) -> Option<&'a Cost> {
+        self.as_ref().iter().find_map(|d| match d {
+            TypeSystemDirective::Cost(cost) => Some(cost),
+            _ => None,
+        })
+    }
+
+    pub fn list_size(&self) -> Option<&'a ListSize> {
+        self.as_ref().iter().find_map(|d| match d {
+            TypeSystemDirective::ListSize(list_size) => Some(list_size),
+            _ => None,
+        })
+    }
+
     pub fn has_deprecated(&self) -> bool {
         self.as_ref()
             .iter()
@@ -41,6 +55,13 @@ impl<'a> TypeSystemDirectivesWalker<'a> {
         })
     }
 
+    pub fn policy(&self) -> Option<PolicyWalker<'a>> {
+        self.as_ref().iter().find_map(|d| match d {
+            TypeSystemDirective::Policy(id) => Some(self.walk(*id)),
+            _ => None,
+        })
+    }
+
     pub fn iter_required_fields(&self) -> impl Iterator<Item = &'a RequiredFieldSet> + 'a {
         let schema = self.schema;
         self.as_ref().iter().filter_map(|d| match d {
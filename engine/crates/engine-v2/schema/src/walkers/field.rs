@@ -2,8 +2,8 @@ use std::borrow::Cow;
 
 use super::{resolver::ResolverDefinitionWalker, SchemaWalker};
 use crate::{
-    EntityWalker, FieldDefinitionId, InputValueDefinitionWalker, ProvidableFieldSet, RequiredFieldSet, SubgraphId,
-    TypeSystemDirectivesWalker, TypeWalker,
+    EntityWalker, FieldDefinitionId, InputValueDefinitionWalker, OverrideLabelId, ProvidableFieldSet, RequiredFieldSet,
+    SubgraphId, TypeSystemDirectivesWalker, TypeWalker,
 };
 
 pub type FieldDefinitionWalker<'a> = SchemaWalker<'a, FieldDefinitionId>;
@@ -22,6 +22,18 @@ impl<'a> FieldDefinitionWalker<'a> {
         r.is_empty() || r.contains(&subgraph_id)
     }
 
+    /// Whether a progressive override prevents this subgraph from resolving the field with the
+    /// given active override labels of the request.
+    pub fn is_overridden_in(&self, subgraph_id: SubgraphId, active_override_labels: &[OverrideLabelId]) -> bool {
+        self.as_ref().progressive_overrides.iter().any(|r#override| {
+            if active_override_labels.contains(&r#override.label_id) {
+                r#override.from_subgraph_id == subgraph_id
+            } else {
+                r#override.subgraph_id == subgraph_id
+            }
+        })
+    }
+
     pub fn provides(&self, subgraph_id: SubgraphId) -> &'a ProvidableFieldSet {
         self.as_ref()
             .provides
@@ -1,8 +1,12 @@
-use crate::{HeaderRule, HeaderRuleId, NameOrPattern, SchemaWalker};
+use crate::{
+    HeaderMergeStrategy, HeaderRule, HeaderRuleId, NameOrPattern, ResponseHeaderRule, ResponseHeaderRuleId,
+    SchemaWalker,
+};
 use regex::Regex;
 use std::fmt;
 
 pub type HeaderRuleWalker<'a> = SchemaWalker<'a, HeaderRuleId>;
+pub type ResponseHeaderRuleWalker<'a> = SchemaWalker<'a, ResponseHeaderRuleId>;
 
 impl<'a> HeaderRuleWalker<'a> {
     pub fn rule(&self) -> HeaderRuleRef<'a> {
@@ -35,6 +39,50 @@ impl<'a> HeaderRuleWalker<'a> {
     }
 }
 
+impl<'a> ResponseHeaderRuleWalker<'a> {
+    pub fn rule(&self) -> ResponseHeaderRuleRef<'a> {
+        match &self.schema[self.item] {
+            ResponseHeaderRule::Forward {
+                name,
+                default,
+                rename,
+                merge,
+            } => ResponseHeaderRuleRef::Forward {
+                name: self.name_or_pattern_ref(name),
+                default: default.map(|id| self.schema[id].as_str()),
+                rename: rename.map(|id| self.schema[id].as_str()),
+                merge: *merge,
+            },
+            ResponseHeaderRule::Insert { name, value, merge } => ResponseHeaderRuleRef::Insert {
+                name: self.schema[*name].as_str(),
+                value: self.schema[*value].as_str(),
+                merge: *merge,
+            },
+            ResponseHeaderRule::Remove { name } => ResponseHeaderRuleRef::Remove {
+                name: self.name_or_pattern_ref(name),
+            },
+            ResponseHeaderRule::Rename {
+                name,
+                default,
+                rename,
+                merge,
+            } => ResponseHeaderRuleRef::Rename {
+                name: self.schema[*name].as_str(),
+                default: default.map(|id| self.schema[id].as_str()),
+                rename: self.schema[*rename].as_str(),
+                merge: *merge,
+            },
+        }
+    }
+
+    fn name_or_pattern_ref(&self, name: &'a NameOrPattern) -> NameOrPatternRef<'a> {
+        match name {
+            NameOrPattern::Pattern(regex_id) => NameOrPatternRef::Pattern(&self.schema[*regex_id]),
+            NameOrPattern::Name(name_id) => NameOrPatternRef::Name(self.schema[*name_id].as_str()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum NameOrPatternRef<'a> {
     Pattern(&'a Regex),
@@ -62,6 +110,30 @@ pub enum HeaderRuleRef<'a> {
     },
 }
 
+#[derive(Debug)]
+pub enum ResponseHeaderRuleRef<'a> {
+    Forward {
+        name: NameOrPatternRef<'a>,
+        default: Option<&'a str>,
+        rename: Option<&'a str>,
+        merge: HeaderMergeStrategy,
+    },
+    Insert {
+        name: &'a str,
+        value: &'a str,
+        merge: HeaderMergeStrategy,
+    },
+    Remove {
+        name: NameOrPatternRef<'a>,
+    },
+    Rename {
+        name: &'a str,
+        default: Option<&'a str>,
+        rename: &'a str,
+        merge: HeaderMergeStrategy,
+    },
+}
+
 impl<'a> fmt::Debug for HeaderRuleWalker<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("SubgraphHeaderWalker")
@@ -69,3 +141,11 @@ impl<'a> fmt::Debug for HeaderRuleWalker<'a> {
             .finish()
     }
 }
+
+impl<'a> fmt::Debug for ResponseHeaderRuleWalker<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResponseHeaderRuleWalker")
+            .field("rule", &self.rule())
+            .finish()
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
-    FieldDefinitionId, Names, RequiredFieldSet, ResolverDefinition, ResolverDefinitionId, SchemaWalker, SubgraphId,
+    FieldDefinitionId, Names, OverrideLabelId, RequiredFieldSet, ResolverDefinition, ResolverDefinitionId,
+    SchemaWalker, SubgraphId,
 };
 
 pub type ResolverDefinitionWalker<'a> = SchemaWalker<'a, ResolverDefinitionId>;
@@ -44,8 +45,10 @@ impl<'a> ResolverDefinitionWalker<'a> {
         }
     }
 
-    pub fn can_provide(&self, field_id: FieldDefinitionId) -> bool {
-        self.walk(field_id).is_resolvable_in(self.subgraph_id())
+    pub fn can_provide(&self, field_id: FieldDefinitionId, active_override_labels: &[OverrideLabelId]) -> bool {
+        let field = self.walk(field_id);
+        field.is_resolvable_in(self.subgraph_id())
+            && !field.is_overridden_in(self.subgraph_id(), active_override_labels)
     }
 }
 
@@ -1,44 +1,65 @@
 use ::runtime::{
     auth::AccessToken,
-    hooks::Hooks,
+    hooks::{ExecutedOperation, Hooks},
     hot_cache::{CachedDataKind, HotCache, HotCacheFactory},
     rate_limiting::RateLimitKey,
 };
 use async_runtime::stream::StreamExt as _;
+use circuit_breaker::CircuitBreakers;
 use engine::{BatchRequest, Request};
 use engine_parser::types::OperationType;
-use futures::{channel::mpsc, FutureExt, StreamExt};
+use futures::{
+    channel::{mpsc, oneshot},
+    FutureExt, StreamExt,
+};
 use futures_util::{SinkExt, Stream};
 use gateway_core::StreamingFormat;
 use gateway_v2_auth::AuthService;
 use grafbase_telemetry::{
     gql_response_status::GraphqlResponseStatus,
     grafbase_client::Client,
-    metrics::{GraphqlOperationMetrics, GraphqlRequestMetricsAttributes, OperationMetricsAttributes},
+    metrics::{
+        GraphqlOperationMetrics, GraphqlRequestMetricsAttributes, OperationMetricsAttributes,
+        SubgraphCircuitBreakerMetrics, SubgraphShadowMetrics, SubgraphSubscriptionMetrics,
+    },
     span::{gql::GqlRequestSpan, GqlRecorderSpanExt, GRAFBASE_TARGET},
 };
 use headers::HeaderMapExt;
+use load_balancer::LoadBalancers;
+use request_deduplication::InflightRequests;
 use retry_budget::RetryBudgets;
 use schema::Schema;
 use std::{borrow::Cow, sync::Arc};
+use subscription_deduplication::SubscriptionDeduplication;
 use tracing::Instrument;
 use trusted_documents::PreparedOperationDocument;
 use web_time::Instant;
 
 use crate::{
-    execution::{ExecutableOperation, PreExecutionContext},
+    execution::{ExecutableOperation, PreExecutionContext, QueryPlan, QueryPlanMode, ResponseHeaders},
     http_response::{HttpGraphqlResponse, HttpGraphqlResponseExtraMetadata},
     operation::{Operation, PreparedOperation, Variables},
     response::{ErrorCode, GraphqlError, Response},
+    sources::TrafficVariantSelections,
     websocket,
 };
 
 mod cache;
+mod circuit_breaker;
+mod demand_control;
+mod load_balancer;
+mod progressive_override;
+mod request_deduplication;
+mod response_cache;
 mod retry_budget;
 mod runtime;
+mod subscription_deduplication;
 mod trusted_documents;
 
+pub use circuit_breaker::CircuitBreakerState;
+pub(crate) use response_cache::get_claim;
 pub use runtime::Runtime;
+pub(crate) use subscription_deduplication::{UpstreamEvent, UpstreamSubscriptionRequest};
 
 pub(crate) struct SchemaVersion(Vec<u8>);
 
@@ -59,6 +80,11 @@ pub struct Engine<R: Runtime> {
     operation_metrics: GraphqlOperationMetrics,
     auth: AuthService,
     retry_budgets: RetryBudgets,
+    circuit_breakers: CircuitBreakers,
+    load_balancers: LoadBalancers,
+    pub(crate) shadow_metrics: SubgraphShadowMetrics,
+    subscription_deduplication: SubscriptionDeduplication,
+    inflight_requests: InflightRequests,
     trusted_documents_cache: <R::CacheFactory as HotCacheFactory>::Cache<String>,
     operation_cache: <R::CacheFactory as HotCacheFactory>::Cache<Arc<PreparedOperation>>,
 }
@@ -89,7 +115,14 @@ impl<R: Runtime> Engine<R> {
             }),
             auth,
             retry_budgets: RetryBudgets::build(&schema),
+            circuit_breakers: CircuitBreakers::build(&schema, SubgraphCircuitBreakerMetrics::build(runtime.meter())),
+            load_balancers: LoadBalancers::build(&schema),
+            shadow_metrics: SubgraphShadowMetrics::build(runtime.meter()),
             operation_metrics: GraphqlOperationMetrics::build(runtime.meter()),
+            inflight_requests: InflightRequests::default(),
+            subscription_deduplication: SubscriptionDeduplication::new(SubgraphSubscriptionMetrics::build(
+                runtime.meter(),
+            )),
             trusted_documents_cache: runtime.cache_factory().create(CachedDataKind::TrustedDocument).await,
             operation_cache: runtime.cache_factory().create(CachedDataKind::Operation).await,
             schema,
@@ -120,7 +153,7 @@ impl<R: Runtime> Engine<R> {
 
         let mut timeout = match format {
             Some(_) => {
-                // Streaming requests are subscriptions so shouldn't timeout
+                // Streaming requests are subscriptions or deferred queries so shouldn't timeout
                 std::future::pending().boxed()
             }
             None => async move {
@@ -170,8 +203,9 @@ impl<R: Runtime> Engine<R> {
     ) -> Result<RequestContext<<R::Hooks as Hooks>::Context>, Response> {
         let client = Client::extract_from(&headers);
         let streaming_format = headers.typed_get::<StreamingFormat>();
+        let query_plan = QueryPlanMode::from_headers(&headers, self.schema.settings.query_plan);
 
-        let (hooks_context, headers) = self
+        let (mut hooks_context, headers) = self
             .runtime
             .hooks()
             .on_gateway_request(headers)
@@ -179,12 +213,17 @@ impl<R: Runtime> Engine<R> {
             .map_err(Response::pre_execution_error)?;
 
         if let Some(access_token) = self.auth.authenticate(&headers).await {
+            self.runtime.hooks().authenticated(&mut hooks_context, &access_token);
+
             Ok(RequestContext {
                 headers,
                 streaming_format,
+                query_plan,
                 client,
                 access_token,
                 hooks_context,
+                response_headers: ResponseHeaders::default(),
+                traffic_variant_selections: TrafficVariantSelections::default(),
             })
         } else {
             Err(Response::pre_execution_error(GraphqlError::new(
@@ -199,16 +238,28 @@ impl<R: Runtime> Engine<R> {
         request_context: RequestContext<<R::Hooks as Hooks>::Context>,
         batch_request: BatchRequest,
     ) -> HttpGraphqlResponse {
-        match batch_request {
+        let request_context = Arc::new(request_context);
+        let (mut response, operations) = match batch_request {
             BatchRequest::Single(request) => {
                 if let Some(streaming_format) = request_context.streaming_format {
-                    convert_stream_to_http_response(
-                        streaming_format,
-                        self.execute_stream(Arc::new(request_context), request),
-                    )
-                    .await
+                    let start = Instant::now();
+                    let (operation_receiver, stream) = self.execute_stream(Arc::clone(&request_context), request);
+                    let response = convert_stream_to_http_response(streaming_format, stream).await;
+                    // Sent before the first response, so it's always available at this point.
+                    let attributes = operation_receiver.await.ok().flatten();
+                    let operation = ExecutedOperationSummary {
+                        name: attributes.as_ref().and_then(|attributes| attributes.name.clone()),
+                        ty: attributes.map(|attributes| attributes.ty),
+                        status: response
+                            .headers
+                            .typed_get::<GraphqlResponseStatus>()
+                            .unwrap_or(GraphqlResponseStatus::Success),
+                        duration: start.elapsed(),
+                    };
+                    (response, vec![operation])
                 } else {
-                    self.execute_single(&request_context, request).await
+                    let (response, operation) = self.execute_single(&request_context, request).await;
+                    (response, vec![operation])
                 }
             }
             BatchRequest::Batch(requests) => {
@@ -217,21 +268,35 @@ impl<R: Runtime> Engine<R> {
                         "batch requests can't use multipart or event-stream responses",
                     );
                 }
-                HttpGraphqlResponse::from_batch(
-                    futures_util::stream::iter(requests.into_iter())
-                        .then(|request| self.execute_single(&request_context, request))
-                        .collect::<Vec<_>>()
-                        .await,
-                )
+                let (responses, operations) = futures_util::stream::iter(requests.into_iter())
+                    .then(|request| self.execute_single(&request_context, request))
+                    .collect::<Vec<_>>()
+                    .await
+                    .into_iter()
+                    .unzip();
+                (HttpGraphqlResponse::from_batch(responses), operations)
             }
+        };
+
+        // For streaming responses, only the headers received before the first response are
+        // forwarded.
+        request_context.response_headers.write_into(&mut response.headers);
+
+        // The hook sees the final headers, after response header rules and cache headers were
+        // applied. For batch requests it's called once per operation on the shared headers.
+        for operation in operations {
+            self.on_gateway_response(&request_context, operation, &mut response.headers)
+                .await;
         }
+
+        response
     }
 
     async fn execute_single(
         &self,
         request_context: &RequestContext<<R::Hooks as Hooks>::Context>,
         request: Request,
-    ) -> HttpGraphqlResponse {
+    ) -> (HttpGraphqlResponse, ExecutedOperationSummary) {
         let start = Instant::now();
         let span = GqlRequestSpan::create();
         async {
@@ -246,6 +311,9 @@ impl<R: Runtime> Engine<R> {
             };
 
             let elapsed = start.elapsed();
+            let operation_type = operation_metrics_attributes
+                .as_ref()
+                .map(|attributes| attributes.ty.clone());
 
             if let Some(operation_metrics_attributes) = operation_metrics_attributes {
                 tracing::Span::current().record_gql_request((&operation_metrics_attributes).into());
@@ -279,27 +347,62 @@ impl<R: Runtime> Engine<R> {
                 tracing::debug!(target: GRAFBASE_TARGET, "{message}")
             }
 
-            HttpGraphqlResponse::build(response, None, response_metadata)
+            let operation = ExecutedOperationSummary {
+                name: response_metadata.operation_name.clone(),
+                ty: operation_type,
+                status,
+                duration: elapsed,
+            };
+
+            (HttpGraphqlResponse::build(response, None, response_metadata), operation)
         }
         .instrument(span)
         .await
     }
 
+    async fn on_gateway_response(
+        &self,
+        request_context: &RequestContext<<R::Hooks as Hooks>::Context>,
+        operation: ExecutedOperationSummary,
+        headers: &mut http::HeaderMap,
+    ) {
+        let operation = ExecutedOperation {
+            name: operation.name.as_deref(),
+            ty: operation.ty,
+            status: operation.status,
+            duration: operation.duration,
+        };
+        match self
+            .runtime
+            .hooks()
+            .on_gateway_response(&request_context.hooks_context, operation, headers.clone())
+            .await
+        {
+            Ok(hook_headers) => *headers = hook_headers,
+            Err(err) => tracing::error!("on_gateway_response hook failed: {}", err.message),
+        }
+    }
+
     fn execute_stream(
         self: &Arc<Self>,
         request_context: Arc<RequestContext<<R::Hooks as Hooks>::Context>>,
         request: Request,
-    ) -> impl Stream<Item = Response> + Send + 'static {
+    ) -> (
+        oneshot::Receiver<Option<OperationMetricsAttributes>>,
+        impl Stream<Item = Response> + Send + 'static,
+    ) {
         let start = Instant::now();
         let engine = Arc::clone(self);
         let (sender, receiver) = mpsc::channel(2);
+        let (operation_sender, operation_receiver) = oneshot::channel();
 
         let span = GqlRequestSpan::create();
         let span_clone = span.clone();
-        receiver.join(
+        let stream = receiver.join(
             async move {
                 let ctx = PreExecutionContext::new(&engine, &request_context);
-                let (operation_metrics_attributes, status) = ctx.execute_stream(request, sender).await;
+                let (operation_metrics_attributes, status) =
+                    ctx.execute_stream(request, operation_sender, sender).await;
                 let elapsed = start.elapsed();
 
                 if let Some(operation_metrics_attributes) = operation_metrics_attributes {
@@ -325,10 +428,19 @@ impl<R: Runtime> Engine<R> {
                 }
             }
             .instrument(span_clone),
-        )
+        );
+        (operation_receiver, stream)
     }
 }
 
+/// Owned counterpart of [ExecutedOperation], kept until the final response headers are known.
+struct ExecutedOperationSummary {
+    name: Option<String>,
+    ty: Option<grafbase_telemetry::metrics::OperationType>,
+    status: GraphqlResponseStatus,
+    duration: std::time::Duration,
+}
+
 async fn convert_stream_to_http_response(
     streaming_format: StreamingFormat,
     stream: impl Stream<Item = Response> + Send + 'static,
@@ -353,26 +465,54 @@ impl<'ctx, R: Runtime> PreExecutionContext<'ctx, R> {
         };
 
         let metrics_attributes = Some(operation_plan.metrics_attributes.clone());
+        let query_plan = match self.query_plan(&operation_plan) {
+            Some((QueryPlanMode::PlanOnly, query_plan)) => {
+                let response = Response::query_plan_only(
+                    Arc::clone(&self.schema),
+                    Arc::clone(&operation_plan.prepared),
+                    query_plan,
+                );
+                return (metrics_attributes, response);
+            }
+            query_plan => query_plan.map(|(_, query_plan)| query_plan),
+        };
+
+        let estimated_cost = self.estimated_cost;
         let response = if matches!(operation_plan.ty(), OperationType::Subscription) {
             Response::pre_execution_error(GraphqlError::new(
                 "Subscriptions are only suported on streaming transports. Try making a request with SSE or WebSockets",
                 ErrorCode::BadRequest,
             ))
+        } else if let Some(key) = self.response_cache_key.take().filter(|_| query_plan.is_none()) {
+            self.execute_with_response_cache(operation_plan, key).await
         } else {
             self.execute_query_or_mutation(operation_plan).await
         };
 
+        let response = match query_plan {
+            Some(query_plan) => response.with_query_plan(query_plan),
+            None => response,
+        };
+        let response = match estimated_cost {
+            Some(cost) => response.with_estimated_cost(cost),
+            None => response,
+        };
+
         (metrics_attributes, response)
     }
 
+    /// The operation attributes are sent before the first response, so that the gateway response
+    /// hook can be called before the response headers are sent.
     async fn execute_stream(
         mut self,
         request: Request,
+        operation_sender: oneshot::Sender<Option<OperationMetricsAttributes>>,
         mut sender: mpsc::Sender<Response>,
     ) -> (Option<OperationMetricsAttributes>, GraphqlResponseStatus) {
         let operation_plan = match self.prepare_operation(request).await {
             Ok(operation_plan) => operation_plan,
             Err((metadata, response)) => {
+                operation_sender.send(metadata.clone()).ok();
                 let status = response.status();
                 sender.send(response).await.ok();
                 return (metadata, status);
@@ -380,9 +520,44 @@ impl<'ctx, R: Runtime> PreExecutionContext<'ctx, R> {
         };
         let operation_type = operation_plan.ty();
         let metrics_attributes = Some(operation_plan.metrics_attributes.clone());
+        operation_sender.send(metrics_attributes.clone()).ok();
+        let query_plan = match self.query_plan(&operation_plan) {
+            Some((QueryPlanMode::PlanOnly, query_plan)) => {
+                let response = Response::query_plan_only(
+                    Arc::clone(&self.schema),
+                    Arc::clone(&operation_plan.prepared),
+                    query_plan,
+                );
+                let status = response.status();
+                sender.send(response).await.ok();
+                return (metrics_attributes, status);
+            }
+            query_plan => query_plan.map(|(_, query_plan)| query_plan),
+        };
 
-        if matches!(operation_type, OperationType::Query | OperationType::Mutation) {
-            let response = self.execute_query_or_mutation(operation_plan).await;
+        let estimated_cost = self.estimated_cost;
+
+        // Deferred fragments are only taken into account with streaming responses, they're
+        // otherwise part of the single response like any other field.
+        if matches!(operation_type, OperationType::Query | OperationType::Mutation)
+            && !operation_plan
+                .deferred_fragments
+                .iter()
+                .any(|fragment| fragment.is_enabled(&operation_plan, &operation_plan.variables))
+        {
+            let response = if let Some(key) = self.response_cache_key.take().filter(|_| query_plan.is_none()) {
+                self.execute_with_response_cache(operation_plan, key).await
+            } else {
+                self.execute_query_or_mutation(operation_plan).await
+            };
+            let response = match query_plan {
+                Some(query_plan) => response.with_query_plan(query_plan),
+                None => response,
+            };
+            let response = match estimated_cost {
+                Some(cost) => response.with_estimated_cost(cost),
+                None => response,
+            };
             let status = response.status();
             sender.send(response).await.ok();
             return (metrics_attributes, status);
@@ -392,32 +567,54 @@ impl<'ctx, R: Runtime> PreExecutionContext<'ctx, R> {
         struct Sender<'a> {
             sender: mpsc::Sender<Response>,
             status: &'a mut GraphqlResponseStatus,
+            // Added to the first response only.
+            query_plan: Option<QueryPlan>,
+            estimated_cost: Option<u64>,
         }
 
         impl crate::execution::ResponseSender for Sender<'_> {
             type Error = mpsc::SendError;
             async fn send(&mut self, response: Response) -> Result<(), Self::Error> {
+                let response = match self.query_plan.take() {
+                    Some(query_plan) => response.with_query_plan(query_plan),
+                    None => response,
+                };
+                let response = match self.estimated_cost.take() {
+                    Some(cost) => response.with_estimated_cost(cost),
+                    None => response,
+                };
                 *self.status = self.status.union(response.status());
                 self.sender.send(response).await
             }
         }
 
-        self.execute_subscription(
-            operation_plan,
-            Sender {
-                sender,
-                status: &mut status,
-            },
-        )
-        .await;
+        let sender = Sender {
+            sender,
+            status: &mut status,
+            query_plan,
+            estimated_cost,
+        };
+        if matches!(operation_type, OperationType::Subscription) {
+            self.execute_subscription(operation_plan, sender).await;
+        } else {
+            self.execute_incremental_query(operation_plan, sender).await;
+        }
         (metrics_attributes, status)
     }
 
+    fn query_plan(&self, operation: &ExecutableOperation) -> Option<(QueryPlanMode, QueryPlan)> {
+        self.request_context
+            .query_plan
+            .map(|mode| (mode, QueryPlan::build(&self.schema, operation)))
+    }
+
     async fn prepare_operation(
         &mut self,
         mut request: Request,
     ) -> Result<ExecutableOperation, (Option<OperationMetricsAttributes>, Response)> {
-        let result = {
+        self.select_override_labels();
+
+        let (cache_key, result) = {
             let PreparedOperationDocument {
                 cache_key,
                 document_fut,
@@ -426,33 +623,41 @@ impl<'ctx, R: Runtime> PreExecutionContext<'ctx, R> {
                 Err(err) => return Err((None, Response::pre_execution_error(err))),
             };
 
-            if let Some(operation) = self.operation_cache.get(&cache_key).await {
+            let result = if let Some(operation) = self.operation_cache.get(&cache_key).await {
                 Ok(operation)
             } else if let Some(persisted_query) = document_fut {
                 match persisted_query.await {
-                    Ok(query) => Err((cache_key, Some(query))),
+                    Ok(query) => Err(Some(query)),
                     Err(err) => return Err((None, Response::pre_execution_error(err))),
                 }
             } else {
-                Err((cache_key, None))
-            }
+                Err(None)
+            };
+            (cache_key, result)
         };
 
         let operation = match result {
             Ok(operation) => operation,
-            Err((cache_key, query)) => {
+            Err(query) => {
                 if let Some(query) = query {
                     request.query = query
                 }
-                let operation = Operation::build(&self.schema, &request)
+                let operation = Operation::build(&self.schema, &request, &self.override_labels)
                     .map(Arc::new)
                     .map_err(|mut err| (err.take_metrics_attributes(), Response::pre_execution_error(err)))?;
 
-                self.push_background_future(self.engine.operation_cache.insert(cache_key, operation.clone()).boxed());
+                self.push_background_future(
+                    self.engine
+                        .operation_cache
+                        .insert(cache_key.clone(), operation.clone())
+                        .boxed(),
+                );
                 operation
             }
         };
 
+        self.response_cache_key = self.build_response_cache_key(&cache_key, &operation, &request.variables);
+
         let variables = Variables::build(self.schema.as_ref(), &operation, request.variables).map_err(|errors| {
             (
                 Some(operation.metrics_attributes.clone()),
@@ -460,6 +665,13 @@ impl<'ctx, R: Runtime> PreExecutionContext<'ctx, R> {
             )
         })?;
 
+        self.enforce_demand_control(&operation, &variables).map_err(|err| {
+            (
+                Some(operation.metrics_attributes.clone()),
+                Response::pre_execution_error(err),
+            )
+        })?;
+
         self.finalize_operation(Arc::clone(&operation), variables)
             .await
             .map_err(|err| {
@@ -488,24 +700,29 @@ impl<R: Runtime> Clone for Session<R> {
 pub(crate) struct RequestContext<C> {
     pub headers: http::HeaderMap,
     pub streaming_format: Option<StreamingFormat>,
+    pub query_plan: Option<QueryPlanMode>,
     pub client: Option<Client>,
     pub access_token: AccessToken,
     pub hooks_context: C,
+    /// Subgraph response headers forwarded to the client.
+    pub response_headers: ResponseHeaders,
+    pub traffic_variant_selections: TrafficVariantSelections,
 }
 
 impl<R: Runtime> Session<R> {
     pub fn execute_websocket(&self, id: String, request: Request) -> impl Stream<Item = websocket::Message> {
-        self.engine
-            .execute_stream(self.request_context.clone(), request)
-            .map(move |response| match response {
-                Response::PreExecutionError(_) => websocket::Message::Error {
-                    id: id.clone(),
-                    payload: websocket::Payload(response),
-                },
-                response => websocket::Message::Next {
-                    id: id.clone(),
-                    payload: websocket::Payload(response),
-                },
-            })
+        // There are no response headers for websocket operations, so the gateway response hook
+        // isn't called.
+        let (_, stream) = self.engine.execute_stream(self.request_context.clone(), request);
+        stream.map(move |response| match response {
+            Response::PreExecutionError(_) => websocket::Message::Error {
+                id: id.clone(),
+                payload: websocket::Payload(response),
+            },
+            response => websocket::Message::Next {
+                id: id.clone(),
+                payload: websocket::Payload(response),
+            },
+        })
     }
 }
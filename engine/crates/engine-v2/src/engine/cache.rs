@@ -1,6 +1,6 @@
 use base64::{display::Base64Display, engine::general_purpose::URL_SAFE_NO_PAD};
 use engine::PersistedQueryRequestExtension;
-use schema::Schema;
+use schema::{OverrideLabelId, Schema};
 
 use super::SchemaVersion;
 
@@ -8,6 +8,7 @@ mod namespaces {
     pub const OPERATION: &str = "op";
     pub const TRUSTED_DOCUMENT: &str = "tdoc";
     pub const APQ: &str = "apq";
+    pub const RESPONSE: &str = "resp";
 }
 
 /// Unique cache key that generates a URL-safe string.
@@ -16,6 +17,7 @@ pub(super) enum Key<'a> {
     Operation {
         name: Option<&'a str>,
         schema_version: &'a SchemaVersion,
+        override_labels: &'a [OverrideLabelId],
         document: Document<'a>,
     },
     TrustedDocument {
@@ -25,6 +27,15 @@ pub(super) enum Key<'a> {
     Apq {
         ext: &'a PersistedQueryRequestExtension,
     },
+    /// Whole response of a query, the operation being identified by its own cache key.
+    Response {
+        operation: &'a str,
+        variables: &'a engine::Variables,
+        headers: Vec<Option<&'a [u8]>>,
+        claims: Vec<&'a serde_json::Value>,
+        /// Signature of the access token for private responses.
+        private: Option<&'a [u8]>,
+    },
 }
 
 pub(super) enum Document<'a> {
@@ -41,6 +52,7 @@ impl std::fmt::Display for Key<'_> {
             Key::Operation {
                 name,
                 schema_version,
+                override_labels,
                 document,
             } => {
                 let mut hasher = blake3::Hasher::new();
@@ -55,6 +67,11 @@ impl std::fmt::Display for Key<'_> {
                 // NULL bytes acting as a separator as it cannot be present in the
                 // operation name.
                 hasher.update(&[0x00]);
+                // Progressive overrides change the plan of the operation.
+                hasher.update(&override_labels.len().to_ne_bytes());
+                for id in override_labels.iter() {
+                    hasher.update(&usize::from(*id).to_ne_bytes());
+                }
                 match document {
                     Document::PersistedQueryExt(ext) => {
                         hasher.update(b"apq");
@@ -98,6 +115,47 @@ impl std::fmt::Display for Key<'_> {
                 version,
                 Base64Display::new(sha256_hash, &URL_SAFE_NO_PAD)
             )),
+            Key::Response {
+                operation,
+                variables,
+                headers,
+                claims,
+                private,
+            } => {
+                let mut hasher = blake3::Hasher::new();
+                hasher.update(&operation.len().to_ne_bytes());
+                hasher.update(operation.as_bytes());
+                // Variables are sorted by name, so serialization is deterministic.
+                hasher.update(&serde_json::to_vec(variables).unwrap_or_default());
+                for header in headers {
+                    match header {
+                        Some(value) => {
+                            hasher.update(&[0x01]);
+                            hasher.update(&value.len().to_ne_bytes());
+                            hasher.update(value);
+                        }
+                        None => {
+                            hasher.update(&[0x00]);
+                        }
+                    }
+                }
+                for claim in claims {
+                    let value = serde_json::to_vec(claim).unwrap_or_default();
+                    hasher.update(&value.len().to_ne_bytes());
+                    hasher.update(&value);
+                }
+                if let Some(signature) = private {
+                    hasher.update(&signature.len().to_ne_bytes());
+                    hasher.update(signature);
+                }
+                let hash = hasher.finalize();
+
+                f.write_fmt(format_args!(
+                    "{}.blake3.{}",
+                    namespaces::RESPONSE,
+                    Base64Display::new(hash.as_bytes(), &URL_SAFE_NO_PAD)
+                ))
+            }
         }
     }
 }
@@ -0,0 +1,289 @@
+use std::{collections::VecDeque, sync::Mutex};
+
+use grafbase_telemetry::metrics::SubgraphCircuitBreakerMetrics;
+use schema::{sources::graphql::GraphqlEndpointId, CircuitBreakerConfig, Schema};
+use web_time::{Duration, Instant};
+
+use super::Runtime;
+
+const DEFAULT_CONSECUTIVE_FAILURES: u32 = 5;
+const DEFAULT_WINDOW: Duration = Duration::from_secs(10);
+const DEFAULT_MINIMUM_REQUESTS: u32 = 10;
+const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// The failure rate window is split into this many buckets, each dropped once it's entirely
+/// outside of the window.
+const WINDOW_BUCKETS: u32 = 10;
+
+/// State of the circuit breaker of a subgraph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitBreakerState {
+    /// Requests are sent to the subgraph.
+    Closed,
+    /// Requests fail immediately.
+    Open,
+    /// The cooldown is over, the next request decides whether the circuit closes.
+    HalfOpen,
+}
+
+pub(super) struct CircuitBreakers {
+    by_graphql_endpoints: Vec<Option<CircuitBreaker>>,
+}
+
+id_newtypes::index! {
+    CircuitBreakers.by_graphql_endpoints[GraphqlEndpointId] => Option<CircuitBreaker>,
+}
+
+impl CircuitBreakers {
+    pub fn build(schema: &Schema, metrics: SubgraphCircuitBreakerMetrics) -> Self {
+        Self {
+            by_graphql_endpoints: schema
+                .walker()
+                .graphql_endpoints()
+                .map(|endpoint| {
+                    let config = endpoint.circuit_breaker_config()?;
+                    Some(CircuitBreaker::new(
+                        endpoint.subgraph_name().to_string(),
+                        config,
+                        metrics.clone(),
+                    ))
+                })
+                .collect(),
+        }
+    }
+}
+
+pub(crate) struct CircuitBreaker {
+    subgraph_name: String,
+    consecutive_failures: Option<u32>,
+    failure_rate: Option<FailureRate>,
+    cooldown: Duration,
+    state: Mutex<State>,
+    metrics: SubgraphCircuitBreakerMetrics,
+}
+
+struct FailureRate {
+    threshold: f32,
+    window: Duration,
+    minimum_requests: u32,
+}
+
+#[derive(Default)]
+struct State {
+    status: Status,
+    consecutive_failures: u32,
+    buckets: VecDeque<Bucket>,
+}
+
+#[derive(Default)]
+enum Status {
+    #[default]
+    Closed,
+    Open {
+        until: Instant,
+    },
+    HalfOpen {
+        probing: bool,
+    },
+}
+
+struct Bucket {
+    start: Instant,
+    successes: u32,
+    failures: u32,
+}
+
+impl CircuitBreaker {
+    fn new(subgraph_name: String, config: &CircuitBreakerConfig, metrics: SubgraphCircuitBreakerMetrics) -> Self {
+        let failure_rate = config.failure_rate.map(|threshold| FailureRate {
+            threshold,
+            window: config.window.unwrap_or(DEFAULT_WINDOW),
+            minimum_requests: config.minimum_requests.unwrap_or(DEFAULT_MINIMUM_REQUESTS),
+        });
+
+        Self {
+            subgraph_name,
+            consecutive_failures: config
+                .consecutive_failures
+                .or(failure_rate.is_none().then_some(DEFAULT_CONSECUTIVE_FAILURES)),
+            failure_rate,
+            cooldown: config.cooldown.unwrap_or(DEFAULT_COOLDOWN),
+            state: Mutex::new(State::default()),
+            metrics,
+        }
+    }
+
+    /// Returns a permit if a request may be sent to the subgraph. Once the cooldown is over, a
+    /// single probe request is let through at a time.
+    pub fn try_acquire(&self) -> Option<CircuitPermit<'_>> {
+        let mut state = self.state.lock().unwrap();
+        let probe = match state.status {
+            Status::Closed => false,
+            Status::Open { until } if Instant::now() >= until => {
+                state.status = Status::HalfOpen { probing: true };
+                true
+            }
+            Status::HalfOpen { probing: false } => {
+                state.status = Status::HalfOpen { probing: true };
+                true
+            }
+            Status::Open { .. } | Status::HalfOpen { probing: true } => {
+                drop(state);
+                self.metrics.record_rejected_request(&self.subgraph_name);
+                return None;
+            }
+        };
+
+        Some(CircuitPermit {
+            breaker: self,
+            probe,
+            recorded: false,
+        })
+    }
+
+    pub fn is_closed(&self) -> bool {
+        matches!(self.state.lock().unwrap().status, Status::Closed)
+    }
+
+    pub fn state(&self) -> CircuitBreakerState {
+        match self.state.lock().unwrap().status {
+            Status::Closed => CircuitBreakerState::Closed,
+            Status::Open { until } if Instant::now() < until => CircuitBreakerState::Open,
+            Status::Open { .. } | Status::HalfOpen { .. } => CircuitBreakerState::HalfOpen,
+        }
+    }
+
+    fn record(&self, probe: bool, success: bool) {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+
+        if !matches!(state.status, Status::Closed) {
+            // Requests sent before the circuit opened don't matter anymore, only the probe does.
+            if !probe {
+                return;
+            }
+            if success {
+                *state = State::default();
+                drop(state);
+                tracing::info!("Circuit breaker of the subgraph '{}' closed", self.subgraph_name);
+                self.metrics.record_closed(&self.subgraph_name);
+            } else {
+                state.status = Status::Open {
+                    until: now + self.cooldown,
+                };
+            }
+            return;
+        }
+
+        if success {
+            state.consecutive_failures = 0;
+        } else {
+            state.consecutive_failures += 1;
+        }
+
+        let too_many_consecutive_failures = self
+            .consecutive_failures
+            .is_some_and(|max| state.consecutive_failures >= max);
+
+        let failure_rate_exceeded = self.failure_rate.as_ref().is_some_and(|failure_rate| {
+            state.record_in_window(now, failure_rate.window, success);
+            let (successes, failures) = state
+                .buckets
+                .iter()
+                .fold((0, 0), |(s, f), bucket| (s + bucket.successes, f + bucket.failures));
+            let total = successes + failures;
+            total >= failure_rate.minimum_requests.max(1) && (failures as f32 / total as f32) >= failure_rate.threshold
+        });
+
+        if too_many_consecutive_failures || failure_rate_exceeded {
+            *state = State {
+                status: Status::Open {
+                    until: now + self.cooldown,
+                },
+                ..Default::default()
+            };
+            drop(state);
+            tracing::warn!(
+                "Circuit breaker of the subgraph '{}' opened for {:?}",
+                self.subgraph_name,
+                self.cooldown
+            );
+            self.metrics.record_opened(&self.subgraph_name);
+        }
+    }
+}
+
+impl State {
+    fn record_in_window(&mut self, now: Instant, window: Duration, success: bool) {
+        while self
+            .buckets
+            .front()
+            .is_some_and(|bucket| now.duration_since(bucket.start) >= window)
+        {
+            self.buckets.pop_front();
+        }
+
+        let bucket_width = window / WINDOW_BUCKETS;
+        let bucket = match self.buckets.back_mut() {
+            Some(bucket) if now.duration_since(bucket.start) < bucket_width => bucket,
+            _ => {
+                self.buckets.push_back(Bucket {
+                    start: now,
+                    successes: 0,
+                    failures: 0,
+                });
+                self.buckets.back_mut().expect("just pushed")
+            }
+        };
+
+        if success {
+            bucket.successes += 1;
+        } else {
+            bucket.failures += 1;
+        }
+    }
+}
+
+/// Permission to send a request to the subgraph. Its outcome must be recorded, otherwise the
+/// request is ignored by the circuit breaker.
+pub(crate) struct CircuitPermit<'a> {
+    breaker: &'a CircuitBreaker,
+    probe: bool,
+    recorded: bool,
+}
+
+impl CircuitPermit<'_> {
+    pub fn record(mut self, success: bool) {
+        self.recorded = true;
+        self.breaker.record(self.probe, success);
+    }
+}
+
+impl Drop for CircuitPermit<'_> {
+    fn drop(&mut self) {
+        if self.recorded || !self.probe {
+            return;
+        }
+        // The probe was cancelled, letting the next request probe the subgraph instead.
+        let mut state = self.breaker.state.lock().unwrap();
+        if let Status::HalfOpen { probing } = &mut state.status {
+            *probing = false;
+        }
+    }
+}
+
+impl<R: Runtime> super::Engine<R> {
+    pub(crate) fn circuit_breaker(&self, endpoint_id: GraphqlEndpointId) -> Option<&CircuitBreaker> {
+        self.circuit_breakers[endpoint_id].as_ref()
+    }
+
+    /// State of the circuit breaker of every subgraph having one.
+    pub fn subgraph_circuit_breakers(&self) -> impl Iterator<Item = (&str, CircuitBreakerState)> + '_ {
+        self.circuit_breakers
+            .by_graphql_endpoints
+            .iter()
+            .flatten()
+            .map(|breaker| (breaker.subgraph_name.as_str(), breaker.state()))
+    }
+}
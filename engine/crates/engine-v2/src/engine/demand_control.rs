@@ -0,0 +1,41 @@
+use schema::DemandControlMode;
+
+use super::Runtime;
+use crate::{
+    execution::PreExecutionContext,
+    operation::{estimate_cost, PreparedOperation, Variables},
+    response::{ErrorCode, GraphqlError},
+};
+
+impl<'ctx, R: Runtime> PreExecutionContext<'ctx, R> {
+    /// Estimates the cost of the operation if demand control is enabled. The cost is always
+    /// recorded, but operations above the maximum are only rejected in the enforce mode.
+    pub(super) fn enforce_demand_control(
+        &mut self,
+        operation: &PreparedOperation,
+        variables: &Variables,
+    ) -> Result<(), GraphqlError> {
+        let Some(config) = self.schema.settings.demand_control else {
+            return Ok(());
+        };
+
+        let cost = estimate_cost(&self.schema, operation, variables, config.default_list_size);
+        let max_cost = config
+            .max_cost
+            .filter(|max_cost| config.mode == DemandControlMode::Enforce && cost > *max_cost);
+
+        self.engine
+            .operation_metrics
+            .record_estimated_cost(&operation.metrics_attributes, cost, max_cost.is_some());
+        self.estimated_cost = Some(cost);
+
+        match max_cost {
+            Some(max_cost) => Err(GraphqlError::new(
+                format!("Operation estimated cost {cost} exceeds the maximum cost of {max_cost}"),
+                ErrorCode::OperationCostExceeded,
+            )
+            .with_extension("estimatedCost", cost)),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use std::sync::{
+    atomic::{AtomicU32, AtomicUsize, Ordering},
+    Mutex,
+};
+
+use schema::{
+    sources::graphql::{GraphqlEndpointId, LoadBalancing},
+    LoadBalancingStrategy, Schema,
+};
+use web_time::{Duration, Instant};
+
+use super::Runtime;
+
+pub(super) struct LoadBalancers {
+    by_graphql_endpoints: Vec<Option<LoadBalancer>>,
+}
+
+id_newtypes::index! {
+    LoadBalancers.by_graphql_endpoints[GraphqlEndpointId] => Option<LoadBalancer>,
+}
+
+impl LoadBalancers {
+    pub fn build(schema: &Schema) -> Self {
+        Self {
+            by_graphql_endpoints: schema
+                .walker()
+                .graphql_endpoints()
+                .map(|endpoint| endpoint.load_balancing().map(LoadBalancer::new))
+                .collect(),
+        }
+    }
+}
+
+/// Chooses the upstream of each request sent to a subgraph with multiple URLs. Upstreams failing
+/// too many times in a row are ejected for a while, unless all of them are.
+pub(crate) struct LoadBalancer {
+    strategy: LoadBalancingStrategy,
+    upstreams: Vec<UpstreamState>,
+    total_weight: u32,
+    next: AtomicUsize,
+    ejection_threshold: u32,
+    ejection_duration: Duration,
+}
+
+struct UpstreamState {
+    weight: u32,
+    outstanding: AtomicUsize,
+    consecutive_failures: AtomicU32,
+    ejected_until: Mutex<Option<Instant>>,
+}
+
+impl UpstreamState {
+    fn is_ejected(&self, now: Instant) -> bool {
+        self.ejected_until.lock().unwrap().is_some_and(|until| now < until)
+    }
+}
+
+impl LoadBalancer {
+    fn new(config: &LoadBalancing) -> Self {
+        let upstreams = config
+            .upstreams()
+            .iter()
+            .map(|upstream| UpstreamState {
+                weight: upstream.weight,
+                outstanding: AtomicUsize::new(0),
+                consecutive_failures: AtomicU32::new(0),
+                ejected_until: Mutex::new(None),
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            strategy: config.strategy,
+            total_weight: upstreams.iter().map(|upstream| upstream.weight).sum(),
+            upstreams,
+            next: AtomicUsize::new(0),
+            ejection_threshold: config.outlier_ejection_consecutive_failures,
+            ejection_duration: config.outlier_ejection_duration,
+        }
+    }
+
+    /// Selects the upstream of a request, avoiding the `excluded` ones, already attempted by
+    /// previous retries, whenever possible.
+    pub fn select(&self, excluded: &[usize]) -> UpstreamGuard<'_> {
+        let now = Instant::now();
+        let all = 0..self.upstreams.len();
+
+        let mut candidates = all
+            .clone()
+            .filter(|index| !excluded.contains(index) && !self.upstreams[*index].is_ejected(now))
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            candidates = all.clone().filter(|index| !excluded.contains(index)).collect();
+        }
+        if candidates.is_empty() {
+            candidates = all.collect();
+        }
+
+        let index = match self.strategy {
+            LoadBalancingStrategy::RoundRobin => {
+                candidates[self.next.fetch_add(1, Ordering::Relaxed) % candidates.len()]
+            }
+            LoadBalancingStrategy::LeastOutstanding => {
+                // Starting at a different candidate each time, so ties don't all go to the first one.
+                let offset = self.next.fetch_add(1, Ordering::Relaxed);
+                (0..candidates.len())
+                    .map(|i| candidates[(offset + i) % candidates.len()])
+                    .min_by_key(|index| self.upstreams[*index].outstanding.load(Ordering::Relaxed))
+                    .expect("at least one candidate")
+            }
+            LoadBalancingStrategy::Weighted => {
+                let total_weight = if candidates.len() == self.upstreams.len() {
+                    self.total_weight
+                } else {
+                    candidates.iter().map(|index| self.upstreams[*index].weight).sum()
+                };
+                let mut point = rand::random::<u32>() % total_weight.max(1);
+                candidates
+                    .iter()
+                    .copied()
+                    .find(|index| {
+                        let weight = self.upstreams[*index].weight;
+                        if point < weight {
+                            true
+                        } else {
+                            point -= weight;
+                            false
+                        }
+                    })
+                    .unwrap_or(candidates[0])
+            }
+        };
+
+        self.upstreams[index].outstanding.fetch_add(1, Ordering::Relaxed);
+        UpstreamGuard {
+            load_balancer: self,
+            index,
+        }
+    }
+
+    /// Selects an upstream for a long-lived subscription, which isn't tracked.
+    pub fn pick(&self) -> usize {
+        self.select(&[]).index()
+    }
+
+    fn record(&self, index: usize, success: bool) {
+        let upstream = &self.upstreams[index];
+        if success {
+            upstream.consecutive_failures.store(0, Ordering::Relaxed);
+            return;
+        }
+
+        let failures = upstream.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.ejection_threshold > 0 && failures >= self.ejection_threshold {
+            upstream.consecutive_failures.store(0, Ordering::Relaxed);
+            *upstream.ejected_until.lock().unwrap() = Some(Instant::now() + self.ejection_duration);
+            tracing::warn!(
+                "Ejecting upstream {index} for {:?} after {failures} consecutive failures",
+                self.ejection_duration
+            );
+        }
+    }
+}
+
+/// An upstream selected for a request, counted as outstanding until dropped.
+pub(crate) struct UpstreamGuard<'a> {
+    load_balancer: &'a LoadBalancer,
+    index: usize,
+}
+
+impl UpstreamGuard<'_> {
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn record(&self, success: bool) {
+        self.load_balancer.record(self.index, success);
+    }
+}
+
+impl Drop for UpstreamGuard<'_> {
+    fn drop(&mut self) {
+        self.load_balancer.upstreams[self.index]
+            .outstanding
+            .fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl<R: Runtime> super::Engine<R> {
+    pub(crate) fn load_balancer(&self, endpoint_id: GraphqlEndpointId) -> Option<&LoadBalancer> {
+        self.load_balancers[endpoint_id].as_ref()
+    }
+}
@@ -0,0 +1,23 @@
+use schema::OverrideLabel;
+
+use super::Runtime;
+use crate::execution::PreExecutionContext;
+
+impl<'ctx, R: Runtime> PreExecutionContext<'ctx, R> {
+    /// Selects the progressive override labels active for this request. Percentages are rolled
+    /// independently for each label, while custom labels are enabled by the gateway configuration.
+    pub(super) fn select_override_labels(&mut self) {
+        let override_labels = self
+            .schema
+            .override_labels()
+            .filter_map(|(id, label)| {
+                let active = match *label {
+                    OverrideLabel::Percent(percent) => rand::random::<f32>() * 100.0 < f32::from(percent),
+                    OverrideLabel::Custom { enabled, .. } => enabled,
+                };
+                active.then_some(id)
+            })
+            .collect();
+        self.override_labels = override_labels;
+    }
+}
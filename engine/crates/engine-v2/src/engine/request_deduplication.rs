@@ -0,0 +1,139 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use futures::{
+    channel::oneshot,
+    future::{FutureExt, Shared},
+    Future,
+};
+use runtime::fetch::{FetchRequest, FetchResponse};
+
+use super::Runtime;
+use crate::{
+    execution::{ExecutionError, ExecutionResult},
+    response::GraphqlError,
+};
+
+/// Subgraph requests currently in flight, shared with identical requests sent in the meantime.
+#[derive(Default)]
+pub(super) struct InflightRequests {
+    requests: Mutex<HashMap<RequestKey, InflightRequest>>,
+}
+
+type RequestKey = [u8; 32];
+
+type SharedResponse = Shared<oneshot::Receiver<Result<FetchResponse, GraphqlError>>>;
+
+struct InflightRequest {
+    id: u64,
+    response: SharedResponse,
+}
+
+enum Role {
+    Leader {
+        id: u64,
+        sender: oneshot::Sender<Result<FetchResponse, GraphqlError>>,
+    },
+    Follower(SharedResponse),
+}
+
+/// The request identity is the subgraph, the body and the headers after the header rules and
+/// hooks were applied.
+fn request_key(subgraph_name: &str, request: &FetchRequest<'_>) -> RequestKey {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&subgraph_name.len().to_be_bytes());
+    hasher.update(subgraph_name.as_bytes());
+    hasher.update(request.url.as_str().as_bytes());
+    hasher.update(&request.json_body.len().to_be_bytes());
+    hasher.update(&request.json_body);
+
+    let mut headers = request
+        .headers
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_bytes()))
+        .collect::<Vec<_>>();
+    headers.sort_unstable();
+    for (name, value) in headers {
+        hasher.update(&name.len().to_be_bytes());
+        hasher.update(name.as_bytes());
+        hasher.update(&value.len().to_be_bytes());
+        hasher.update(value);
+    }
+
+    hasher.finalize().into()
+}
+
+impl InflightRequests {
+    async fn deduplicate(
+        &self,
+        key: RequestKey,
+        fetch: impl Future<Output = ExecutionResult<FetchResponse>>,
+    ) -> ExecutionResult<FetchResponse> {
+        let role = {
+            let mut requests = self.requests.lock().unwrap();
+            match requests.get(&key) {
+                Some(inflight) => Role::Follower(inflight.response.clone()),
+                None => {
+                    let id = rand::random();
+                    let (sender, receiver) = oneshot::channel();
+                    requests.insert(
+                        key,
+                        InflightRequest {
+                            id,
+                            response: receiver.shared(),
+                        },
+                    );
+                    Role::Leader { id, sender }
+                }
+            }
+        };
+
+        match role {
+            Role::Follower(response) => match response.await {
+                Ok(result) => result.map_err(ExecutionError::Graphql),
+                // The request we were waiting for was cancelled, so we send our own.
+                Err(oneshot::Canceled) => fetch.await,
+            },
+            Role::Leader { id, sender } => {
+                let guard = InflightGuard {
+                    requests: self,
+                    key,
+                    id,
+                };
+                let result = fetch.await.map_err(GraphqlError::from);
+                drop(guard);
+                let _ = sender.send(result.clone());
+                result.map_err(ExecutionError::Graphql)
+            }
+        }
+    }
+}
+
+/// Ensures the request is removed once finished or cancelled.
+struct InflightGuard<'a> {
+    requests: &'a InflightRequests,
+    key: RequestKey,
+    id: u64,
+}
+
+impl Drop for InflightGuard<'_> {
+    fn drop(&mut self) {
+        let mut requests = self.requests.requests.lock().unwrap();
+        if requests.get(&self.key).is_some_and(|inflight| inflight.id == self.id) {
+            requests.remove(&self.key);
+        }
+    }
+}
+
+impl<R: Runtime> super::Engine<R> {
+    /// Identical requests in flight at the same time share the same response. Only queries
+    /// should ever be deduplicated.
+    pub(crate) async fn deduplicate_subgraph_request(
+        &self,
+        subgraph_name: &str,
+        request: &FetchRequest<'_>,
+        fetch: impl Future<Output = ExecutionResult<FetchResponse>>,
+    ) -> ExecutionResult<FetchResponse> {
+        let key = request_key(subgraph_name, request);
+        self.inflight_requests.deduplicate(key, fetch).await
+    }
+}
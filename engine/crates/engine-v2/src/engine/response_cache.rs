@@ -0,0 +1,158 @@
+use std::borrow::Cow;
+
+use ::runtime::auth::AccessToken;
+use engine_parser::types::OperationType;
+use schema::{CacheControl, CacheControlScope};
+
+use super::{cache::Key, Runtime};
+use crate::{
+    execution::{ExecutableOperation, PreExecutionContext},
+    operation::PreparedOperation,
+    response::Response,
+};
+
+impl<'ctx, R: Runtime> PreExecutionContext<'ctx, R> {
+    /// Cache key of the whole response, if it can be cached at all. Operations relying on
+    /// authorization directives are never cached as their response depends on the access token.
+    /// Neither are responses to requests with headers forwarded to subgraphs but absent from
+    /// `vary_headers`, and private ones are only cached per access token.
+    pub(super) fn build_response_cache_key(
+        &self,
+        operation_cache_key: &str,
+        operation: &PreparedOperation,
+        variables: &engine::Variables,
+    ) -> Option<String> {
+        let config = &self.schema.settings.response_caching;
+        if !config.enabled
+            || operation.cache_control.is_none()
+            || !matches!(operation.ty, OperationType::Query)
+            || !operation.query_modifiers.is_empty()
+            || !operation.response_modifiers.is_empty()
+        {
+            return None;
+        }
+
+        let headers = self.headers();
+        let access_token = self.access_token();
+
+        if self.forwarded_request_headers().into_iter().any(|name| {
+            !config
+                .vary_headers
+                .iter()
+                .any(|vary| vary.eq_ignore_ascii_case(name.as_str()))
+        }) {
+            return None;
+        }
+
+        let private = match operation.cache_control.map(|cache_control| cache_control.scope) {
+            Some(CacheControlScope::Private) => match access_token {
+                AccessToken::Jwt(token) => Some(token.signature.as_slice()),
+                AccessToken::Anonymous | AccessToken::V1(_) => return None,
+            },
+            _ => None,
+        };
+
+        let key = Key::Response {
+            operation: operation_cache_key,
+            variables,
+            headers: config
+                .vary_headers
+                .iter()
+                .map(|name| headers.get(name.as_str()).map(|value| value.as_bytes()))
+                .collect(),
+            claims: config
+                .vary_claims
+                .iter()
+                .map(|path| get_claim(access_token, path))
+                .collect(),
+            private,
+        };
+
+        Some(key.to_string())
+    }
+
+    /// Serves the response from the cache if present, otherwise executes the operation and caches
+    /// the response if successful.
+    pub(super) async fn execute_with_response_cache(self, operation: ExecutableOperation, key: String) -> Response {
+        let Some(cache_control) = operation.cache_control else {
+            return self.execute_query_or_mutation(operation).await;
+        };
+
+        let engine = self.engine;
+        let request_context = self.request_context;
+        let entity_cache = engine.runtime.entity_cache();
+        let header = cache_control_header(
+            cache_control,
+            !engine.schema.settings.response_caching.vary_claims.is_empty()
+                || !self.forwarded_request_headers().is_empty(),
+        );
+
+        let cached = entity_cache
+            .get(&key)
+            .await
+            .inspect_err(|err| tracing::warn!("Failed to read the cache key {key}: {err}"))
+            .ok()
+            .flatten()
+            .and_then(|bytes| serde_json::from_slice::<CachedEntry>(&bytes).ok());
+
+        if let Some(CachedEntry { data }) = cached {
+            self.skip_execution().await;
+            request_context.response_headers.merge_cache_control(header);
+            return Response::cached(data);
+        }
+
+        let response = self.execute_query_or_mutation(operation).await;
+
+        // Responses with errors are neither cached by us nor by any downstream cache.
+        if response.status().is_success() {
+            request_context.response_headers.merge_cache_control(header);
+            if cache_control.max_age.is_zero() {
+                return response;
+            }
+            match serde_json::to_vec(&response) {
+                Ok(bytes) => {
+                    entity_cache
+                        .put(&key, Cow::Owned(bytes), cache_control.max_age)
+                        .await
+                        .inspect_err(|err| tracing::warn!("Failed to write the cache key {key}: {err}"))
+                        .ok();
+                }
+                Err(err) => tracing::warn!("Failed to serialize the response for the cache: {err}"),
+            }
+        }
+
+        response
+    }
+}
+
+/// Stored responses only keep the data, extensions like the estimated cost are computed for each
+/// request.
+#[derive(serde::Deserialize)]
+struct CachedEntry {
+    data: Box<serde_json::value::RawValue>,
+}
+
+/// Responses varying on the access token claims or on headers forwarded to subgraphs may only be
+/// stored by private caches.
+fn cache_control_header(cache_control: CacheControl, varies_by_user: bool) -> http::HeaderValue {
+    let private = varies_by_user || cache_control.scope == CacheControlScope::Private;
+    let mut value = format!(
+        "{}, max-age={}",
+        if private { "private" } else { "public" },
+        cache_control.max_age.as_secs()
+    );
+    if !cache_control.stale_while_revalidate.is_zero() {
+        value.push_str(&format!(
+            ", stale-while-revalidate={}",
+            cache_control.stale_while_revalidate.as_secs()
+        ));
+    }
+    http::HeaderValue::from_str(&value).expect("valid header value")
+}
+
+/// Claims are configured as dot-separated paths.
+pub(crate) fn get_claim<'a>(access_token: &'a AccessToken, path: &str) -> &'a serde_json::Value {
+    let mut keys = path.split('.');
+    let root = access_token.get_claim(keys.next().unwrap_or_default());
+    keys.fold(root, |parent, key| parent.get(key).unwrap_or(&serde_json::Value::Null))
+}
@@ -0,0 +1,252 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures::{
+    future::{AbortHandle, Abortable},
+    stream::BoxStream,
+    StreamExt,
+};
+use grafbase_telemetry::metrics::SubgraphSubscriptionMetrics;
+use runtime::fetch::{FetchResult, Fetcher, GraphqlRequest, HttpClientSettings, SubscriptionProtocol};
+use tokio::sync::broadcast;
+
+use super::Runtime;
+
+/// Events are shared between all the subscribers of an upstream subscription.
+pub(crate) type UpstreamEvent = Arc<FetchResult<serde_json::Value>>;
+
+/// Upstream subscriptions shared between all clients sending identical subscriptions to a
+/// subgraph. Each one is driven by a background task, fanning out its events through a broadcast
+/// channel, and is torn down when its last subscriber leaves.
+pub(super) struct SubscriptionDeduplication {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    upstreams: Mutex<HashMap<UpstreamKey, Upstream>>,
+    metrics: SubgraphSubscriptionMetrics,
+}
+
+type UpstreamKey = [u8; 32];
+
+struct Upstream {
+    // A new upstream may replace a finished one with the same key, so we check the id before
+    // removing anything.
+    id: u64,
+    sender: broadcast::Sender<UpstreamEvent>,
+    abort_handle: AbortHandle,
+    max_subscribers: usize,
+}
+
+/// Size of the broadcast buffer, subscribers lagging behind it miss events.
+const CHANNEL_CAPACITY: usize = 256;
+
+pub(crate) struct UpstreamSubscriptionRequest {
+    pub subgraph_name: String,
+    pub url: url::Url,
+    pub headers: http::HeaderMap,
+    pub query: String,
+    pub variables: serde_json::Value,
+    pub protocol: SubscriptionProtocol,
+    pub heartbeat_timeout: Option<Duration>,
+    pub max_reconnect_attempts: Option<u32>,
+    pub client_settings: HttpClientSettings,
+}
+
+impl UpstreamSubscriptionRequest {
+    /// Identical requests share the same upstream subscription.
+    fn key(&self) -> UpstreamKey {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&self.subgraph_name.len().to_be_bytes());
+        hasher.update(self.subgraph_name.as_bytes());
+        // Traffic split variants and load balanced URLs of a subgraph are distinct upstreams.
+        hasher.update(&self.url.as_str().len().to_be_bytes());
+        hasher.update(self.url.as_str().as_bytes());
+        hasher.update(&[self.protocol as u8]);
+        hasher.update(&self.query.len().to_be_bytes());
+        hasher.update(self.query.as_bytes());
+        hasher.update(&serde_json::to_vec(&self.variables).unwrap_or_default());
+
+        // Header order doesn't change the meaning of the request.
+        let mut headers = self
+            .headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_bytes()))
+            .collect::<Vec<_>>();
+        headers.sort_unstable();
+        for (name, value) in headers {
+            hasher.update(&name.len().to_be_bytes());
+            hasher.update(name.as_bytes());
+            hasher.update(&value.len().to_be_bytes());
+            hasher.update(value);
+        }
+
+        hasher.finalize().into()
+    }
+}
+
+impl SubscriptionDeduplication {
+    pub fn new(metrics: SubgraphSubscriptionMetrics) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                upstreams: Mutex::new(HashMap::new()),
+                metrics,
+            }),
+        }
+    }
+
+    fn subscribe(&self, fetcher: &Fetcher, request: UpstreamSubscriptionRequest) -> BoxStream<'static, UpstreamEvent> {
+        let key = request.key();
+        let subgraph_name = request.subgraph_name.clone();
+        let mut upstreams = self.inner.upstreams.lock().unwrap();
+
+        let (id, receiver) = match upstreams.get_mut(&key) {
+            Some(upstream) => {
+                let receiver = upstream.sender.subscribe();
+                upstream.max_subscribers = upstream.max_subscribers.max(upstream.sender.receiver_count());
+                (upstream.id, receiver)
+            }
+            None => {
+                let id = rand::random();
+                let (sender, receiver) = broadcast::channel(CHANNEL_CAPACITY);
+                let (abort_handle, abort_registration) = AbortHandle::new_pair();
+
+                self.inner.metrics.record_upstream_opened(&subgraph_name);
+                let task = run_upstream(self.inner.clone(), fetcher.clone(), key, id, request, sender.clone());
+                async_runtime::spawn(async move {
+                    let _ = Abortable::new(task, abort_registration).await;
+                });
+
+                upstreams.insert(
+                    key,
+                    Upstream {
+                        id,
+                        sender,
+                        abort_handle,
+                        max_subscribers: 1,
+                    },
+                );
+                (id, receiver)
+            }
+        };
+
+        drop(upstreams);
+        self.inner.metrics.record_subscriber_added(&subgraph_name);
+
+        let subscriber = Subscriber {
+            inner: self.inner.clone(),
+            key,
+            id,
+            subgraph_name,
+            receiver: Some(receiver),
+        };
+
+        futures::stream::unfold(subscriber, |mut subscriber| async move {
+            let receiver = subscriber.receiver.as_mut()?;
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => return Some((event, subscriber)),
+                    Err(broadcast::error::RecvError::Lagged(count)) => {
+                        tracing::warn!(
+                            "Subscriber of the subgraph '{}' lagged behind and missed {count} events",
+                            subscriber.subgraph_name
+                        );
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+        .boxed()
+    }
+}
+
+async fn run_upstream(
+    inner: Arc<Inner>,
+    fetcher: Fetcher,
+    key: UpstreamKey,
+    id: u64,
+    request: UpstreamSubscriptionRequest,
+    sender: broadcast::Sender<UpstreamEvent>,
+) {
+    let stream = fetcher
+        .stream(GraphqlRequest {
+            url: &request.url,
+            headers: request.headers,
+            query: &request.query,
+            variables: request.variables,
+            protocol: request.protocol,
+            heartbeat_timeout: request.heartbeat_timeout,
+            max_reconnect_attempts: request.max_reconnect_attempts,
+            client_settings: request.client_settings,
+        })
+        .await;
+
+    match stream {
+        Ok(mut stream) => {
+            while let Some(event) = stream.next().await {
+                if sender.send(Arc::new(event)).is_err() {
+                    break;
+                }
+            }
+        }
+        Err(error) => {
+            let _ = sender.send(Arc::new(Err(error)));
+        }
+    }
+
+    // Removing the upstream before closing the channel, so that no new subscriber joins it.
+    let mut upstreams = inner.upstreams.lock().unwrap();
+    if upstreams.get(&key).is_some_and(|upstream| upstream.id == id) {
+        let upstream = upstreams.remove(&key).expect("Upstream to exist");
+        drop(upstreams);
+        inner
+            .metrics
+            .record_upstream_closed(&request.subgraph_name, upstream.max_subscribers);
+    }
+}
+
+struct Subscriber {
+    inner: Arc<Inner>,
+    key: UpstreamKey,
+    id: u64,
+    subgraph_name: String,
+    receiver: Option<broadcast::Receiver<UpstreamEvent>>,
+}
+
+impl Drop for Subscriber {
+    fn drop(&mut self) {
+        self.inner.metrics.record_subscriber_removed(&self.subgraph_name);
+
+        let mut upstreams = self.inner.upstreams.lock().unwrap();
+        drop(self.receiver.take());
+
+        let Some(upstream) = upstreams.get(&self.key) else {
+            return;
+        };
+        if upstream.id != self.id || upstream.sender.receiver_count() > 0 {
+            return;
+        }
+
+        // Last subscriber, tearing down the upstream subscription.
+        let upstream = upstreams.remove(&self.key).expect("Upstream to exist");
+        drop(upstreams);
+        upstream.abort_handle.abort();
+        self.inner
+            .metrics
+            .record_upstream_closed(&self.subgraph_name, upstream.max_subscribers);
+    }
+}
+
+impl<R: Runtime> super::Engine<R> {
+    /// Subscribes to the subgraph, sharing the upstream subscription with identical ones.
+    pub(crate) fn subscribe_to_deduplicated_upstream(
+        &self,
+        request: UpstreamSubscriptionRequest,
+    ) -> BoxStream<'static, UpstreamEvent> {
+        self.subscription_deduplication
+            .subscribe(self.runtime.fetcher(), request)
+    }
+}
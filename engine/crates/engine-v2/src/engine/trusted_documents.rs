@@ -38,6 +38,7 @@ impl<'ctx, R: Runtime> PreExecutionContext<'ctx, R> {
         let document_id = request.document_id.as_ref();
         let name = request.operation_name();
         let schema_version = &self.engine.schema_version;
+        let override_labels = &self.override_labels;
 
         match (trusted_documents_enabled, persisted_query_extension, document_id) {
             (true, None, None) => {
@@ -52,6 +53,7 @@ impl<'ctx, R: Runtime> PreExecutionContext<'ctx, R> {
                         cache_key: Key::Operation {
                             name,
                             schema_version,
+                            override_labels,
                             document: Document::Text(request.query()),
                         }
                         .to_string(),
@@ -69,6 +71,7 @@ impl<'ctx, R: Runtime> PreExecutionContext<'ctx, R> {
                 cache_key: Key::Operation {
                     name,
                     schema_version,
+                    override_labels,
                     document: Document::PersistedQueryExt(ext),
                 }
                 .to_string(),
@@ -78,6 +81,7 @@ impl<'ctx, R: Runtime> PreExecutionContext<'ctx, R> {
                 cache_key: Key::Operation {
                     name,
                     schema_version,
+                    override_labels,
                     document: Document::Id(document_id),
                 }
                 .to_string(),
@@ -87,6 +91,7 @@ impl<'ctx, R: Runtime> PreExecutionContext<'ctx, R> {
                 cache_key: Key::Operation {
                     name,
                     schema_version,
+                    override_labels,
                     document: Document::Text(request.query()),
                 }
                 .to_string(),
@@ -96,6 +101,7 @@ impl<'ctx, R: Runtime> PreExecutionContext<'ctx, R> {
                 cache_key: Key::Operation {
                     name,
                     schema_version,
+                    override_labels,
                     document: Document::PersistedQueryExt(ext),
                 }
                 .to_string(),
@@ -1,11 +1,14 @@
 use ::runtime::hooks::Hooks;
 use futures::future::BoxFuture;
 use runtime::auth::AccessToken;
-use schema::{HeaderRuleWalker, Schema};
+use schema::{HeaderRuleWalker, OverrideLabelId, ResponseHeaderRuleWalker, Schema};
 
-use crate::{engine::RequestContext, Engine, Runtime};
+use crate::{engine::RequestContext, sources::TrafficVariantSelections, Engine, Runtime};
 
-use super::{header_rule::create_subgraph_headers_with_rules, ExecutableOperation, RequestHooks};
+use super::{
+    header_rule::{create_subgraph_headers_with_rules, forwards_request_header},
+    ExecutableOperation, RequestHooks,
+};
 
 /// Context before starting to operation plan execution.
 /// Background futures will be started in parallel to avoid delaying the plan.
@@ -14,6 +17,13 @@ pub(crate) struct PreExecutionContext<'ctx, R: Runtime> {
     pub(crate) request_context: &'ctx RequestContext<<R::Hooks as Hooks>::Context>,
     // needs to be Send so that futures are Send.
     pub(super) background_futures: crossbeam_queue::SegQueue<BoxFuture<'ctx, ()>>,
+    /// Set during the operation preparation if the response can be cached.
+    pub(crate) response_cache_key: Option<String>,
+    /// Set during the operation preparation if demand control is enabled.
+    pub(crate) estimated_cost: Option<u64>,
+    /// Progressive override labels active for this request, decided before the operation
+    /// preparation as they change the plan.
+    pub(crate) override_labels: Vec<OverrideLabelId>,
 }
 
 impl<'ctx, R: Runtime> PreExecutionContext<'ctx, R> {
@@ -22,6 +32,9 @@ impl<'ctx, R: Runtime> PreExecutionContext<'ctx, R> {
             engine,
             request_context,
             background_futures: Default::default(),
+            response_cache_key: None,
+            estimated_cost: None,
+            override_labels: Vec::new(),
         }
     }
 
@@ -44,6 +57,21 @@ impl<'ctx, R: Runtime> PreExecutionContext<'ctx, R> {
     pub fn hooks(&self) -> RequestHooks<'ctx, R::Hooks> {
         self.into()
     }
+
+    /// Request headers sent to at least one subgraph by the header rules.
+    pub fn forwarded_request_headers(&self) -> Vec<&'ctx http::HeaderName> {
+        let schema = self.engine.schema.walker();
+        let rules = schema
+            .default_header_rules()
+            .chain(schema.graphql_endpoints().flat_map(|endpoint| endpoint.header_rules()))
+            .collect::<Vec<_>>();
+
+        self.request_context
+            .headers
+            .keys()
+            .filter(|name| rules.iter().any(|rule| forwards_request_header(*rule, name)))
+            .collect()
+    }
 }
 
 impl<'ctx, R: Runtime> std::ops::Deref for PreExecutionContext<'ctx, R> {
@@ -69,11 +97,14 @@ impl<R: Runtime> Clone for ExecutionContext<'_, R> {
 impl<R: Runtime> std::marker::Copy for ExecutionContext<'_, R> {}
 
 impl<'ctx, R: Runtime> ExecutionContext<'ctx, R> {
-    #[allow(unused)]
     pub fn access_token(&self) -> &'ctx AccessToken {
         &self.request_context.access_token
     }
 
+    pub fn headers(&self) -> &'ctx http::HeaderMap {
+        &self.request_context.headers
+    }
+
     pub fn subgraph_headers_with_rules(&self, rules: impl Iterator<Item = HeaderRuleWalker<'ctx>>) -> http::HeaderMap {
         create_subgraph_headers_with_rules(
             self.request_context,
@@ -82,6 +113,15 @@ impl<'ctx, R: Runtime> ExecutionContext<'ctx, R> {
         )
     }
 
+    /// Applies the response header rules of a subgraph, keeping the result for the client response.
+    pub fn record_subgraph_response_headers(
+        &self,
+        rules: impl ExactSizeIterator<Item = ResponseHeaderRuleWalker<'ctx>>,
+        headers: &http::HeaderMap,
+    ) {
+        self.request_context.response_headers.apply_rules(rules, headers)
+    }
+
     #[allow(unused)]
     pub fn hooks(&self) -> RequestHooks<'ctx, R::Hooks> {
         self.into()
@@ -90,4 +130,8 @@ impl<'ctx, R: Runtime> ExecutionContext<'ctx, R> {
     pub fn schema(&self) -> &'ctx Schema {
         &self.engine.schema
     }
+
+    pub fn traffic_variant_selections(&self) -> &'ctx TrafficVariantSelections {
+        &self.request_context.traffic_variant_selections
+    }
 }
@@ -12,15 +12,18 @@ use tracing::instrument;
 
 use crate::{
     execution::{ExecutableOperation, ExecutionContext},
-    operation::PlanWalker,
+    operation::{DeferId, PlanWalker},
     response::{
-        InputdResponseObjectSet, ObjectIdentifier, Response, ResponseBuilder, ResponseEdge, ResponseObjectField,
-        ResponseValue, SubgraphResponse, SubgraphResponseRefMut,
+        DeferredResponseObject, InputdResponseObjectSet, ObjectIdentifier, Response, ResponseBuilder, ResponseEdge,
+        ResponseObjectField, ResponseObjectId, ResponseValue, SubgraphResponse, SubgraphResponseRefMut,
     },
     Runtime,
 };
 
-use super::{state::OperationExecutionState, ExecutionError, ExecutionPlanId, ExecutionResult, PreExecutionContext};
+use super::{
+    incremental::IncrementalDelivery, state::OperationExecutionState, ExecutionError, ExecutionPlanId, ExecutionResult,
+    PreExecutionContext,
+};
 
 pub(crate) trait ResponseSender: Send {
     type Error;
@@ -46,6 +49,12 @@ impl<'ctx, R: Runtime> PreExecutionContext<'ctx, R> {
         response
     }
 
+    /// The operation didn't need to be executed, only the background work is done.
+    pub async fn skip_execution(self) {
+        let background_futures: FuturesUnordered<_> = self.background_futures.into_iter().collect();
+        background_futures.collect::<Vec<_>>().await;
+    }
+
     #[instrument(skip_all)]
     pub async fn execute_subscription(self, operation: ExecutableOperation, responses: impl ResponseSender) {
         let background_futures: FuturesUnordered<_> = self.background_futures.into_iter().collect();
@@ -61,6 +70,24 @@ impl<'ctx, R: Runtime> PreExecutionContext<'ctx, R> {
         tracing::trace!("Starting execution...");
         futures_util::join!(subscription_fut, background_fut);
     }
+
+    /// Executes a query with deferred fragments, sending the initial payload as soon as possible
+    /// and each deferred fragment afterwards.
+    #[instrument(skip_all)]
+    pub async fn execute_incremental_query(self, operation: ExecutableOperation, responses: impl ResponseSender) {
+        let background_futures: FuturesUnordered<_> = self.background_futures.into_iter().collect();
+        let background_fut = background_futures.collect::<Vec<_>>();
+        let ctx = ExecutionContext {
+            engine: self.engine,
+            operation: &operation,
+            request_context: self.request_context,
+        };
+
+        let incremental_fut = ctx.execute_incremental(responses);
+
+        tracing::trace!("Starting execution...");
+        futures_util::join!(incremental_fut, background_fut);
+    }
 }
 
 impl<'ctx, R: Runtime> ExecutionContext<'ctx, R> {
@@ -99,6 +126,24 @@ impl<'ctx, R: Runtime> ExecutionContext<'ctx, R> {
         .await
     }
 
+    async fn execute_incremental(self, mut responses: impl ResponseSender) {
+        assert!(matches!(self.operation.ty(), OperationType::Query));
+
+        if let Some(response) = self.response_if_root_errors() {
+            let _ = responses.send(response).await;
+            return;
+        }
+
+        OperationExecution {
+            futures: ResolverFutureSet::new(),
+            state: self.new_execution_state(),
+            response: ResponseBuilder::new(self.operation.root_object_id),
+            ctx: self,
+        }
+        .run_incremental(responses)
+        .await
+    }
+
     async fn execute_subscription(self, mut responses: impl ResponseSender) {
         assert!(matches!(self.operation.ty(), OperationType::Subscription));
 
@@ -303,44 +348,238 @@ where
             self.spawn_resolver(plan_id);
         }
 
-        while let Some(ResolverFutureResult { plan_id, result }) = self.futures.next().await {
-            // Retrieving the first edge (response key) appearing in the query to provide a better
-            // error path if necessary.
-            let (any_edge, default_fields) = self.get_first_edge_and_default_object(plan_id);
-            match result {
-                Ok(subgraph_response) => {
-                    tracing::trace!(%plan_id, "Succeeded");
-                    let tracked_response_object_sets =
-                        self.response.ingest(subgraph_response, any_edge, default_fields);
-                    for (set_id, response_object_refs) in tracked_response_object_sets.into_iter() {
-                        self.state.push_response_objects(set_id, response_object_refs);
-                    }
+        while let Some(result) = self.futures.next().await {
+            for plan_id in self.ingest(result).await {
+                self.spawn_resolver(plan_id);
+            }
+        }
 
-                    let response_modifier_executor_ids = self.state.get_next_executable_response_modifiers(plan_id);
-                    for id in &response_modifier_executor_ids {
-                        self.ctx
-                            .execute_response_modifier(&mut self.state, &mut self.response, *id)
-                            .await;
-                    }
+        let schema = self.engine.schema.clone();
+        let operation = self.operation.prepared.clone();
+        self.response.build(schema, operation)
+    }
 
-                    for plan_id in self
-                        .state
-                        .get_next_executable_plans(plan_id, response_modifier_executor_ids)
-                    {
-                        self.spawn_resolver(plan_id);
+    /// Runs the execution to completion, sending the initial payload and every deferred
+    /// fragment as soon as they're ready.
+    async fn run_incremental(mut self, mut responses: impl ResponseSender) {
+        let mut delivery = IncrementalDelivery::new(self.operation);
+        for plan_id in self.state.get_executable_plans() {
+            self.spawn_deferrable_resolver(&mut delivery, plan_id);
+        }
+
+        let mut has_next = true;
+        loop {
+            let ready = delivery.ready();
+            if !ready.is_empty() {
+                let Some(response) = self.build_ready_payload(&mut delivery, ready).await else {
+                    continue;
+                };
+                has_next = delivery.has_next();
+                let response = match response {
+                    Response::IncrementalInitial(response) => {
+                        Response::IncrementalInitial(response.with_has_next(has_next))
                     }
+                    Response::IncrementalSubsequent(response) => {
+                        Response::IncrementalSubsequent(response.with_has_next(has_next))
+                    }
+                    response => response,
+                };
+                if responses.send(response).await.is_err() {
+                    return;
                 }
-                Err((root_response_object_set, error)) => {
-                    tracing::trace!(%plan_id, "Failed");
-                    self.response
-                        .propagate_execution_error(root_response_object_set, error, any_edge, default_fields);
+                continue;
+            }
+
+            if let Some(result) = self.futures.next().await {
+                let defer_id = self.defer_id(result.plan_id);
+                if let Some(result) = delivery.plan_finished(defer_id, result) {
+                    self.ingest_deferrable(&mut delivery, result).await;
+                }
+            } else {
+                let buffered = delivery.release_all();
+                if buffered.is_empty() {
+                    break;
+                }
+                for result in buffered {
+                    self.ingest_deferrable(&mut delivery, result).await;
                 }
             }
         }
 
+        if has_next {
+            let schema = self.engine.schema.clone();
+            let operation = self.operation.prepared.clone();
+            let response = self
+                .response
+                .build_incremental_subsequent(
+                    &schema,
+                    operation,
+                    &self.ctx.operation.variables,
+                    Vec::new(),
+                    Vec::new(),
+                )
+                .with_has_next(false);
+            let _ = responses.send(Response::IncrementalSubsequent(response)).await;
+        }
+    }
+
+    /// Builds the payload for all the ready fragments and releases their nested fragments
+    /// afterwards. Returns nothing if there is no data to send, which happens when all plans of a
+    /// fragment were skipped. Streamed lists are sent once, after all of their plans finished.
+    async fn build_ready_payload(
+        &mut self,
+        delivery: &mut IncrementalDelivery<ResolverFutureResult>,
+        ready: Vec<Option<DeferId>>,
+    ) -> Option<Response> {
         let schema = self.engine.schema.clone();
         let operation = self.operation.prepared.clone();
-        self.response.build(schema, operation)
+
+        if ready.contains(&None) && !delivery.is_sent(None) {
+            delivery.take_ingested(None);
+            let response = self
+                .response
+                .build_incremental_initial(&schema, operation, &self.ctx.operation.variables);
+            self.release_nested_fragments(delivery, None).await;
+            return Some(Response::IncrementalInitial(response));
+        }
+
+        let mut objects = Vec::<DeferredResponseObject>::new();
+        let mut streams = Vec::new();
+        for &defer_id in &ready {
+            let label = defer_id.and_then(|id| self.operation[id].label.clone());
+            let ingested = delivery.take_ingested(defer_id);
+            // Streamed items are all sent at once, the plans within them only provide data for
+            // those items.
+            if let Some(stream) = defer_id.and_then(|id| self.operation[id].stream) {
+                if !delivery.is_sent(defer_id) {
+                    streams.push((label, stream));
+                }
+                continue;
+            }
+            let mut positions = std::collections::BTreeMap::<ResponseObjectId, usize>::new();
+            for (plan_id, root_response_object_set) in ingested {
+                let edges = self.get_root_edges(plan_id);
+                for object in root_response_object_set.iter() {
+                    if let Some(&i) = positions.get(&object.id) {
+                        objects[i].edges.extend(edges.iter().copied());
+                    } else {
+                        positions.insert(object.id, objects.len());
+                        objects.push(DeferredResponseObject {
+                            label: label.clone(),
+                            object: object.clone(),
+                            edges: edges.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        let response = if objects.is_empty() && streams.is_empty() {
+            None
+        } else {
+            Some(Response::IncrementalSubsequent(
+                self.response.build_incremental_subsequent(
+                    &schema,
+                    operation,
+                    &self.ctx.operation.variables,
+                    objects,
+                    streams,
+                ),
+            ))
+        };
+        for defer_id in ready {
+            self.release_nested_fragments(delivery, defer_id).await;
+        }
+        response
+    }
+
+    async fn release_nested_fragments(
+        &mut self,
+        delivery: &mut IncrementalDelivery<ResolverFutureResult>,
+        defer_id: Option<DeferId>,
+    ) {
+        for result in delivery.mark_sent(defer_id) {
+            self.ingest_deferrable(delivery, result).await;
+        }
+    }
+
+    async fn ingest_deferrable(
+        &mut self,
+        delivery: &mut IncrementalDelivery<ResolverFutureResult>,
+        result: ResolverFutureResult,
+    ) {
+        delivery.plan_ingested(
+            self.defer_id(result.plan_id),
+            result.plan_id,
+            result.root_response_object_set().clone(),
+        );
+        for plan_id in self.ingest(result).await {
+            self.spawn_deferrable_resolver(delivery, plan_id);
+        }
+    }
+
+    fn spawn_deferrable_resolver(
+        &mut self,
+        delivery: &mut IncrementalDelivery<ResolverFutureResult>,
+        plan_id: ExecutionPlanId,
+    ) {
+        if self.spawn_resolver(plan_id) {
+            delivery.plan_started(self.defer_id(plan_id));
+        }
+    }
+
+    fn defer_id(&self, plan_id: ExecutionPlanId) -> Option<DeferId> {
+        self.operation[self.operation[plan_id].logical_plan_id].defer_id
+    }
+
+    /// Ingests the result of a plan into the response, returning the plans that can be executed
+    /// next.
+    async fn ingest(&mut self, ResolverFutureResult { plan_id, result }: ResolverFutureResult) -> Vec<ExecutionPlanId> {
+        // Retrieving the first edge (response key) appearing in the query to provide a better
+        // error path if necessary.
+        let (any_edge, default_fields) = self.get_first_edge_and_default_object(plan_id);
+        match result {
+            Ok(subgraph_response) => {
+                tracing::trace!(%plan_id, "Succeeded");
+                let tracked_response_object_sets = self.response.ingest(subgraph_response, any_edge, default_fields);
+                for (set_id, response_object_refs) in tracked_response_object_sets.into_iter() {
+                    self.state.push_response_objects(set_id, response_object_refs);
+                }
+
+                let response_modifier_executor_ids = self.state.get_next_executable_response_modifiers(plan_id);
+                for id in &response_modifier_executor_ids {
+                    self.ctx
+                        .execute_response_modifier(&mut self.state, &mut self.response, *id)
+                        .await;
+                }
+
+                self.state
+                    .get_next_executable_plans(plan_id, response_modifier_executor_ids)
+            }
+            Err((root_response_object_set, error)) => {
+                tracing::trace!(%plan_id, "Failed");
+                self.response
+                    .propagate_execution_error(root_response_object_set, error, any_edge, default_fields);
+                Vec::new()
+            }
+        }
+    }
+
+    /// All the response edges written by the plan into its root objects.
+    fn get_root_edges(&self, plan_id: ExecutionPlanId) -> Vec<ResponseEdge> {
+        let shape_id = self
+            .ctx
+            .plan_walker(plan_id)
+            .logical_plan()
+            .response_blueprint()
+            .concrete_shape_id;
+        let shapes = &self.operation.response_blueprint.shapes;
+        let shape = &shapes[shape_id];
+        shapes[shape.field_shape_ids]
+            .iter()
+            .map(|field| field.edge)
+            .chain(shape.typename_response_edges.iter().copied())
+            .collect()
     }
 
     fn get_first_edge_and_default_object(
@@ -389,13 +628,15 @@ where
         (first_edge, Some(fields))
     }
 
-    fn spawn_resolver(&mut self, plan_id: ExecutionPlanId) {
+    /// Returns whether the plan was actually started, it's skipped if there is nothing to
+    /// resolve.
+    fn spawn_resolver(&mut self, plan_id: ExecutionPlanId) -> bool {
         tracing::trace!(%plan_id, "Starting plan");
         let root_response_object_set = Arc::new(self.state.get_input(&self.response, plan_id));
 
         tracing::trace!(%plan_id, "Found {} root response objects", root_response_object_set.len());
         if root_response_object_set.is_empty() {
-            return;
+            return false;
         }
 
         self.futures.push_fut({
@@ -421,6 +662,7 @@ where
             }))
             .boxed()
         });
+        true
     }
 }
 
@@ -452,3 +694,12 @@ struct ResolverFutureResult {
     plan_id: ExecutionPlanId,
     result: Result<SubgraphResponse, (Arc<InputdResponseObjectSet>, ExecutionError)>,
 }
+
+impl ResolverFutureResult {
+    fn root_response_object_set(&self) -> &Arc<InputdResponseObjectSet> {
+        match &self.result {
+            Ok(subgraph_response) => subgraph_response.root_response_object_set(),
+            Err((root_response_object_set, _)) => root_response_object_set,
+        }
+    }
+}
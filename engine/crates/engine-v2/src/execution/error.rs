@@ -39,6 +39,13 @@ pub enum ExecutionError {
         subgraph_name: String,
         error: runtime::fetch::FetchError,
     },
+    #[error("Subgraph '{subgraph_name}' responded with HTTP status {status}")]
+    SubgraphHttpStatus {
+        subgraph_name: String,
+        status: http::StatusCode,
+    },
+    #[error("Subgraph '{subgraph_name}' is unavailable, its circuit breaker is open")]
+    CircuitOpen { subgraph_name: String },
     #[error(transparent)]
     RateLimit(#[from] runtime::rate_limiting::Error),
     #[error("{0}")]
@@ -56,7 +63,8 @@ impl From<ExecutionError> for GraphqlError {
         let code = match &err {
             ExecutionError::Internal(_) => ErrorCode::InternalServerError,
             ExecutionError::DeserializationError(_) => ErrorCode::SubgraphInvalidResponseError,
-            ExecutionError::Fetch { .. } => ErrorCode::SubgraphRequestError,
+            ExecutionError::Fetch { .. } | ExecutionError::SubgraphHttpStatus { .. } => ErrorCode::SubgraphRequestError,
+            ExecutionError::CircuitOpen { .. } => ErrorCode::SubgraphUnavailable,
             ExecutionError::RateLimit(_) => ErrorCode::RateLimited,
             ExecutionError::Graphql(err) => err.code,
         };
@@ -30,6 +30,24 @@ pub(super) fn create_subgraph_headers_with_rules<'ctx, C>(
     headers
 }
 
+/// Whether the rule sends the value of the request header to the subgraph.
+pub(super) fn forwards_request_header(rule: HeaderRuleWalker<'_>, name: &HeaderName) -> bool {
+    match rule.rule() {
+        schema::HeaderRuleRef::Forward {
+            name: NameOrPatternRef::Pattern(regex),
+            ..
+        } => !is_header_denied(name) && regex.is_match(name.as_str()),
+        schema::HeaderRuleRef::Forward {
+            name: NameOrPatternRef::Name(forwarded),
+            ..
+        }
+        | schema::HeaderRuleRef::RenameDuplicate { name: forwarded, .. } => {
+            forwarded.eq_ignore_ascii_case(name.as_str())
+        }
+        schema::HeaderRuleRef::Insert { .. } | schema::HeaderRuleRef::Remove { .. } => false,
+    }
+}
+
 fn handle_rename_duplicate<C>(
     headers: &mut http::HeaderMap,
     name: &str,
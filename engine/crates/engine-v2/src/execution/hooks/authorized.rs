@@ -90,6 +90,15 @@ impl<'ctx, H: Hooks> super::RequestHooks<'ctx, H> {
             .map_err(Into::into)
     }
 
+    #[instrument(skip_all, ret(level = Level::DEBUG))]
+    pub async fn authorize_policies(&self, policies: Vec<String>) -> Result<Vec<String>, GraphqlError> {
+        self.hooks
+            .authorized()
+            .authorize_policies(self.context, policies)
+            .await
+            .map_err(Into::into)
+    }
+
     #[instrument(skip_all, ret(level = Level::DEBUG))]
     pub async fn authorize_node_pre_execution(
         &self,
@@ -1,5 +1,5 @@
 use http::HeaderMap;
-use runtime::hooks::{Hooks, SubgraphHooks};
+use runtime::hooks::{ExecutedSubgraphRequest, Hooks, SubgraphHooks};
 use tracing::{instrument, Level};
 
 use crate::response::GraphqlError;
@@ -19,4 +19,13 @@ impl<'ctx, H: Hooks> super::RequestHooks<'ctx, H> {
             .await
             .map_err(Into::into)
     }
+
+    #[instrument(skip_all, ret(level = Level::DEBUG))]
+    pub async fn on_subgraph_response(&self, request: ExecutedSubgraphRequest<'_>) -> Result<(), GraphqlError> {
+        self.hooks
+            .subgraph()
+            .on_subgraph_response(self.context, request)
+            .await
+            .map_err(Into::into)
+    }
 }
@@ -0,0 +1,174 @@
+use std::sync::Arc;
+
+use crate::{operation::DeferId, response::InputdResponseObjectSet};
+
+use super::{ExecutableOperation, ExecutionPlanId};
+
+/// Keeps track of the deferred fragments during incremental delivery. Plans of a deferred
+/// fragment are executed as soon as possible, but their results are only ingested into the
+/// response once the payload of the parent fragment has been sent. Otherwise we would send
+/// deferred data earlier than expected.
+///
+/// The initial payload is treated as a fragment like any other, always the first one.
+///
+/// Fragments disabled by their `if` variable are delivered with their closest enabled ancestor,
+/// so their plans are tracked with it instead.
+pub(super) struct IncrementalDelivery<T> {
+    fragments: Vec<FragmentState<T>>,
+    parents: Vec<Option<DeferId>>,
+    delivered_with: Vec<Option<DeferId>>,
+}
+
+struct FragmentState<T> {
+    released: bool,
+    sent: bool,
+    in_flight: usize,
+    /// Results received before the fragment was released.
+    buffered: Vec<T>,
+    /// Plans ingested since the last payload of this fragment.
+    ingested: Vec<(ExecutionPlanId, Arc<InputdResponseObjectSet>)>,
+}
+
+impl<T> Default for FragmentState<T> {
+    fn default() -> Self {
+        Self {
+            released: false,
+            sent: false,
+            in_flight: 0,
+            buffered: Vec::new(),
+            ingested: Vec::new(),
+        }
+    }
+}
+
+impl<T> IncrementalDelivery<T> {
+    pub fn new(operation: &ExecutableOperation) -> Self {
+        let mut fragments = Vec::with_capacity(operation.deferred_fragments.len() + 1);
+        fragments.push(FragmentState {
+            released: true,
+            ..Default::default()
+        });
+        let mut parents = Vec::with_capacity(operation.deferred_fragments.len());
+        let mut delivered_with = Vec::<Option<DeferId>>::with_capacity(operation.deferred_fragments.len());
+        // Parents are always bound before their nested fragments.
+        for (i, fragment) in operation.deferred_fragments.iter().enumerate() {
+            let parent_id = fragment.parent_id.and_then(|id| delivered_with[usize::from(id)]);
+            if fragment.is_enabled(operation, &operation.variables) {
+                fragments.push(FragmentState::default());
+                delivered_with.push(Some(DeferId::from(i)));
+            } else {
+                // Never ready, nor waited for.
+                fragments.push(FragmentState {
+                    released: true,
+                    sent: true,
+                    ..Default::default()
+                });
+                delivered_with.push(parent_id);
+            }
+            parents.push(parent_id);
+        }
+        Self {
+            fragments,
+            parents,
+            delivered_with,
+        }
+    }
+
+    pub fn plan_started(&mut self, defer_id: Option<DeferId>) {
+        self.fragment_mut(defer_id).in_flight += 1;
+    }
+
+    /// Returns the result if it can be ingested right away.
+    pub fn plan_finished(&mut self, defer_id: Option<DeferId>, result: T) -> Option<T> {
+        let fragment = self.fragment_mut(defer_id);
+        fragment.in_flight -= 1;
+        if fragment.released {
+            Some(result)
+        } else {
+            fragment.buffered.push(result);
+            None
+        }
+    }
+
+    pub fn plan_ingested(
+        &mut self,
+        defer_id: Option<DeferId>,
+        plan_id: ExecutionPlanId,
+        root_response_object_set: Arc<InputdResponseObjectSet>,
+    ) {
+        self.fragment_mut(defer_id)
+            .ingested
+            .push((plan_id, root_response_object_set));
+    }
+
+    /// Fragments for which we can send a payload, nothing being in flight anymore.
+    pub fn ready(&self) -> Vec<Option<DeferId>> {
+        self.fragments
+            .iter()
+            .enumerate()
+            .filter(|(_, fragment)| {
+                fragment.released && fragment.in_flight == 0 && (!fragment.sent || !fragment.ingested.is_empty())
+            })
+            .map(|(i, _)| i.checked_sub(1).map(DeferId::from))
+            .collect()
+    }
+
+    pub fn is_sent(&self, defer_id: Option<DeferId>) -> bool {
+        self.fragments[self.fragment_index(defer_id)].sent
+    }
+
+    pub fn take_ingested(&mut self, defer_id: Option<DeferId>) -> Vec<(ExecutionPlanId, Arc<InputdResponseObjectSet>)> {
+        std::mem::take(&mut self.fragment_mut(defer_id).ingested)
+    }
+
+    /// Marks the fragment payload as sent, releasing all of its nested fragments. Their buffered
+    /// results are returned to be ingested.
+    pub fn mark_sent(&mut self, defer_id: Option<DeferId>) -> Vec<T> {
+        self.fragment_mut(defer_id).sent = true;
+        let mut buffered = Vec::new();
+        for (i, parent_id) in self.parents.iter().enumerate() {
+            if *parent_id == defer_id {
+                let fragment = &mut self.fragments[i + 1];
+                fragment.released = true;
+                buffered.append(&mut fragment.buffered);
+            }
+        }
+        buffered
+    }
+
+    /// Releases every fragment with buffered results. Only used when nothing is in flight
+    /// anymore, which should never happen with buffered results as a plan never depends on a
+    /// plan from a fragment sent after its own.
+    pub fn release_all(&mut self) -> Vec<T> {
+        let mut buffered = Vec::new();
+        for fragment in &mut self.fragments {
+            if !fragment.buffered.is_empty() {
+                fragment.released = true;
+                buffered.append(&mut fragment.buffered);
+            }
+        }
+        buffered
+    }
+
+    /// Whether there will be any payload after the current one.
+    pub fn has_next(&self) -> bool {
+        self.fragments.iter().any(|fragment| {
+            fragment.in_flight > 0
+                || !fragment.buffered.is_empty()
+                || !fragment.ingested.is_empty()
+                || (fragment.released && !fragment.sent)
+        })
+    }
+
+    fn fragment_mut(&mut self, defer_id: Option<DeferId>) -> &mut FragmentState<T> {
+        let i = self.fragment_index(defer_id);
+        &mut self.fragments[i]
+    }
+
+    fn fragment_index(&self, defer_id: Option<DeferId>) -> usize {
+        defer_id
+            .and_then(|id| self.delivered_with[usize::from(id)])
+            .map(|id| usize::from(id) + 1)
+            .unwrap_or_default()
+    }
+}
@@ -4,7 +4,10 @@ mod error;
 mod header_rule;
 pub(crate) mod hooks;
 mod ids;
+mod incremental;
 mod planner;
+mod query_plan;
+mod response_header_rule;
 mod response_modifier;
 mod state;
 
@@ -21,6 +24,8 @@ pub(crate) use coordinator::*;
 pub(crate) use error::*;
 pub(crate) use hooks::RequestHooks;
 pub(crate) use ids::*;
+pub(crate) use query_plan::*;
+pub(crate) use response_header_rule::ResponseHeaders;
 use schema::EntityId;
 use tracing::instrument;
 
@@ -0,0 +1,131 @@
+use config::latest::QueryPlanConfig;
+use schema::Schema;
+
+use crate::{
+    operation::PlanWalker,
+    response::{ResponseViewSelectionSet, ResponseViews},
+};
+
+use super::ExecutableOperation;
+
+const QUERY_PLAN_HEADER: &str = "x-grafbase-query-plan";
+
+/// How the query plan is exposed to the client, if at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum QueryPlanMode {
+    /// Operation is executed as usual with the query plan in the extensions.
+    Include,
+    /// Operation isn't executed at all, only the query plan is returned. Useful to snapshot
+    /// query plans in CI.
+    PlanOnly,
+}
+
+impl QueryPlanMode {
+    pub(crate) fn from_headers(headers: &http::HeaderMap, config: QueryPlanConfig) -> Option<Self> {
+        let requested = headers
+            .get(QUERY_PLAN_HEADER)
+            .filter(|_| config.enabled)
+            .and_then(|value| match value.as_bytes() {
+                b"include" => Some(QueryPlanMode::Include),
+                b"plan-only" => Some(QueryPlanMode::PlanOnly),
+                _ => None,
+            });
+        requested.or(config.always_include.then_some(QueryPlanMode::Include))
+    }
+}
+
+/// Query plan as exposed in the `queryPlan` response extension. Each node is an execution
+/// plan, executed once all of the plans it depends on have finished.
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct QueryPlan {
+    nodes: Vec<QueryPlanNode>,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryPlanNode {
+    id: usize,
+    resolver: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    subgraph: Option<String>,
+    entity: String,
+    /// Response keys of the root fields of the plan.
+    fields: Vec<String>,
+    /// Operation sent to the subgraph.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    query: Option<String>,
+    /// Fields read from the response before executing the plan, such as the entity keys
+    /// and `@requires` fields.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    requires: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    depends_on: Vec<usize>,
+}
+
+impl QueryPlan {
+    pub(crate) fn build(schema: &Schema, operation: &ExecutableOperation) -> Self {
+        let mut depends_on = vec![Vec::new(); operation.execution_plans.len()];
+        for (i, plan) in operation.execution_plans.iter().enumerate() {
+            for child in &plan.children {
+                depends_on[usize::from(*child)].push(i);
+            }
+        }
+
+        let nodes = operation
+            .execution_plans
+            .iter()
+            .zip(depends_on)
+            .enumerate()
+            .map(|(i, (plan, depends_on))| {
+                let logical_plan = &operation[plan.logical_plan_id];
+                let walker = PlanWalker {
+                    schema_walker: schema.walker(),
+                    operation: &operation.prepared,
+                    variables: &operation.variables,
+                    query_modifications: &operation.query_modifications,
+                    logical_plan_id: plan.logical_plan_id,
+                    item: (),
+                };
+                QueryPlanNode {
+                    id: i,
+                    resolver: schema.walk(logical_plan.resolver_id).name(),
+                    subgraph: plan
+                        .resolver
+                        .endpoint_id()
+                        .map(|id| schema.walk(id).subgraph_name().to_string()),
+                    entity: schema.walk(logical_plan.entity_id).name().to_string(),
+                    fields: walker
+                        .selection_set()
+                        .fields()
+                        .into_iter()
+                        .map(|field| field.response_key_str().to_string())
+                        .collect(),
+                    query: plan.resolver.subgraph_query().map(str::to_string),
+                    requires: Some(plan.requires)
+                        .filter(|requires| !requires.is_empty())
+                        .map(|requires| render_selection_set(schema, &operation.response_views, requires)),
+                    depends_on,
+                }
+            })
+            .collect();
+
+        QueryPlan { nodes }
+    }
+}
+
+fn render_selection_set(schema: &Schema, views: &ResponseViews, selection_set: ResponseViewSelectionSet) -> String {
+    let mut out = String::new();
+    for (i, id) in selection_set.into_iter().enumerate() {
+        if i > 0 {
+            out.push(' ');
+        }
+        let selection = &views[id];
+        out.push_str(&schema[selection.name]);
+        if !selection.subselection.is_empty() {
+            out.push_str(" { ");
+            out.push_str(&render_selection_set(schema, views, selection.subselection));
+            out.push_str(" }");
+        }
+    }
+    out
+}
@@ -0,0 +1,330 @@
+use std::{str::FromStr, sync::Mutex};
+
+use headers::Header;
+use http::{HeaderName, HeaderValue};
+use schema::{HeaderMergeStrategy, NameOrPatternRef, ResponseHeaderRuleRef, ResponseHeaderRuleWalker};
+
+/// Headers of all the subgraph responses forwarded to the client, merged according to the
+/// strategy of the rule which produced them.
+#[derive(Default)]
+pub(crate) struct ResponseHeaders {
+    headers: Mutex<http::HeaderMap>,
+}
+
+/// Headers produced by the response header rules of a single subgraph response.
+#[derive(Default)]
+struct SubgraphResponseHeaders {
+    headers: http::HeaderMap,
+    merge_strategies: Vec<(HeaderName, HeaderMergeStrategy)>,
+}
+
+impl ResponseHeaders {
+    pub(crate) fn apply_rules<'a>(
+        &self,
+        rules: impl ExactSizeIterator<Item = ResponseHeaderRuleWalker<'a>>,
+        subgraph_headers: &http::HeaderMap,
+    ) {
+        if rules.len() == 0 {
+            return;
+        }
+
+        let mut output = SubgraphResponseHeaders::default();
+
+        for rule in rules {
+            match rule.rule() {
+                ResponseHeaderRuleRef::Forward {
+                    name,
+                    default,
+                    rename,
+                    merge,
+                } => output.handle_forward(subgraph_headers, name, rename, default, merge),
+                ResponseHeaderRuleRef::Insert { name, value, merge } => output.handle_insert(name, value, merge),
+                ResponseHeaderRuleRef::Remove { name } => output.handle_remove(name),
+                ResponseHeaderRuleRef::Rename {
+                    name,
+                    default,
+                    rename,
+                    merge,
+                } => output.handle_rename(subgraph_headers, name, rename, default, merge),
+            }
+        }
+
+        let mut headers = self.headers.lock().unwrap();
+        output.merge_into(&mut headers);
+    }
+
+    /// Merges the `cache-control` computed by the gateway with the most restrictive one forwarded
+    /// from the subgraphs.
+    pub(crate) fn merge_cache_control(&self, value: HeaderValue) {
+        let mut output = SubgraphResponseHeaders::default();
+        output.append(
+            http::header::CACHE_CONTROL,
+            value,
+            HeaderMergeStrategy::MostRestrictiveCacheControl,
+        );
+
+        let mut headers = self.headers.lock().unwrap();
+        output.merge_into(&mut headers);
+    }
+
+    /// Headers set by the engine itself, such as the content type, take precedence.
+    pub(crate) fn write_into(&self, target: &mut http::HeaderMap) {
+        let headers = std::mem::take(&mut *self.headers.lock().unwrap());
+
+        for name in headers.keys() {
+            if target.contains_key(name) {
+                continue;
+            }
+
+            for value in headers.get_all(name) {
+                target.append(name.clone(), value.clone());
+            }
+        }
+    }
+}
+
+impl SubgraphResponseHeaders {
+    fn handle_forward(
+        &mut self,
+        subgraph_headers: &http::HeaderMap,
+        name: NameOrPatternRef<'_>,
+        rename: Option<&str>,
+        default: Option<&str>,
+        merge: HeaderMergeStrategy,
+    ) {
+        let rename = match rename.map(HeaderName::from_str) {
+            Some(Ok(rename)) => Some(rename),
+            Some(Err(_)) => return,
+            None => None,
+        };
+
+        match name {
+            NameOrPatternRef::Pattern(regex) => {
+                let filtered = subgraph_headers
+                    .iter()
+                    .filter(|(name, _)| !is_header_denied(name))
+                    .filter(|(name, _)| regex.is_match(name.as_str()));
+
+                for (name, value) in filtered {
+                    let name = rename.clone().unwrap_or_else(|| name.clone());
+                    self.append(name, value.clone(), merge);
+                }
+            }
+            NameOrPatternRef::Name(name) => {
+                let Ok(name) = HeaderName::from_str(name) else {
+                    return;
+                };
+                let target = rename.unwrap_or_else(|| name.clone());
+                self.forward_values(subgraph_headers, &name, target, default, merge);
+            }
+        }
+    }
+
+    fn handle_insert(&mut self, name: &str, value: &str, merge: HeaderMergeStrategy) {
+        let name = HeaderName::from_str(name).ok();
+        let value = HeaderValue::from_str(value).ok();
+
+        if let Some((name, value)) = name.zip(value) {
+            if is_header_denied(&name) {
+                return;
+            }
+
+            self.append(name, value, merge);
+        }
+    }
+
+    fn handle_remove(&mut self, name: NameOrPatternRef<'_>) {
+        match name {
+            NameOrPatternRef::Pattern(regex) => {
+                // https://github.com/hyperium/http/issues/632
+                let delete_list: Vec<_> = self
+                    .headers
+                    .keys()
+                    .filter(|key| regex.is_match(key.as_str()))
+                    .cloned()
+                    .collect();
+
+                for key in delete_list {
+                    self.headers.remove(key);
+                }
+            }
+            NameOrPatternRef::Name(name) => {
+                self.headers.remove(name);
+            }
+        }
+    }
+
+    fn handle_rename(
+        &mut self,
+        subgraph_headers: &http::HeaderMap,
+        name: &str,
+        rename: &str,
+        default: Option<&str>,
+        merge: HeaderMergeStrategy,
+    ) {
+        let Ok(name) = HeaderName::from_str(name) else {
+            return;
+        };
+
+        let Ok(rename) = HeaderName::from_str(rename) else {
+            return;
+        };
+
+        self.forward_values(subgraph_headers, &name, rename, default, merge);
+    }
+
+    fn forward_values(
+        &mut self,
+        subgraph_headers: &http::HeaderMap,
+        name: &HeaderName,
+        target: HeaderName,
+        default: Option<&str>,
+        merge: HeaderMergeStrategy,
+    ) {
+        if is_header_denied(&target) {
+            return;
+        }
+
+        // if a previous rule added a header with the same name, remove the old one.
+        self.headers.remove(&target);
+
+        let mut inserted = false;
+        for value in subgraph_headers.get_all(name) {
+            inserted = true;
+            self.append(target.clone(), value.clone(), merge);
+        }
+
+        if let Some(value) = default
+            .and_then(|d| HeaderValue::from_str(d).ok())
+            .filter(|_| !inserted)
+        {
+            self.append(target, value, merge);
+        }
+    }
+
+    fn append(&mut self, name: HeaderName, value: HeaderValue, merge: HeaderMergeStrategy) {
+        match self.merge_strategies.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, strategy)) => *strategy = merge,
+            None => self.merge_strategies.push((name.clone(), merge)),
+        }
+        self.headers.append(name, value);
+    }
+
+    fn merge_into(self, target: &mut http::HeaderMap) {
+        let Self {
+            mut headers,
+            merge_strategies,
+        } = self;
+
+        for (name, strategy) in merge_strategies {
+            if !headers.contains_key(&name) {
+                continue;
+            }
+
+            match strategy {
+                HeaderMergeStrategy::First => {
+                    if !target.contains_key(&name) {
+                        append_all(target, &name, &mut headers);
+                    }
+                }
+                HeaderMergeStrategy::Last => {
+                    target.remove(&name);
+                    append_all(target, &name, &mut headers);
+                }
+                HeaderMergeStrategy::Append => append_all(target, &name, &mut headers),
+                HeaderMergeStrategy::MostRestrictiveCacheControl => {
+                    let merged = most_restrictive_cache_control(
+                        target.get_all(&name).iter().chain(headers.get_all(&name).iter()),
+                    );
+                    target.remove(&name);
+                    if let Some(value) = merged {
+                        target.insert(name, value);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn append_all(target: &mut http::HeaderMap, name: &HeaderName, source: &mut http::HeaderMap) {
+    if let http::header::Entry::Occupied(entry) = source.entry(name) {
+        for value in entry.remove_entry_mult().1 {
+            target.append(name.clone(), value);
+        }
+    }
+}
+
+/// Combines multiple `cache-control` values, keeping the directives restricting caching the
+/// most and the lowest max-age. Unparseable values are ignored. A single value is kept as is to
+/// not lose any directive unknown to the parser, such as `stale-while-revalidate`.
+fn most_restrictive_cache_control<'a>(values: impl Iterator<Item = &'a HeaderValue>) -> Option<HeaderValue> {
+    let values = values.collect::<Vec<_>>();
+    if let [value] = values.as_slice() {
+        return Some((*value).clone());
+    }
+
+    let mut merged: Option<headers::CacheControl> = None;
+
+    for value in values {
+        let Ok(cache_control) = headers::CacheControl::decode(&mut std::iter::once(value)) else {
+            continue;
+        };
+
+        merged = Some(match merged {
+            None => cache_control,
+            Some(current) => {
+                let mut result = headers::CacheControl::new();
+                if current.no_store() || cache_control.no_store() {
+                    result = result.with_no_store();
+                }
+                if current.no_cache() || cache_control.no_cache() {
+                    result = result.with_no_cache();
+                }
+                if current.must_revalidate() || cache_control.must_revalidate() {
+                    result = result.with_must_revalidate();
+                }
+                if current.private() || cache_control.private() {
+                    result = result.with_private();
+                } else if current.public() && cache_control.public() {
+                    result = result.with_public();
+                }
+                if let Some(max_age) = min_duration(current.max_age(), cache_control.max_age()) {
+                    result = result.with_max_age(max_age);
+                }
+                if let Some(s_max_age) = min_duration(current.s_max_age(), cache_control.s_max_age()) {
+                    result = result.with_s_max_age(s_max_age);
+                }
+                result
+            }
+        });
+    }
+
+    let mut values = Vec::new();
+    merged?.encode(&mut values);
+    values.into_iter().next()
+}
+
+fn min_duration(left: Option<std::time::Duration>, right: Option<std::time::Duration>) -> Option<std::time::Duration> {
+    match (left, right) {
+        (Some(left), Some(right)) => Some(left.min(right)),
+        (left, right) => left.or(right),
+    }
+}
+
+fn is_header_denied(name: &HeaderName) -> bool {
+    // Sorted for the binary search, those are either managed by the gateway or hop-by-hop.
+    const DENY_LIST: [&str; 11] = [
+        "connection",
+        "content-encoding",
+        "content-length",
+        "content-type",
+        "keep-alive",
+        "proxy-authenticate",
+        "proxy-authorization",
+        "te",
+        "trailer",
+        "transfer-encoding",
+        "upgrade",
+    ];
+    DENY_LIST.binary_search(&name.as_str()).is_ok()
+}
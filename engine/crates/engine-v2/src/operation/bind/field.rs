@@ -6,7 +6,7 @@ use schema::{Definition, FieldDefinitionId, FieldDefinitionWalker};
 use super::{coercion::coerce_query_value, BindError, BindResult, Binder};
 use crate::{
     operation::{
-        Field, FieldArgument, FieldArgumentId, FieldId, Location, QueryField, QueryInputValue, SelectionSetId,
+        DeferId, Field, FieldArgument, FieldArgumentId, FieldId, Location, QueryField, QueryInputValue, SelectionSetId,
         SelectionSetType, TypeNameField,
     },
     response::BoundResponseKey,
@@ -19,12 +19,14 @@ impl<'schema, 'p> Binder<'schema, 'p> {
         type_condition: SelectionSetType,
         bound_response_key: BoundResponseKey,
         Positioned { pos, .. }: &'p Positioned<engine_parser::types::Field>,
+        defer_id: Option<DeferId>,
     ) -> BindResult<FieldId> {
         Ok(self.push_field(Field::TypeName(TypeNameField {
             type_condition,
             bound_response_key,
             location: (*pos).try_into()?,
             parent_selection_set_id,
+            defer_id,
         })))
     }
 
@@ -35,6 +37,7 @@ impl<'schema, 'p> Binder<'schema, 'p> {
         definition_id: FieldDefinitionId,
         Positioned { pos, node: field }: &'p Positioned<engine_parser::types::Field>,
         selection_set_id: Option<SelectionSetId>,
+        defer_id: Option<DeferId>,
     ) -> BindResult<FieldId> {
         let location: Location = (*pos).try_into()?;
         let definition: FieldDefinitionWalker<'_> = self.schema.walk(definition_id);
@@ -69,6 +72,7 @@ impl<'schema, 'p> Binder<'schema, 'p> {
             argument_ids,
             selection_set_id,
             parent_selection_set_id,
+            defer_id,
         }));
 
         self.generate_field_modifiers(field_id, argument_ids, definition);
@@ -21,7 +21,8 @@ use super::{
 use crate::{
     operation::SelectionSetType,
     operation::{
-        Field, FieldArgument, FieldArgumentId, Location, Operation, SelectionSet, SelectionSetId, VariableDefinition,
+        DeferredFragment, Field, FieldArgument, FieldArgumentId, Location, Operation, SelectionSet, SelectionSetId,
+        VariableDefinition,
     },
     response::{ErrorCode, GraphqlError, ResponseKeys},
 };
@@ -107,6 +108,28 @@ pub enum BindError {
     QueryContainsTooManyRootFields { count: usize, location: Location },
     #[error("Query contains too many aliases.")]
     QueryContainsTooManyAliases { count: usize, location: Location },
+    #[error("Label '{label}' is used more than once by @defer or @stream, labels must be unique within an operation.")]
+    DuplicateIncrementalDeliveryLabel { label: String, location: Location },
+    #[error("@defer argument '{name}' must be a {expected}.")]
+    InvalidDeferArgument {
+        name: String,
+        expected: &'static str,
+        location: Location,
+    },
+    #[error("@stream argument '{name}' must be a {expected}.")]
+    InvalidStreamArgument {
+        name: String,
+        expected: &'static str,
+        location: Location,
+    },
+    #[error("@stream can only be used on list fields, but field '{name}' has type '{ty}'.")]
+    StreamOnNonListField {
+        name: String,
+        ty: String,
+        location: Location,
+    },
+    #[error("Field '{name}' is selected multiple times with different @stream directives.")]
+    InconsistentStreamDirectives { name: String, location: Location },
 }
 
 impl From<BindError> for GraphqlError {
@@ -129,7 +152,12 @@ impl From<BindError> for GraphqlError {
             | BindError::QueryTooComplex { location, .. }
             | BindError::QueryTooDeep { location, .. }
             | BindError::QueryContainsTooManyAliases { location, .. }
-            | BindError::QueryContainsTooManyRootFields { location, .. } => vec![location],
+            | BindError::QueryContainsTooManyRootFields { location, .. }
+            | BindError::DuplicateIncrementalDeliveryLabel { location, .. }
+            | BindError::InvalidDeferArgument { location, .. }
+            | BindError::InvalidStreamArgument { location, .. }
+            | BindError::StreamOnNonListField { location, .. }
+            | BindError::InconsistentStreamDirectives { location, .. } => vec![location],
             BindError::InvalidInputValue(ref err) => vec![err.location()],
             BindError::NoMutationDefined | BindError::NoSubscriptionDefined | BindError::QueryTooBig { .. } => {
                 vec![]
@@ -154,6 +182,7 @@ pub(crate) struct Binder<'schema, 'p> {
     input_values: QueryInputValues,
     query_modifiers: HashMap<QueryModifierRule, (QueryModifierId, Vec<FieldId>)>,
     response_modifiers: HashMap<ResponseModifierRule, (ResponseModifierId, Vec<FieldId>)>,
+    deferred_fragments: Vec<DeferredFragment>,
 }
 
 id_newtypes::index! {
@@ -188,6 +217,7 @@ pub fn bind_operation(schema: &Schema, mut parsed_operation: ParsedOperation) ->
         query_modifiers: Default::default(),
         input_values: QueryInputValues::default(),
         response_modifiers: Default::default(),
+        deferred_fragments: Vec::new(),
     };
 
     // Must be executed before binding selection sets
@@ -195,7 +225,7 @@ pub fn bind_operation(schema: &Schema, mut parsed_operation: ParsedOperation) ->
 
     let root_selection_set_id = binder.bind_merged_selection_sets(
         SelectionSetType::Object(root_object_id),
-        &[&parsed_operation.definition.selection_set],
+        &[(&parsed_operation.definition.selection_set, None)],
     )?;
 
     binder.validate_all_variables_used()?;
@@ -219,6 +249,7 @@ pub fn bind_operation(schema: &Schema, mut parsed_operation: ParsedOperation) ->
         query_modifier_impacted_fields,
         response_modifiers,
         response_modifier_impacted_fields,
+        deferred_fragments: binder.deferred_fragments,
     })
 }
 
@@ -23,6 +23,9 @@ impl<'schema, 'p> super::Binder<'schema, 'p> {
                 TypeSystemDirective::RequiresScopes(id) => {
                     self.register_field_impacted_by_query_modifier(QueryModifierRule::RequiresScopes(*id), field_id);
                 }
+                TypeSystemDirective::Policy(id) => {
+                    self.register_field_impacted_by_query_modifier(QueryModifierRule::Policy(*id), field_id);
+                }
                 TypeSystemDirective::Authorized(id) => {
                     let directive = &self.schema[*id];
                     match (directive.fields.is_some(), directive.node.is_some()) {
@@ -72,6 +75,9 @@ impl<'schema, 'p> super::Binder<'schema, 'p> {
                 TypeSystemDirective::RequiresScopes(id) => {
                     self.register_field_impacted_by_query_modifier(QueryModifierRule::RequiresScopes(*id), field_id);
                 }
+                TypeSystemDirective::Policy(id) => {
+                    self.register_field_impacted_by_query_modifier(QueryModifierRule::Policy(*id), field_id);
+                }
                 TypeSystemDirective::Authorized(id) => {
                     self.register_field_impacted_by_query_modifier(
                         QueryModifierRule::AuthorizedDefinition {
@@ -99,6 +105,9 @@ impl<'schema, 'p> super::Binder<'schema, 'p> {
                 TypeSystemDirective::RequiresScopes(id) => {
                     modifiers.push(self.push_root_object_query_modifier(QueryModifierRule::RequiresScopes(*id)));
                 }
+                TypeSystemDirective::Policy(id) => {
+                    modifiers.push(self.push_root_object_query_modifier(QueryModifierRule::Policy(*id)));
+                }
                 TypeSystemDirective::Authorized(id) => {
                     modifiers.push(
                         self.push_root_object_query_modifier(QueryModifierRule::AuthorizedDefinition {
@@ -1,21 +1,24 @@
 use std::borrow::Cow;
 
-use engine_parser::Positioned;
+use engine_parser::{types::OperationType, Positioned};
 use im::HashMap;
 use schema::{Definition, FieldDefinitionId, ObjectDefinitionId};
 
 use crate::{
-    operation::{FieldId, Location, QueryPosition, SelectionSet, SelectionSetId, SelectionSetType},
-    response::SafeResponseKey,
+    operation::{
+        closest_common_deferred_fragment, DeferId, DeferredFragment, FieldId, Location, QueryPosition, SelectionSet,
+        SelectionSetId, SelectionSetType, StreamInitialCount, StreamedList, VariableDefinitionId,
+    },
+    response::{BoundResponseKey, SafeResponseKey},
 };
 
-use super::{BindError, BindResult, Binder};
+use super::{coercion::InputValueError, BindError, BindResult, Binder};
 
 impl<'schema, 'p> Binder<'schema, 'p> {
     pub(super) fn bind_merged_selection_sets(
         &mut self,
         ty: SelectionSetType,
-        merged_selection_sets: &[&'p Positioned<engine_parser::types::SelectionSet>],
+        merged_selection_sets: &[(&'p Positioned<engine_parser::types::SelectionSet>, Option<DeferId>)],
     ) -> BindResult<SelectionSetId> {
         SelectionSetBinder::new(self).bind(ty, merged_selection_sets)
     }
@@ -27,12 +30,22 @@ pub(super) struct SelectionSetBinder<'schema, 'parsed, 'binder> {
     #[allow(clippy::type_complexity)]
     fields: HashMap<
         (SafeResponseKey, FieldDefinitionId),
-        (QueryPosition, Vec<&'parsed Positioned<engine_parser::types::Field>>),
+        (
+            QueryPosition,
+            Vec<(&'parsed Positioned<engine_parser::types::Field>, Option<DeferId>)>,
+        ),
     >,
     #[allow(clippy::type_complexity)]
     typename_fields: HashMap<
         SafeResponseKey,
-        HashMap<SelectionSetType, (QueryPosition, &'parsed Positioned<engine_parser::types::Field>)>,
+        HashMap<
+            SelectionSetType,
+            (
+                QueryPosition,
+                &'parsed Positioned<engine_parser::types::Field>,
+                Option<DeferId>,
+            ),
+        >,
     >,
 }
 
@@ -63,10 +76,10 @@ impl<'schema, 'p, 'binder> SelectionSetBinder<'schema, 'p, 'binder> {
     fn bind(
         mut self,
         ty: SelectionSetType,
-        merged_selection_sets: &[&'p Positioned<engine_parser::types::SelectionSet>],
+        merged_selection_sets: &[(&'p Positioned<engine_parser::types::SelectionSet>, Option<DeferId>)],
     ) -> BindResult<SelectionSetId> {
-        for selection_set in merged_selection_sets {
-            self.register_selection_set_fields(ty, selection_set)?;
+        for (selection_set, defer_id) in merged_selection_sets {
+            self.register_selection_set_fields(ty, selection_set, *defer_id)?;
         }
         let id = SelectionSetId::from(self.selection_sets.len());
         self.selection_sets.push(SelectionSet::default());
@@ -89,24 +102,40 @@ impl<'schema, 'p, 'binder> SelectionSetBinder<'schema, 'p, 'binder> {
         for ((response_key, definition_id), (query_position, fields)) in std::mem::take(&mut self.fields) {
             let field: &'p Positioned<engine_parser::types::Field> = fields
                 .iter()
+                .map(|(field, _)| *field)
                 .min_by_key(|field| field.pos.line)
                 .expect("At least one occurence");
+            // A field is delivered with the earliest payload any of its occurrences belongs to.
+            let defer_id = fields
+                .iter()
+                .map(|(_, defer_id)| *defer_id)
+                .reduce(|acc, defer_id| closest_common_deferred_fragment(&self.deferred_fragments, acc, defer_id))
+                .flatten();
             let bound_response_key = response_key
                 .with_position(query_position)
                 .ok_or(BindError::TooManyFields {
                     location: field.pos.try_into()?,
                 })?;
+            let stream_id = self.bind_stream_directive(&fields, definition_id, bound_response_key, defer_id)?;
+            // If no items are sent initially, nothing within them needs to be resolved for the
+            // initial payload. With a variable we can't know it while binding.
+            let items_defer_id = stream_id.filter(|id| {
+                self.deferred_fragments[usize::from(*id)]
+                    .stream
+                    .map(|s| s.initial_count)
+                    == Some(StreamInitialCount::Value(0))
+            });
             let selection_set_id = SelectionSetType::maybe_from(self.schema.walk(definition_id).ty().inner().id())
                 .map(|ty| {
                     let merged_selection_sets = fields
                         .into_iter()
-                        .map(|field| &field.node.selection_set)
+                        .map(|(field, defer_id)| (&field.node.selection_set, items_defer_id.or(defer_id)))
                         .collect::<Vec<_>>();
                     self.binder.bind_merged_selection_sets(ty, &merged_selection_sets)
                 })
                 .transpose()?;
 
-            field_ids.push(self.bind_field(id, bound_response_key, definition_id, field, selection_set_id)?)
+            field_ids.push(self.bind_field(id, bound_response_key, definition_id, field, selection_set_id, defer_id)?)
         }
 
         for (response_key, typename_fields) in std::mem::take(&mut self.typename_fields) {
@@ -114,28 +143,28 @@ impl<'schema, 'p, 'binder> SelectionSetBinder<'schema, 'p, 'binder> {
             // only keep that one.
             if typename_fields
                 .get(&ty)
-                .map(|(qpos, _)| Some(qpos) == typename_fields.values().map(|(qpos, _)| qpos).min())
+                .map(|(qpos, _, _)| Some(qpos) == typename_fields.values().map(|(qpos, _, _)| qpos).min())
                 .unwrap_or_default()
             {
-                let (query_position, field) = typename_fields.get(&ty).unwrap();
+                let (query_position, field, defer_id) = typename_fields.get(&ty).unwrap();
                 let bound_response_key =
                     response_key
                         .with_position(*query_position)
                         .ok_or(BindError::TooManyFields {
                             location: field.pos.try_into()?,
                         })?;
-                field_ids.push(self.bind_typename_field(id, ty, bound_response_key, field)?);
+                field_ids.push(self.bind_typename_field(id, ty, bound_response_key, field, *defer_id)?);
 
                 continue;
             }
-            for (type_condition, (query_position, field)) in typename_fields {
+            for (type_condition, (query_position, field, defer_id)) in typename_fields {
                 let bound_response_key =
                     response_key
                         .with_position(query_position)
                         .ok_or(BindError::TooManyFields {
                             location: field.pos.try_into()?,
                         })?;
-                field_ids.push(self.bind_typename_field(id, type_condition, bound_response_key, field)?)
+                field_ids.push(self.bind_typename_field(id, type_condition, bound_response_key, field, defer_id)?)
             }
         }
 
@@ -146,6 +175,7 @@ impl<'schema, 'p, 'binder> SelectionSetBinder<'schema, 'p, 'binder> {
         &mut self,
         ty: SelectionSetType,
         selection_set: &'p Positioned<engine_parser::types::SelectionSet>,
+        defer_id: Option<DeferId>,
     ) -> BindResult<()> {
         let Positioned {
             node: selection_set, ..
@@ -154,13 +184,13 @@ impl<'schema, 'p, 'binder> SelectionSetBinder<'schema, 'p, 'binder> {
         for Positioned { node: selection, .. } in &selection_set.items {
             match selection {
                 engine_parser::types::Selection::Field(field) => {
-                    self.register_field(ty, field)?;
+                    self.register_field(ty, field, defer_id)?;
                 }
                 engine_parser::types::Selection::FragmentSpread(spread) => {
-                    self.register_fragment_spread_fields(ty, spread)?;
+                    self.register_fragment_spread_fields(ty, spread, defer_id)?;
                 }
                 engine_parser::types::Selection::InlineFragment(fragment) => {
-                    self.register_inline_fragment_fields(ty, fragment)?;
+                    self.register_inline_fragment_fields(ty, fragment, defer_id)?;
                 }
             }
         }
@@ -172,6 +202,7 @@ impl<'schema, 'p, 'binder> SelectionSetBinder<'schema, 'p, 'binder> {
         &mut self,
         parent: SelectionSetType,
         field: &'p Positioned<engine_parser::types::Field>,
+        defer_id: Option<DeferId>,
     ) -> BindResult<()> {
         let name_location: Location = field.pos.try_into()?;
         let walker = self.schema.walker();
@@ -186,11 +217,15 @@ impl<'schema, 'p, 'binder> SelectionSetBinder<'schema, 'p, 'binder> {
         let query_position = self.next_query_position();
 
         if name == "__typename" {
-            self.typename_fields
+            let (_, _, typename_defer_id) = self
+                .typename_fields
                 .entry(response_key)
                 .or_default()
                 .entry(parent)
-                .or_insert((query_position, field));
+                .or_insert((query_position, field, defer_id));
+            // Same as other fields, delivered with the earliest payload of its occurrences.
+            *typename_defer_id =
+                closest_common_deferred_fragment(&self.binder.deferred_fragments, *typename_defer_id, defer_id);
             return Ok(());
         }
 
@@ -215,7 +250,7 @@ impl<'schema, 'p, 'binder> SelectionSetBinder<'schema, 'p, 'binder> {
             .entry((response_key, definition_id))
             .or_insert((query_position, Vec::new()))
             .1
-            .push(field);
+            .push((field, defer_id));
 
         Ok(())
     }
@@ -224,6 +259,7 @@ impl<'schema, 'p, 'binder> SelectionSetBinder<'schema, 'p, 'binder> {
         &mut self,
         parent: SelectionSetType,
         Positioned { pos, node: spread }: &'p Positioned<engine_parser::types::FragmentSpread>,
+        defer_id: Option<DeferId>,
     ) -> BindResult<()> {
         let location = (*pos).try_into()?;
         // We always create a new selection set from a named fragment. It may not be split in the
@@ -238,7 +274,8 @@ impl<'schema, 'p, 'binder> SelectionSetBinder<'schema, 'p, 'binder> {
             })?;
 
         let ty = self.bind_selection_set_type(parent, &fragment.node.type_condition)?;
-        self.register_selection_set_fields(ty, &fragment.node.selection_set)?;
+        let defer_id = self.bind_defer_directive(&spread.directives, defer_id)?;
+        self.register_selection_set_fields(ty, &fragment.node.selection_set, defer_id)?;
 
         Ok(())
     }
@@ -247,6 +284,7 @@ impl<'schema, 'p, 'binder> SelectionSetBinder<'schema, 'p, 'binder> {
         &mut self,
         parent: SelectionSetType,
         Positioned { node: fragment, .. }: &'p Positioned<engine_parser::types::InlineFragment>,
+        defer_id: Option<DeferId>,
     ) -> BindResult<()> {
         let ty = fragment
             .type_condition
@@ -254,7 +292,252 @@ impl<'schema, 'p, 'binder> SelectionSetBinder<'schema, 'p, 'binder> {
             .map(|condition| self.bind_selection_set_type(parent, condition))
             .transpose()?
             .unwrap_or(parent);
-        self.register_selection_set_fields(ty, &fragment.selection_set)
+        let defer_id = self.bind_defer_directive(&fragment.directives, defer_id)?;
+        self.register_selection_set_fields(ty, &fragment.selection_set, defer_id)
+    }
+
+    /// Returns the deferred fragment the selection set belongs to, creating a new one if the
+    /// fragment has a `@defer` directive.
+    fn bind_defer_directive(
+        &mut self,
+        directives: &'p [Positioned<engine_parser::types::Directive>],
+        parent_id: Option<DeferId>,
+    ) -> BindResult<Option<DeferId>> {
+        // Mutation root fields must be executed serially and subscriptions already stream their
+        // responses, so like other servers we only honor @defer within queries. Ignoring it is
+        // always valid for the client.
+        if !matches!(self.parsed_operation.definition.ty, OperationType::Query) {
+            return Ok(parent_id);
+        }
+
+        let Some(Positioned { pos, node: directive }) = directives
+            .iter()
+            .find(|directive| directive.node.name.node.as_str() == "defer")
+        else {
+            return Ok(parent_id);
+        };
+        let location: Location = (*pos).try_into()?;
+
+        let mut label = None;
+        let mut if_variable = None;
+        for (name, value) in &directive.arguments {
+            match (name.node.as_str(), &value.node) {
+                ("label", engine_value::Value::String(value)) => label = Some(value.clone()),
+                ("if", engine_value::Value::Boolean(true)) => {}
+                ("if", engine_value::Value::Boolean(false)) => return Ok(parent_id),
+                ("if", engine_value::Value::Variable(name)) => {
+                    if_variable = Some(self.bind_directive_variable(name, "Boolean", location)?)
+                }
+                (name @ "label", _) => {
+                    return Err(BindError::InvalidDeferArgument {
+                        name: name.to_string(),
+                        expected: "string literal",
+                        location,
+                    })
+                }
+                (name @ "if", _) => {
+                    return Err(BindError::InvalidDeferArgument {
+                        name: name.to_string(),
+                        expected: "boolean",
+                        location,
+                    })
+                }
+                _ => {}
+            }
+        }
+
+        // Named fragments may be spread multiple times, but it's still the same deferred fragment.
+        if let Some(i) = self.deferred_fragments.iter().position(|fragment| {
+            fragment.stream.is_none() && fragment.location == location && fragment.parent_id == parent_id
+        }) {
+            return Ok(Some(DeferId::from(i)));
+        }
+
+        self.ensure_unique_label(label.as_ref(), location)?;
+        self.deferred_fragments.push(DeferredFragment {
+            label,
+            parent_id,
+            location,
+            if_variable,
+            stream: None,
+        });
+        Ok(Some(DeferId::from(self.deferred_fragments.len() - 1)))
+    }
+
+    /// Returns the deferred fragment for the remaining items of the list field if it has a
+    /// `@stream` directive.
+    fn bind_stream_directive(
+        &mut self,
+        fields: &[(&'p Positioned<engine_parser::types::Field>, Option<DeferId>)],
+        definition_id: FieldDefinitionId,
+        bound_response_key: BoundResponseKey,
+        parent_id: Option<DeferId>,
+    ) -> BindResult<Option<DeferId>> {
+        // Same as @defer, only honored within queries.
+        if !matches!(self.parsed_operation.definition.ty, OperationType::Query) {
+            return Ok(None);
+        }
+
+        let mut streams = Vec::with_capacity(fields.len());
+        for (field, _) in fields {
+            streams.push(self.parse_stream_directive(&field.node.directives)?);
+        }
+        let Some(Some(ParsedStream {
+            label,
+            initial_count,
+            if_variable,
+            location,
+        })) = streams.first().cloned()
+        else {
+            if let Some(stream) = streams.into_iter().flatten().next() {
+                return Err(self.inconsistent_stream_directives(definition_id, stream.location));
+            }
+            return Ok(None);
+        };
+        // Which items are sent first would be ambiguous otherwise.
+        if streams.iter().any(|stream| {
+            stream
+                .as_ref()
+                .map(|stream| (&stream.label, stream.initial_count, stream.if_variable))
+                != Some((&label, initial_count, if_variable))
+        }) {
+            return Err(self.inconsistent_stream_directives(definition_id, location));
+        }
+
+        let definition = self.schema.walk(definition_id);
+        if !definition.ty().wrapping().is_list() {
+            return Err(BindError::StreamOnNonListField {
+                name: definition.name().to_string(),
+                ty: definition.ty().to_string(),
+                location,
+            });
+        }
+
+        self.ensure_unique_label(label.as_ref(), location)?;
+        self.deferred_fragments.push(DeferredFragment {
+            label,
+            parent_id,
+            location,
+            if_variable,
+            stream: Some(StreamedList {
+                bound_response_key,
+                initial_count,
+            }),
+        });
+        Ok(Some(DeferId::from(self.deferred_fragments.len() - 1)))
+    }
+
+    /// Returns the `@stream` directive arguments if present and enabled.
+    fn parse_stream_directive(
+        &self,
+        directives: &'p [Positioned<engine_parser::types::Directive>],
+    ) -> BindResult<Option<ParsedStream>> {
+        let Some(Positioned { pos, node: directive }) = directives
+            .iter()
+            .find(|directive| directive.node.name.node.as_str() == "stream")
+        else {
+            return Ok(None);
+        };
+        let location: Location = (*pos).try_into()?;
+
+        let mut label = None;
+        let mut initial_count = StreamInitialCount::Value(0);
+        let mut if_variable = None;
+        for (name, value) in &directive.arguments {
+            match (name.node.as_str(), &value.node) {
+                ("label", engine_value::Value::String(value)) => label = Some(value.clone()),
+                ("initialCount", engine_value::Value::Number(value)) if value.as_u64().is_some() => {
+                    initial_count = StreamInitialCount::Value(value.as_u64().unwrap_or_default() as usize)
+                }
+                ("initialCount", engine_value::Value::Variable(name)) => {
+                    initial_count = StreamInitialCount::Variable(self.bind_directive_variable(name, "Int", location)?)
+                }
+                ("if", engine_value::Value::Boolean(true)) => {}
+                ("if", engine_value::Value::Boolean(false)) => return Ok(None),
+                ("if", engine_value::Value::Variable(name)) => {
+                    if_variable = Some(self.bind_directive_variable(name, "Boolean", location)?)
+                }
+                (name, _) => {
+                    let expected = match name {
+                        "label" => "string literal",
+                        "initialCount" => "non-negative integer",
+                        "if" => "boolean",
+                        _ => continue,
+                    };
+                    return Err(BindError::InvalidStreamArgument {
+                        name: name.to_string(),
+                        expected,
+                        location,
+                    });
+                }
+            }
+        }
+
+        Ok(Some(ParsedStream {
+            label,
+            initial_count,
+            if_variable,
+            location,
+        }))
+    }
+
+    /// Variables used by `@defer` and `@stream` arguments are resolved during execution, as
+    /// operations are cached independently of them.
+    fn bind_directive_variable(
+        &self,
+        name: &str,
+        scalar: &str,
+        location: Location,
+    ) -> BindResult<VariableDefinitionId> {
+        let Some(id) = self
+            .variable_definitions
+            .iter()
+            .position(|variable| variable.name == name)
+        else {
+            return Err(InputValueError::UnknownVariable {
+                name: name.to_string(),
+                location,
+                path: String::new(),
+            }
+            .into());
+        };
+
+        let ty = self.variable_definitions[id].ty;
+        if ty.wrapping.is_list() || self.schema.walker().walk(ty.inner).name() != scalar {
+            return Err(InputValueError::IncorrectVariableType {
+                name: name.to_string(),
+                variable_ty: self.schema.walk(ty).to_string(),
+                actual_ty: scalar.to_string(),
+                location,
+                path: String::new(),
+            }
+            .into());
+        }
+
+        Ok(id.into())
+    }
+
+    fn inconsistent_stream_directives(&self, definition_id: FieldDefinitionId, location: Location) -> BindError {
+        BindError::InconsistentStreamDirectives {
+            name: self.schema.walk(definition_id).name().to_string(),
+            location,
+        }
+    }
+
+    fn ensure_unique_label(&self, label: Option<&String>, location: Location) -> BindResult<()> {
+        if let Some(label) = label {
+            if self
+                .deferred_fragments
+                .iter()
+                .any(|fragment| fragment.label.as_ref() == Some(label) && fragment.location != location)
+            {
+                return Err(BindError::DuplicateIncrementalDeliveryLabel {
+                    label: label.clone(),
+                    location,
+                });
+            }
+        }
+        Ok(())
     }
 
     fn bind_selection_set_type(
@@ -318,3 +601,11 @@ impl<'schema, 'p, 'binder> SelectionSetBinder<'schema, 'p, 'binder> {
         QueryPosition::from(query_position)
     }
 }
+
+#[derive(Clone)]
+struct ParsedStream {
+    label: Option<String>,
+    initial_count: StreamInitialCount,
+    if_variable: Option<VariableDefinitionId>,
+    location: Location,
+}
@@ -4,7 +4,10 @@ use engine::Positioned;
 use schema::{Definition, Schema};
 
 use crate::{
-    operation::{Location, Operation, VariableDefinition, VariableInputValues, VariableValue, Variables},
+    operation::{
+        Location, Operation, StreamInitialCount, VariableDefinition, VariableDefinitionId, VariableInputValues,
+        VariableValue, Variables,
+    },
     response::{ErrorCode, GraphqlError},
 };
 
@@ -113,8 +116,14 @@ impl<'schema, 'p> Binder<'schema, 'p> {
     }
 
     pub(super) fn validate_all_variables_used(&self) -> BindResult<()> {
-        for variable in &self.variable_definitions {
-            if variable.used_by.is_empty() {
+        for (i, variable) in self.variable_definitions.iter().enumerate() {
+            let id = VariableDefinitionId::from(i);
+            // Variables of @defer and @stream arguments aren't used by any field.
+            let used_by_directive = self.deferred_fragments.iter().any(|fragment| {
+                fragment.if_variable == Some(id)
+                    || fragment.stream.map(|stream| stream.initial_count) == Some(StreamInitialCount::Variable(id))
+            });
+            if variable.used_by.is_empty() && !used_by_directive {
                 return Err(BindError::UnusedVariable {
                     name: variable.name.clone(),
                     operation: self.operation_name.clone(),
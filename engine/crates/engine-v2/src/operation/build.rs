@@ -1,4 +1,4 @@
-use schema::Schema;
+use schema::{OverrideLabelId, Schema};
 use tracing::instrument;
 
 use crate::response::{ErrorCode, GraphqlError};
@@ -6,6 +6,7 @@ use crate::response::{ErrorCode, GraphqlError};
 use super::{
     bind::{bind_operation, BindError},
     blueprint::ResponseBlueprintBuilder,
+    cache_control::compute_operation_cache_control,
     logical_planner::{LogicalPlanner, LogicalPlanningError},
     metrics::{generate_used_fields, prepare_metrics_attributes},
     parse::{parse_operation, ParseError},
@@ -65,8 +66,15 @@ impl Operation {
     ///
     /// All field names are mapped to their actual field id in the schema and respective configuration.
     /// At this stage the operation might not be resolvable but it should make sense given the schema types.
+    ///
+    /// Fields with a progressive override are planned with the subgraph selected by the active
+    /// override labels.
     #[instrument(skip_all)]
-    pub fn build(schema: &Schema, request: &engine::Request) -> Result<PreparedOperation, OperationError> {
+    pub fn build(
+        schema: &Schema,
+        request: &engine::Request,
+        active_override_labels: &[OverrideLabelId],
+    ) -> Result<PreparedOperation, OperationError> {
         let parsed_operation = parse_operation(request)?;
         let metrics_attributes = prepare_metrics_attributes(&parsed_operation, request);
 
@@ -87,7 +95,7 @@ impl Operation {
             });
         }
 
-        let plan = match LogicalPlanner::new(schema, &mut operation).plan() {
+        let plan = match LogicalPlanner::new(schema, active_override_labels, &mut operation).plan() {
             Ok(plan) => plan,
             Err(err) => {
                 return Err(OperationError::LogicalPlanning {
@@ -101,12 +109,14 @@ impl Operation {
 
         let mut metrics_attributes = metrics_attributes.ok_or(OperationError::NormalizationError)?;
         metrics_attributes.used_fields = generate_used_fields(schema, &operation);
+        let cache_control = compute_operation_cache_control(schema, &operation);
 
         Ok(PreparedOperation {
             operation,
             metrics_attributes,
             plan,
             response_blueprint,
+            cache_control,
         })
     }
 }
@@ -0,0 +1,40 @@
+use schema::{CacheControl, Schema};
+
+use super::Operation;
+
+/// Computes the cache control of the whole response, the most restrictive one over all the
+/// fields the operation touches.
+///
+/// A root field without any `@cacheControl` makes the response uncacheable. Nested fields without
+/// one inherit from their parent field.
+pub(super) fn compute_operation_cache_control(schema: &Schema, operation: &Operation) -> Option<CacheControl> {
+    let mut cache_control: Option<CacheControl> = None;
+
+    for field in &operation.fields {
+        let Some(definition_id) = field.definition_id() else {
+            continue;
+        };
+
+        let definition = schema.walk(definition_id);
+        // Skipping introspection related fields
+        if definition.name().starts_with("__") {
+            continue;
+        }
+
+        let entity = definition.parent_entity();
+        let field_cache_control = CacheControl::union_opt(
+            definition.directives().cache_control(),
+            entity.directives().cache_control(),
+        );
+
+        match field_cache_control {
+            Some(field_cache_control) => {
+                cache_control = CacheControl::union_opt(cache_control.as_ref(), Some(&field_cache_control));
+            }
+            None if field.parent_selection_set_id() == operation.root_selection_set_id => return None,
+            None => {}
+        }
+    }
+
+    cache_control.filter(|cache_control| !cache_control.max_age.is_zero())
+}
@@ -0,0 +1,134 @@
+//! Demand control: static estimation of the cost of an operation from the `@cost` and
+//! `@listSize` directives, before executing it.
+use schema::{Definition, FieldDefinitionWalker, ListSize, Schema, StringId};
+use serde::Deserialize;
+
+use super::{Field, FieldId, PreparedOperation, PreparedOperationWalker, SelectionSetId, Variables};
+
+/// Estimates the cost of the operation with the given variables:
+/// - Every field costs the weight of its `@cost` directive, or of the one of its output type.
+///   Without any, composite types cost 1 and leaf types nothing.
+/// - The cost of a list field and its sub-selection is multiplied by its estimated size: the
+///   largest provided slicing argument, the assumed size or the default list size otherwise.
+/// - With `sizedFields`, the estimated size applies to those child fields instead, as for
+///   connections.
+pub(crate) fn estimate_cost(
+    schema: &Schema,
+    operation: &PreparedOperation,
+    variables: &Variables,
+    default_list_size: u32,
+) -> u64 {
+    let estimator = CostEstimator {
+        walker: PreparedOperationWalker {
+            schema_walker: schema.walker(),
+            operation,
+            variables,
+            item: (),
+        },
+        default_list_size: u64::from(default_list_size),
+    };
+    estimator.selection_set_cost(operation.root_selection_set_id, None)
+}
+
+struct CostEstimator<'a> {
+    walker: PreparedOperationWalker<'a, (), ()>,
+    default_list_size: u64,
+}
+
+/// Size estimated by the `@listSize` directive of a parent field for some of its children.
+struct SizedFields<'a> {
+    size: u64,
+    names: &'a [StringId],
+}
+
+impl<'a> CostEstimator<'a> {
+    fn selection_set_cost(&self, id: SelectionSetId, sized_fields: Option<&SizedFields<'_>>) -> u64 {
+        self.walker.operation[id]
+            .field_ids_ordered_by_parent_entity_id_then_position
+            .iter()
+            .map(|field_id| {
+                let size = sized_fields.and_then(|sized_fields| {
+                    let name_id = self.definition(*field_id)?.as_ref().name;
+                    sized_fields.names.contains(&name_id).then_some(sized_fields.size)
+                });
+                self.field_cost(*field_id, size)
+            })
+            .fold(0, u64::saturating_add)
+    }
+
+    fn field_cost(&self, field_id: FieldId, size_from_parent: Option<u64>) -> u64 {
+        // Fields added by the planner to satisfy requirements aren't part of the operation sent
+        // by the client.
+        let field = &self.walker.operation[field_id];
+        let Field::Query(_) = field else {
+            return 0;
+        };
+        let Some(definition) = self.definition(field_id) else {
+            return 0;
+        };
+
+        let output = definition.ty().inner();
+        let weight = definition
+            .directives()
+            .cost()
+            .or_else(|| output.directives().cost())
+            .map(|cost| u64::try_from(cost.weight).unwrap_or_default())
+            .unwrap_or_else(|| match output.id() {
+                Definition::Object(_) | Definition::Interface(_) | Definition::Union(_) => 1,
+                _ => 0,
+            });
+
+        let list_size = definition.directives().list_size();
+        let mut sized_fields = None;
+        let multiplier = match list_size {
+            Some(list_size) if !list_size.sized_fields.is_empty() => {
+                sized_fields = Some(SizedFields {
+                    size: self.estimated_list_size(field, Some(list_size)),
+                    names: &list_size.sized_fields,
+                });
+                size_from_parent.unwrap_or(1)
+            }
+            _ if definition.ty().wrapping().is_list() => {
+                size_from_parent.unwrap_or_else(|| self.estimated_list_size(field, list_size))
+            }
+            _ => size_from_parent.unwrap_or(1),
+        };
+
+        let children = field
+            .selection_set_id()
+            .map(|id| self.selection_set_cost(id, sized_fields.as_ref()))
+            .unwrap_or_default();
+
+        multiplier.saturating_mul(weight.saturating_add(children))
+    }
+
+    fn estimated_list_size(&self, field: &Field, list_size: Option<&ListSize>) -> u64 {
+        let Some(list_size) = list_size else {
+            return self.default_list_size;
+        };
+
+        let schema = self.walker.schema_walker;
+        let sliced_size = self
+            .walker
+            .walk(field.argument_ids())
+            .into_iter()
+            .filter(|argument| {
+                list_size
+                    .slicing_arguments
+                    .iter()
+                    .any(|name| &schema[*name] == argument.name())
+            })
+            .filter_map(|argument| argument.value().and_then(|value| u64::deserialize(value).ok()))
+            .max();
+
+        sliced_size
+            .or(list_size.assumed_size.map(u64::from))
+            .unwrap_or(self.default_list_size)
+    }
+
+    fn definition(&self, field_id: FieldId) -> Option<FieldDefinitionWalker<'a>> {
+        self.walker.operation[field_id]
+            .definition_id()
+            .map(|id| self.walker.schema_walker.walk(id))
+    }
+}
@@ -1,4 +1,4 @@
-use schema::{FieldDefinitionId, ProvidableFieldSet, ResolverDefinitionWalker};
+use schema::{FieldDefinitionId, OverrideLabelId, ProvidableFieldSet, ResolverDefinitionWalker};
 
 use crate::operation::LogicalPlanId;
 
@@ -11,6 +11,7 @@ pub(super) enum PlanningLogic<'schema> {
         id: LogicalPlanId,
         resolver: ResolverDefinitionWalker<'schema>,
         providable: ProvidableFieldSet,
+        active_override_labels: &'schema [OverrideLabelId],
     },
     /// Only an explicitly providable (@provide) field can be provided.
     OnlyProvidable {
@@ -27,19 +28,27 @@ impl std::fmt::Display for PlanningLogic<'_> {
 }
 
 impl<'schema> PlanningLogic<'schema> {
-    pub(super) fn new(id: LogicalPlanId, resolver: ResolverDefinitionWalker<'schema>) -> Self {
+    pub(super) fn new(
+        id: LogicalPlanId,
+        resolver: ResolverDefinitionWalker<'schema>,
+        active_override_labels: &'schema [OverrideLabelId],
+    ) -> Self {
         PlanningLogic::SameSubgrah {
             id,
             resolver,
             providable: Default::default(),
+            active_override_labels,
         }
     }
 
     pub(super) fn is_providable(&self, field_id: FieldDefinitionId) -> bool {
         match self {
             PlanningLogic::SameSubgrah {
-                resolver, providable, ..
-            } => resolver.can_provide(field_id) || providable.contains(field_id),
+                resolver,
+                providable,
+                active_override_labels,
+                ..
+            } => resolver.can_provide(field_id, active_override_labels) || providable.contains(field_id),
             PlanningLogic::OnlyProvidable { providable, .. } => providable.contains(field_id),
         }
     }
@@ -50,17 +59,19 @@ impl<'schema> PlanningLogic<'schema> {
                 id,
                 resolver,
                 providable,
+                active_override_labels,
             } => {
                 let subgraph_id = resolver.subgraph_id();
                 let providable = ProvidableFieldSet::union_opt(
                     providable.get(field_id).map(|s| &s.subselection),
                     Some(resolver.walk(field_id).provides(subgraph_id)),
                 );
-                if resolver.can_provide(field_id) {
+                if resolver.can_provide(field_id, active_override_labels) {
                     PlanningLogic::SameSubgrah {
                         id: *id,
                         resolver: *resolver,
                         providable,
+                        active_override_labels,
                     }
                 } else {
                     PlanningLogic::OnlyProvidable {
@@ -6,13 +6,15 @@ use std::borrow::Cow;
 use engine_parser::types::OperationType;
 use id_newtypes::{BitSet, IdToMany};
 use itertools::Itertools;
-use schema::{EntityId, FieldDefinitionId, RequiredFieldId, RequiredFieldSet, ResolverDefinitionId, Schema};
+use schema::{
+    EntityId, FieldDefinitionId, OverrideLabelId, RequiredFieldId, RequiredFieldSet, ResolverDefinitionId, Schema,
+};
 use tracing::instrument;
 
 use crate::{
     operation::{
-        FieldId, LogicalPlan, LogicalPlanId, Operation, OperationWalker, QueryPath, ResponseModifierRule,
-        SelectionSetId, SolvedRequiredFieldSet,
+        closest_common_deferred_fragment, DeferId, Field, FieldId, LogicalPlan, LogicalPlanId, Operation,
+        OperationWalker, QueryPath, ResponseModifierRule, SelectionSetId, SolvedRequiredFieldSet,
     },
     response::{ErrorCode, GraphqlError},
 };
@@ -48,6 +50,7 @@ pub(super) type LogicalPlanningResult<T> = Result<T, LogicalPlanningError>;
 
 pub(super) struct LogicalPlanner<'a> {
     schema: &'a Schema,
+    active_override_labels: &'a [OverrideLabelId],
     operation: &'a mut Operation,
     field_to_logical_plan_id: Vec<Option<LogicalPlanId>>,
     field_to_solved_requirement: Vec<Option<RequiredFieldId>>,
@@ -72,9 +75,14 @@ id_newtypes::index! {
 }
 
 impl<'a> LogicalPlanner<'a> {
-    pub(super) fn new(schema: &'a Schema, operation: &'a mut Operation) -> Self {
+    pub(super) fn new(
+        schema: &'a Schema,
+        active_override_labels: &'a [OverrideLabelId],
+        operation: &'a mut Operation,
+    ) -> Self {
         Self {
             schema,
+            active_override_labels,
             field_to_logical_plan_id: vec![None; operation.fields.len()],
             field_to_solved_requirement: vec![None; operation.fields.len()],
             selection_set_to_objects_must_be_tracked: BitSet::init_with(false, operation.selection_sets.len()),
@@ -169,6 +177,7 @@ impl<'a> LogicalPlanner<'a> {
         };
 
         plan.in_topological_order = sorted_plan_ids_by_topological_order(&plan);
+        promote_deferred_plans_with_earlier_dependents(operation, &mut plan);
         Ok(plan)
     }
 
@@ -197,6 +206,7 @@ impl<'a> LogicalPlanner<'a> {
                 QueryPath::default(),
                 introspection.resolver_id,
                 self.operation.root_object_id.into(),
+                None,
                 &introspection_field_ids,
             )?;
         }
@@ -238,12 +248,10 @@ impl<'a> LogicalPlanner<'a> {
                 .definition_id()
                 .expect("Introspection resolver should have taken metadata fields");
 
-            let resolver = self
-                .schema
-                .walker()
-                .walk(definition_id)
+            let definition = self.schema.walker().walk(definition_id);
+            let resolver = definition
                 .resolvers()
-                .next()
+                .find(|resolver| !definition.is_overridden_in(resolver.subgraph_id(), self.active_override_labels))
                 .ok_or_else(|| LogicalPlanningError::CouldNotPlanAnyField {
                     missing: vec![self.operation.response_keys[field.response_key()].to_string()],
                     query_path: vec![],
@@ -253,6 +261,7 @@ impl<'a> LogicalPlanner<'a> {
                 QueryPath::default(),
                 resolver.id(),
                 self.operation.root_object_id.into(),
+                None,
                 &field_ids,
             )?;
             self.mutation_fields_plan_order.push(plan_id);
@@ -260,8 +269,8 @@ impl<'a> LogicalPlanner<'a> {
         Ok(())
     }
 
-    /// Obviously providable fields have no requirements and can be provided by the current
-    /// resolver.
+    /// Obviously providable fields have no requirements, can be provided by the current resolver
+    /// and are delivered at the same time.
     fn grow_with_obviously_providable_subselections(
         &mut self,
         path: &QueryPath,
@@ -306,6 +315,7 @@ impl<'a> LogicalPlanner<'a> {
                 if let Some(definition) = walker.walk(*field_id).definition() {
                     logic.is_providable(definition.id())
                         && !definition.has_required_fields(logic.resolver().subgraph_id())
+                        && self.is_delivered_with(*field_id, logic.id())
                 } else {
                     true
                 }
@@ -341,6 +351,7 @@ impl<'a> LogicalPlanner<'a> {
         query_path: QueryPath,
         resolver_id: ResolverDefinitionId,
         entity_id: EntityId,
+        defer_id: Option<DeferId>,
         root_field_ids: &[FieldId],
     ) -> LogicalPlanningResult<LogicalPlanId> {
         let id = LogicalPlanId::from(self.logical_plans.len());
@@ -355,10 +366,11 @@ impl<'a> LogicalPlanner<'a> {
         self.logical_plans.push(LogicalPlan {
             resolver_id,
             entity_id,
+            defer_id,
             // Sorted at the end as may need to add extra fields.
             root_field_ids_ordered_by_parent_entity_id_then_position: root_field_ids.to_vec(),
         });
-        let logic = PlanningLogic::new(id, self.schema.walk(resolver_id));
+        let logic = PlanningLogic::new(id, self.schema.walk(resolver_id), self.active_override_labels);
         self.grow_with_obviously_providable_subselections(&query_path, &logic, root_field_ids)?;
         Ok(id)
     }
@@ -375,6 +387,34 @@ impl<'a> LogicalPlanner<'a> {
             self[*field_id] = Some(id);
         }
     }
+
+    /// Whether the field is delivered with the plan's data, so within the same deferred fragment.
+    /// Extra fields are always retrieved by whichever plan needs them.
+    pub fn is_delivered_with(&self, field_id: FieldId, plan_id: LogicalPlanId) -> bool {
+        let field = &self.operation[field_id];
+        matches!(field, Field::Extra(_)) || field.defer_id() == self[plan_id].defer_id
+    }
+}
+
+/// Plans are only grouped by deferred fragments during planning, but a plan may still be needed
+/// by a plan of an enclosing (or sibling) deferred fragment, to satisfy its requirements for
+/// example. As a deferred fragment is sent only after its parent, such a plan is moved to the
+/// closest common deferred fragment to ensure no payload ever waits for a later one.
+fn promote_deferred_plans_with_earlier_dependents(operation: &Operation, plan: &mut OperationPlan) {
+    if operation.deferred_fragments.is_empty() {
+        return;
+    }
+
+    // Children are always processed before their parents.
+    for &plan_id in plan.in_topological_order.iter().rev() {
+        let defer_id = plan
+            .children
+            .find_all(plan_id)
+            .fold(plan[plan_id].defer_id, |defer_id, child| {
+                closest_common_deferred_fragment(&operation.deferred_fragments, defer_id, plan[*child].defer_id)
+            });
+        plan.logical_plans[usize::from(plan_id)].defer_id = defer_id;
+    }
 }
 
 fn sorted_plan_ids_by_topological_order(plan: &OperationPlan) -> Vec<LogicalPlanId> {
@@ -14,7 +14,7 @@ use tracing::{instrument, Level};
 use super::{logic::PlanningLogic, LogicalPlanner, LogicalPlanningError, LogicalPlanningResult, ParentToChildEdge};
 use crate::{
     operation::{
-        ExtraField, Field, FieldArgument, FieldArgumentId, FieldId, LogicalPlanId, QueryInputValue, QueryPath,
+        DeferId, ExtraField, Field, FieldArgument, FieldArgumentId, FieldId, LogicalPlanId, QueryInputValue, QueryPath,
         SelectionSet, SelectionSetId, SolvedRequiredField, SolvedRequiredFieldSet,
     },
     response::{SafeResponseKey, UnpackedResponseEdge},
@@ -224,6 +224,7 @@ impl PlannedField {
 struct ChildPlanCandidate<'schema> {
     entity_id: EntityId,
     resolver_id: ResolverDefinitionId,
+    defer_id: Option<DeferId>,
     /// Providable fields by the resolvers with their requirements
     providable_fields: Vec<(FieldId, Cow<'schema, RequiredFieldSet>)>,
 }
@@ -240,42 +241,12 @@ impl<'schema, 'a> SelectionSetLogicalPlanner<'schema, 'a> {
         mut parent_field_requirements: Option<(FieldId, Cow<'schema, RequiredFieldSet>)>,
         mut unplanned_fields: HashMap<FieldId, FieldDefinitionWalker<'schema>>,
     ) -> LogicalPlanningResult<()> {
-        // unplanned_field may be still be provided by the parent plan, but at this stage it
-        // means they had requirements.
-        if let Some(parent_logic) = self.maybe_parent {
-            let mut requires = Cow::Owned(RequiredFieldSet::default());
-            let mut planned_field_ids = vec![];
-
-            for (&id, definition) in &unplanned_fields {
-                // If the parent plan can provide the field, we don't need to plan it.
-                let required_fields = definition.required_fields(parent_logic.resolver().subgraph_id());
-                if parent_logic.is_providable(definition.id())
-                    && self.could_plan_requirements(planned_selection_set, id, &required_fields)?
-                {
-                    requires = RequiredFieldSet::union_cow(requires, required_fields);
-                    planned_field_ids.push(id);
-                    continue;
-                }
-            }
-
-            if let Some((parent_field_id, parent_extra_requirements)) = &mut parent_field_requirements {
-                // If the parent plan can provide the field, we don't need to plan it.
-                if self.could_plan_requirements(planned_selection_set, *parent_field_id, parent_extra_requirements)? {
-                    requires = RequiredFieldSet::union_cow(requires, std::mem::take(parent_extra_requirements));
-                }
-            }
-
-            for id in &planned_field_ids {
-                unplanned_fields.remove(id);
-            }
-
-            self.planner.grow_with_obviously_providable_subselections(
-                self.query_path,
-                parent_logic,
-                &planned_field_ids,
-            )?;
-            self.register_necessary_extra_fields(None, planned_selection_set, &requires);
-        }
+        self.plan_fields_with_parent(
+            planned_selection_set,
+            &mut parent_field_requirements,
+            &mut unplanned_fields,
+            false,
+        )?;
 
         if unplanned_fields.is_empty()
             && parent_field_requirements
@@ -287,11 +258,34 @@ impl<'schema, 'a> SelectionSetLogicalPlanner<'schema, 'a> {
         }
 
         // Actual planning, we plan one child plan at a time.
-        let mut candidates: HashMap<ResolverDefinitionId, ChildPlanCandidate<'schema>> = HashMap::default();
+        let mut candidates: HashMap<(ResolverDefinitionId, Option<DeferId>), ChildPlanCandidate<'schema>> =
+            HashMap::default();
+        let mut ignored_defer = false;
         while !unplanned_fields.is_empty() {
             candidates.clear();
             self.generate_all_candidates(&unplanned_fields, planned_selection_set, &mut candidates)?;
 
+            if candidates
+                .values()
+                .all(|candidate| candidate.providable_fields.is_empty())
+                && !ignored_defer
+            {
+                // Deferred fields that only the parent plan can provide, typically because their
+                // subgraph has no entity resolver, are not deferred. The specification allows it
+                // and it's always better than failing.
+                ignored_defer = true;
+                let unplanned_count = unplanned_fields.len();
+                self.plan_fields_with_parent(
+                    planned_selection_set,
+                    &mut parent_field_requirements,
+                    &mut unplanned_fields,
+                    true,
+                )?;
+                if unplanned_fields.len() < unplanned_count {
+                    continue;
+                }
+            }
+
             let Some(candidate) = select_best_child_plan(&mut candidates) else {
                 let walker = self.walker();
                 let parent_subgraph_id = self.maybe_parent.map(|parent| parent.resolver().subgraph_id());
@@ -332,6 +326,7 @@ impl<'schema, 'a> SelectionSetLogicalPlanner<'schema, 'a> {
                 candidate.resolver_id,
                 requires,
                 candidate.entity_id,
+                candidate.defer_id,
                 field_ids,
             )?;
         }
@@ -367,16 +362,67 @@ impl<'schema, 'a> SelectionSetLogicalPlanner<'schema, 'a> {
         Ok(())
     }
 
+    /// Unplanned fields may be still be provided by the parent plan, but at this stage it means
+    /// they had requirements or belong to a different deferred fragment. The latter are only
+    /// planned with the parent when `ignore_defer` is set.
+    fn plan_fields_with_parent(
+        &mut self,
+        planned_selection_set: &mut PlannedSelectionSet,
+        parent_field_requirements: &mut Option<(FieldId, Cow<'schema, RequiredFieldSet>)>,
+        unplanned_fields: &mut HashMap<FieldId, FieldDefinitionWalker<'schema>>,
+        ignore_defer: bool,
+    ) -> LogicalPlanningResult<()> {
+        let Some(parent_logic) = self.maybe_parent else {
+            return Ok(());
+        };
+        let mut requires = Cow::Owned(RequiredFieldSet::default());
+        let mut planned_field_ids = vec![];
+
+        for (&id, definition) in unplanned_fields.iter() {
+            if !ignore_defer && !self.is_delivered_with(id, parent_logic.id()) {
+                continue;
+            }
+            // If the parent plan can provide the field, we don't need to plan it.
+            let required_fields = definition.required_fields(parent_logic.resolver().subgraph_id());
+            if parent_logic.is_providable(definition.id())
+                && self.could_plan_requirements(planned_selection_set, id, &required_fields)?
+            {
+                requires = RequiredFieldSet::union_cow(requires, required_fields);
+                planned_field_ids.push(id);
+                continue;
+            }
+        }
+
+        if let Some((parent_field_id, parent_extra_requirements)) = parent_field_requirements {
+            // If the parent plan can provide the field, we don't need to plan it.
+            if self.could_plan_requirements(planned_selection_set, *parent_field_id, parent_extra_requirements)? {
+                requires = RequiredFieldSet::union_cow(requires, std::mem::take(parent_extra_requirements));
+            }
+        }
+
+        for id in &planned_field_ids {
+            unplanned_fields.remove(id);
+        }
+
+        self.planner
+            .grow_with_obviously_providable_subselections(self.query_path, parent_logic, &planned_field_ids)?;
+        self.register_necessary_extra_fields(None, planned_selection_set, &requires);
+        Ok(())
+    }
+
     fn push_child(
         &mut self,
         planned_selection_set: &mut PlannedSelectionSet,
         resolver_id: ResolverDefinitionId,
         requires: Cow<'_, RequiredFieldSet>,
         entity_id: EntityId,
+        defer_id: Option<DeferId>,
         root_field_ids: Vec<FieldId>,
     ) -> LogicalPlanningResult<()> {
         let path = self.query_path.clone();
-        let plan_id = self.planner.push_plan(path, resolver_id, entity_id, &root_field_ids)?;
+        let plan_id = self
+            .planner
+            .push_plan(path, resolver_id, entity_id, defer_id, &root_field_ids)?;
         self.register_necessary_extra_fields(Some(plan_id), planned_selection_set, &requires);
         for field_id in root_field_ids {
             let definition_id = self.operation[field_id]
@@ -517,16 +563,21 @@ impl<'schema, 'a> SelectionSetLogicalPlanner<'schema, 'a> {
         &mut self,
         unplanned_fields: &HashMap<FieldId, FieldDefinitionWalker<'schema>>,
         planned_selection_set: &mut PlannedSelectionSet,
-        candidates: &mut HashMap<ResolverDefinitionId, ChildPlanCandidate<'schema>>,
+        candidates: &mut HashMap<(ResolverDefinitionId, Option<DeferId>), ChildPlanCandidate<'schema>>,
     ) -> LogicalPlanningResult<()>
     where
         'schema: 'field,
     {
         for (&id, definition) in unplanned_fields {
+            // Fields of different deferred fragments are never part of the same plan.
+            let defer_id = self.operation[id].defer_id();
             for resolver in definition.resolvers() {
+                if definition.is_overridden_in(resolver.subgraph_id(), self.active_override_labels) {
+                    continue;
+                }
                 tracing::trace!("Trying to plan '{}' with: {}", definition.name(), resolver.name());
                 let required_fields = definition.required_fields(resolver.subgraph_id());
-                match candidates.entry(resolver.id()) {
+                match candidates.entry((resolver.id(), defer_id)) {
                     Entry::Occupied(mut entry) => {
                         let candidate = entry.get_mut();
                         if self.could_plan_requirements(planned_selection_set, id, &required_fields)? {
@@ -540,6 +591,7 @@ impl<'schema, 'a> SelectionSetLogicalPlanner<'schema, 'a> {
                             entry.insert(ChildPlanCandidate {
                                 resolver_id: resolver.id(),
                                 entity_id: definition.parent_entity().id(),
+                                defer_id,
                                 providable_fields: vec![(id, required_fields)],
                             });
                         }
@@ -670,6 +722,7 @@ impl<'schema, 'a> SelectionSetLogicalPlanner<'schema, 'a> {
                     &PlanningLogic::new(
                         parent_resolved_query_part_id,
                         self.schema.walk(self[parent_resolved_query_part_id].resolver_id),
+                        self.active_override_labels,
                     ),
                     required,
                 ));
@@ -688,7 +741,11 @@ impl<'schema, 'a> SelectionSetLogicalPlanner<'schema, 'a> {
                 if self.could_plan_exra_field(
                     planned_selection_set,
                     petitioner_field_id,
-                    &PlanningLogic::new(plan_id, self.schema.walk(self[plan_id].resolver_id)),
+                    &PlanningLogic::new(
+                        plan_id,
+                        self.schema.walk(self[plan_id].resolver_id),
+                        self.active_override_labels,
+                    ),
                     required,
                 ) {
                     continue 'requires;
@@ -791,7 +848,7 @@ impl<'schema, 'a> SelectionSetLogicalPlanner<'schema, 'a> {
 }
 
 fn select_best_child_plan<'c, 'op>(
-    candidates: &'c mut HashMap<ResolverDefinitionId, ChildPlanCandidate<'op>>,
+    candidates: &'c mut HashMap<(ResolverDefinitionId, Option<DeferId>), ChildPlanCandidate<'op>>,
 ) -> Option<&'c mut ChildPlanCandidate<'op>> {
     // We could be smarter, but we need to be sure there is no intersection between
     // candidates (which impacts ordering among other things) and some fields may now be
@@ -1,6 +1,8 @@
 mod bind;
 mod blueprint;
 mod build;
+mod cache_control;
+mod cost;
 pub mod ids;
 mod input_value;
 mod location;
@@ -15,6 +17,7 @@ mod variables;
 mod walkers;
 
 use crate::response::{ConcreteObjectShapeId, FieldShapeId, ResponseKeys, ResponseObjectSetId, Shapes};
+pub(crate) use cost::estimate_cost;
 pub(crate) use engine_parser::types::OperationType;
 use grafbase_telemetry::metrics::OperationMetricsAttributes;
 use id_newtypes::{BitSet, IdRange, IdToMany};
@@ -34,6 +37,8 @@ pub(crate) struct PreparedOperation {
     pub metrics_attributes: OperationMetricsAttributes,
     pub plan: OperationPlan,
     pub response_blueprint: ResponseBlueprint,
+    /// Cache control of the whole response if it can be cached at all.
+    pub cache_control: Option<schema::CacheControl>,
 }
 
 impl std::ops::Deref for PreparedOperation {
@@ -72,6 +77,7 @@ pub(crate) struct Operation {
     // deduplicated by rule
     pub response_modifiers: Vec<ResponseModifier>,
     pub response_modifier_impacted_fields: Vec<FieldId>,
+    pub deferred_fragments: Vec<DeferredFragment>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -94,6 +100,9 @@ pub(crate) struct OperationPlan {
 pub(crate) struct LogicalPlan {
     pub resolver_id: ResolverDefinitionId,
     pub entity_id: EntityId,
+    /// Deferred fragment with which the plan's data is delivered, if any. A plan is never part of
+    /// a deferred fragment nested deeper than any of its children.
+    pub defer_id: Option<DeferId>,
     pub root_field_ids_ordered_by_parent_entity_id_then_position: Vec<FieldId>,
 }
 
@@ -1,7 +1,7 @@
 mod query;
 
 use id_newtypes::IdRange;
-use schema::{AuthorizedDirectiveId, Definition, FieldDefinitionId, RequiredScopesId};
+use schema::{AuthorizedDirectiveId, Definition, FieldDefinitionId, PolicyId, RequiredScopesId};
 
 use super::{FieldArgumentId, QueryModifierImpactedFieldId, ResponseModifierImpactedFieldId};
 
@@ -17,6 +17,7 @@ pub(crate) struct QueryModifier {
 pub(crate) enum QueryModifierRule {
     Authenticated,
     RequiresScopes(RequiredScopesId),
+    Policy(PolicyId),
     AuthorizedField {
         directive_id: AuthorizedDirectiveId,
         definition_id: FieldDefinitionId,
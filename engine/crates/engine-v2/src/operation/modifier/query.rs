@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use id_newtypes::{BitSet, IdRange, IdToMany};
 use schema::Schema;
 
@@ -63,6 +65,7 @@ where
 {
     pub(super) async fn build(mut self) -> PlanningResult<QueryModifications> {
         let mut scopes = None;
+        let mut granted_policies = None;
 
         for (i, modifier) in self.operation.query_modifiers.iter().enumerate() {
             let modifier_id = QueryModifierId::from(i);
@@ -95,6 +98,20 @@ where
                         )
                     }
                 }
+                QueryModifierRule::Policy(id) => {
+                    if granted_policies.is_none() {
+                        granted_policies = Some(self.authorize_policies().await);
+                    }
+
+                    let error = match granted_policies.as_ref() {
+                        Some(Ok(granted)) if self.schema().walk(id).matches(granted) => None,
+                        Some(Err(err)) => Some(err.clone()),
+                        _ => Some(GraphqlError::new("Not allowed by policy", ErrorCode::Unauthorized)),
+                    };
+                    if let Some(error) = error {
+                        self.handle_modifier_resulted_in_error(modifier_id, modifier.impacted_fields, error);
+                    }
+                }
                 QueryModifierRule::AuthorizedField {
                     directive_id,
                     definition_id,
@@ -137,6 +154,25 @@ where
         Ok(self.finalize())
     }
 
+    /// All the policies referenced by the operation are evaluated at once by the hook.
+    async fn authorize_policies(&self) -> Result<Vec<String>, GraphqlError> {
+        let policies = self
+            .operation
+            .query_modifiers
+            .iter()
+            .filter_map(|modifier| match modifier.rule {
+                QueryModifierRule::Policy(id) => Some(self.schema().walk(id)),
+                _ => None,
+            })
+            .flat_map(|policy| policy.policies())
+            .collect::<BTreeSet<_>>();
+
+        self.ctx
+            .hooks()
+            .authorize_policies(policies.into_iter().map(str::to_string).collect())
+            .await
+    }
+
     fn finalize(mut self) -> QueryModifications {
         self.modifications.field_shape_id_to_error_ids = self.field_shape_id_to_error_ids_builder.into();
         let mut field_shape_ids_with_errors = self.modifications.field_shape_id_to_error_ids.ids();
@@ -6,7 +6,10 @@ use schema::{
 
 use crate::response::{BoundResponseKey, ResponseEdge, ResponseKey};
 
-use super::{FieldArgumentId, FieldId, Location, QueryInputValueId, SelectionSetId};
+use super::{
+    DeferId, FieldArgumentId, FieldId, Location, Operation, QueryInputValueId, SelectionSetId, VariableDefinitionId,
+    Variables,
+};
 
 #[derive(Default, serde::Serialize, serde::Deserialize)]
 pub(crate) struct SelectionSet {
@@ -97,6 +100,7 @@ pub struct TypeNameField {
     pub bound_response_key: BoundResponseKey,
     pub location: Location,
     pub parent_selection_set_id: SelectionSetId,
+    pub defer_id: Option<DeferId>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -107,6 +111,9 @@ pub struct QueryField {
     pub argument_ids: IdRange<FieldArgumentId>,
     pub selection_set_id: Option<SelectionSetId>,
     pub parent_selection_set_id: SelectionSetId,
+    /// Innermost deferred fragment this field belongs to, if any. A field present both inside
+    /// and outside of a deferred fragment is never deferred.
+    pub defer_id: Option<DeferId>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -193,6 +200,92 @@ impl Field {
             }) => *parent_selection_set_id,
         }
     }
+
+    /// Extra fields are only added to satisfy requirements, they're retrieved with whatever plan
+    /// needs them.
+    pub fn defer_id(&self) -> Option<DeferId> {
+        match self {
+            Field::TypeName(TypeNameField { defer_id, .. }) => *defer_id,
+            Field::Query(QueryField { defer_id, .. }) => *defer_id,
+            Field::Extra(ExtraField { .. }) => None,
+        }
+    }
+}
+
+/// A fragment spread or inline fragment with a `@defer` directive. Its fields are delivered in a
+/// subsequent payload of the response for clients supporting incremental delivery.
+///
+/// The remaining items of a list field with `@stream` are delivered the same way, so they're
+/// also represented as a deferred fragment.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DeferredFragment {
+    pub label: Option<String>,
+    /// Enclosing deferred fragment if any, its payload is always sent before this one.
+    pub parent_id: Option<DeferId>,
+    pub location: Location,
+    /// Variable of the `if` argument. Operations are cached independently of their variables,
+    /// so a disabled fragment is only merged into its parent during execution.
+    pub if_variable: Option<VariableDefinitionId>,
+    pub stream: Option<StreamedList>,
+}
+
+impl DeferredFragment {
+    /// Whether the fragment is delivered separately, `if` defaulting to true as for the directive.
+    pub(crate) fn is_enabled(&self, operation: &Operation, variables: &Variables) -> bool {
+        self.if_variable
+            .and_then(|id| variables.boolean(operation, id))
+            .unwrap_or(true)
+    }
+}
+
+/// Closest deferred fragment enclosing both, `None` being the initial payload.
+pub(crate) fn closest_common_deferred_fragment(
+    fragments: &[DeferredFragment],
+    left: Option<DeferId>,
+    right: Option<DeferId>,
+) -> Option<DeferId> {
+    let mut left_ancestors = Vec::new();
+    let mut current = left;
+    while let Some(id) = current {
+        left_ancestors.push(id);
+        current = fragments[usize::from(id)].parent_id;
+    }
+
+    let mut current = right;
+    while let Some(id) = current {
+        if left_ancestors.contains(&id) {
+            return Some(id);
+        }
+        current = fragments[usize::from(id)].parent_id;
+    }
+    None
+}
+
+/// List field with a `@stream` directive. Only the first `initial_count` items are part of the
+/// payload of the field itself.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub struct StreamedList {
+    pub bound_response_key: BoundResponseKey,
+    pub initial_count: StreamInitialCount,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum StreamInitialCount {
+    Value(usize),
+    Variable(VariableDefinitionId),
+}
+
+impl StreamedList {
+    /// Number of items sent with the list itself, a null or negative variable being treated as 0.
+    pub(crate) fn resolve_initial_count(&self, operation: &Operation, variables: &Variables) -> usize {
+        match self.initial_count {
+            StreamInitialCount::Value(count) => count,
+            StreamInitialCount::Variable(id) => variables
+                .int(operation, id)
+                .and_then(|count| usize::try_from(count).ok())
+                .unwrap_or_default(),
+        }
+    }
 }
 
 /// Represents arguments that were specified in the query with a value
@@ -3,7 +3,8 @@ use tracing::instrument;
 
 use super::{
     bind::{bind_variables, VariableError},
-    FieldId, Location, Operation, QueryInputValueId, VariableDefinitionId, VariableInputValueId, VariableInputValues,
+    FieldId, Location, Operation, QueryInputValue, QueryInputValueId, VariableDefinitionId, VariableInputValue,
+    VariableInputValueId, VariableInputValues,
 };
 
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
@@ -57,4 +58,36 @@ impl Variables {
     ) -> Result<Self, Vec<VariableError>> {
         bind_variables(schema, operation, request_variables)
     }
+
+    /// Boolean value of the variable or its default value, if any.
+    pub(crate) fn boolean(&self, operation: &Operation, id: VariableDefinitionId) -> Option<bool> {
+        match self[id] {
+            VariableValue::InputValue(id) => match self[id] {
+                VariableInputValue::Boolean(value) => Some(value),
+                _ => None,
+            },
+            VariableValue::Undefined => match operation[operation[id].default_value?] {
+                QueryInputValue::Boolean(value) => Some(value),
+                _ => None,
+            },
+        }
+    }
+
+    /// Integer value of the variable or its default value, if any.
+    pub(crate) fn int(&self, operation: &Operation, id: VariableDefinitionId) -> Option<i64> {
+        match self[id] {
+            VariableValue::InputValue(id) => match self[id] {
+                VariableInputValue::Int(value) => Some(value.into()),
+                VariableInputValue::BigInt(value) => Some(value),
+                VariableInputValue::U64(value) => i64::try_from(value).ok(),
+                _ => None,
+            },
+            VariableValue::Undefined => match operation[operation[id].default_value?] {
+                QueryInputValue::Int(value) => Some(value.into()),
+                QueryInputValue::BigInt(value) => Some(value),
+                QueryInputValue::U64(value) => i64::try_from(value).ok(),
+                _ => None,
+            },
+        }
+    }
 }
@@ -20,6 +20,7 @@ pub(crate) enum ErrorCode {
     SubgraphError,
     SubgraphInvalidResponseError,
     SubgraphRequestError,
+    SubgraphUnavailable,
     // Auth
     Unauthenticated,
     Unauthorized,
@@ -27,6 +28,8 @@ pub(crate) enum ErrorCode {
     OperationParsingError,
     OperationValidationError,
     OperationPlanningError,
+    // Demand control
+    OperationCostExceeded,
     // Runtime
     HookError,
     // Rate limit
@@ -46,6 +49,20 @@ impl From<PartialErrorCode> for ErrorCode {
     }
 }
 
+impl From<schema::PublicErrorCode> for ErrorCode {
+    fn from(code: schema::PublicErrorCode) -> Self {
+        match code {
+            schema::PublicErrorCode::BadRequest => Self::BadRequest,
+            schema::PublicErrorCode::Unauthenticated => Self::Unauthenticated,
+            schema::PublicErrorCode::Unauthorized => Self::Unauthorized,
+            schema::PublicErrorCode::RateLimited => Self::RateLimited,
+            schema::PublicErrorCode::GatewayTimeout => Self::GatewayTimeout,
+            schema::PublicErrorCode::InternalServerError => Self::InternalServerError,
+            schema::PublicErrorCode::SubgraphError => Self::SubgraphError,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct GraphqlError {
     pub message: Cow<'static, str>,
@@ -11,7 +11,7 @@ pub(crate) use shape::*;
 pub(crate) use value::*;
 pub(crate) use write::*;
 
-use crate::operation::PreparedOperation;
+use crate::{execution::QueryPlan, operation::PreparedOperation};
 
 mod error;
 mod key;
@@ -24,17 +24,24 @@ mod write;
 
 pub(crate) enum Response {
     Initial(InitialResponse),
+    /// First payload of an operation with deferred fragments when using incremental delivery.
+    IncrementalInitial(IncrementalInitialResponse),
+    /// Payloads with the deferred fragments data following the initial one.
+    IncrementalSubsequent(IncrementalSubsequentResponse),
     /// Engine could not process the request at all, but request was valid.
     /// Meaning `data` field is present, but null.
     ExecutionFailure(ExecutionFailureResponse),
     /// Invalid request
     PreExecutionError(PreExecutionErrorResponse),
+    /// Successful response of a previous execution, served from the response cache.
+    Cached(CachedResponse),
 }
 
 pub(crate) struct InitialResponse {
     // will be None if an error propagated up to the root.
     data: ResponseData,
     errors: Vec<GraphqlError>,
+    extensions: ResponseExtensions,
 }
 
 struct ResponseData {
@@ -44,6 +51,103 @@ struct ResponseData {
     parts: Vec<ResponseDataPart>,
 }
 
+/// Deferred plans keep writing into the response after this payload is sent, so the data is
+/// serialized eagerly.
+pub(crate) struct IncrementalInitialResponse {
+    operation: Arc<PreparedOperation>,
+    // will be None if an error propagated up to the root.
+    data: Option<Box<serde_json::value::RawValue>>,
+    errors: Vec<GraphqlError>,
+    extensions: ResponseExtensions,
+    has_next: bool,
+}
+
+pub(crate) struct IncrementalSubsequentResponse {
+    operation: Arc<PreparedOperation>,
+    incremental: Vec<IncrementalPayload>,
+    // Errors which couldn't be associated with any incremental payload.
+    errors: Vec<GraphqlError>,
+    has_next: bool,
+}
+
+#[derive(Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ResponseExtensions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    query_plan: Option<QueryPlan>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cost: Option<OperationCost>,
+}
+
+impl ResponseExtensions {
+    fn is_empty(&self) -> bool {
+        self.query_plan.is_none() && self.cost.is_none()
+    }
+}
+
+/// Cost of the operation estimated by demand control.
+#[derive(serde::Serialize)]
+struct OperationCost {
+    estimated: u64,
+}
+
+/// Data of a deferred fragment for a single response object or the remaining items of a
+/// streamed list.
+pub(crate) struct IncrementalPayload {
+    label: Option<String>,
+    /// For streamed lists, the path of the first item of the payload.
+    path: ResponsePath,
+    data: IncrementalPayloadData,
+    errors: Vec<GraphqlError>,
+}
+
+pub(crate) enum IncrementalPayloadData {
+    // will be None if an error propagated to the object or one of its parents.
+    Object(Option<Box<serde_json::value::RawValue>>),
+    Items(Box<serde_json::value::RawValue>),
+}
+
+impl IncrementalPayload {
+    /// Whether the value at this path is part of the payload.
+    fn contains(&self, path: &[ResponseEdge]) -> bool {
+        match self.data {
+            IncrementalPayloadData::Object(_) => path.starts_with(&self.path),
+            IncrementalPayloadData::Items(_) => {
+                let Some((first_item, list_path)) = self.path.split_last() else {
+                    return false;
+                };
+                let (UnpackedResponseEdge::Index(first), Some(UnpackedResponseEdge::Index(index))) =
+                    (first_item.unpack(), path.get(list_path.len()).map(|edge| edge.unpack()))
+                else {
+                    return false;
+                };
+                path.starts_with(list_path) && index >= first
+            }
+        }
+    }
+}
+
+impl IncrementalInitialResponse {
+    pub(crate) fn with_has_next(mut self, has_next: bool) -> Self {
+        self.has_next = has_next;
+        self
+    }
+}
+
+impl IncrementalSubsequentResponse {
+    pub(crate) fn with_has_next(mut self, has_next: bool) -> Self {
+        self.has_next = has_next;
+        self
+    }
+
+    fn errors(&self) -> impl Iterator<Item = &GraphqlError> + '_ {
+        self.incremental
+            .iter()
+            .flat_map(|payload| payload.errors.iter())
+            .chain(self.errors.iter())
+    }
+}
+
 pub(crate) struct PreExecutionErrorResponse {
     errors: Vec<GraphqlError>,
 }
@@ -52,6 +156,13 @@ pub(crate) struct ExecutionFailureResponse {
     errors: Vec<GraphqlError>,
 }
 
+/// Only successful responses without any errors are cached. Extensions are specific to each
+/// request, so only the data is kept.
+pub(crate) struct CachedResponse {
+    data: Box<serde_json::value::RawValue>,
+    extensions: ResponseExtensions,
+}
+
 impl Response {
     pub(crate) fn pre_execution_error(error: impl Into<GraphqlError>) -> Self {
         Self::PreExecutionError(PreExecutionErrorResponse {
@@ -74,6 +185,59 @@ impl Response {
         })
     }
 
+    pub(crate) fn cached(data: Box<serde_json::value::RawValue>) -> Self {
+        Self::Cached(CachedResponse {
+            data,
+            extensions: ResponseExtensions::default(),
+        })
+    }
+
+    /// Response with only the query plan, the operation isn't executed.
+    pub(crate) fn query_plan_only(
+        schema: Arc<Schema>,
+        operation: Arc<PreparedOperation>,
+        query_plan: QueryPlan,
+    ) -> Self {
+        Self::Initial(InitialResponse {
+            data: ResponseData {
+                schema,
+                operation,
+                root: None,
+                parts: Vec::new(),
+            },
+            errors: Vec::new(),
+            extensions: ResponseExtensions {
+                query_plan: Some(query_plan),
+                cost: None,
+            },
+        })
+    }
+
+    /// Adds the query plan to the extensions of the response, only the first payload of an
+    /// operation has any.
+    pub(crate) fn with_query_plan(mut self, query_plan: QueryPlan) -> Self {
+        match &mut self {
+            Self::Initial(resp) => resp.extensions.query_plan = Some(query_plan),
+            Self::IncrementalInitial(resp) => resp.extensions.query_plan = Some(query_plan),
+            Self::Cached(resp) => resp.extensions.query_plan = Some(query_plan),
+            Self::IncrementalSubsequent(_) | Self::ExecutionFailure(_) | Self::PreExecutionError(_) => (),
+        }
+        self
+    }
+
+    /// Adds the estimated cost of the operation to the extensions of the response, only the first
+    /// payload of an operation has any.
+    pub(crate) fn with_estimated_cost(mut self, estimated: u64) -> Self {
+        let cost = Some(OperationCost { estimated });
+        match &mut self {
+            Self::Initial(resp) => resp.extensions.cost = cost,
+            Self::IncrementalInitial(resp) => resp.extensions.cost = cost,
+            Self::Cached(resp) => resp.extensions.cost = cost,
+            Self::IncrementalSubsequent(_) | Self::ExecutionFailure(_) | Self::PreExecutionError(_) => (),
+        }
+        self
+    }
+
     pub(crate) fn status(&self) -> GraphqlResponseStatus {
         match self {
             Self::Initial(resp) => {
@@ -86,6 +250,27 @@ impl Response {
                     }
                 }
             }
+            Self::IncrementalInitial(resp) => {
+                if resp.errors.is_empty() {
+                    GraphqlResponseStatus::Success
+                } else {
+                    GraphqlResponseStatus::FieldError {
+                        count: resp.errors.len() as u64,
+                        data_is_null: resp.data.is_none(),
+                    }
+                }
+            }
+            Self::IncrementalSubsequent(resp) => {
+                let count = resp.errors().count();
+                if count == 0 {
+                    GraphqlResponseStatus::Success
+                } else {
+                    GraphqlResponseStatus::FieldError {
+                        count: count as u64,
+                        data_is_null: false,
+                    }
+                }
+            }
             Self::ExecutionFailure(resp) => GraphqlResponseStatus::FieldError {
                 count: resp.errors.len() as u64,
                 data_is_null: true,
@@ -93,14 +278,18 @@ impl Response {
             Self::PreExecutionError(resp) => GraphqlResponseStatus::RequestError {
                 count: resp.errors.len() as u64,
             },
+            Self::Cached(_) => GraphqlResponseStatus::Success,
         }
     }
 
     pub(crate) fn first_error_message(&self) -> Option<Cow<'static, str>> {
         match self {
             Response::Initial(resp) => resp.errors.first(),
+            Response::IncrementalInitial(resp) => resp.errors.first(),
+            Response::IncrementalSubsequent(resp) => resp.errors().next(),
             Response::ExecutionFailure(resp) => resp.errors.first(),
             Response::PreExecutionError(resp) => resp.errors.first(),
+            Response::Cached(_) => None,
         }
         .map(|error| error.message.clone())
     }
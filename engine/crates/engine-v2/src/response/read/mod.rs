@@ -7,6 +7,7 @@ mod view;
 
 use schema::Schema;
 pub(crate) use selection_set::*;
+pub(super) use ser::{SerializableResponseList, SerializableResponseObject, SerializationContext};
 pub(crate) use view::*;
 
 impl ResponseBuilder {
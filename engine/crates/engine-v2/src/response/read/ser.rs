@@ -1,11 +1,16 @@
 use std::borrow::Cow;
 
-use serde::ser::{SerializeMap, SerializeSeq};
+use schema::Schema;
+use serde::{
+    ser::{SerializeMap, SerializeSeq},
+    Serialize,
+};
 
 use crate::response::{
-    value::ResponseObjectField, ErrorCode, ExecutionFailureResponse, GraphqlError, InitialResponse,
-    PreExecutionErrorResponse, Response, ResponseData, ResponseKeys, ResponseListId, ResponseObject, ResponseObjectId,
-    ResponsePath, ResponseValue, UnpackedResponseEdge,
+    value::ResponseObjectField, CachedResponse, ErrorCode, ExecutionFailureResponse, GraphqlError,
+    IncrementalInitialResponse, IncrementalPayload, IncrementalPayloadData, IncrementalSubsequentResponse,
+    InitialResponse, PreExecutionErrorResponse, Response, ResponseData, ResponseDataPart, ResponseEdge, ResponseKeys,
+    ResponseListId, ResponseObject, ResponseObjectId, ResponsePath, ResponseValue, UnpackedResponseEdge,
 };
 
 impl serde::Serialize for Response {
@@ -14,7 +19,11 @@ impl serde::Serialize for Response {
         S: serde::Serializer,
     {
         match self {
-            Response::Initial(InitialResponse { data, errors, .. }) => {
+            Response::Initial(InitialResponse {
+                data,
+                errors,
+                extensions,
+            }) => {
                 let mut map = serializer.serialize_map(Some(1))?;
                 map.serialize_entry("data", &SerializableResponseData { data })?;
                 if !errors.is_empty() {
@@ -26,6 +35,56 @@ impl serde::Serialize for Response {
                         },
                     )?;
                 }
+                if !extensions.is_empty() {
+                    map.serialize_entry("extensions", extensions)?;
+                }
+                map.end()
+            }
+            Response::IncrementalInitial(IncrementalInitialResponse {
+                operation,
+                data,
+                errors,
+                extensions,
+                has_next,
+            }) => {
+                let mut map = serializer.serialize_map(Some(3))?;
+                map.serialize_entry("data", data)?;
+                if !errors.is_empty() {
+                    map.serialize_entry(
+                        "errors",
+                        &SerializableErrors {
+                            keys: &operation.response_keys,
+                            errors,
+                        },
+                    )?;
+                }
+                if !extensions.is_empty() {
+                    map.serialize_entry("extensions", extensions)?;
+                }
+                map.serialize_entry("hasNext", has_next)?;
+                map.end()
+            }
+            Response::IncrementalSubsequent(IncrementalSubsequentResponse {
+                operation,
+                incremental,
+                errors,
+                has_next,
+            }) => {
+                let keys = &operation.response_keys;
+                let mut map = serializer.serialize_map(Some(3))?;
+                if !incremental.is_empty() {
+                    map.serialize_entry(
+                        "incremental",
+                        &SerializableIncrementalPayloads {
+                            keys,
+                            payloads: incremental,
+                        },
+                    )?;
+                }
+                if !errors.is_empty() {
+                    map.serialize_entry("errors", &SerializableErrors { keys, errors })?;
+                }
+                map.serialize_entry("hasNext", has_next)?;
                 map.end()
             }
             Response::PreExecutionError(PreExecutionErrorResponse { errors, .. }) => {
@@ -59,10 +118,89 @@ impl serde::Serialize for Response {
                 }
                 map.end()
             }
+            Response::Cached(CachedResponse { data, extensions }) => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("data", data)?;
+                if !extensions.is_empty() {
+                    map.serialize_entry("extensions", extensions)?;
+                }
+                map.end()
+            }
         }
     }
 }
 
+struct SerializableIncrementalPayloads<'a> {
+    keys: &'a ResponseKeys,
+    payloads: &'a [IncrementalPayload],
+}
+
+impl<'a> serde::Serialize for SerializableIncrementalPayloads<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.payloads.len()))?;
+        for IncrementalPayload {
+            label,
+            path,
+            data,
+            errors,
+        } in self.payloads
+        {
+            seq.serialize_element(&SerializableIncrementalPayload {
+                keys: self.keys,
+                label: label.as_deref(),
+                path,
+                data,
+                errors,
+            })?;
+        }
+        seq.end()
+    }
+}
+
+struct SerializableIncrementalPayload<'a> {
+    keys: &'a ResponseKeys,
+    label: Option<&'a str>,
+    path: &'a ResponsePath,
+    data: &'a IncrementalPayloadData,
+    errors: &'a [GraphqlError],
+}
+
+impl<'a> serde::Serialize for SerializableIncrementalPayload<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        match self.data {
+            IncrementalPayloadData::Object(data) => map.serialize_entry("data", data)?,
+            IncrementalPayloadData::Items(items) => map.serialize_entry("items", items)?,
+        }
+        map.serialize_entry(
+            "path",
+            &SerializableResponsePath {
+                keys: self.keys,
+                path: self.path,
+            },
+        )?;
+        if let Some(label) = self.label {
+            map.serialize_entry("label", label)?;
+        }
+        if !self.errors.is_empty() {
+            map.serialize_entry(
+                "errors",
+                &SerializableErrors {
+                    keys: self.keys,
+                    errors: self.errors,
+                },
+            )?;
+        }
+        map.end()
+    }
+}
+
 struct SerializableErrors<'a> {
     keys: &'a ResponseKeys,
     errors: &'a [GraphqlError],
@@ -179,20 +317,56 @@ impl<'a> serde::Serialize for SerializableResponseData<'a> {
     where
         S: serde::Serializer,
     {
+        let ctx = SerializationContext {
+            schema: &self.data.schema,
+            keys: &self.data.operation.response_keys,
+            parts: &self.data.parts,
+            streamed_lists: &[],
+        };
         self.data
             .root
-            .as_ref()
             .map(|root_id| SerializableResponseObject {
-                data: self.data,
-                object: &self.data[*root_id],
+                ctx,
+                object: ctx.object(root_id),
+                only: None,
             })
             .serialize(serializer)
     }
 }
 
-struct SerializableResponseObject<'a> {
-    data: &'a ResponseData,
-    object: &'a ResponseObject,
+/// Everything needed to serialize response data, whether the response is complete or still
+/// being written to.
+#[derive(Clone, Copy)]
+pub(in crate::response) struct SerializationContext<'a> {
+    pub schema: &'a Schema,
+    pub keys: &'a ResponseKeys,
+    pub parts: &'a [ResponseDataPart],
+    /// Lists for which only the first items are serialized, the others being streamed afterwards.
+    pub streamed_lists: &'a [(ResponseEdge, usize)],
+}
+
+impl<'a> SerializationContext<'a> {
+    pub fn object(&self, id: ResponseObjectId) -> &'a ResponseObject {
+        &self.parts[usize::from(id.part_id)][id]
+    }
+
+    pub fn list(&self, id: ResponseListId) -> &'a [ResponseValue] {
+        &self.parts[usize::from(id.part_id)][id]
+    }
+
+    fn initial_count(&self, edge: ResponseEdge) -> Option<usize> {
+        self.streamed_lists
+            .iter()
+            .find(|(streamed_edge, _)| *streamed_edge == edge)
+            .map(|(_, initial_count)| *initial_count)
+    }
+}
+
+pub(in crate::response) struct SerializableResponseObject<'a> {
+    pub ctx: SerializationContext<'a>,
+    pub object: &'a ResponseObject,
+    /// If present, only those fields are serialized. Used for deferred fragments.
+    pub only: Option<&'a [ResponseEdge]>,
 }
 
 impl<'a> serde::Serialize for SerializableResponseObject<'a> {
@@ -201,7 +375,7 @@ impl<'a> serde::Serialize for SerializableResponseObject<'a> {
         S: serde::Serializer,
     {
         let mut map = serializer.serialize_map(Some(self.object.len()))?;
-        let keys = &self.data.operation.response_keys;
+        let keys = self.ctx.keys;
         // Thanks to the BoundResponseKey starting with the position and the fields being a BTreeMap
         // we're ensuring the fields are serialized in the order they appear in the query.
         for ResponseObjectField { edge, value, .. } in self.object.fields() {
@@ -210,42 +384,25 @@ impl<'a> serde::Serialize for SerializableResponseObject<'a> {
                 // don't need to be serialized.
                 break;
             };
-            map.serialize_key(&keys[key])?;
-            match value {
-                ResponseValue::Null => map.serialize_value(&())?,
-                ResponseValue::Boolean { value, .. } => map.serialize_value(value)?,
-                ResponseValue::Int { value, .. } => map.serialize_value(value)?,
-                ResponseValue::Float { value, .. } => map.serialize_value(value)?,
-                ResponseValue::String { value, .. } => map.serialize_value(&value)?,
-                ResponseValue::StringId { id, .. } => map.serialize_value(&self.data.schema[*id])?,
-                ResponseValue::BigInt { value, .. } => map.serialize_value(value)?,
-                &ResponseValue::List {
-                    part_id,
-                    offset,
-                    length,
-                    ..
-                } => map.serialize_value(&SerializableResponseList {
-                    data: self.data,
-                    value: &self.data[ResponseListId {
-                        part_id,
-                        offset,
-                        length,
-                    }],
-                })?,
-                &ResponseValue::Object { part_id, index, .. } => map.serialize_value(&SerializableResponseObject {
-                    data: self.data,
-                    object: &self.data[ResponseObjectId { part_id, index }],
-                })?,
-                ResponseValue::Json { value, .. } => map.serialize_value(value)?,
+            if self.only.is_some_and(|only| !only.contains(edge)) {
+                continue;
             }
+            map.serialize_entry(
+                &keys[key],
+                &SerializableResponseValue {
+                    ctx: self.ctx,
+                    value,
+                    max_items: self.ctx.initial_count(*edge),
+                },
+            )?;
         }
         map.end()
     }
 }
 
-struct SerializableResponseList<'a> {
-    data: &'a ResponseData,
-    value: &'a [ResponseValue],
+pub(in crate::response) struct SerializableResponseList<'a> {
+    pub ctx: SerializationContext<'a>,
+    pub value: &'a [ResponseValue],
 }
 
 impl<'a> serde::Serialize for SerializableResponseList<'a> {
@@ -254,37 +411,61 @@ impl<'a> serde::Serialize for SerializableResponseList<'a> {
         S: serde::Serializer,
     {
         let mut seq = serializer.serialize_seq(Some(self.value.len()))?;
-        for node in self.value {
-            match node {
-                ResponseValue::Null => seq.serialize_element(&())?,
-                ResponseValue::Boolean { value, .. } => seq.serialize_element(value)?,
-                ResponseValue::Int { value, .. } => seq.serialize_element(value)?,
-                ResponseValue::Float { value, .. } => seq.serialize_element(value)?,
-                ResponseValue::String { value, .. } => seq.serialize_element(&value)?,
-                ResponseValue::StringId { id, .. } => seq.serialize_element(&self.data.schema[*id])?,
-                ResponseValue::BigInt { value, .. } => seq.serialize_element(value)?,
-                &ResponseValue::List {
+        for value in self.value {
+            seq.serialize_element(&SerializableResponseValue {
+                ctx: self.ctx,
+                value,
+                max_items: None,
+            })?;
+        }
+        seq.end()
+    }
+}
+
+struct SerializableResponseValue<'a> {
+    ctx: SerializationContext<'a>,
+    value: &'a ResponseValue,
+    /// Only set for streamed lists.
+    max_items: Option<usize>,
+}
+
+impl<'a> serde::Serialize for SerializableResponseValue<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self.value {
+            ResponseValue::Null => serializer.serialize_unit(),
+            ResponseValue::Boolean { value, .. } => value.serialize(serializer),
+            ResponseValue::Int { value, .. } => value.serialize(serializer),
+            ResponseValue::Float { value, .. } => value.serialize(serializer),
+            ResponseValue::String { value, .. } => value.serialize(serializer),
+            ResponseValue::StringId { id, .. } => self.ctx.schema[*id].serialize(serializer),
+            ResponseValue::BigInt { value, .. } => value.serialize(serializer),
+            &ResponseValue::List {
+                part_id,
+                offset,
+                length,
+                ..
+            } => {
+                let value = self.ctx.list(ResponseListId {
                     part_id,
                     offset,
                     length,
-                    ..
-                } => seq.serialize_element(&SerializableResponseList {
-                    data: self.data,
-                    value: &self.data[ResponseListId {
-                        part_id,
-                        offset,
-                        length,
-                    }],
-                })?,
-                &ResponseValue::Object { part_id, index, .. } => {
-                    seq.serialize_element(&SerializableResponseObject {
-                        data: self.data,
-                        object: &self.data[ResponseObjectId { part_id, index }],
-                    })?
+                });
+                SerializableResponseList {
+                    ctx: self.ctx,
+                    value: &value[..self.max_items.unwrap_or(value.len()).min(value.len())],
                 }
-                ResponseValue::Json { value, .. } => seq.serialize_element(value)?,
+                .serialize(serializer)
             }
+            &ResponseValue::Object { part_id, index, .. } => SerializableResponseObject {
+                ctx: self.ctx,
+                object: self.ctx.object(ResponseObjectId { part_id, index }),
+                only: None,
+            }
+            .serialize(serializer),
+            ResponseValue::Json { value, .. } => value.serialize(serializer),
         }
-        seq.end()
     }
 }
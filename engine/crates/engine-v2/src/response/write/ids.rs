@@ -89,6 +89,14 @@ impl std::ops::Index<ResponseListId> for ResponseData {
     }
 }
 
+impl std::ops::Index<ResponseObjectId> for ResponseDataPart {
+    type Output = ResponseObject;
+
+    fn index(&self, index: ResponseObjectId) -> &Self::Output {
+        &self.objects[index.index as usize]
+    }
+}
+
 impl std::ops::Index<ResponseListId> for ResponseDataPart {
     type Output = [ResponseValue];
 
@@ -15,13 +15,16 @@ use schema::{ObjectDefinitionId, Schema};
 use self::deserialize::UpdateSeed;
 
 use super::{
-    value::ResponseObjectField, ErrorCode, GraphqlError, InitialResponse, InputdResponseObjectSet,
-    OutputResponseObjectSets, Response, ResponseData, ResponseEdge, ResponseObject, ResponseObjectRef,
-    ResponseObjectSet, ResponseObjectSetId, ResponsePath, ResponseValue, UnpackedResponseEdge,
+    read::{SerializableResponseList, SerializableResponseObject, SerializationContext},
+    value::ResponseObjectField,
+    ErrorCode, GraphqlError, IncrementalInitialResponse, IncrementalPayload, IncrementalPayloadData,
+    IncrementalSubsequentResponse, InitialResponse, InputdResponseObjectSet, OutputResponseObjectSets, Response,
+    ResponseData, ResponseEdge, ResponseObject, ResponseObjectRef, ResponseObjectSet, ResponseObjectSetId,
+    ResponsePath, ResponseValue, UnpackedResponseEdge,
 };
 use crate::{
     execution::{ExecutionContext, ExecutionError},
-    operation::{LogicalPlanId, PreparedOperation},
+    operation::{LogicalPlanId, PreparedOperation, StreamedList, Variables},
     Runtime,
 };
 
@@ -45,6 +48,13 @@ impl ResponseDataPart {
     }
 }
 
+/// Response object for which a deferred fragment provides some of the fields.
+pub(crate) struct DeferredResponseObject {
+    pub label: Option<String>,
+    pub object: ResponseObjectRef,
+    pub edges: Vec<ResponseEdge>,
+}
+
 pub(crate) struct ResponseBuilder {
     // will be None if an error propagated up to the root.
     pub(super) root: Option<(ResponseObjectId, ObjectDefinitionId)>,
@@ -242,9 +252,236 @@ impl ResponseBuilder {
                 parts: self.parts,
             },
             errors: self.errors,
+            extensions: Default::default(),
         })
     }
 
+    /// Initial payload for incremental delivery. Contrary to `build()` deferred plans will keep
+    /// writing into the response afterwards.
+    pub fn build_incremental_initial(
+        &mut self,
+        schema: &Schema,
+        operation: Arc<PreparedOperation>,
+        variables: &Variables,
+    ) -> IncrementalInitialResponse {
+        let streamed_lists = streamed_lists(&operation, variables);
+        let ctx = SerializationContext {
+            schema,
+            keys: &operation.response_keys,
+            parts: &self.parts,
+            streamed_lists: &streamed_lists,
+        };
+        let data = self.root.and_then(|(root_id, _)| {
+            to_raw_value(&SerializableResponseObject {
+                ctx,
+                object: ctx.object(root_id),
+                only: None,
+            })
+        });
+        IncrementalInitialResponse {
+            data,
+            errors: std::mem::take(&mut self.errors),
+            extensions: Default::default(),
+            operation,
+            has_next: true,
+        }
+    }
+
+    /// Subsequent payload for incremental delivery with all the errors since the previous one.
+    /// Errors are added to the first incremental payload containing them.
+    pub fn build_incremental_subsequent(
+        &mut self,
+        schema: &Schema,
+        operation: Arc<PreparedOperation>,
+        variables: &Variables,
+        objects: Vec<DeferredResponseObject>,
+        streams: Vec<(Option<String>, StreamedList)>,
+    ) -> IncrementalSubsequentResponse {
+        let streamed_lists = streamed_lists(&operation, variables);
+        let ctx = SerializationContext {
+            schema,
+            keys: &operation.response_keys,
+            parts: &self.parts,
+            streamed_lists: &streamed_lists,
+        };
+        let mut incremental = objects
+            .into_iter()
+            .map(|DeferredResponseObject { label, object, edges }| {
+                let data = if self.is_reachable(&object.path) {
+                    to_raw_value(&SerializableResponseObject {
+                        ctx,
+                        object: ctx.object(object.id),
+                        only: Some(&edges),
+                    })
+                } else {
+                    None
+                };
+                IncrementalPayload {
+                    label,
+                    path: object.path,
+                    data: IncrementalPayloadData::Object(data),
+                    errors: Vec::new(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        for (label, stream) in streams {
+            let initial_count = stream.resolve_initial_count(&operation, variables);
+            let mut lists = Vec::new();
+            if let Some((root_id, _)) = self.root {
+                self.collect_lists(
+                    &self[root_id],
+                    ResponsePath::default(),
+                    stream.bound_response_key.into(),
+                    &mut lists,
+                );
+            }
+            for (path, list_id) in lists {
+                let items = &ctx.list(list_id)[initial_count.min(list_id.length as usize)..];
+                if items.is_empty() {
+                    continue;
+                }
+                let Some(items) = to_raw_value(&SerializableResponseList { ctx, value: items }) else {
+                    continue;
+                };
+                incremental.push(IncrementalPayload {
+                    label: label.clone(),
+                    path: path.child(initial_count),
+                    data: IncrementalPayloadData::Items(items),
+                    errors: Vec::new(),
+                });
+            }
+        }
+
+        let mut errors = Vec::new();
+        for error in std::mem::take(&mut self.errors) {
+            let payload = error
+                .path
+                .as_ref()
+                .and_then(|path| incremental.iter_mut().find(|payload| payload.contains(path)));
+            match payload {
+                Some(payload) => payload.errors.push(error),
+                None => errors.push(error),
+            }
+        }
+
+        IncrementalSubsequentResponse {
+            operation,
+            incremental,
+            errors,
+            has_next: true,
+        }
+    }
+
+    /// Finds all the lists, reachable from this object, stored under the given edge.
+    fn collect_lists(
+        &self,
+        object: &ResponseObject,
+        path: ResponsePath,
+        edge: ResponseEdge,
+        lists: &mut Vec<(ResponsePath, ResponseListId)>,
+    ) {
+        for field in object.fields() {
+            let path = path.child(field.edge);
+            if field.edge == edge {
+                if let ResponseValue::List {
+                    part_id,
+                    offset,
+                    length,
+                    ..
+                } = field.value
+                {
+                    lists.push((
+                        path.clone(),
+                        ResponseListId {
+                            part_id,
+                            offset,
+                            length,
+                        },
+                    ));
+                }
+            }
+            self.collect_value_lists(&field.value, path, edge, lists);
+        }
+    }
+
+    fn collect_value_lists(
+        &self,
+        value: &ResponseValue,
+        path: ResponsePath,
+        edge: ResponseEdge,
+        lists: &mut Vec<(ResponsePath, ResponseListId)>,
+    ) {
+        match *value {
+            ResponseValue::Object { part_id, index, .. } => {
+                self.collect_lists(&self[ResponseObjectId { part_id, index }], path, edge, lists)
+            }
+            ResponseValue::List {
+                part_id,
+                offset,
+                length,
+                ..
+            } => {
+                for (i, item) in self[ResponseListId {
+                    part_id,
+                    offset,
+                    length,
+                }]
+                .iter()
+                .enumerate()
+                {
+                    self.collect_value_lists(item, path.child(i), edge, lists);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether the value at this path is still present in the response. An error may have
+    /// propagated up to one of its parents.
+    fn is_reachable(&self, path: &[ResponseEdge]) -> bool {
+        let Some((root, _)) = self.root else {
+            return false;
+        };
+
+        let mut previous: Either<ResponseObjectId, ResponseListId> = Either::Left(root);
+        for &edge in path {
+            let value = match (previous, edge.unpack()) {
+                (
+                    Either::Left(object_id),
+                    UnpackedResponseEdge::BoundResponseKey(_) | UnpackedResponseEdge::ExtraFieldResponseKey(_),
+                ) => {
+                    let Some(field_position) = self[object_id].field_position(edge) else {
+                        return false;
+                    };
+                    &self[object_id][field_position]
+                }
+                (Either::Right(list_id), UnpackedResponseEdge::Index(index)) => {
+                    let Some(value) = self[list_id].get(index) else {
+                        return false;
+                    };
+                    value
+                }
+                _ => return false,
+            };
+            previous = match *value {
+                ResponseValue::Object { part_id, index, .. } => Either::Left(ResponseObjectId { part_id, index }),
+                ResponseValue::List {
+                    part_id,
+                    offset,
+                    length,
+                    ..
+                } => Either::Right(ResponseListId {
+                    part_id,
+                    offset,
+                    length,
+                }),
+                _ => return false,
+            };
+        }
+        true
+    }
+
     // The path corresponds to place where a plan failed but couldn't go propagate higher as data
     // was in a different part (provided by a parent plan).
     // To correctly propagate error we're finding the last nullable element in the path and make it
@@ -335,6 +572,28 @@ impl ResponseBuilder {
     }
 }
 
+/// Edges of all the enabled streamed lists with their initial count.
+fn streamed_lists(operation: &PreparedOperation, variables: &Variables) -> Vec<(ResponseEdge, usize)> {
+    operation
+        .deferred_fragments
+        .iter()
+        .filter(|fragment| fragment.is_enabled(operation, variables))
+        .filter_map(|fragment| fragment.stream)
+        .map(|stream| {
+            (
+                stream.bound_response_key.into(),
+                stream.resolve_initial_count(operation, variables),
+            )
+        })
+        .collect()
+}
+
+fn to_raw_value(value: &impl serde::Serialize) -> Option<Box<serde_json::value::RawValue>> {
+    serde_json::value::to_raw_value(value)
+        .map_err(|err| tracing::error!("Failed to serialize response data: {err}"))
+        .ok()
+}
+
 enum ResponseValueId {
     ObjectField {
         object_id: ResponseObjectId,
@@ -385,6 +644,10 @@ impl SubgraphResponse {
         }
     }
 
+    pub fn root_response_object_set(&self) -> &Arc<InputdResponseObjectSet> {
+        &self.root_response_object_set
+    }
+
     pub fn subgraph_errors(&self) -> impl Iterator<Item = &GraphqlError> + '_ {
         self.errors.iter().filter(|e| {
             matches!(
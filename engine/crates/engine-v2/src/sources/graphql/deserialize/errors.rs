@@ -1,3 +1,4 @@
+use schema::sources::graphql::{GraphqlEndpointId, GraphqlEndpointWalker};
 use serde::{de::DeserializeSeed, Deserializer};
 
 use crate::{
@@ -8,19 +9,26 @@ use crate::{
 
 pub(super) trait GraphqlErrorsSeed<'resp> {
     fn response(&self) -> &SubgraphResponseRefMut<'resp>;
+    fn endpoint(&self) -> GraphqlEndpointWalker<'resp>;
     fn convert_path(&self, path: &serde_json::Value) -> Option<ResponsePath>;
 }
 
 pub(in crate::sources::graphql) struct RootGraphqlErrors<'resp> {
     response: SubgraphResponseRefMut<'resp>,
     response_keys: &'resp ResponseKeys,
+    endpoint: GraphqlEndpointWalker<'resp>,
 }
 
 impl<'resp> RootGraphqlErrors<'resp> {
-    pub fn new<R: Runtime>(ctx: ExecutionContext<'resp, R>, response: SubgraphResponseRefMut<'resp>) -> Self {
+    pub fn new<R: Runtime>(
+        ctx: ExecutionContext<'resp, R>,
+        endpoint_id: GraphqlEndpointId,
+        response: SubgraphResponseRefMut<'resp>,
+    ) -> Self {
         Self {
             response,
             response_keys: &ctx.operation.response_keys,
+            endpoint: ctx.schema().walk(endpoint_id),
         }
     }
 }
@@ -30,6 +38,10 @@ impl<'resp> GraphqlErrorsSeed<'resp> for RootGraphqlErrors<'resp> {
         &self.response
     }
 
+    fn endpoint(&self) -> GraphqlEndpointWalker<'resp> {
+        self.endpoint
+    }
+
     fn convert_path(&self, path: &serde_json::Value) -> Option<ResponsePath> {
         let mut out = ResponsePath::default();
         for edge in path.as_array()? {
@@ -78,11 +90,39 @@ where
     {
         let errors = <Vec<SubgraphGraphqlError> as serde::Deserialize>::deserialize(deserializer)?;
         let errors_count = errors.len();
+        let endpoint = self.0.endpoint();
+        let masking = endpoint.error_masking();
         let errors = errors
             .into_iter()
             .map(|subgraph_error| {
-                let mut error = GraphqlError::new(subgraph_error.message, ErrorCode::SubgraphError);
-                if let Some(path) = self.0.convert_path(&subgraph_error.path) {
+                let upstream_code = subgraph_error.extensions.get("code").and_then(|code| code.as_str());
+                let code = upstream_code
+                    .and_then(|code| masking.public_code(code))
+                    .map(ErrorCode::from)
+                    .unwrap_or(ErrorCode::SubgraphError);
+                let path = self.0.convert_path(&subgraph_error.path);
+
+                if masking.should_mask(upstream_code) {
+                    let correlation_id = ulid::Ulid::new().to_string();
+                    tracing::warn!(
+                        subgraph.name = endpoint.subgraph_name(),
+                        correlation_id = correlation_id.as_str(),
+                        upstream.path = %subgraph_error.path,
+                        upstream.extensions = %subgraph_error.extensions,
+                        "Masked subgraph error: {}",
+                        subgraph_error.message
+                    );
+
+                    let mut error = GraphqlError::new(masking.message().to_string(), code)
+                        .with_extension("correlation_id", correlation_id);
+                    if let Some(path) = path {
+                        error = error.with_path(path);
+                    }
+                    return error;
+                }
+
+                let mut error = GraphqlError::new(subgraph_error.message, code);
+                if let Some(path) = path {
                     error = error.with_path(path);
                 } else if !subgraph_error.path.is_null() {
                     error = error.with_extension("upstream_path", subgraph_error.path);
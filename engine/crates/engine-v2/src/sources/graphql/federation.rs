@@ -1,12 +1,12 @@
 use bytes::Bytes;
-use futures::future::join_all;
+use futures::future::{join_all, try_join_all};
 use grafbase_telemetry::{gql_response_status::GraphqlResponseStatus, span::subgraph::SubgraphRequestSpan};
 use http::HeaderMap;
 use runtime::fetch::FetchRequest;
 use schema::sources::graphql::{FederationEntityResolveDefinitionrWalker, GraphqlEndpointId};
-use serde::{de::DeserializeSeed, Deserialize};
+use serde::de::DeserializeSeed;
 use serde_json::value::RawValue;
-use std::{borrow::Cow, future::Future, time::Duration};
+use std::{borrow::Cow, collections::HashMap, future::Future, time::Duration};
 use tracing::Instrument;
 
 use crate::{
@@ -15,7 +15,7 @@ use crate::{
     response::{ResponseObjectsView, SubgraphResponse},
     sources::{
         graphql::{
-            deserialize::{EntitiesErrorsSeed, GraphqlResponseSeed},
+            deserialize::{ConcreteGraphqlErrorsSeed, EntitiesErrorsSeed},
             request::{SubgraphGraphqlRequest, SubgraphVariables},
         },
         ExecutionResult, Resolver,
@@ -24,8 +24,11 @@ use crate::{
 };
 
 use super::{
-    deserialize::EntitiesDataSeed,
-    request::{execute_subgraph_request, PreparedFederationEntityOperation, ResponseIngester},
+    request::{
+        execute_subgraph_request, http_client_settings, request_compression, IngestedResponse,
+        PreparedFederationEntityOperation, ResponseIngester,
+    },
+    traffic_split::{route_subgraph_request, SubgraphRoute},
 };
 
 pub(crate) struct FederationEntityResolver {
@@ -46,12 +49,20 @@ impl FederationEntityResolver {
         }))
     }
 
+    pub fn endpoint_id(&self) -> GraphqlEndpointId {
+        self.endpoint_id
+    }
+
+    pub fn query(&self) -> &str {
+        &self.operation.query
+    }
+
     pub fn execute<'ctx, 'fut, R: Runtime>(
         &'ctx self,
         ctx: ExecutionContext<'ctx, R>,
         plan: PlanWalker<'ctx, (), ()>,
         root_response_objects: ResponseObjectsView<'_>,
-        subgraph_response: SubgraphResponse,
+        mut subgraph_response: SubgraphResponse,
     ) -> ExecutionResult<impl Future<Output = ExecutionResult<SubgraphResponse>> + Send + 'fut>
     where
         'ctx: 'fut,
@@ -60,143 +71,288 @@ impl FederationEntityResolver {
             "__typename".to_string(),
             serde_json::Value::String(entity_name(ctx, plan)),
         )]);
-        let mut representations = root_response_objects
+        let representations = root_response_objects
             .iter()
             .map(|object| serde_json::to_string(&object).and_then(RawValue::from_string))
             .collect::<Result<Vec<_>, _>>()?;
+        let representations = Representations::deduplicate(representations);
 
         let endpoint = ctx.engine.schema.walk(self.endpoint_id);
-        let span = SubgraphRequestSpan {
-            name: endpoint.subgraph_name(),
-            operation_type: OperationType::Query.as_str(),
-            // The generated query does not contain any data, everything are in the variables, so
-            // it's safe to use.
-            sanitized_query: &self.operation.query,
-            url: endpoint.url(),
-        }
-        .into_span();
+        let route = route_subgraph_request(ctx, endpoint);
+
+        Ok(async move {
+            let headers = ctx.subgraph_headers_with_rules(endpoint.header_rules());
+
+            let chunk_size = endpoint
+                .max_entities_per_request()
+                .unwrap_or(representations.unique.len())
+                .max(1);
+            let chunks = try_join_all(
+                representations
+                    .unique
+                    .chunks(chunk_size)
+                    .map(|chunk| self.fetch_chunk(ctx, plan, &route, &headers, chunk)),
+            )
+            .await?;
+
+            representations.ingest(ctx, self.endpoint_id, chunks, &mut subgraph_response)?;
+
+            Ok(subgraph_response)
+        })
+    }
 
+    /// Retrieves the entities of a chunk of distinct representations, from the cache if possible
+    /// and from the subgraph otherwise.
+    async fn fetch_chunk<R: Runtime>(
+        &self,
+        ctx: ExecutionContext<'_, R>,
+        plan: PlanWalker<'_, (), ()>,
+        route: &SubgraphRoute<'_>,
+        headers: &http::HeaderMap,
+        representations: &[Box<RawValue>],
+    ) -> ExecutionResult<EntitiesChunk> {
+        let endpoint = ctx.engine.schema.walk(self.endpoint_id);
         let cache_ttl = endpoint.entity_cache_ttl();
 
-        let fut = {
-            let span = span.clone();
-            async move {
-                let mut ingester = EntityIngester {
+        let cache_entries = match cache_ttl {
+            Some(_) => {
+                join_all(
+                    representations
+                        .iter()
+                        .map(|repr| cache_fetch(ctx, endpoint.subgraph_name(), headers, repr)),
+                )
+                .await
+            }
+            None => Vec::new(),
+        };
+
+        // Position within the chunk of every representation we need to request.
+        let missing_positions = (0..representations.len())
+            .filter(|&i| cache_entries.get(i).map(CacheEntry::is_miss).unwrap_or(true))
+            .collect::<Vec<_>>();
+
+        let fetched = if missing_positions.is_empty() {
+            FetchedEntities::default()
+        } else {
+            let variables = SubgraphVariables {
+                plan,
+                variables: &self.operation.variables,
+                extra_variables: vec![(
+                    self.operation.entities_variable_name.as_str(),
+                    missing_positions
+                        .iter()
+                        .map(|&i| representations[i].as_ref())
+                        .collect::<Vec<_>>(),
+                )],
+            };
+
+            tracing::debug!(
+                "Query {}\n{}\n{}",
+                endpoint.subgraph_name(),
+                self.operation.query,
+                serde_json::to_string_pretty(&variables).unwrap_or_default()
+            );
+            let body = serde_json::to_vec(&SubgraphGraphqlRequest {
+                query: &self.operation.query,
+                variables,
+            })
+            .map_err(|err| format!("Failed to serialize query: {err}"))?;
+
+            let retry_budget = ctx.engine.get_retry_budget_for_query(self.endpoint_id);
+
+            // Each chunk is a distinct subgraph request with its own status.
+            let span = SubgraphRequestSpan {
+                name: endpoint.subgraph_name(),
+                operation_type: OperationType::Query.as_str(),
+                // The generated query does not contain any data, everything are in the variables, so
+                // it's safe to use.
+                sanitized_query: &self.operation.query,
+                url: route.url,
+                variant: route.variant,
+            }
+            .into_span();
+
+            execute_subgraph_request(
+                ctx,
+                span.clone(),
+                self.endpoint_id,
+                OperationType::Query,
+                retry_budget,
+                || FetchRequest {
+                    url: route.url,
+                    headers: headers.clone(),
+                    json_body: Bytes::from(body),
+                    timeout: endpoint.timeout(),
+                    compression: request_compression(endpoint),
+                    client_settings: http_client_settings(endpoint),
+                },
+                EntityIngester {
                     ctx,
-                    cache_entries: None,
-                    subgraph_response,
-                    cache_ttl,
-                };
+                    cache_ttl_and_keys: cache_ttl.map(|ttl| {
+                        let keys = cache_entries
+                            .iter()
+                            .filter_map(|entry| match entry {
+                                CacheEntry::Miss { key } => Some(key.clone()),
+                                CacheEntry::Hit { .. } => None,
+                            })
+                            .collect();
+                        (ttl, keys)
+                    }),
+                },
+            )
+            .instrument(span)
+            .await?
+        };
 
-                let headers = ctx.subgraph_headers_with_rules(endpoint.header_rules());
+        let FetchedEntities { data, mut errors } = fetched;
+        let mut fetched_entities = data.map(|data| data.entities).unwrap_or_default().into_iter();
+
+        let entities = (0..representations.len())
+            .map(|i| match cache_entries.get(i) {
+                Some(CacheEntry::Hit { data }) => serde_json::from_slice(data)
+                    .inspect_err(|err| tracing::warn!("Invalid cached entity: {err}"))
+                    .ok(),
+                _ => fetched_entities.next(),
+            })
+            .collect();
+        if fetched_entities.next().is_some() {
+            errors.push(serde_json::json!({ "message": "Received more entities than expected" }));
+        }
 
-                if cache_ttl.is_some() {
-                    match cache_fetches(ctx, endpoint, &headers, representations).await {
-                        CacheFetchOutcome::FullyCached { cache_entries } => {
-                            ingester.cache_entries = Some(cache_entries);
+        // Entity errors refer to the requested representations, converting them to chunk positions.
+        for error in &mut errors {
+            if let Some(index) = entity_error_index(error) {
+                if let Some(&position) = missing_positions.get(index) {
+                    error["path"][1] = position.into();
+                }
+            }
+        }
 
-                            let (_, response) = ingester
-                                .ingest(Bytes::from_static(br#"{"data": {"_entities": []}}"#))
-                                .await?;
+        Ok(EntitiesChunk { entities, errors })
+    }
+}
 
-                            return Ok(response);
-                        }
-                        CacheFetchOutcome::Other {
-                            cache_entries,
-                            filtered_representations,
-                        } => {
-                            ingester.cache_entries = cache_entries;
-                            representations = filtered_representations;
+/// Representations of the root response objects, deduplicated to only request each entity once.
+struct Representations {
+    unique: Vec<Box<RawValue>>,
+    /// Index within `unique` of the representation of each root response object.
+    positions: Vec<usize>,
+}
+
+impl Representations {
+    fn deduplicate(representations: Vec<Box<RawValue>>) -> Self {
+        let mut unique = Vec::<Box<RawValue>>::new();
+        let mut positions = Vec::with_capacity(representations.len());
+        let mut index_by_repr = HashMap::<Box<str>, usize>::new();
+
+        for repr in representations {
+            let index = match index_by_repr.get(repr.get()) {
+                Some(&index) => index,
+                None => {
+                    let index = unique.len();
+                    index_by_repr.insert(repr.get().into(), index);
+                    unique.push(repr);
+                    index
+                }
+            };
+            positions.push(index);
+        }
+
+        Self { unique, positions }
+    }
+
+    /// Writes the entities of the root response objects into the subgraph response, as if each of
+    /// them had been requested.
+    fn ingest<R: Runtime>(
+        self,
+        ctx: ExecutionContext<'_, R>,
+        endpoint_id: GraphqlEndpointId,
+        chunks: Vec<EntitiesChunk>,
+        subgraph_response: &mut SubgraphResponse,
+    ) -> ExecutionResult<()> {
+        let mut entities = Vec::with_capacity(self.unique.len());
+        let mut errors = Vec::new();
+        let mut positions_by_unique = vec![Vec::new(); self.unique.len()];
+        for (position, &index) in self.positions.iter().enumerate() {
+            positions_by_unique[index].push(position);
+        }
+
+        for chunk in chunks {
+            let offset = entities.len();
+            for error in chunk.errors {
+                match entity_error_index(&error) {
+                    Some(index) => {
+                        for &position in positions_by_unique.get(offset + index).into_iter().flatten() {
+                            let mut error = error.clone();
+                            error["path"][1] = position.into();
+                            errors.push(error);
                         }
                     }
+                    None => errors.push(error),
                 }
-                let variables = SubgraphVariables {
-                    plan,
-                    variables: &self.operation.variables,
-                    extra_variables: vec![(&self.operation.entities_variable_name, representations)],
-                };
-
-                tracing::debug!(
-                    "Query {}\n{}\n{}",
-                    endpoint.subgraph_name(),
-                    self.operation.query,
-                    serde_json::to_string_pretty(&variables).unwrap_or_default()
-                );
-                let body = serde_json::to_vec(&SubgraphGraphqlRequest {
-                    query: &self.operation.query,
-                    variables,
-                })
-                .map_err(|err| format!("Failed to serialize query: {err}"))?;
-
-                let retry_budget = ctx.engine.get_retry_budget_for_query(self.endpoint_id);
-
-                execute_subgraph_request(
-                    ctx,
-                    span.clone(),
-                    self.endpoint_id,
-                    retry_budget,
-                    move || FetchRequest {
-                        url: endpoint.url(),
-                        headers,
-                        json_body: Bytes::from(body),
-                        timeout: endpoint.timeout(),
-                    },
-                    ingester,
-                )
-                .await
+            }
+            entities.extend(chunk.entities);
+        }
+
+        let response = subgraph_response.as_mut();
+        for &index in &self.positions {
+            let Some(seed) = response.next_seed(ctx) else {
+                break;
+            };
+            // Missing entities keep their reserved slot, like a null one.
+            if let Some(entity) = entities.get(index).and_then(Option::as_deref) {
+                seed.deserialize(&mut serde_json::Deserializer::from_str(entity.get()))?;
             }
         }
-        .instrument(span);
 
-        Ok(fut)
+        if !errors.is_empty() {
+            ConcreteGraphqlErrorsSeed(EntitiesErrorsSeed::new(ctx, endpoint_id, response))
+                .deserialize(serde_json::Value::Array(errors))?;
+        }
+
+        Ok(())
     }
 }
 
-async fn cache_fetches<'ctx, R: Runtime>(
-    ctx: ExecutionContext<'ctx, R>,
-    endpoint: schema::SchemaWalker<'_, GraphqlEndpointId>,
-    headers: &http::HeaderMap,
-    representations: Vec<Box<RawValue>>,
-) -> CacheFetchOutcome {
-    let fetches = representations
-        .iter()
-        .map(|repr| cache_fetch(ctx, endpoint.subgraph_name(), headers, repr));
-
-    let cache_entries = join_all(fetches).await;
-    let fully_cached = !cache_entries.iter().any(CacheEntry::is_miss);
-
-    if fully_cached {
-        return CacheFetchOutcome::FullyCached { cache_entries };
+/// Index of the entity an error refers to, if any.
+fn entity_error_index(error: &serde_json::Value) -> Option<usize> {
+    let path = error.get("path")?.as_array()?;
+    if path.first()?.as_str()? != "_entities" {
+        return None;
     }
+    path.get(1)?.as_u64().map(|index| index as usize)
+}
 
-    let filtered_representations = representations
-        .into_iter()
-        .zip(cache_entries.iter())
-        .filter(|(_, cache_entry)| cache_entry.is_miss())
-        .map(|(repr, _)| repr)
-        .collect();
+struct EntitiesChunk {
+    /// Entity of every representation in the chunk, if present.
+    entities: Vec<Option<Box<RawValue>>>,
+    errors: Vec<serde_json::Value>,
+}
 
-    CacheFetchOutcome::Other {
-        cache_entries: Some(cache_entries),
-        filtered_representations,
-    }
+#[derive(Default, serde::Deserialize)]
+struct FetchedEntities {
+    #[serde(default)]
+    data: Option<FetchedEntitiesData>,
+    #[serde(default)]
+    errors: Vec<serde_json::Value>,
 }
 
-enum CacheFetchOutcome {
-    FullyCached {
-        cache_entries: Vec<CacheEntry>,
-    },
-    Other {
-        cache_entries: Option<Vec<CacheEntry>>,
-        filtered_representations: Vec<Box<RawValue>>,
-    },
+#[derive(serde::Deserialize)]
+struct FetchedEntitiesData {
+    #[serde(rename = "_entities", default)]
+    entities: Vec<Box<RawValue>>,
+}
+
+impl IngestedResponse for FetchedEntities {
+    fn first_subgraph_error_message(&self) -> Option<&str> {
+        self.errors.first()?.get("message")?.as_str()
+    }
 }
 
 struct EntityIngester<'ctx, R: Runtime> {
     ctx: ExecutionContext<'ctx, R>,
-    cache_entries: Option<Vec<CacheEntry>>,
-    subgraph_response: SubgraphResponse,
-    cache_ttl: Option<Duration>,
+    /// Cache keys of the requested entities.
+    cache_ttl_and_keys: Option<(Duration, Vec<String>)>,
 }
 
 pub enum CacheEntry {
@@ -208,102 +364,60 @@ impl CacheEntry {
     pub fn is_miss(&self) -> bool {
         matches!(self, CacheEntry::Miss { .. })
     }
-
-    pub fn as_data(&self) -> Option<&[u8]> {
-        match self {
-            CacheEntry::Hit { data } => Some(data),
-            _ => None,
-        }
-    }
 }
 
 impl<'ctx, R> ResponseIngester for EntityIngester<'ctx, R>
 where
     R: Runtime,
 {
-    async fn ingest(self, bytes: Bytes) -> Result<(GraphqlResponseStatus, SubgraphResponse), ExecutionError> {
-        let Self {
-            ctx,
-            cache_entries,
-            mut subgraph_response,
-            cache_ttl,
-        } = self;
-
-        let status = {
-            let response = subgraph_response.as_mut();
-            GraphqlResponseSeed::new(
-                EntitiesDataSeed {
-                    ctx,
-                    response: response.clone(),
-                    cache_entries: cache_entries.as_deref(),
-                },
-                EntitiesErrorsSeed::new(ctx, response),
-            )
-            .deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?
+    type Output = FetchedEntities;
+
+    async fn ingest(self, bytes: Bytes) -> Result<(GraphqlResponseStatus, FetchedEntities), ExecutionError> {
+        let fetched = serde_json::from_slice::<FetchedEntities>(&bytes)
+            .map_err(|err| ExecutionError::DeserializationError(err.to_string()))?;
+
+        let status = if fetched.errors.is_empty() {
+            GraphqlResponseStatus::Success
+        } else if fetched.data.is_some() {
+            GraphqlResponseStatus::FieldError {
+                count: fetched.errors.len() as u64,
+                data_is_null: false,
+            }
+        } else {
+            GraphqlResponseStatus::RequestError {
+                count: fetched.errors.len() as u64,
+            }
         };
 
-        if let Some(cache_ttl) = cache_ttl {
-            if let Some(cache_entries) = cache_entries.filter(|_| status.is_success()) {
-                update_cache(ctx, cache_ttl, bytes, cache_entries).await
+        if let Some((cache_ttl, cache_keys)) = self.cache_ttl_and_keys.filter(|_| status.is_success()) {
+            if let Some(data) = &fetched.data {
+                update_cache(self.ctx, cache_ttl, &data.entities, cache_keys).await
             }
         }
 
-        Ok((status, subgraph_response))
+        Ok((status, fetched))
     }
 }
 
 async fn update_cache<R: Runtime>(
     ctx: ExecutionContext<'_, R>,
     cache_ttl: Duration,
-    bytes: Bytes,
-    cache_entries: Vec<CacheEntry>,
+    entities: &[Box<RawValue>],
+    cache_keys: Vec<String>,
 ) {
-    let mut entities = match Response::deserialize(&mut serde_json::Deserializer::from_slice(&bytes)) {
-        Ok(response) => response.data.entities.into_iter(),
-        Err(err) => {
-            tracing::warn!("Couldn't deserialize response for cache update: {err}");
-            // This shouldn't really happen but if it does lets ignore it
-            // Don't want cache stuff to break the actual request
-            return;
-        }
-    };
-
-    let mut update_futures = vec![];
-    for entry in cache_entries {
-        let CacheEntry::Miss { key } = entry else { continue };
-
-        let Some(data) = entities.next() else {
-            // This shouldn't really happen but if it does lets ignore it
-            // Don't want cache stuff to break the actual request
-            return;
-        };
-        let bytes = data.get().as_bytes();
-        update_futures.push(async move {
-            ctx.engine
-                .runtime
-                .entity_cache()
-                .put(&key, Cow::Borrowed(bytes), cache_ttl)
-                .await
-                .inspect_err(|err| tracing::warn!("Failed to write the cache key {key}: {err}"))
-                .ok();
-        })
-    }
+    let update_futures = cache_keys.into_iter().zip(entities).map(|(key, data)| async move {
+        ctx.engine
+            .runtime
+            .entity_cache()
+            .put(&key, Cow::Borrowed(data.get().as_bytes()), cache_ttl)
+            .await
+            .inspect_err(|err| tracing::warn!("Failed to write the cache key {key}: {err}"))
+            .ok();
+    });
 
     join_all(update_futures).await;
 }
 
-#[derive(serde::Deserialize)]
-struct Response<'a> {
-    #[serde(borrow)]
-    data: Data<'a>,
-}
-
-#[derive(serde::Deserialize)]
-struct Data<'a> {
-    #[serde(borrow, rename = "_entities")]
-    entities: Vec<&'a serde_json::value::RawValue>,
-}
-
 async fn cache_fetch<R: Runtime>(
     ctx: ExecutionContext<'_, R>,
     subgraph_name: &str,
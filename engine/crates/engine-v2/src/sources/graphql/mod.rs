@@ -3,6 +3,8 @@ mod federation;
 mod request;
 mod root_fields;
 mod subscription;
+mod traffic_split;
 
 pub(crate) use federation::*;
 pub(crate) use root_fields::*;
+pub(crate) use traffic_split::TrafficVariantSelections;
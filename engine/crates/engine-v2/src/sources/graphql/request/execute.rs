@@ -2,47 +2,66 @@ use bytes::Bytes;
 use futures::Future;
 use grafbase_telemetry::{
     gql_response_status::{GraphqlResponseStatus, SubgraphResponseStatus},
-    span::{GqlRecorderSpanExt, GRAFBASE_TARGET},
+    span::{GqlRecorderSpanExt, HttpRecorderSpanExt, GRAFBASE_TARGET},
 };
 use runtime::{
-    fetch::{FetchRequest, FetchResponse},
+    fetch::{Compression, FetchRequest, FetchResponse, HttpClientSettings},
+    hooks::ExecutedSubgraphRequest,
     rate_limiting::RateLimitKey,
 };
 use schema::sources::graphql::{GraphqlEndpointId, GraphqlEndpointWalker};
 use tower::retry::budget::Budget;
 use tracing::Span;
-use web_time::Duration;
+use web_time::{Duration, Instant};
 
+use super::shadow::mirror_subgraph_request;
 use crate::{
     execution::{ExecutionContext, ExecutionError, ExecutionResult},
+    operation::OperationType,
     response::SubgraphResponse,
     Runtime,
 };
 
 pub trait ResponseIngester: Send {
+    type Output: IngestedResponse;
+
     fn ingest(
         self,
         bytes: Bytes,
-    ) -> impl Future<Output = Result<(GraphqlResponseStatus, SubgraphResponse), ExecutionError>> + Send;
+    ) -> impl Future<Output = Result<(GraphqlResponseStatus, Self::Output), ExecutionError>> + Send;
+}
+
+/// What a subgraph response was ingested into.
+pub trait IngestedResponse {
+    fn first_subgraph_error_message(&self) -> Option<&str>;
+}
+
+impl IngestedResponse for SubgraphResponse {
+    fn first_subgraph_error_message(&self) -> Option<&str> {
+        self.subgraph_errors().next().map(|error| error.message.as_ref())
+    }
 }
 
 impl<T> ResponseIngester for T
 where
     T: FnOnce(Bytes) -> Result<(GraphqlResponseStatus, SubgraphResponse), ExecutionError> + Send,
 {
+    type Output = SubgraphResponse;
+
     async fn ingest(self, bytes: Bytes) -> Result<(GraphqlResponseStatus, SubgraphResponse), ExecutionError> {
         self(bytes)
     }
 }
 
-pub(crate) async fn execute_subgraph_request<'ctx, 'a, R: Runtime>(
+pub(crate) async fn execute_subgraph_request<'ctx, 'a, R: Runtime, I: ResponseIngester>(
     ctx: ExecutionContext<'ctx, R>,
     span: Span,
     endpoint_id: GraphqlEndpointId,
+    operation_type: OperationType,
     retry_budget: Option<&Budget>,
     make_request: impl FnOnce() -> FetchRequest<'a> + Send,
-    ingester: impl ResponseIngester,
-) -> ExecutionResult<SubgraphResponse> {
+    ingester: I,
+) -> ExecutionResult<I::Output> {
     let endpoint = ctx.schema().walk(endpoint_id);
 
     let mut request = make_request();
@@ -60,19 +79,64 @@ pub(crate) async fn execute_subgraph_request<'ctx, 'a, R: Runtime>(
         .headers
         .insert(http::header::ACCEPT, http::HeaderValue::from_static("application/json"));
 
-    let fetch_response = retrying_fetch(ctx, &request, endpoint, retry_budget).await?;
-
-    tracing::debug!("{}", String::from_utf8_lossy(&fetch_response.bytes));
+    let start = Instant::now();
+    let fetch = retrying_fetch(ctx, &request, endpoint, retry_budget);
+    let fetch_response = if endpoint.deduplicate_requests() && !operation_type.is_mutation() {
+        ctx.engine
+            .deduplicate_subgraph_request(endpoint.subgraph_name(), &request, fetch)
+            .await
+    } else {
+        fetch.await
+    };
 
-    let (status, response) = ingester.ingest(fetch_response.bytes).await.inspect_err(|err| {
-        let status = SubgraphResponseStatus::InvalidResponseError;
-        span.record_subgraph_status(status);
+    let fetch_response = fetch_response.inspect_err(|err| {
+        span.record_subgraph_status(SubgraphResponseStatus::HttpError);
         tracing::error!(target: GRAFBASE_TARGET, "{err}");
     })?;
 
+    let http_status = fetch_response.status;
+    span.record_status_code(http_status);
+
+    mirror_subgraph_request(ctx, endpoint, operation_type, &request, &fetch_response);
+
+    ctx.record_subgraph_response_headers(endpoint.response_header_rules(), &fetch_response.headers);
+
+    ctx.hooks()
+        .on_subgraph_response(ExecutedSubgraphRequest {
+            subgraph_name: endpoint.subgraph_name(),
+            status: http_status,
+            headers: &fetch_response.headers,
+            duration: start.elapsed(),
+            body: &fetch_response.bytes,
+        })
+        .await?;
+
+    tracing::debug!("{}", String::from_utf8_lossy(&fetch_response.bytes));
+
+    // A subgraph may send a GraphQL response with a non-2xx status, so we only fall back to an
+    // HTTP error if the body couldn't be read.
+    let (status, response) = ingester
+        .ingest(fetch_response.bytes)
+        .await
+        .map_err(|err| {
+            if http_status.is_success() {
+                span.record_subgraph_status(SubgraphResponseStatus::InvalidResponseError);
+                err
+            } else {
+                span.record_subgraph_status(SubgraphResponseStatus::HttpError);
+                ExecutionError::SubgraphHttpStatus {
+                    subgraph_name: endpoint.subgraph_name().to_string(),
+                    status: http_status,
+                }
+            }
+        })
+        .inspect_err(|err| {
+            tracing::error!(target: GRAFBASE_TARGET, "{err}");
+        })?;
+
     span.record_subgraph_status(SubgraphResponseStatus::GraphqlResponse(status));
 
-    match response.subgraph_errors().next().map(|e| &e.message) {
+    match response.first_subgraph_error_message() {
         Some(error) => {
             tracing::error!(target: GRAFBASE_TARGET, "{error}");
         }
@@ -84,13 +148,36 @@ pub(crate) async fn execute_subgraph_request<'ctx, 'a, R: Runtime>(
     Ok(response)
 }
 
+/// Compression of the request bodies sent to the subgraph.
+pub(crate) fn request_compression(endpoint: GraphqlEndpointWalker<'_>) -> Option<Compression> {
+    endpoint.compression().request.map(|algorithm| match algorithm {
+        schema::CompressionAlgorithm::Gzip => Compression::Gzip,
+        schema::CompressionAlgorithm::Brotli => Compression::Brotli,
+        schema::CompressionAlgorithm::Zstd => Compression::Zstd,
+    })
+}
+
+pub(crate) fn http_client_settings(endpoint: GraphqlEndpointWalker<'_>) -> HttpClientSettings {
+    let pool = endpoint.connection_pool();
+    HttpClientSettings {
+        decompress_responses: endpoint.compression().response,
+        pool_max_idle_per_host: pool.max_idle_per_host,
+        pool_idle_timeout: pool.idle_timeout,
+        tcp_keepalive: pool.tcp_keepalive,
+        http2_prior_knowledge: pool.http2_prior_knowledge,
+        connect_timeout: pool.connect_timeout,
+    }
+}
+
 async fn retrying_fetch<'ctx, R: Runtime>(
     ctx: ExecutionContext<'ctx, R>,
     request: &FetchRequest<'_>,
     endpoint: GraphqlEndpointWalker<'_>,
     retry_budget: Option<&Budget>,
 ) -> ExecutionResult<FetchResponse> {
-    let mut result = rate_limited_fetch(ctx, endpoint, request).await;
+    // Upstreams already attempted, so retries fail over to the other ones.
+    let mut attempted = Vec::new();
+    let mut result = guarded_fetch(ctx, endpoint, request, &mut attempted).await;
 
     let Some(retry_budget) = retry_budget else {
         return result;
@@ -99,27 +186,143 @@ async fn retrying_fetch<'ctx, R: Runtime>(
     let mut counter = 0;
 
     loop {
-        match result {
-            Ok(bytes) => {
+        // Only connection errors and the configured status codes are retried. Anything else,
+        // like a 400, will fail the same way on the next attempt.
+        let retry_after = match &result {
+            Ok(response)
+                if !endpoint
+                    .retry_config()
+                    .is_some_and(|config| config.should_retry_status(response.status.as_u16())) =>
+            {
                 retry_budget.deposit();
-                return Ok(bytes);
+                return result;
             }
-            Err(err) => {
-                if retry_budget.withdraw().is_ok() {
-                    let jitter = rand::random::<f64>() * 2.0;
-                    let exp_backoff = (100 * 2u64.pow(counter)) as f64;
-                    let backoff_ms = (exp_backoff * jitter).round() as u64;
+            Ok(response) => parse_retry_after(&response.headers),
+            Err(ExecutionError::Fetch { .. }) => None,
+            Err(_) => return result,
+        };
 
-                    ctx.engine.runtime.sleep(Duration::from_millis(backoff_ms)).await;
+        // Retries would only be rejected once the circuit breaker opened.
+        if ctx
+            .engine
+            .circuit_breaker(endpoint.id())
+            .is_some_and(|breaker| !breaker.is_closed())
+        {
+            return result;
+        }
 
-                    counter += 1;
+        if retry_budget.withdraw().is_err() {
+            return result;
+        }
 
-                    result = rate_limited_fetch(ctx, endpoint, request).await;
-                } else {
-                    return Err(err);
-                }
+        let backoff = match retry_after {
+            // Never wait longer than the subgraph timeout.
+            Some(retry_after) => retry_after.min(endpoint.timeout()),
+            None => {
+                let jitter = rand::random::<f64>() * 2.0;
+                let exp_backoff = (100 * 2u64.pow(counter)) as f64;
+                Duration::from_millis((exp_backoff * jitter).round() as u64)
             }
-        }
+        };
+
+        ctx.engine.runtime.sleep(backoff).await;
+
+        counter += 1;
+
+        result = guarded_fetch(ctx, endpoint, request, &mut attempted).await;
+    }
+}
+
+/// `Retry-After` is either a number of seconds or an HTTP date.
+fn parse_retry_after(headers: &http::HeaderMap) -> Option<Duration> {
+    let value = headers.get(http::header::RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = httpdate::parse_http_date(value)
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?;
+    let now = web_time::SystemTime::now().duration_since(web_time::UNIX_EPOCH).ok()?;
+
+    Some(date.saturating_sub(now))
+}
+
+/// Fails immediately while the circuit breaker of the subgraph is open. Otherwise the outcome of
+/// the request is recorded by the circuit breaker.
+async fn guarded_fetch<'ctx, R: Runtime>(
+    ctx: ExecutionContext<'ctx, R>,
+    endpoint: GraphqlEndpointWalker<'ctx>,
+    request: &FetchRequest<'_>,
+    attempted: &mut Vec<usize>,
+) -> ExecutionResult<FetchResponse> {
+    let Some(circuit_breaker) = ctx.engine.circuit_breaker(endpoint.id()) else {
+        return balanced_fetch(ctx, endpoint, request, attempted).await;
+    };
+
+    let Some(permit) = circuit_breaker.try_acquire() else {
+        return Err(ExecutionError::CircuitOpen {
+            subgraph_name: endpoint.subgraph_name().to_string(),
+        });
+    };
+
+    let result = balanced_fetch(ctx, endpoint, request, attempted).await;
+    if let Some(success) = is_success(&result) {
+        permit.record(success);
+    }
+
+    result
+}
+
+/// Sends the request to one of the upstreams of the subgraph when it has multiple URLs,
+/// preferring those not attempted yet. Failures count towards the ejection of the upstream.
+async fn balanced_fetch<'ctx, R: Runtime>(
+    ctx: ExecutionContext<'ctx, R>,
+    endpoint: GraphqlEndpointWalker<'ctx>,
+    request: &FetchRequest<'_>,
+    attempted: &mut Vec<usize>,
+) -> ExecutionResult<FetchResponse> {
+    let Some(load_balancer) = ctx.engine.load_balancer(endpoint.id()) else {
+        return rate_limited_fetch(ctx, endpoint, request).await;
+    };
+
+    // Requests routed to a traffic variant have their own URL.
+    if request.url != endpoint.url() {
+        return rate_limited_fetch(ctx, endpoint, request).await;
+    }
+
+    let upstream = load_balancer.select(attempted);
+    attempted.push(upstream.index());
+
+    let url = endpoint.upstream_url(upstream.index());
+    // The subgraph span is created before the upstream is known.
+    Span::current().record("subgraph.url", url.as_str());
+
+    let request = FetchRequest {
+        url,
+        headers: request.headers.clone(),
+        json_body: request.json_body.clone(),
+        timeout: request.timeout,
+        compression: request.compression,
+        client_settings: request.client_settings,
+    };
+
+    let result = rate_limited_fetch(ctx, endpoint, &request).await;
+    if let Some(success) = is_success(&result) {
+        upstream.record(success);
+    }
+
+    result
+}
+
+/// Whether the subgraph handled the request successfully, `None` if it never reached it.
+fn is_success(result: &ExecutionResult<FetchResponse>) -> Option<bool> {
+    match result {
+        Ok(response) => Some(!response.status.is_server_error()),
+        Err(ExecutionError::Fetch { .. }) => Some(false),
+        // Rate limited requests never reached the subgraph.
+        Err(_) => None,
     }
 }
 
@@ -1,5 +1,6 @@
 mod execute;
 mod prepare;
+mod shadow;
 mod types;
 
 pub(super) use execute::*;
@@ -0,0 +1,167 @@
+use grafbase_telemetry::{metrics::ShadowComparison, span::GRAFBASE_TARGET};
+use runtime::fetch::{FetchRequest, FetchResponse};
+use schema::sources::graphql::GraphqlEndpointWalker;
+use serde_json::Value;
+
+use crate::{execution::ExecutionContext, operation::OperationType, Runtime};
+
+/// At most this many differing paths are logged.
+const MAX_REPORTED_DIFFERENCES: usize = 10;
+
+/// Mirrors a sample of the queries to the candidate version of the subgraph. The shadow request
+/// runs in the background and never affects the client response, its response is only compared
+/// with the one of the subgraph.
+pub(super) fn mirror_subgraph_request<R: Runtime>(
+    ctx: ExecutionContext<'_, R>,
+    endpoint: GraphqlEndpointWalker<'_>,
+    operation_type: OperationType,
+    request: &FetchRequest<'_>,
+    response: &FetchResponse,
+) {
+    let Some(shadow_url) = endpoint.shadow_url() else {
+        return;
+    };
+
+    if operation_type.is_mutation() || rand::random::<f64>() >= endpoint.shadow_sampling() {
+        return;
+    }
+
+    let fetcher = ctx.engine.runtime.fetcher().clone();
+    let metrics = ctx.engine.shadow_metrics.clone();
+    let subgraph_name = endpoint.subgraph_name().to_string();
+    let url = shadow_url.clone();
+    let headers = request.headers.clone();
+    let json_body = request.json_body.clone();
+    let timeout = request.timeout;
+    let compression = request.compression;
+    let client_settings = request.client_settings;
+    let primary = response.bytes.clone();
+
+    async_runtime::spawn(async move {
+        let request = FetchRequest {
+            url: &url,
+            headers,
+            json_body,
+            timeout,
+            compression,
+            client_settings,
+        };
+
+        let comparison = match fetcher.post(&request).await {
+            Ok(candidate) => compare_responses(&subgraph_name, &primary, &candidate.bytes),
+            Err(error) => {
+                tracing::debug!(target: GRAFBASE_TARGET, "Shadow request of subgraph '{subgraph_name}' failed: {error}");
+                ShadowComparison::Error
+            }
+        };
+
+        metrics.record_comparison(&subgraph_name, comparison);
+    });
+}
+
+fn compare_responses(subgraph_name: &str, primary: &[u8], candidate: &[u8]) -> ShadowComparison {
+    let (Ok(primary), Ok(candidate)) = (
+        serde_json::from_slice::<Value>(primary),
+        serde_json::from_slice::<Value>(candidate),
+    ) else {
+        tracing::debug!(target: GRAFBASE_TARGET, "Shadow response of subgraph '{subgraph_name}' could not be compared");
+        return ShadowComparison::Error;
+    };
+
+    let mut differences = Vec::new();
+    collect_differences(&mut String::new(), &primary, &candidate, &mut differences);
+    if differences.is_empty() {
+        return ShadowComparison::Match;
+    }
+
+    tracing::warn!(
+        target: GRAFBASE_TARGET,
+        "Shadow response of subgraph '{subgraph_name}' differs at {}\nprimary: {}\ncandidate: {}",
+        differences.join(", "),
+        redact(&primary),
+        redact(&candidate),
+    );
+
+    ShadowComparison::Mismatch
+}
+
+/// Collects the paths at which both values differ, ignoring the order of object keys.
+fn collect_differences(path: &mut String, primary: &Value, candidate: &Value, differences: &mut Vec<String>) {
+    if differences.len() >= MAX_REPORTED_DIFFERENCES {
+        return;
+    }
+
+    match (primary, candidate) {
+        (Value::Object(primary), Value::Object(candidate)) => {
+            let keys = primary
+                .keys()
+                .chain(candidate.keys().filter(|key| !primary.contains_key(*key)));
+            for key in keys {
+                let len = path.len();
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(key);
+                match (primary.get(key), candidate.get(key)) {
+                    (Some(primary), Some(candidate)) => collect_differences(path, primary, candidate, differences),
+                    _ => differences.push(path.clone()),
+                }
+                path.truncate(len);
+            }
+        }
+        (Value::Array(primary), Value::Array(candidate)) if primary.len() == candidate.len() => {
+            for (index, (primary, candidate)) in primary.iter().zip(candidate).enumerate() {
+                let len = path.len();
+                path.push_str(&format!("[{index}]"));
+                collect_differences(path, primary, candidate, differences);
+                path.truncate(len);
+            }
+        }
+        (primary, candidate) if primary == candidate => {}
+        _ => differences.push(if path.is_empty() { ".".to_string() } else { path.clone() }),
+    }
+}
+
+/// Keeps the shape of the response while replacing every scalar by its type, so that no data
+/// ends up in the logs.
+fn redact(value: &Value) -> Value {
+    match value {
+        Value::Null => Value::Null,
+        Value::Bool(_) => Value::String("<boolean>".into()),
+        Value::Number(_) => Value::String("<number>".into()),
+        Value::String(_) => Value::String("<string>".into()),
+        Value::Array(values) => Value::Array(values.iter().map(redact).collect()),
+        Value::Object(fields) => {
+            Value::Object(fields.iter().map(|(key, value)| (key.clone(), redact(value))).collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn differences() {
+        let primary = json!({"data": {"products": [{"name": "a", "price": 1}, {"name": "b", "price": 2}]}});
+        let candidate =
+            json!({"data": {"products": [{"price": 1, "name": "a"}, {"name": "b", "price": 3, "stock": 0}]}});
+
+        let mut differences = Vec::new();
+        collect_differences(&mut String::new(), &primary, &candidate, &mut differences);
+
+        assert_eq!(differences, ["data.products[1].price", "data.products[1].stock"]);
+    }
+
+    #[test]
+    fn redacted_values() {
+        let value = json!({"data": {"user": {"name": "alice", "age": 30, "admin": false, "friends": [null]}}});
+
+        assert_eq!(
+            redact(&value),
+            json!({"data": {"user": {"name": "<string>", "age": "<number>", "admin": "<boolean>", "friends": [null]}}})
+        );
+    }
+}
@@ -9,7 +9,11 @@ use tracing::Instrument;
 
 use super::{
     deserialize::{GraphqlResponseSeed, RootGraphqlErrors},
-    request::{execute_subgraph_request, PreparedGraphqlOperation, ResponseIngester, SubgraphVariables},
+    request::{
+        execute_subgraph_request, http_client_settings, request_compression, PreparedGraphqlOperation,
+        ResponseIngester, SubgraphVariables,
+    },
+    traffic_split::route_subgraph_request,
 };
 use crate::{
     execution::PlanningResult,
@@ -39,6 +43,14 @@ impl GraphqlResolver {
         }))
     }
 
+    pub fn endpoint_id(&self) -> GraphqlEndpointId {
+        self.endpoint_id
+    }
+
+    pub fn query(&self) -> &str {
+        &self.operation.query
+    }
+
     #[tracing::instrument(skip_all)]
     pub async fn execute<'ctx, R: Runtime>(
         &'ctx self,
@@ -88,7 +100,7 @@ impl GraphqlResolver {
 
                 GraphqlResponseSeed::new(
                     response.next_seed(ctx).ok_or("No object to update")?,
-                    RootGraphqlErrors::new(ctx, response),
+                    RootGraphqlErrors::new(ctx, self.endpoint_id, response),
                 )
                 .deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?;
 
@@ -102,13 +114,15 @@ impl GraphqlResolver {
             ctx.engine.get_retry_budget_for_query(self.endpoint_id)
         };
 
+        let route = route_subgraph_request(ctx, endpoint);
         let span = SubgraphRequestSpan {
             name: endpoint.subgraph_name(),
             operation_type: self.operation.ty.as_str(),
             // The generated query does not contain any data, everything are in the variables, so
             // it's safe to use.
             sanitized_query: &self.operation.query,
-            url: endpoint.url(),
+            url: route.url,
+            variant: route.variant,
         }
         .into_span();
 
@@ -116,15 +130,19 @@ impl GraphqlResolver {
             ctx,
             span.clone(),
             self.endpoint_id,
+            self.operation.ty,
             retry_budget,
             || FetchRequest {
-                url: endpoint.url(),
+                url: route.url,
                 headers,
                 json_body: Bytes::from(body),
                 timeout: endpoint.timeout(),
+                compression: request_compression(endpoint),
+                client_settings: http_client_settings(endpoint),
             },
             GraphqlIngester {
                 ctx,
+                endpoint_id: self.endpoint_id,
                 cache_ttl_and_key,
                 subgraph_response,
             },
@@ -150,6 +168,7 @@ fn build_cache_key(subgraph_name: &str, subgraph_request_body: &[u8], headers: &
 
 struct GraphqlIngester<'ctx, R: Runtime> {
     ctx: ExecutionContext<'ctx, R>,
+    endpoint_id: GraphqlEndpointId,
     subgraph_response: SubgraphResponse,
     cache_ttl_and_key: Option<(Duration, String)>,
 }
@@ -158,6 +177,8 @@ impl<'ctx, R> ResponseIngester for GraphqlIngester<'ctx, R>
 where
     R: Runtime,
 {
+    type Output = SubgraphResponse;
+
     async fn ingest(
         mut self,
         bytes: Bytes,
@@ -166,7 +187,7 @@ where
             let response = self.subgraph_response.as_mut();
             GraphqlResponseSeed::new(
                 response.next_seed(self.ctx).ok_or("No object to update")?,
-                RootGraphqlErrors::new(self.ctx, response),
+                RootGraphqlErrors::new(self.ctx, self.endpoint_id, response),
             )
             .deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?
         };
@@ -1,13 +1,20 @@
+use std::sync::Arc;
+
 use futures_util::{stream::BoxStream, StreamExt};
-use runtime::{fetch::GraphqlRequest, rate_limiting::RateLimitKey};
+use runtime::{
+    fetch::{GraphqlRequest, SubscriptionProtocol},
+    rate_limiting::RateLimitKey,
+};
+use schema::sources::graphql::GraphqlEndpointId;
 use serde::de::DeserializeSeed;
 
 use super::{
     deserialize::{GraphqlResponseSeed, RootGraphqlErrors},
-    request::SubgraphVariables,
+    request::{http_client_settings, SubgraphVariables},
     GraphqlResolver,
 };
 use crate::{
+    engine::{UpstreamEvent, UpstreamSubscriptionRequest},
     execution::{ExecutionContext, ExecutionError, SubscriptionResponse},
     operation::PlanWalker,
     sources::ExecutionResult,
@@ -23,16 +30,35 @@ impl GraphqlResolver {
     ) -> ExecutionResult<BoxStream<'ctx, ExecutionResult<SubscriptionResponse>>> {
         let endpoint = ctx.schema().walk(self.endpoint_id);
 
-        let url = {
-            let mut url = endpoint.websocket_url().clone();
-            // If the user doesn't provide an explicit websocket URL we use the normal URL,
-            // so make sure to convert the scheme to something appropriate
-            match url.scheme() {
-                "http" => url.set_scheme("ws").expect("this to work"),
-                "https" => url.set_scheme("wss").expect("this to work"),
-                _ => {}
+        let protocol = match endpoint.subscription_protocol() {
+            schema::SubscriptionProtocol::Websocket => SubscriptionProtocol::Websocket,
+            schema::SubscriptionProtocol::ServerSentEvents => SubscriptionProtocol::ServerSentEvents,
+            schema::SubscriptionProtocol::Multipart => SubscriptionProtocol::Multipart,
+        };
+
+        // Subgraphs with multiple URLs have their subscriptions spread across them too.
+        let upstream = ctx
+            .engine
+            .load_balancer(self.endpoint_id)
+            .map(|load_balancer| load_balancer.pick())
+            .unwrap_or_default();
+
+        let url = match protocol {
+            SubscriptionProtocol::Websocket => {
+                let mut url = endpoint.upstream_websocket_url(upstream).clone();
+                // If the user doesn't provide an explicit websocket URL we use the normal URL,
+                // so make sure to convert the scheme to something appropriate
+                match url.scheme() {
+                    "http" => url.set_scheme("ws").expect("this to work"),
+                    "https" => url.set_scheme("wss").expect("this to work"),
+                    _ => {}
+                }
+                url
+            }
+            // HTTP based protocols are served by the main GraphQL endpoint.
+            SubscriptionProtocol::ServerSentEvents | SubscriptionProtocol::Multipart => {
+                endpoint.upstream_url(upstream).clone()
             }
-            url
         };
 
         ctx.engine
@@ -41,36 +67,63 @@ impl GraphqlResolver {
             .limit(&RateLimitKey::Subgraph(endpoint.subgraph_name().into()))
             .await?;
 
-        let stream = ctx
-            .engine
-            .runtime
-            .fetcher()
-            .stream(GraphqlRequest {
-                url: &url,
-                query: &self.operation.query,
-                variables: serde_json::to_value(&SubgraphVariables::<()> {
-                    plan,
-                    variables: &self.operation.variables,
-                    extra_variables: Vec::new(),
+        let variables = serde_json::to_value(&SubgraphVariables::<()> {
+            plan,
+            variables: &self.operation.variables,
+            extra_variables: Vec::new(),
+        })
+        .map_err(|error| error.to_string())?;
+        let headers = ctx.subgraph_headers_with_rules(endpoint.header_rules());
+
+        let stream: BoxStream<'static, UpstreamEvent> = if endpoint.deduplicate_subscriptions() {
+            ctx.engine
+                .subscribe_to_deduplicated_upstream(UpstreamSubscriptionRequest {
+                    subgraph_name: endpoint.subgraph_name().to_string(),
+                    url,
+                    headers,
+                    query: self.operation.query.clone(),
+                    variables,
+                    protocol,
+                    heartbeat_timeout: endpoint.subscription_heartbeat_timeout(),
+                    max_reconnect_attempts: endpoint.subscription_max_reconnect_attempts(),
+                    client_settings: http_client_settings(endpoint),
                 })
-                .map_err(|error| error.to_string())?,
-                headers: ctx.subgraph_headers_with_rules(endpoint.header_rules()),
-            })
-            .await
-            .map_err(|error| ExecutionError::Fetch {
-                subgraph_name: endpoint.subgraph_name().to_string(),
-                error,
-            })?;
-        Ok(Box::pin(stream.map(move |subgraph_response| {
-            let mut subscription_response = new_response();
-            ingest_response(
-                ctx,
-                &mut subscription_response,
-                subgraph_response.map_err(|error| ExecutionError::Fetch {
+        } else {
+            ctx.engine
+                .runtime
+                .fetcher()
+                .stream(GraphqlRequest {
+                    url: &url,
+                    query: &self.operation.query,
+                    variables,
+                    headers,
+                    protocol,
+                    heartbeat_timeout: endpoint.subscription_heartbeat_timeout(),
+                    max_reconnect_attempts: endpoint.subscription_max_reconnect_attempts(),
+                    client_settings: http_client_settings(endpoint),
+                })
+                .await
+                .map_err(|error| ExecutionError::Fetch {
                     subgraph_name: endpoint.subgraph_name().to_string(),
                     error,
-                })?,
-            )?;
+                })?
+                .map(Arc::new)
+                .boxed()
+        };
+
+        Ok(Box::pin(stream.map(move |subgraph_response| {
+            let mut subscription_response = new_response();
+            match subgraph_response.as_ref() {
+                Ok(subgraph_response) => {
+                    ingest_response(ctx, self.endpoint_id, &mut subscription_response, subgraph_response)?
+                }
+                Err(error) => {
+                    return Err(ExecutionError::Fetch {
+                        subgraph_name: endpoint.subgraph_name().to_string(),
+                        error: error.clone(),
+                    })
+                }
+            }
             Ok(subscription_response)
         })))
     }
@@ -78,13 +131,14 @@ impl GraphqlResolver {
 
 fn ingest_response<R: Runtime>(
     ctx: ExecutionContext<'_, R>,
+    endpoint_id: GraphqlEndpointId,
     subscription_response: &mut SubscriptionResponse,
-    subgraph_response: serde_json::Value,
+    subgraph_response: &serde_json::Value,
 ) -> ExecutionResult<()> {
     let response = subscription_response.root_response();
     GraphqlResponseSeed::new(
         response.next_seed(ctx).expect("Must have a root object to update"),
-        RootGraphqlErrors::new(ctx, response),
+        RootGraphqlErrors::new(ctx, endpoint_id, response),
     )
     .deserialize(subgraph_response)?;
     Ok(())
@@ -0,0 +1,107 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use schema::sources::graphql::{GraphqlEndpointId, GraphqlEndpointWalker};
+use url::Url;
+
+use crate::{engine::get_claim, execution::ExecutionContext, Runtime};
+
+/// Name recorded for requests sent to the subgraph URL while a traffic split is configured.
+const PRIMARY_VARIANT: &str = "primary";
+
+/// Traffic variants selected during a request, so that all the requests sent to a subgraph for a
+/// single request are served by the same variant.
+#[derive(Default)]
+pub(crate) struct TrafficVariantSelections {
+    /// Index of the selected variant, `None` for the subgraph URL.
+    selected: Mutex<HashMap<GraphqlEndpointId, Option<usize>>>,
+}
+
+pub(super) struct SubgraphRoute<'ctx> {
+    pub url: &'ctx Url,
+    /// The variant serving the request, `None` if the subgraph has no traffic split.
+    pub variant: Option<&'ctx str>,
+}
+
+pub(super) fn route_subgraph_request<'ctx, R: Runtime>(
+    ctx: ExecutionContext<'ctx, R>,
+    endpoint: GraphqlEndpointWalker<'ctx>,
+) -> SubgraphRoute<'ctx> {
+    let selected = *ctx
+        .traffic_variant_selections()
+        .selected
+        .lock()
+        .unwrap()
+        .entry(endpoint.id())
+        .or_insert_with(|| select_traffic_variant(ctx, endpoint));
+
+    match selected.and_then(|index| endpoint.traffic_variants().nth(index)) {
+        Some(variant) => SubgraphRoute {
+            url: variant.url(),
+            variant: Some(variant.name()),
+        },
+        None => SubgraphRoute {
+            url: endpoint.url(),
+            variant: endpoint.has_traffic_split().then_some(PRIMARY_VARIANT),
+        },
+    }
+}
+
+/// Selects the version of the subgraph serving a request, the first time the request reaches it.
+/// Variants matching a request header or claim take precedence, otherwise the request is assigned
+/// based on the variant percentages. Returns the index of the variant, `None` means the subgraph
+/// URL.
+fn select_traffic_variant<'ctx, R: Runtime>(
+    ctx: ExecutionContext<'ctx, R>,
+    endpoint: GraphqlEndpointWalker<'ctx>,
+) -> Option<usize> {
+    if !endpoint.has_traffic_split() {
+        return None;
+    }
+
+    let headers = ctx.headers();
+    let access_token = ctx.access_token();
+
+    let matched = endpoint.traffic_variants().position(|variant| {
+        variant.header().is_some_and(|(name, value)| {
+            headers
+                .get_all(name)
+                .iter()
+                .any(|header| header.as_bytes() == value.as_bytes())
+        }) || variant
+            .claim()
+            .is_some_and(|(path, value)| claim_matches(get_claim(access_token, path), value))
+    });
+    if matched.is_some() {
+        return matched;
+    }
+
+    // A point in [0, 100), stable for a given sticky header value.
+    let point = match endpoint
+        .traffic_split_sticky_header()
+        .and_then(|name| headers.get(name))
+    {
+        Some(value) => {
+            let mut hasher = blake3::Hasher::new();
+            hasher.update(endpoint.subgraph_name().as_bytes());
+            hasher.update(value.as_bytes());
+            let hash = u64::from_le_bytes(hasher.finalize().as_bytes()[..8].try_into().unwrap());
+            (hash % 10_000) as f32 / 100.0
+        }
+        None => rand::random::<f32>() * 100.0,
+    };
+
+    let mut cumulative = 0.0;
+    endpoint.traffic_variants().position(|variant| {
+        cumulative += variant.percentage();
+        point < cumulative
+    })
+}
+
+fn claim_matches(claim: &serde_json::Value, expected: &str) -> bool {
+    match claim {
+        serde_json::Value::String(value) => value == expected,
+        serde_json::Value::Array(values) => values.iter().any(|value| claim_matches(value, expected)),
+        serde_json::Value::Null | serde_json::Value::Object(_) => false,
+        value => value.to_string() == expected,
+    }
+}
@@ -44,7 +44,7 @@
 //! executor will have a root for each product in the response.
 use futures::{future::BoxFuture, FutureExt};
 use futures_util::stream::BoxStream;
-use schema::{ResolverDefinition, ResolverDefinitionWalker};
+use schema::{sources::graphql::GraphqlEndpointId, ResolverDefinition, ResolverDefinitionWalker};
 use std::future::Future;
 
 use crate::{
@@ -59,6 +59,8 @@ use self::{
     introspection::IntrospectionResolver,
 };
 
+pub(crate) use graphql::TrafficVariantSelections;
+
 mod graphql;
 mod introspection;
 
@@ -73,6 +75,23 @@ impl Resolver {
         Resolver::Introspection(IntrospectionResolver)
     }
 
+    pub fn endpoint_id(&self) -> Option<GraphqlEndpointId> {
+        match self {
+            Resolver::GraphQL(resolver) => Some(resolver.endpoint_id()),
+            Resolver::FederationEntity(resolver) => Some(resolver.endpoint_id()),
+            Resolver::Introspection(_) => None,
+        }
+    }
+
+    /// Operation sent to the subgraph, if any.
+    pub fn subgraph_query(&self) -> Option<&str> {
+        match self {
+            Resolver::GraphQL(resolver) => Some(resolver.query()),
+            Resolver::FederationEntity(resolver) => Some(resolver.query()),
+            Resolver::Introspection(_) => None,
+        }
+    }
+
     pub fn prepare(
         definition: ResolverDefinitionWalker<'_>,
         operation_type: OperationType,
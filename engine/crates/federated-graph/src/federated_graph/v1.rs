@@ -237,6 +237,9 @@ pub struct Override {
 #[derive(serde::Serialize, serde::Deserialize, Clone, Default, Debug)]
 pub enum OverrideLabel {
     Percent(u8),
+    /// A label whose activation is decided by the gateway, rather than by a percentage of the
+    /// traffic.
+    Custom(String),
     #[serde(other)]
     #[default]
     Unknown,
@@ -260,6 +263,7 @@ impl std::fmt::Display for OverrideLabel {
                 percent.fmt(f)?;
                 f.write_str(")")
             }
+            OverrideLabel::Custom(label) => f.write_str(label),
             OverrideLabel::Unknown => Ok(()),
         }
     }
@@ -275,12 +279,23 @@ impl std::str::FromStr for OverrideLabel {
             .and_then(|percent| u8::from_str(percent).ok())
         {
             Ok(OverrideLabel::Percent(percent))
+        } else if is_custom_override_label(s) {
+            Ok(OverrideLabel::Custom(s.to_owned()))
         } else {
-            Err(r#"Expected a field of the format "percent(<number>)" "#)
+            Err(
+                r#"Expected a field of the format "percent(<number>)" or a custom label matching [a-zA-Z][a-zA-Z0-9_\-:.]*"#,
+            )
         }
     }
 }
 
+/// Custom labels follow the same rules as in the Apollo Federation specification.
+fn is_custom_override_label(label: &str) -> bool {
+    let mut chars = label.chars();
+    chars.next().is_some_and(|first| first.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | ':' | '.'))
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Clone)]
 pub enum OverrideSource {
     Subgraph(SubgraphId),
@@ -103,10 +103,14 @@ impl std::fmt::Debug for FederatedGraphV3 {
 #[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, PartialOrd)]
 pub enum Directive {
     Authenticated,
+    Cost {
+        weight: i32,
+    },
     Deprecated {
         reason: Option<StringId>,
     },
     Inaccessible,
+    ListSize(ListSize),
     Policy(Vec<Vec<StringId>>),
     RequiresScopes(Vec<Vec<StringId>>),
 
@@ -116,6 +120,14 @@ pub enum Directive {
     },
 }
 
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, PartialOrd, Default)]
+pub struct ListSize {
+    pub assumed_size: Option<u32>,
+    pub slicing_arguments: Vec<StringId>,
+    pub sized_fields: Vec<StringId>,
+    pub require_one_slicing_argument: bool,
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, PartialOrd)]
 pub struct AuthorizedDirective {
     pub fields: Option<FieldSet>,
@@ -1271,6 +1271,58 @@ fn collect_composed_directives(directives: &[Positioned<ast::ConstDirective>], s
                 }
             }
             "authenticated" => state.directives.push(Directive::Authenticated),
+            "cost" => {
+                let weight = directive
+                    .node
+                    .get_argument("weight")
+                    .and_then(|value| match &value.node {
+                        async_graphql_value::ConstValue::Number(n) => n.as_i64(),
+                        _ => None,
+                    })
+                    .and_then(|weight| i32::try_from(weight).ok());
+
+                if let Some(weight) = weight {
+                    state.directives.push(Directive::Cost { weight });
+                }
+            }
+            "listSize" => {
+                let strings = |state: &mut State<'_>, name: &str| -> Vec<StringId> {
+                    let values: Option<Vec<String>> = directive
+                        .node
+                        .get_argument(name)
+                        .and_then(|value| value.node.clone().into_json().ok())
+                        .and_then(|value| serde_json::from_value(value).ok());
+
+                    values
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|value| state.insert_string(value))
+                        .collect()
+                };
+
+                let list_size = ListSize {
+                    assumed_size: directive
+                        .node
+                        .get_argument("assumedSize")
+                        .and_then(|value| match &value.node {
+                            async_graphql_value::ConstValue::Number(n) => n.as_u64(),
+                            _ => None,
+                        })
+                        .and_then(|size| u32::try_from(size).ok()),
+                    slicing_arguments: strings(state, "slicingArguments"),
+                    sized_fields: strings(state, "sizedFields"),
+                    require_one_slicing_argument: directive
+                        .node
+                        .get_argument("requireOneSlicingArgument")
+                        .and_then(|value| match &value.node {
+                            async_graphql_value::ConstValue::Boolean(b) => Some(*b),
+                            _ => None,
+                        })
+                        .unwrap_or(true),
+                };
+
+                state.directives.push(Directive::ListSize(list_size));
+            }
             // Added later after ingesting the graph.
             "authorized" => {}
             other => {
@@ -238,6 +238,33 @@ pub(crate) fn write_composed_directive(
     match directive {
         Directive::Authenticated => write_directive(f, "authenticated", iter::empty::<(&str, Value)>(), graph),
         Directive::Inaccessible => write_directive(f, "inaccessible", iter::empty::<(&str, Value)>(), graph),
+        Directive::Cost { weight } => write_directive(
+            f,
+            "cost",
+            std::iter::once(("weight", Value::Int(i64::from(*weight)))),
+            graph,
+        ),
+        Directive::ListSize(ListSize {
+            assumed_size,
+            slicing_arguments,
+            sized_fields,
+            require_one_slicing_argument,
+        }) => {
+            let strings = |ids: &[StringId]| Value::List(ids.iter().map(|id| Value::String(*id)).collect());
+            write_directive(
+                f,
+                "listSize",
+                assumed_size
+                    .map(|size| ("assumedSize", Value::Int(i64::from(size))))
+                    .into_iter()
+                    .chain((!slicing_arguments.is_empty()).then(|| ("slicingArguments", strings(slicing_arguments))))
+                    .chain((!sized_fields.is_empty()).then(|| ("sizedFields", strings(sized_fields))))
+                    .chain(
+                        (!require_one_slicing_argument).then_some(("requireOneSlicingArgument", Value::Boolean(false))),
+                    ),
+                graph,
+            )
+        }
         Directive::Deprecated { reason } => write_directive(
             f,
             "deprecated",
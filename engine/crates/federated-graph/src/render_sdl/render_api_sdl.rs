@@ -239,7 +239,7 @@ fn write_public_directives(
     graph: &FederatedGraphV3,
 ) -> fmt::Result {
     for directive in graph[directives].iter().filter(|directive| match directive {
-        Directive::Inaccessible | Directive::Policy(_) => false,
+        Directive::Inaccessible | Directive::Policy(_) | Directive::Cost { .. } | Directive::ListSize(_) => false,
 
         Directive::Other { name, .. } if graph[*name] == "tag" => false,
         Directive::RequiresScopes(_)
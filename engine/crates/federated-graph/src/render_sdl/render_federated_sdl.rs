@@ -371,10 +371,9 @@ fn write_overrides(field: &Field, graph: &FederatedGraphV3, sdl: &mut String) ->
             OverrideSource::Missing(string) => &graph[*string],
         };
 
-        let optional_label = if let OverrideLabel::Percent(_) = label {
-            format!(", overrideLabel: \"{}\"", label)
-        } else {
-            String::new()
+        let optional_label = match label {
+            OverrideLabel::Percent(_) | OverrideLabel::Custom(_) => format!(", overrideLabel: \"{}\"", label),
+            OverrideLabel::Unknown => String::new(),
         };
 
         let subgraph_name = GraphEnumVariantName(&graph[graph[*overriding_graph].name]);
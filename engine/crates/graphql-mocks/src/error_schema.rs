@@ -1,4 +1,4 @@
-use async_graphql::{EmptyMutation, EmptySubscription, FieldResult, Object};
+use async_graphql::{EmptyMutation, EmptySubscription, ErrorExtensions, FieldResult, Object};
 
 /// A schema that exposes a field with errors
 pub type ErrorSchema = async_graphql::Schema<Query, EmptyMutation, EmptySubscription>;
@@ -21,6 +21,10 @@ impl Query {
         Err(error.into())
     }
 
+    async fn broken_field_with_code(&self, error: String, code: String) -> FieldResult<Option<String>> {
+        Err(async_graphql::Error::new(error).extend_with(|_, extensions| extensions.set("code", code)))
+    }
+
     async fn broken_list(&self, error: String) -> FieldResult<Option<Vec<String>>> {
         Err(error.into())
     }
@@ -1,6 +1,6 @@
 //! A mock GraphQL server for testing the GraphQL connector
 
-use std::{sync::Arc, time::Duration};
+use std::{io::Read, sync::Arc, time::Duration};
 
 use async_graphql_axum::{GraphQLRequest, GraphQLResponse, GraphQLSubscription};
 use axum::{extract::State, http::HeaderMap, response::IntoResponse, routing::post, Router};
@@ -80,7 +80,17 @@ impl MockGraphQlServer {
         };
 
         let app = Router::new()
-            .route("/", post(graphql_handler))
+            .route(
+                "/",
+                post(graphql_handler)
+                    .layer(axum::middleware::from_fn(decompress_request))
+                    // Like real servers, responses are compressed if the client accepts it. Even
+                    // tiny ones, so that tests exercise the decoding.
+                    .layer(
+                        tower_http::compression::CompressionLayer::new()
+                            .compress_when(tower_http::compression::predicate::SizeAbove::new(0)),
+                    ),
+            )
             .route_service("/ws", GraphQLSubscription::new(SchemaExecutor(schema)))
             .with_state(state.clone());
 
@@ -151,6 +161,17 @@ async fn graphql_handler(
         return response;
     }
 
+    let accept = headers
+        .get(http::header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    if accept.contains("text/event-stream") {
+        return streaming_response(state.schema.execute_stream(req), StreamingFormat::ServerSentEvents);
+    } else if accept.contains("multipart/mixed") {
+        return streaming_response(state.schema.execute_stream(req), StreamingFormat::Multipart);
+    }
+
     let headers = headers
         .iter()
         .map(|(name, value)| (name.to_string(), String::from_utf8_lossy(value.as_bytes()).to_string()))
@@ -160,6 +181,93 @@ async fn graphql_handler(
     response.into_response()
 }
 
+/// Decodes compressed request bodies. The `Content-Encoding` header is kept so that tests can
+/// check how the request was sent.
+async fn decompress_request(request: axum::extract::Request, next: axum::middleware::Next) -> axum::response::Response {
+    let (mut parts, body) = request.into_parts();
+    let encoding = parts
+        .headers
+        .get(http::header::CONTENT_ENCODING)
+        .and_then(|value| value.to_str().ok());
+
+    let body = match axum::body::to_bytes(body, usize::MAX)
+        .await
+        .map_err(|error| error.to_string())
+        .and_then(|body| decode_body(encoding, &body).map_err(|error| error.to_string()))
+    {
+        Ok(body) => body,
+        Err(error) => return (http::StatusCode::BAD_REQUEST, error).into_response(),
+    };
+
+    parts.headers.remove(http::header::CONTENT_LENGTH);
+    next.run(axum::extract::Request::from_parts(parts, axum::body::Body::from(body)))
+        .await
+}
+
+/// Decodes request bodies compressed with gzip, brotli or zstd.
+fn decode_body(encoding: Option<&str>, body: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+
+    match encoding {
+        None | Some("identity") => decoded.extend_from_slice(body),
+        Some("gzip") => {
+            flate2::read::GzDecoder::new(body).read_to_end(&mut decoded)?;
+        }
+        Some("br") => {
+            brotli::Decompressor::new(body, 4096).read_to_end(&mut decoded)?;
+        }
+        Some("zstd") => decoded = zstd::decode_all(body)?,
+        Some(encoding) => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("unsupported content encoding: {encoding}"),
+            ))
+        }
+    }
+
+    Ok(decoded)
+}
+
+#[derive(Clone, Copy)]
+enum StreamingFormat {
+    ServerSentEvents,
+    Multipart,
+}
+
+/// Serves subscriptions over HTTP, either as server-sent events or as multipart parts.
+fn streaming_response(
+    responses: futures::stream::BoxStream<'static, async_graphql::Response>,
+    format: StreamingFormat,
+) -> axum::response::Response {
+    use futures::StreamExt;
+
+    let (content_type, end) = match format {
+        StreamingFormat::ServerSentEvents => ("text/event-stream", "event: complete\n\n"),
+        StreamingFormat::Multipart => (
+            "multipart/mixed; boundary=\"graphql\"; subscriptionSpec=1.0",
+            "\r\n--graphql--\r\n",
+        ),
+    };
+
+    let parts = responses
+        .map(move |response| {
+            let response = serde_json::to_string(&response).unwrap();
+            match format {
+                StreamingFormat::ServerSentEvents => format!("event: next\ndata: {response}\n\n"),
+                StreamingFormat::Multipart => {
+                    format!("\r\n--graphql\r\ncontent-type: application/json\r\n\r\n{{\"payload\":{response}}}")
+                }
+            }
+        })
+        .chain(futures::stream::once(async move { end.to_string() }))
+        .map(Ok::<_, std::convert::Infallible>);
+
+    axum::response::Response::builder()
+        .header(http::header::CONTENT_TYPE, content_type)
+        .body(axum::body::Body::from_stream(parts))
+        .unwrap()
+}
+
 #[derive(Clone)]
 struct AppState {
     schema: Arc<dyn Schema>,
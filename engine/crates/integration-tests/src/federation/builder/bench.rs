@@ -61,6 +61,8 @@ impl<'a> DeterministicEngineBuilder<'a> {
             self.subgraphs_json_responses
                 .into_iter()
                 .map(|resp| FetchResponse {
+                    status: http::StatusCode::OK,
+                    headers: http::HeaderMap::new(),
                     bytes: resp.into_bytes().into(),
                 })
                 .collect(),
@@ -9,17 +9,28 @@ use runtime::fetch::{FetchError, FetchRequest, FetchResponse, FetchResult, Graph
 
 #[derive(Clone, Default)]
 pub struct MockFetch {
-    responses: Arc<Mutex<HashMap<String, crossbeam_queue::SegQueue<Vec<u8>>>>>,
+    responses: Arc<Mutex<HashMap<String, crossbeam_queue::SegQueue<FetchResponse>>>>,
     requests: Arc<crossbeam_queue::SegQueue<(String, ReceivedRequest)>>,
 }
 
 impl MockFetch {
     #[must_use]
     pub fn with_responses<R: serde::Serialize>(self, host: &str, responses: impl IntoIterator<Item = R>) -> Self {
+        self.with_http_responses(
+            host,
+            responses
+                .into_iter()
+                .map(|response| http_response(http::StatusCode::OK, &[], &serde_json::to_string(&response).unwrap())),
+        )
+    }
+
+    /// Responses with a specific HTTP status code and headers.
+    #[must_use]
+    pub fn with_http_responses(self, host: &str, responses: impl IntoIterator<Item = FetchResponse>) -> Self {
         let mut responses_by_host = self.responses.lock().unwrap();
         let queue = responses_by_host.entry(host.to_string()).or_default();
         for response in responses {
-            queue.push(serde_json::to_vec(&response).unwrap());
+            queue.push(response);
         }
         drop(responses_by_host);
         self
@@ -28,6 +39,41 @@ impl MockFetch {
     pub fn drain_received_requests(&self) -> impl Iterator<Item = (String, ReceivedRequest)> + '_ {
         std::iter::from_fn(|| self.requests.pop())
     }
+
+    /// Hosts of the received requests, in order.
+    pub fn drain_requested_hosts(&self) -> Vec<String> {
+        self.drain_received_requests().map(|(host, _)| host).collect()
+    }
+}
+
+/// Subgraph response with a specific HTTP status code, headers and raw body.
+pub fn http_response(status: http::StatusCode, headers: &[(&'static str, &'static str)], body: &str) -> FetchResponse {
+    FetchResponse {
+        status,
+        headers: headers
+            .iter()
+            .map(|(name, value)| {
+                (
+                    http::HeaderName::from_static(name),
+                    http::HeaderValue::from_static(value),
+                )
+            })
+            .collect(),
+        bytes: body.to_string().into(),
+    }
+}
+
+/// Successful response to `query { hello }`.
+pub fn hello_world() -> FetchResponse {
+    http_response(http::StatusCode::OK, &[], r#"{"data":{"hello":"world"}}"#)
+}
+
+pub fn service_unavailable() -> FetchResponse {
+    http_response(
+        http::StatusCode::SERVICE_UNAVAILABLE,
+        &[],
+        "<html>Service Unavailable</html>",
+    )
 }
 
 #[async_trait::async_trait]
@@ -47,7 +93,6 @@ impl runtime::fetch::FetcherInner for MockFetch {
             .unwrap()
             .get(host)
             .and_then(|responses| responses.pop())
-            .map(|bytes| FetchResponse { bytes: bytes.into() })
             .ok_or(FetchError::any("No more responses"))
     }
 
@@ -0,0 +1,102 @@
+use engine_v2::Engine;
+use integration_tests::{
+    federation::EngineV2Ext,
+    fetch::{hello_world, service_unavailable, MockFetch},
+    runtime,
+};
+use serde_json::json;
+
+const SCHEMA: &str = r###"
+    enum join__Graph {
+      A @join__graph(name: "a", url: "https://a/graphql")
+    }
+
+    type Query {
+      hello: String @join__field(graph: A)
+    }
+    "###;
+
+#[test]
+fn open_circuit_fails_fast() {
+    runtime().block_on(async move {
+        let fetcher = MockFetch::default()
+            .with_http_responses("a", [service_unavailable(), service_unavailable(), hello_world()]);
+        let engine = Engine::builder()
+            .with_federated_sdl(SCHEMA)
+            .with_mock_fetcher(fetcher.clone())
+            .with_toml_config(
+                r#"
+                [subgraphs.a.circuit_breaker]
+                consecutive_failures = 2
+                cooldown = "1h"
+                "#,
+            )
+            .build()
+            .await;
+
+        for _ in 0..2 {
+            let response = engine.execute("query { hello }").await;
+            assert_eq!(response.errors()[0]["extensions"]["code"], "SUBGRAPH_REQUEST_ERROR");
+        }
+
+        let response = engine.execute("query { hello }").await;
+        insta::assert_json_snapshot!(response, @r###"
+        {
+          "data": {
+            "hello": null
+          },
+          "errors": [
+            {
+              "message": "Subgraph 'a' is unavailable, its circuit breaker is open",
+              "path": [
+                "hello"
+              ],
+              "extensions": {
+                "code": "SUBGRAPH_UNAVAILABLE"
+              }
+            }
+          ]
+        }
+        "###);
+
+        assert_eq!(fetcher.drain_received_requests().count(), 2);
+    });
+}
+
+#[test]
+fn successful_probe_closes_the_circuit() {
+    runtime().block_on(async move {
+        let fetcher = MockFetch::default().with_http_responses(
+            "a",
+            [
+                service_unavailable(),
+                service_unavailable(),
+                hello_world(),
+                hello_world(),
+            ],
+        );
+        let engine = Engine::builder()
+            .with_federated_sdl(SCHEMA)
+            .with_mock_fetcher(fetcher.clone())
+            .with_toml_config(
+                r#"
+                [subgraphs.a.circuit_breaker]
+                consecutive_failures = 2
+                cooldown = "0s"
+                "#,
+            )
+            .build()
+            .await;
+
+        for _ in 0..2 {
+            engine.execute("query { hello }").await;
+        }
+
+        for _ in 0..2 {
+            let response = engine.execute("query { hello }").await;
+            assert_eq!(response.into_data(), json!({"hello": "world"}));
+        }
+
+        assert_eq!(fetcher.drain_received_requests().count(), 4);
+    });
+}
@@ -0,0 +1,95 @@
+use engine_v2::Engine;
+use graphql_mocks::FakeGithubSchema;
+use integration_tests::{federation::EngineV2Ext, runtime};
+
+#[test]
+fn requests_are_compressed() {
+    for (algorithm, encoding) in [("gzip", "gzip"), ("brotli", "br"), ("zstd", "zstd")] {
+        runtime().block_on(async move {
+            let engine = Engine::builder()
+                .with_subgraph(FakeGithubSchema)
+                .with_toml_config(format!(
+                    r#"
+                    [subgraphs.github.compression]
+                    request = "{algorithm}"
+                    "#
+                ))
+                .build()
+                .await;
+
+            let response = engine.execute("query { serverVersion }").await;
+
+            insta::assert_json_snapshot!(response, @r###"
+            {
+              "data": {
+                "serverVersion": "1"
+              }
+            }
+            "###);
+
+            let requests = engine.drain_http_requests_sent_to::<FakeGithubSchema>();
+            assert_eq!(requests.len(), 1);
+            assert_eq!(
+                requests[0].headers.get(http::header::CONTENT_ENCODING).unwrap(),
+                encoding
+            );
+            assert_eq!(requests[0].query, "query {\n  serverVersion\n}\n");
+        });
+    }
+}
+
+#[test]
+fn compressed_responses_are_decoded() {
+    runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_subgraph(FakeGithubSchema)
+            .with_toml_config(
+                r#"
+                [subgraphs.github.compression]
+                response = true
+                "#,
+            )
+            .build()
+            .await;
+
+        // The mock subgraph compresses its responses with gzip if accepted.
+        let response = engine.execute("query { serverVersion }").await;
+
+        insta::assert_json_snapshot!(response, @r###"
+        {
+          "data": {
+            "serverVersion": "1"
+          }
+        }
+        "###);
+
+        let requests = engine.drain_http_requests_sent_to::<FakeGithubSchema>();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0]
+            .headers
+            .get(http::header::ACCEPT_ENCODING)
+            .is_some_and(|value| value.to_str().unwrap().contains("gzip")));
+    });
+}
+
+#[test]
+fn responses_are_not_compressed_by_default() {
+    runtime().block_on(async move {
+        let engine = Engine::builder().with_subgraph(FakeGithubSchema).build().await;
+
+        let response = engine.execute("query { serverVersion }").await;
+
+        insta::assert_json_snapshot!(response, @r###"
+        {
+          "data": {
+            "serverVersion": "1"
+          }
+        }
+        "###);
+
+        let requests = engine.drain_http_requests_sent_to::<FakeGithubSchema>();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].headers.get(http::header::CONTENT_ENCODING).is_none());
+        assert!(requests[0].headers.get(http::header::ACCEPT_ENCODING).is_none());
+    });
+}
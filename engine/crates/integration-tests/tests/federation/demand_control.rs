@@ -0,0 +1,149 @@
+use engine_v2::Engine;
+use integration_tests::{federation::EngineV2Ext, fetch::MockFetch, runtime};
+use serde_json::json;
+
+const SCHEMA: &str = r###"
+    enum join__Graph {
+      A @join__graph(name: "a", url: "https://a/graphql")
+    }
+
+    type Product @join__type(graph: A) {
+      name: String @join__field(graph: A)
+      reviews: [Review!]! @join__field(graph: A)
+    }
+
+    type Review @join__type(graph: A) {
+      body: String @join__field(graph: A)
+    }
+
+    type Query {
+      products(first: Int): [Product!]! @join__field(graph: A) @listSize(slicingArguments: ["first"], assumedSize: 50)
+      expensive: String @join__field(graph: A) @cost(weight: 20)
+    }
+    "###;
+
+#[test]
+fn estimated_cost_is_exposed_in_measure_mode() {
+    runtime().block_on(async move {
+        let fetcher = MockFetch::default().with_responses(
+            "a",
+            [json!({"data": {"products": [{"name": "Chair", "reviews": []}], "expensive": "yes"}})],
+        );
+        let engine = Engine::builder()
+            .with_federated_sdl(SCHEMA)
+            .with_mock_fetcher(fetcher.clone())
+            .with_toml_config(
+                r#"
+                [demand_control]
+                mode = "measure"
+                max_cost = 10
+                "#,
+            )
+            .build()
+            .await;
+
+        // products: 5 * (1 + reviews: 10 * 1) + expensive: 20
+        let response = engine
+            .execute("query { products(first: 5) { name reviews { body } } expensive }")
+            .await;
+
+        insta::assert_json_snapshot!(response, @r###"
+        {
+          "data": {
+            "products": [
+              {
+                "name": "Chair",
+                "reviews": []
+              }
+            ],
+            "expensive": "yes"
+          },
+          "extensions": {
+            "cost": {
+              "estimated": 75
+            }
+          }
+        }
+        "###);
+    });
+}
+
+#[test]
+fn operations_above_the_maximum_cost_are_rejected() {
+    runtime().block_on(async move {
+        let fetcher = MockFetch::default();
+        let engine = Engine::builder()
+            .with_federated_sdl(SCHEMA)
+            .with_mock_fetcher(fetcher.clone())
+            .with_toml_config(
+                r#"
+                [demand_control]
+                max_cost = 50
+                "#,
+            )
+            .build()
+            .await;
+
+        let response = engine
+            .execute("query { products(first: 5) { name reviews { body } } }")
+            .await;
+
+        insta::assert_json_snapshot!(response, @r###"
+        {
+          "errors": [
+            {
+              "message": "Operation estimated cost 55 exceeds the maximum cost of 50",
+              "extensions": {
+                "estimatedCost": 55,
+                "code": "OPERATION_COST_EXCEEDED"
+              }
+            }
+          ]
+        }
+        "###);
+        assert_eq!(fetcher.drain_received_requests().count(), 0);
+    });
+}
+
+#[test]
+fn slicing_arguments_are_read_from_variables() {
+    runtime().block_on(async move {
+        let fetcher = MockFetch::default().with_responses("a", [json!({"data": {"products": []}})]);
+        let engine = Engine::builder()
+            .with_federated_sdl(SCHEMA)
+            .with_mock_fetcher(fetcher.clone())
+            .with_toml_config(
+                r#"
+                [demand_control]
+                max_cost = 10
+                "#,
+            )
+            .build()
+            .await;
+
+        let response = engine
+            .execute("query($first: Int) { products(first: $first) { name } }")
+            .variables(json!({"first": 2}))
+            .await;
+        assert_eq!(
+            response.to_string(),
+            r#"{"data":{"products":[]},"extensions":{"cost":{"estimated":2}}}"#
+        );
+
+        // Without the slicing argument, the assumed size is used.
+        let response = engine.execute("query { products { name } }").await;
+        insta::assert_json_snapshot!(response, @r###"
+        {
+          "errors": [
+            {
+              "message": "Operation estimated cost 50 exceeds the maximum cost of 10",
+              "extensions": {
+                "estimatedCost": 50,
+                "code": "OPERATION_COST_EXCEEDED"
+              }
+            }
+          ]
+        }
+        "###);
+    });
+}
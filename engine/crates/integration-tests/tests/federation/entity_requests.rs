@@ -0,0 +1,156 @@
+use engine_v2::Engine;
+use graphql_mocks::{FederatedAccountsSchema, FederatedProductsSchema, FederatedReviewsSchema};
+use integration_tests::{
+    federation::{EngineV2Ext, TestEngineV2},
+    runtime,
+};
+
+const QUERY: &str = r"
+    query {
+        topProducts {
+            upc
+            reviews {
+                author {
+                    username
+                }
+            }
+        }
+    }
+";
+
+#[test]
+fn duplicate_representations_are_requested_once() {
+    runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_subgraph(FederatedAccountsSchema)
+            .with_subgraph(FederatedProductsSchema)
+            .with_subgraph(FederatedReviewsSchema)
+            .build()
+            .await;
+
+        let response = engine.execute(QUERY).await;
+        insta::assert_json_snapshot!(response, @r###"
+        {
+          "data": {
+            "topProducts": [
+              {
+                "upc": "top-1",
+                "reviews": [
+                  {
+                    "author": {
+                      "username": "Me"
+                    }
+                  }
+                ]
+              },
+              {
+                "upc": "top-2",
+                "reviews": [
+                  {
+                    "author": {
+                      "username": "Me"
+                    }
+                  }
+                ]
+              },
+              {
+                "upc": "top-3",
+                "reviews": [
+                  {
+                    "author": {
+                      "username": "User 7777"
+                    }
+                  }
+                ]
+              },
+              {
+                "upc": "top-4",
+                "reviews": []
+              },
+              {
+                "upc": "top-5",
+                "reviews": [
+                  {
+                    "author": null
+                  }
+                ]
+              }
+            ]
+          }
+        }
+        "###);
+
+        insta::assert_json_snapshot!(sent_representations(&engine), @r###"
+        [
+          [
+            {
+              "__typename": "User",
+              "id": "1234"
+            },
+            {
+              "__typename": "User",
+              "id": "7777"
+            }
+          ]
+        ]
+        "###);
+    });
+}
+
+#[test]
+fn entities_are_requested_in_chunks() {
+    runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_subgraph(FederatedAccountsSchema)
+            .with_subgraph(FederatedProductsSchema)
+            .with_subgraph(FederatedReviewsSchema)
+            .with_toml_config(
+                r#"
+                [subgraphs.accounts]
+                max_entities_per_request = 1
+                "#,
+            )
+            .build()
+            .await;
+
+        let response = engine.execute(QUERY).await;
+        let usernames = response.into_data()["topProducts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .flat_map(|product| product["reviews"].as_array().unwrap().clone())
+            .map(|review| review["author"]["username"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(usernames, ["Me", "Me", "User 7777", serde_json::Value::Null]);
+
+        let mut representations = sent_representations(&engine);
+        representations.sort_by_key(|representations| representations.to_string());
+        insta::assert_json_snapshot!(representations, @r###"
+        [
+          [
+            {
+              "__typename": "User",
+              "id": "1234"
+            }
+          ],
+          [
+            {
+              "__typename": "User",
+              "id": "7777"
+            }
+          ]
+        ]
+        "###);
+    });
+}
+
+fn sent_representations(engine: &TestEngineV2) -> Vec<serde_json::Value> {
+    engine
+        .drain_graphql_requests_sent_to::<FederatedAccountsSchema>()
+        .into_iter()
+        .filter_map(|request| {
+            let variables = serde_json::to_value(&request.variables).unwrap();
+            variables.as_object()?.values().find(|value| value.is_array()).cloned()
+        })
+        .collect()
+}
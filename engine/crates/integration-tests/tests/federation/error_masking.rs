@@ -0,0 +1,144 @@
+use engine_v2::Engine;
+use graphql_mocks::ErrorSchema;
+use integration_tests::{federation::EngineV2Ext, runtime};
+
+#[test]
+fn errors_are_passed_through_by_default() {
+    let response = runtime().block_on(async move {
+        let engine = Engine::builder().with_subgraph(ErrorSchema::default()).build().await;
+
+        engine
+            .execute(r#"query { brokenFieldWithCode(error: "connection to db-1.internal refused", code: "DB") }"#)
+            .await
+    });
+
+    insta::assert_json_snapshot!(response, @r###"
+    {
+      "data": {
+        "brokenFieldWithCode": null
+      },
+      "errors": [
+        {
+          "message": "connection to db-1.internal refused",
+          "path": [
+            "brokenFieldWithCode"
+          ],
+          "extensions": {
+            "upstream_extensions": {
+              "code": "DB"
+            },
+            "code": "SUBGRAPH_ERROR"
+          }
+        }
+      ]
+    }
+    "###);
+}
+
+#[test]
+fn errors_are_masked() {
+    let response = runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_subgraph(ErrorSchema::default())
+            .with_toml_config(
+                r#"
+                [error_masking]
+                mode = "mask"
+                "#,
+            )
+            .build()
+            .await;
+
+        engine
+            .execute(r#"query { brokenFieldWithCode(error: "connection to db-1.internal refused", code: "DB") }"#)
+            .await
+    });
+
+    insta::assert_json_snapshot!(response, { ".errors[].extensions.correlation_id" => "[correlation_id]" }, @r###"
+    {
+      "data": {
+        "brokenFieldWithCode": null
+      },
+      "errors": [
+        {
+          "message": "Internal subgraph error",
+          "path": [
+            "brokenFieldWithCode"
+          ],
+          "extensions": {
+            "correlation_id": "[correlation_id]",
+            "code": "SUBGRAPH_ERROR"
+          }
+        }
+      ]
+    }
+    "###);
+}
+
+#[test]
+fn subgraph_allowlist_and_code_mappings() {
+    let response = runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_subgraph(ErrorSchema::default())
+            .with_toml_config(
+                r#"
+                [error_masking]
+                mode = "pass_through"
+
+                [subgraphs.errors.error_masking]
+                mode = "allowlist"
+                message = "Something went wrong"
+                allowed_codes = ["NOT_FOUND"]
+
+                [subgraphs.errors.error_masking.code_mappings]
+                FORBIDDEN = "UNAUTHORIZED"
+                "#,
+            )
+            .build()
+            .await;
+
+        engine
+            .execute(
+                r#"
+                query {
+                    notFound: brokenFieldWithCode(error: "No such product", code: "NOT_FOUND")
+                    forbidden: brokenFieldWithCode(error: "User 42 lacks role admin", code: "FORBIDDEN")
+                }
+                "#,
+            )
+            .await
+    });
+
+    insta::assert_json_snapshot!(response, { ".errors[].extensions.correlation_id" => "[correlation_id]" }, @r###"
+    {
+      "data": {
+        "notFound": null,
+        "forbidden": null
+      },
+      "errors": [
+        {
+          "message": "No such product",
+          "path": [
+            "notFound"
+          ],
+          "extensions": {
+            "upstream_extensions": {
+              "code": "NOT_FOUND"
+            },
+            "code": "SUBGRAPH_ERROR"
+          }
+        },
+        {
+          "message": "Something went wrong",
+          "path": [
+            "forbidden"
+          ],
+          "extensions": {
+            "correlation_id": "[correlation_id]",
+            "code": "UNAUTHORIZED"
+          }
+        }
+      ]
+    }
+    "###);
+}
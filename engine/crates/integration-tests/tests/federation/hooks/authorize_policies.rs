@@ -0,0 +1,115 @@
+use integration_tests::federation::DeterministicEngine;
+use runtime::{
+    error::{PartialErrorCode, PartialGraphqlError},
+    hooks::{DynHookContext, DynHooks},
+};
+use serde_json::json;
+
+const SCHEMA: &str = r#"
+    enum join__Graph {
+      ACCOUNTS @join__graph(name: "accounts", url: "http://127.0.0.1:46697")
+    }
+
+    type Query {
+        public: String @join__field(graph: ACCOUNTS)
+        read: String @join__field(graph: ACCOUNTS) @policy(policies: [["read"]])
+        admin: String @join__field(graph: ACCOUNTS) @policy(policies: [["admin", "write"], ["root"]])
+    }
+    "#;
+
+#[test]
+fn denied_policies_are_not_fetched() {
+    struct TestHooks;
+
+    #[async_trait::async_trait]
+    impl DynHooks for TestHooks {
+        async fn authorize_policies(
+            &self,
+            _context: &DynHookContext,
+            policies: Vec<String>,
+        ) -> Result<Vec<String>, PartialGraphqlError> {
+            assert_eq!(policies, vec!["admin", "read", "root", "write"]);
+            Ok(vec!["read".to_string(), "admin".to_string()])
+        }
+    }
+
+    let response = integration_tests::runtime().block_on(async {
+        DeterministicEngine::builder(SCHEMA, "query { public read admin }")
+            .with_hooks(TestHooks)
+            .with_subgraph_response(json!({"data": {"public": "public", "read": "read"}}))
+            .build()
+            .await
+            .execute()
+            .await
+    });
+
+    insta::assert_json_snapshot!(response, @r###"
+    {
+      "data": {
+        "public": "public",
+        "read": "read",
+        "admin": null
+      },
+      "errors": [
+        {
+          "message": "Not allowed by policy",
+          "path": [
+            "admin"
+          ],
+          "extensions": {
+            "code": "UNAUTHORIZED"
+          }
+        }
+      ]
+    }
+    "###);
+}
+
+#[test]
+fn hook_error_denies_all_policies() {
+    struct TestHooks;
+
+    #[async_trait::async_trait]
+    impl DynHooks for TestHooks {
+        async fn authorize_policies(
+            &self,
+            _context: &DynHookContext,
+            _policies: Vec<String>,
+        ) -> Result<Vec<String>, PartialGraphqlError> {
+            Err(PartialGraphqlError::new(
+                "Policy service unavailable",
+                PartialErrorCode::Unauthorized,
+            ))
+        }
+    }
+
+    let response = integration_tests::runtime().block_on(async {
+        DeterministicEngine::builder(SCHEMA, "query { public read }")
+            .with_hooks(TestHooks)
+            .with_subgraph_response(json!({"data": {"public": "public"}}))
+            .build()
+            .await
+            .execute()
+            .await
+    });
+
+    insta::assert_json_snapshot!(response, @r###"
+    {
+      "data": {
+        "public": "public",
+        "read": null
+      },
+      "errors": [
+        {
+          "message": "Policy service unavailable",
+          "path": [
+            "read"
+          ],
+          "extensions": {
+            "code": "UNAUTHORIZED"
+          }
+        }
+      ]
+    }
+    "###);
+}
@@ -2,8 +2,11 @@ mod authorize_edge_node_post_execution;
 mod authorize_edge_pre_execution;
 mod authorize_node_pre_execution;
 mod authorize_parent_edge_post_execution;
+mod authorize_policies;
 mod on_gateway_request;
+mod on_gateway_response;
 mod on_subgraph_request;
+mod on_subgraph_response;
 
 use engine_v2::Engine;
 use futures::Future;
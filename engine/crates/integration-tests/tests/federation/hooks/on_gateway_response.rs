@@ -0,0 +1,163 @@
+use engine_v2::Engine;
+use futures::StreamExt;
+use graphql_mocks::FakeGithubSchema;
+use http::HeaderMap;
+use integration_tests::{federation::EngineV2Ext, runtime};
+use runtime::{
+    error::{PartialErrorCode, PartialGraphqlError},
+    hooks::{DynHookContext, DynHooks, ExecutedOperation},
+};
+
+#[test]
+fn can_modify_response_headers() {
+    struct TestHooks;
+
+    #[async_trait::async_trait]
+    impl DynHooks for TestHooks {
+        async fn on_gateway_response(
+            &self,
+            _context: &DynHookContext,
+            operation: ExecutedOperation<'_>,
+            mut headers: HeaderMap,
+        ) -> Result<HeaderMap, PartialGraphqlError> {
+            headers.insert("x-operation-name", operation.name.unwrap_or_default().parse().unwrap());
+            headers.insert("x-operation-type", operation.ty.unwrap().as_str().parse().unwrap());
+            headers.insert("x-operation-status", operation.status.as_str().parse().unwrap());
+            Ok(headers)
+        }
+    }
+
+    let response = runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_mock_hooks(TestHooks)
+            .with_subgraph(FakeGithubSchema)
+            .build()
+            .await;
+
+        engine.execute("query Version { serverVersion }").await
+    });
+
+    insta::assert_json_snapshot!(response, @r###"
+    {
+      "data": {
+        "serverVersion": "1"
+      }
+    }
+    "###);
+
+    assert_eq!(response.headers.get("x-operation-name").unwrap(), "Version");
+    assert_eq!(response.headers.get("x-operation-type").unwrap(), "query");
+    assert_eq!(response.headers.get("x-operation-status").unwrap(), "SUCCESS");
+}
+
+#[test]
+fn error_does_not_change_the_response() {
+    struct TestHooks;
+
+    #[async_trait::async_trait]
+    impl DynHooks for TestHooks {
+        async fn on_gateway_response(
+            &self,
+            _context: &DynHookContext,
+            _operation: ExecutedOperation<'_>,
+            _headers: HeaderMap,
+        ) -> Result<HeaderMap, PartialGraphqlError> {
+            Err(PartialGraphqlError::new("impossible", PartialErrorCode::HookError))
+        }
+    }
+
+    let response = runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_mock_hooks(TestHooks)
+            .with_subgraph(FakeGithubSchema)
+            .build()
+            .await;
+
+        engine.execute("query { serverVersion }").await
+    });
+
+    insta::assert_json_snapshot!(response, @r###"
+    {
+      "data": {
+        "serverVersion": "1"
+      }
+    }
+    "###);
+}
+
+#[test]
+fn can_modify_streaming_response_headers() {
+    struct TestHooks;
+
+    #[async_trait::async_trait]
+    impl DynHooks for TestHooks {
+        async fn on_gateway_response(
+            &self,
+            _context: &DynHookContext,
+            operation: ExecutedOperation<'_>,
+            mut headers: HeaderMap,
+        ) -> Result<HeaderMap, PartialGraphqlError> {
+            headers.insert("x-operation-name", operation.name.unwrap_or_default().parse().unwrap());
+            headers.insert("x-operation-status", operation.status.as_str().parse().unwrap());
+            Ok(headers)
+        }
+    }
+
+    let (headers, response) = runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_mock_hooks(TestHooks)
+            .with_subgraph(FakeGithubSchema)
+            .build()
+            .await;
+
+        let response = engine
+            .execute(
+                r"
+                query Deferred {
+                    serverVersion
+                    ... @defer {
+                        allBotPullRequests {
+                            title
+                        }
+                    }
+                }
+                ",
+            )
+            .into_multipart_stream()
+            .await;
+
+        (response.headers, response.stream.collect::<Vec<_>>().await)
+    });
+
+    insta::assert_json_snapshot!(response, @r###"
+    [
+      {
+        "data": {
+          "serverVersion": "1"
+        },
+        "hasNext": true
+      },
+      {
+        "incremental": [
+          {
+            "data": {
+              "allBotPullRequests": [
+                {
+                  "title": "Creating the thing"
+                },
+                {
+                  "title": "Some bot PR"
+                }
+              ]
+            },
+            "path": []
+          }
+        ],
+        "hasNext": false
+      }
+    ]
+    "###);
+
+    assert_eq!(headers.get("x-operation-name").unwrap(), "Deferred");
+    assert_eq!(headers.get("x-operation-status").unwrap(), "SUCCESS");
+}
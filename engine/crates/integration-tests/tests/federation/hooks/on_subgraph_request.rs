@@ -3,6 +3,7 @@ use graphql_mocks::{EchoSchema, FakeGithubSchema};
 use http::HeaderMap;
 use integration_tests::{federation::EngineV2Ext, runtime};
 use runtime::{
+    auth::AccessToken,
     error::{PartialErrorCode, PartialGraphqlError},
     hooks::{DynHookContext, DynHooks},
 };
@@ -146,6 +147,47 @@ fn can_modify_headers() {
     "###);
 }
 
+#[test]
+fn access_token_is_available() {
+    struct TestHooks;
+
+    #[async_trait::async_trait]
+    impl DynHooks for TestHooks {
+        async fn on_subgraph_request(
+            &self,
+            context: &DynHookContext,
+            _subgraph_name: &str,
+            _method: http::Method,
+            _url: &Url,
+            mut headers: HeaderMap,
+        ) -> Result<HeaderMap, PartialGraphqlError> {
+            let access_token = context.typed_get::<AccessToken>().unwrap();
+            headers.insert("anonymous", access_token.is_anonymous().to_string().parse().unwrap());
+            Ok(headers)
+        }
+    }
+
+    let response = runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_mock_hooks(TestHooks)
+            .with_subgraph(EchoSchema)
+            .build()
+            .await;
+
+        engine
+            .execute(r#"query { anonymous: header(name: "anonymous") }"#)
+            .await
+    });
+
+    insta::assert_json_snapshot!(response, @r###"
+    {
+      "data": {
+        "anonymous": "true"
+      }
+    }
+    "###);
+}
+
 #[test]
 fn error_is_propagated_back_to_the_user() {
     struct TestHooks;
@@ -0,0 +1,111 @@
+use std::sync::{Arc, Mutex};
+
+use engine_v2::Engine;
+use graphql_mocks::FakeGithubSchema;
+use integration_tests::{federation::EngineV2Ext, runtime};
+use runtime::{
+    error::{PartialErrorCode, PartialGraphqlError},
+    hooks::{DynHookContext, DynHooks, ExecutedSubgraphRequest},
+};
+
+#[test]
+fn receives_the_subgraph_response() {
+    #[derive(Clone, Default)]
+    struct TestHooks(Arc<Mutex<Vec<(String, u16, serde_json::Value)>>>);
+
+    #[async_trait::async_trait]
+    impl DynHooks for TestHooks {
+        async fn on_subgraph_response(
+            &self,
+            _context: &DynHookContext,
+            request: ExecutedSubgraphRequest<'_>,
+        ) -> Result<(), PartialGraphqlError> {
+            self.0.lock().unwrap().push((
+                request.subgraph_name.to_string(),
+                request.status.as_u16(),
+                serde_json::from_slice(request.body).unwrap(),
+            ));
+            Ok(())
+        }
+    }
+
+    let hooks = TestHooks::default();
+    let response = runtime().block_on({
+        let hooks = hooks.clone();
+        async move {
+            let engine = Engine::builder()
+                .with_mock_hooks(hooks)
+                .with_subgraph(FakeGithubSchema)
+                .build()
+                .await;
+
+            engine.execute("query { serverVersion }").await
+        }
+    });
+
+    insta::assert_json_snapshot!(response, @r###"
+    {
+      "data": {
+        "serverVersion": "1"
+      }
+    }
+    "###);
+
+    let responses = hooks.0.lock().unwrap().clone();
+    insta::assert_json_snapshot!(responses, @r###"
+    [
+      [
+        "github",
+        200,
+        {
+          "data": {
+            "serverVersion": "1"
+          }
+        }
+      ]
+    ]
+    "###);
+}
+
+#[test]
+fn error_is_propagated_back_to_the_user() {
+    struct TestHooks;
+
+    #[async_trait::async_trait]
+    impl DynHooks for TestHooks {
+        async fn on_subgraph_response(
+            &self,
+            _context: &DynHookContext,
+            _request: ExecutedSubgraphRequest<'_>,
+        ) -> Result<(), PartialGraphqlError> {
+            Err(PartialGraphqlError::new("invalid billing", PartialErrorCode::HookError))
+        }
+    }
+
+    let response = runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_mock_hooks(TestHooks)
+            .with_subgraph(FakeGithubSchema)
+            .build()
+            .await;
+
+        engine.execute("query { serverVersion }").await
+    });
+
+    insta::assert_json_snapshot!(response, @r###"
+    {
+      "data": null,
+      "errors": [
+        {
+          "message": "invalid billing",
+          "path": [
+            "serverVersion"
+          ],
+          "extensions": {
+            "code": "HOOK_ERROR"
+          }
+        }
+      ]
+    }
+    "###);
+}
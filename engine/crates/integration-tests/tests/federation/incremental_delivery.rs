@@ -0,0 +1,452 @@
+//! Tests of @defer and @stream with incremental delivery in engine-v2
+
+use engine_v2::Engine;
+use graphql_mocks::FakeGithubSchema;
+use integration_tests::{federation::EngineV2Ext, runtime};
+
+#[test]
+fn root_fields_can_be_deferred() {
+    runtime().block_on(async move {
+        let engine = Engine::builder().with_subgraph(FakeGithubSchema).build().await;
+
+        let response = engine
+            .execute(
+                r#"
+                query {
+                    serverVersion
+                    ... @defer(label: "bots") {
+                        allBotPullRequests {
+                            title
+                        }
+                    }
+                }
+                "#,
+            )
+            .into_multipart_stream()
+            .collect::<Vec<_>>()
+            .await;
+
+        insta::assert_json_snapshot!(response, @r###"
+        [
+          {
+            "data": {
+              "serverVersion": "1"
+            },
+            "hasNext": true
+          },
+          {
+            "incremental": [
+              {
+                "data": {
+                  "allBotPullRequests": [
+                    {
+                      "title": "Creating the thing"
+                    },
+                    {
+                      "title": "Some bot PR"
+                    }
+                  ]
+                },
+                "path": [],
+                "label": "bots"
+              }
+            ],
+            "hasNext": false
+          }
+        ]
+        "###);
+    })
+}
+
+#[test]
+fn defer_is_ignored_without_streaming() {
+    let response = runtime().block_on(async move {
+        let engine = Engine::builder().with_subgraph(FakeGithubSchema).build().await;
+
+        engine
+            .execute(
+                r"
+                query {
+                    serverVersion
+                    ... @defer {
+                        allBotPullRequests {
+                            title
+                        }
+                    }
+                }
+                ",
+            )
+            .await
+    });
+
+    insta::assert_json_snapshot!(response, @r###"
+    {
+      "data": {
+        "serverVersion": "1",
+        "allBotPullRequests": [
+          {
+            "title": "Creating the thing"
+          },
+          {
+            "title": "Some bot PR"
+          }
+        ]
+      }
+    }
+    "###);
+}
+
+#[test]
+fn defer_if_can_be_a_variable() {
+    runtime().block_on(async move {
+        let engine = Engine::builder().with_subgraph(FakeGithubSchema).build().await;
+        let query = r"
+            query($shouldDefer: Boolean!) {
+                serverVersion
+                ... @defer(if: $shouldDefer) {
+                    allBotPullRequests {
+                        title
+                    }
+                }
+            }
+        ";
+
+        let response = engine
+            .execute(query)
+            .variables(serde_json::json!({"shouldDefer": true}))
+            .into_multipart_stream()
+            .collect::<Vec<_>>()
+            .await;
+
+        insta::assert_json_snapshot!(response, @r###"
+        [
+          {
+            "data": {
+              "serverVersion": "1"
+            },
+            "hasNext": true
+          },
+          {
+            "incremental": [
+              {
+                "data": {
+                  "allBotPullRequests": [
+                    {
+                      "title": "Creating the thing"
+                    },
+                    {
+                      "title": "Some bot PR"
+                    }
+                  ]
+                },
+                "path": []
+              }
+            ],
+            "hasNext": false
+          }
+        ]
+        "###);
+
+        let response = engine
+            .execute(query)
+            .variables(serde_json::json!({"shouldDefer": false}))
+            .into_multipart_stream()
+            .collect::<Vec<_>>()
+            .await;
+
+        insta::assert_json_snapshot!(response, @r###"
+        [
+          {
+            "data": {
+              "serverVersion": "1",
+              "allBotPullRequests": [
+                {
+                  "title": "Creating the thing"
+                },
+                {
+                  "title": "Some bot PR"
+                }
+              ]
+            }
+          }
+        ]
+        "###);
+    })
+}
+
+#[test]
+fn field_selected_outside_of_deferred_fragment_is_not_deferred() {
+    runtime().block_on(async move {
+        let engine = Engine::builder().with_subgraph(FakeGithubSchema).build().await;
+
+        let response = engine
+            .execute(
+                r"
+                query {
+                    allBotPullRequests {
+                        title
+                    }
+                    ... @defer {
+                        serverVersion
+                        allBotPullRequests {
+                            title
+                        }
+                    }
+                }
+                ",
+            )
+            .into_multipart_stream()
+            .collect::<Vec<_>>()
+            .await;
+
+        insta::assert_json_snapshot!(response, @r###"
+        [
+          {
+            "data": {
+              "allBotPullRequests": [
+                {
+                  "title": "Creating the thing"
+                },
+                {
+                  "title": "Some bot PR"
+                }
+              ]
+            },
+            "hasNext": true
+          },
+          {
+            "incremental": [
+              {
+                "data": {
+                  "serverVersion": "1"
+                },
+                "path": []
+              }
+            ],
+            "hasNext": false
+          }
+        ]
+        "###);
+    })
+}
+
+#[test]
+fn field_selected_in_sibling_deferred_fragments_is_delivered_with_their_parent() {
+    runtime().block_on(async move {
+        let engine = Engine::builder().with_subgraph(FakeGithubSchema).build().await;
+
+        let response = engine
+            .execute(
+                r#"
+                query {
+                    ... @defer(label: "a") {
+                        serverVersion
+                    }
+                    ... @defer(label: "b") {
+                        serverVersion
+                        allBotPullRequests {
+                            title
+                        }
+                    }
+                }
+                "#,
+            )
+            .into_multipart_stream()
+            .collect::<Vec<_>>()
+            .await;
+
+        insta::assert_json_snapshot!(response, @r###"
+        [
+          {
+            "data": {
+              "serverVersion": "1"
+            },
+            "hasNext": true
+          },
+          {
+            "incremental": [
+              {
+                "data": {
+                  "allBotPullRequests": [
+                    {
+                      "title": "Creating the thing"
+                    },
+                    {
+                      "title": "Some bot PR"
+                    }
+                  ]
+                },
+                "path": [],
+                "label": "b"
+              }
+            ],
+            "hasNext": false
+          }
+        ]
+        "###);
+    })
+}
+
+#[test]
+fn list_items_can_be_streamed() {
+    runtime().block_on(async move {
+        let engine = Engine::builder().with_subgraph(FakeGithubSchema).build().await;
+
+        let response = engine
+            .execute(
+                r"
+                query {
+                    allBotPullRequests @stream(initialCount: 1) {
+                        title
+                    }
+                }
+                ",
+            )
+            .into_multipart_stream()
+            .collect::<Vec<_>>()
+            .await;
+
+        insta::assert_json_snapshot!(response, @r###"
+        [
+          {
+            "data": {
+              "allBotPullRequests": [
+                {
+                  "title": "Creating the thing"
+                }
+              ]
+            },
+            "hasNext": true
+          },
+          {
+            "incremental": [
+              {
+                "items": [
+                  {
+                    "title": "Some bot PR"
+                  }
+                ],
+                "path": [
+                  "allBotPullRequests",
+                  1
+                ]
+              }
+            ],
+            "hasNext": false
+          }
+        ]
+        "###);
+    })
+}
+
+#[test]
+fn stream_arguments_can_be_variables() {
+    runtime().block_on(async move {
+        let engine = Engine::builder().with_subgraph(FakeGithubSchema).build().await;
+        let query = r"
+            query($initialCount: Int!, $shouldStream: Boolean!) {
+                allBotPullRequests @stream(initialCount: $initialCount, if: $shouldStream) {
+                    title
+                }
+            }
+        ";
+
+        let response = engine
+            .execute(query)
+            .variables(serde_json::json!({"initialCount": 1, "shouldStream": true}))
+            .into_multipart_stream()
+            .collect::<Vec<_>>()
+            .await;
+
+        insta::assert_json_snapshot!(response, @r###"
+        [
+          {
+            "data": {
+              "allBotPullRequests": [
+                {
+                  "title": "Creating the thing"
+                }
+              ]
+            },
+            "hasNext": true
+          },
+          {
+            "incremental": [
+              {
+                "items": [
+                  {
+                    "title": "Some bot PR"
+                  }
+                ],
+                "path": [
+                  "allBotPullRequests",
+                  1
+                ]
+              }
+            ],
+            "hasNext": false
+          }
+        ]
+        "###);
+
+        let response = engine
+            .execute(query)
+            .variables(serde_json::json!({"initialCount": 0, "shouldStream": false}))
+            .into_multipart_stream()
+            .collect::<Vec<_>>()
+            .await;
+
+        insta::assert_json_snapshot!(response, @r###"
+        [
+          {
+            "data": {
+              "allBotPullRequests": [
+                {
+                  "title": "Creating the thing"
+                },
+                {
+                  "title": "Some bot PR"
+                }
+              ]
+            }
+          }
+        ]
+        "###);
+    })
+}
+
+#[test]
+fn stream_is_only_allowed_on_lists() {
+    let response = runtime().block_on(async move {
+        let engine = Engine::builder().with_subgraph(FakeGithubSchema).build().await;
+
+        engine
+            .execute(
+                r"
+                query {
+                    serverVersion @stream
+                }
+                ",
+            )
+            .await
+    });
+
+    insta::assert_json_snapshot!(response, @r###"
+    {
+      "errors": [
+        {
+          "message": "@stream can only be used on list fields, but field 'serverVersion' has type 'String!'.",
+          "locations": [
+            {
+              "line": 3,
+              "column": 35
+            }
+          ],
+          "extensions": {
+            "code": "OPERATION_VALIDATION_ERROR"
+          }
+        }
+      ]
+    }
+    "###);
+}
@@ -0,0 +1,122 @@
+use engine_v2::Engine;
+use integration_tests::{
+    federation::EngineV2Ext,
+    fetch::{hello_world, service_unavailable, MockFetch},
+    runtime,
+};
+
+const SCHEMA: &str = r###"
+    enum join__Graph {
+      A @join__graph(name: "a", url: "https://a/graphql")
+    }
+
+    type Query {
+      hello: String @join__field(graph: A)
+    }
+    "###;
+
+#[test]
+fn round_robin_across_urls() {
+    runtime().block_on(async move {
+        let fetcher = MockFetch::default()
+            .with_http_responses("a1", [hello_world(), hello_world()])
+            .with_http_responses("a2", [hello_world(), hello_world()]);
+        let engine = Engine::builder()
+            .with_federated_sdl(SCHEMA)
+            .with_mock_fetcher(fetcher.clone())
+            .with_toml_config(
+                r#"
+                [subgraphs.a.load_balancing]
+                urls = ["https://a1/graphql", "https://a2/graphql"]
+                "#,
+            )
+            .build()
+            .await;
+
+        for _ in 0..4 {
+            let response = engine.execute("query { hello }").await;
+            assert_eq!(response.to_string(), r#"{"data":{"hello":"world"}}"#);
+        }
+
+        insta::assert_json_snapshot!(fetcher.drain_requested_hosts(), @r###"
+        [
+          "a1",
+          "a2",
+          "a1",
+          "a2"
+        ]
+        "###);
+    });
+}
+
+#[test]
+fn retries_fail_over_to_another_url() {
+    runtime().block_on(async move {
+        let fetcher = MockFetch::default()
+            .with_http_responses("a1", [service_unavailable()])
+            .with_http_responses("a2", [hello_world()]);
+        let engine = Engine::builder()
+            .with_federated_sdl(SCHEMA)
+            .with_mock_fetcher(fetcher.clone())
+            .with_toml_config(
+                r#"
+                [subgraphs.a.retry]
+                enabled = true
+
+                [subgraphs.a.load_balancing]
+                urls = ["https://a1/graphql", "https://a2/graphql"]
+                "#,
+            )
+            .build()
+            .await;
+
+        let response = engine.execute("query { hello }").await;
+        assert_eq!(response.to_string(), r#"{"data":{"hello":"world"}}"#);
+
+        insta::assert_json_snapshot!(fetcher.drain_requested_hosts(), @r###"
+        [
+          "a1",
+          "a2"
+        ]
+        "###);
+    });
+}
+
+#[test]
+fn failing_url_is_ejected() {
+    runtime().block_on(async move {
+        let fetcher = MockFetch::default()
+            .with_http_responses("a1", [service_unavailable()])
+            .with_http_responses("a2", [hello_world(), hello_world(), hello_world()]);
+        let engine = Engine::builder()
+            .with_federated_sdl(SCHEMA)
+            .with_mock_fetcher(fetcher.clone())
+            .with_toml_config(
+                r#"
+                [subgraphs.a.load_balancing]
+                urls = ["https://a1/graphql", { url = "https://a2/graphql", weight = 2 }]
+                outlier_ejection.consecutive_failures = 1
+                outlier_ejection.duration = "1h"
+                "#,
+            )
+            .build()
+            .await;
+
+        let response = engine.execute("query { hello }").await;
+        assert_eq!(response.errors()[0]["extensions"]["code"], "SUBGRAPH_REQUEST_ERROR");
+
+        for _ in 0..3 {
+            let response = engine.execute("query { hello }").await;
+            assert_eq!(response.to_string(), r#"{"data":{"hello":"world"}}"#);
+        }
+
+        insta::assert_json_snapshot!(fetcher.drain_requested_hosts(), @r###"
+        [
+          "a1",
+          "a2",
+          "a2",
+          "a2"
+        ]
+        "###);
+    });
+}
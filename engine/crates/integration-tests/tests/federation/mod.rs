@@ -1,12 +1,26 @@
 mod apq;
 mod auth;
 mod basic;
+mod circuit_breaker;
+mod compression;
+mod demand_control;
 mod entity_caching;
+mod entity_requests;
+mod error_masking;
 mod hooks;
+mod incremental_delivery;
 mod introspection;
 mod issues;
+mod load_balancing;
+mod progressive_override;
+mod query_plan;
+mod request_deduplication;
+mod response_caching;
+mod response_headers;
+mod shadow;
 mod subgraph_retries;
 mod subgraphs;
 mod subscriptions;
 mod timeouts;
+mod traffic_split;
 mod trusted_documents;
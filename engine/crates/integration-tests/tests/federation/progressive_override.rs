@@ -0,0 +1,103 @@
+use engine_v2::Engine;
+use integration_tests::{federation::EngineV2Ext, fetch::MockFetch, runtime};
+use serde_json::json;
+
+fn schema(label: &str) -> String {
+    format!(
+        r###"
+        enum join__Graph {{
+          A @join__graph(name: "a", url: "https://a/graphql")
+          B @join__graph(name: "b", url: "https://b/graphql")
+        }}
+
+        type Query {{
+          hello: String @join__field(graph: A) @join__field(graph: B, override: "a", overrideLabel: "{label}")
+        }}
+        "###
+    )
+}
+
+fn mock_fetcher() -> MockFetch {
+    MockFetch::default()
+        .with_responses("a", [json!({"data": {"hello": "from a"}})])
+        .with_responses("b", [json!({"data": {"hello": "from b"}})])
+}
+
+#[test]
+fn percentage_selects_the_subgraph() {
+    runtime().block_on(async move {
+        let fetcher = mock_fetcher();
+        let engine = Engine::builder()
+            .with_federated_sdl(&schema("percent(100)"))
+            .with_mock_fetcher(fetcher.clone())
+            .build()
+            .await;
+
+        let response = engine.execute("query { hello }").await;
+        assert_eq!(response.to_string(), r#"{"data":{"hello":"from b"}}"#);
+
+        let fetcher = mock_fetcher();
+        let engine = Engine::builder()
+            .with_federated_sdl(&schema("percent(0)"))
+            .with_mock_fetcher(fetcher.clone())
+            .build()
+            .await;
+
+        let response = engine.execute("query { hello }").await;
+        assert_eq!(response.to_string(), r#"{"data":{"hello":"from a"}}"#);
+
+        insta::assert_json_snapshot!(fetcher.drain_requested_hosts(), @r###"
+        [
+          "a"
+        ]
+        "###);
+    });
+}
+
+#[test]
+fn custom_label_enabled_by_configuration() {
+    runtime().block_on(async move {
+        let fetcher = mock_fetcher();
+        let engine = Engine::builder()
+            .with_federated_sdl(&schema("use-b"))
+            .with_mock_fetcher(fetcher.clone())
+            .with_toml_config(
+                r#"
+                [progressive_override]
+                enabled_labels = ["use-b"]
+                "#,
+            )
+            .build()
+            .await;
+
+        let response = engine.execute("query { hello }").await;
+        assert_eq!(response.to_string(), r#"{"data":{"hello":"from b"}}"#);
+
+        insta::assert_json_snapshot!(fetcher.drain_requested_hosts(), @r###"
+        [
+          "b"
+        ]
+        "###);
+    });
+}
+
+#[test]
+fn custom_label_disabled_by_default() {
+    runtime().block_on(async move {
+        let fetcher = mock_fetcher();
+        let engine = Engine::builder()
+            .with_federated_sdl(&schema("use-b"))
+            .with_mock_fetcher(fetcher.clone())
+            .build()
+            .await;
+
+        let response = engine.execute("query { hello }").await;
+        assert_eq!(response.to_string(), r#"{"data":{"hello":"from a"}}"#);
+
+        insta::assert_json_snapshot!(fetcher.drain_requested_hosts(), @r###"
+        [
+          "a"
+        ]
+        "###);
+    });
+}
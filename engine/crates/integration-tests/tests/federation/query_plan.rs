@@ -0,0 +1,113 @@
+use engine_v2::Engine;
+use graphql_mocks::FakeGithubSchema;
+use integration_tests::{federation::EngineV2Ext, runtime};
+
+#[test]
+fn query_plan_is_included_with_header() {
+    runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_subgraph(FakeGithubSchema)
+            .with_toml_config(
+                r###"
+                [query_plan]
+                enabled = true
+                "###,
+            )
+            .build()
+            .await;
+
+        let response = engine
+            .execute("query { serverVersion }")
+            .header("x-grafbase-query-plan", "include")
+            .await;
+
+        insta::assert_json_snapshot!(response, @r###"
+        {
+          "data": {
+            "serverVersion": "1"
+          },
+          "extensions": {
+            "queryPlan": {
+              "nodes": [
+                {
+                  "id": 0,
+                  "resolver": "Graphql root field resolver for subgraph 'github'",
+                  "subgraph": "github",
+                  "entity": "Query",
+                  "fields": [
+                    "serverVersion"
+                  ],
+                  "query": "query {\n  serverVersion\n}\n"
+                }
+              ]
+            }
+          }
+        }
+        "###);
+    })
+}
+
+#[test]
+fn plan_only_does_not_execute_the_operation() {
+    runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_subgraph(FakeGithubSchema)
+            .with_toml_config(
+                r###"
+                [query_plan]
+                enabled = true
+                "###,
+            )
+            .build()
+            .await;
+
+        let response = engine
+            .execute("query { serverVersion }")
+            .header("x-grafbase-query-plan", "plan-only")
+            .await;
+
+        insta::assert_json_snapshot!(response, @r###"
+        {
+          "data": null,
+          "extensions": {
+            "queryPlan": {
+              "nodes": [
+                {
+                  "id": 0,
+                  "resolver": "Graphql root field resolver for subgraph 'github'",
+                  "subgraph": "github",
+                  "entity": "Query",
+                  "fields": [
+                    "serverVersion"
+                  ],
+                  "query": "query {\n  serverVersion\n}\n"
+                }
+              ]
+            }
+          }
+        }
+        "###);
+
+        assert!(engine.drain_graphql_requests_sent_to::<FakeGithubSchema>().is_empty());
+    })
+}
+
+#[test]
+fn query_plan_header_is_ignored_unless_enabled() {
+    runtime().block_on(async move {
+        let engine = Engine::builder().with_subgraph(FakeGithubSchema).build().await;
+
+        let response = engine
+            .execute("query { serverVersion }")
+            .header("x-grafbase-query-plan", "plan-only")
+            .await;
+
+        insta::assert_json_snapshot!(response, @r###"
+        {
+          "data": {
+            "serverVersion": "1"
+          }
+        }
+        "###);
+    })
+}
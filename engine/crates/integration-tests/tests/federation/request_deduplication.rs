@@ -0,0 +1,63 @@
+use engine_v2::Engine;
+use graphql_mocks::SlowSchema;
+use integration_tests::{federation::EngineV2Ext, runtime};
+
+#[test]
+fn identical_inflight_queries_share_the_subgraph_request() {
+    runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_subgraph(SlowSchema)
+            .with_toml_config(
+                r#"
+                [subgraphs.slow]
+                deduplicate_requests = true
+                "#,
+            )
+            .build()
+            .await;
+
+        let (first, second) = futures::join!(
+            engine.execute("query { delay(ms: 200) }"),
+            engine.execute("query { delay(ms: 200) }")
+        );
+
+        insta::assert_json_snapshot!(first, @r###"
+        {
+          "data": {
+            "delay": 200
+          }
+        }
+        "###);
+        assert_eq!(first.into_value(), second.into_value());
+        assert_eq!(engine.drain_http_requests_sent_to::<SlowSchema>().len(), 1);
+    });
+}
+
+#[test]
+fn forwarded_headers_are_part_of_the_request_identity() {
+    runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_subgraph(SlowSchema)
+            .with_toml_config(
+                r#"
+                [subgraphs.slow]
+                deduplicate_requests = true
+
+                [[subgraphs.slow.headers]]
+                rule = "forward"
+                name = "x-user"
+                "#,
+            )
+            .build()
+            .await;
+
+        let (first, second) = futures::join!(
+            engine.execute("query { delay(ms: 200) }").header("x-user", "alice"),
+            engine.execute("query { delay(ms: 200) }").header("x-user", "bob")
+        );
+
+        assert_eq!(first.into_data(), serde_json::json!({ "delay": 200 }));
+        assert_eq!(second.into_data(), serde_json::json!({ "delay": 200 }));
+        assert_eq!(engine.drain_http_requests_sent_to::<SlowSchema>().len(), 2);
+    });
+}
@@ -0,0 +1,349 @@
+use engine_v2::Engine;
+use integration_tests::{federation::EngineV2Ext, fetch::MockFetch, runtime};
+
+const SDL: &str = r###"
+    enum join__Graph {
+      A @join__graph(name: "a", url: "https://a/graphql")
+    }
+
+    type Query {
+      hello: String @join__field(graph: A) @cacheControl(maxAge: 60, staleWhileRevalidate: 30)
+      me: String @join__field(graph: A) @cacheControl(maxAge: 60, scope: PRIVATE)
+      uncached: String @join__field(graph: A)
+      fresh: String @join__field(graph: A) @cacheControl(maxAge: 0)
+      expensive: String @join__field(graph: A) @cacheControl(maxAge: 60) @cost(weight: 20)
+    }
+    "###;
+
+#[test]
+fn serve_cached_response() {
+    runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_federated_sdl(SDL)
+            .with_mock_fetcher(
+                MockFetch::default().with_responses("a", [serde_json::json!({"data": {"hello": "Hello"}})]),
+            )
+            .with_toml_config(
+                r#"
+                [response_caching]
+                enabled = true
+                "#,
+            )
+            .build()
+            .await;
+
+        let first = engine.execute("query { hello }").await;
+        let second = engine.execute("query { hello }").await;
+
+        insta::assert_json_snapshot!(second, @r###"
+        {
+          "data": {
+            "hello": "Hello"
+          }
+        }
+        "###);
+        assert_eq!(first.body, second.body);
+
+        for response in [&first, &second] {
+            assert_eq!(
+                response.headers.get("cache-control").unwrap(),
+                "public, max-age=60, stale-while-revalidate=30"
+            );
+        }
+    });
+}
+
+#[test]
+fn vary_on_request_headers() {
+    runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_federated_sdl(SDL)
+            .with_mock_fetcher(MockFetch::default().with_responses(
+                "a",
+                // Responses are popped from the end.
+                [
+                    serde_json::json!({"data": {"hello": "Bonjour"}}),
+                    serde_json::json!({"data": {"hello": "Hello"}}),
+                ],
+            ))
+            .with_toml_config(
+                r#"
+                [response_caching]
+                enabled = true
+                vary_headers = ["accept-language"]
+                "#,
+            )
+            .build()
+            .await;
+
+        let english = engine
+            .execute("query { hello }")
+            .header("accept-language", "en")
+            .await
+            .into_data();
+        let french = engine
+            .execute("query { hello }")
+            .header("accept-language", "fr")
+            .await
+            .into_data();
+        let english_again = engine
+            .execute("query { hello }")
+            .header("accept-language", "en")
+            .await
+            .into_data();
+
+        assert_eq!(english, serde_json::json!({"hello": "Hello"}));
+        assert_eq!(french, serde_json::json!({"hello": "Bonjour"}));
+        assert_eq!(english_again, english);
+    });
+}
+
+#[test]
+fn fields_without_cache_control_are_not_cached() {
+    runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_federated_sdl(SDL)
+            .with_mock_fetcher(MockFetch::default().with_responses(
+                "a",
+                [
+                    serde_json::json!({"data": {"hello": "Hello", "uncached": "2"}}),
+                    serde_json::json!({"data": {"hello": "Hello", "uncached": "1"}}),
+                ],
+            ))
+            .with_toml_config(
+                r#"
+                [response_caching]
+                enabled = true
+                "#,
+            )
+            .build()
+            .await;
+
+        let first = engine.execute("query { hello uncached }").await;
+        let second = engine.execute("query { hello uncached }").await;
+
+        assert_eq!(
+            first.into_data(),
+            serde_json::json!({"hello": "Hello", "uncached": "1"})
+        );
+        assert!(second.headers.get("cache-control").is_none());
+        assert_eq!(
+            second.into_data(),
+            serde_json::json!({"hello": "Hello", "uncached": "2"})
+        );
+    });
+}
+
+#[test]
+fn forwarded_headers_absent_from_vary_headers_disable_caching() {
+    runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_federated_sdl(SDL)
+            .with_mock_fetcher(MockFetch::default().with_responses(
+                "a",
+                [
+                    serde_json::json!({"data": {"hello": "Hello Bob"}}),
+                    serde_json::json!({"data": {"hello": "Hello Alice"}}),
+                ],
+            ))
+            .with_toml_config(
+                r#"
+                [response_caching]
+                enabled = true
+
+                [[headers]]
+                rule = "forward"
+                name = "authorization"
+                "#,
+            )
+            .build()
+            .await;
+
+        let alice = engine.execute("query { hello }").header("authorization", "alice").await;
+        let bob = engine.execute("query { hello }").header("authorization", "bob").await;
+
+        assert!(alice.headers.get("cache-control").is_none());
+        assert_eq!(alice.into_data(), serde_json::json!({"hello": "Hello Alice"}));
+        assert_eq!(bob.into_data(), serde_json::json!({"hello": "Hello Bob"}));
+    });
+}
+
+#[test]
+fn forwarded_headers_in_vary_headers_are_private() {
+    runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_federated_sdl(SDL)
+            .with_mock_fetcher(MockFetch::default().with_responses(
+                "a",
+                [
+                    serde_json::json!({"data": {"hello": "Hello Bob"}}),
+                    serde_json::json!({"data": {"hello": "Hello Alice"}}),
+                ],
+            ))
+            .with_toml_config(
+                r#"
+                [response_caching]
+                enabled = true
+                vary_headers = ["authorization"]
+
+                [[headers]]
+                rule = "forward"
+                name = "authorization"
+                "#,
+            )
+            .build()
+            .await;
+
+        let alice = engine.execute("query { hello }").header("authorization", "alice").await;
+        let bob = engine.execute("query { hello }").header("authorization", "bob").await;
+        let alice_again = engine.execute("query { hello }").header("authorization", "alice").await;
+
+        assert_eq!(
+            alice_again.headers.get("cache-control").unwrap(),
+            "private, max-age=60, stale-while-revalidate=30"
+        );
+        assert_eq!(alice.into_data(), serde_json::json!({"hello": "Hello Alice"}));
+        assert_eq!(bob.into_data(), serde_json::json!({"hello": "Hello Bob"}));
+        assert_eq!(alice_again.into_data(), serde_json::json!({"hello": "Hello Alice"}));
+    });
+}
+
+#[test]
+fn private_responses_are_not_cached_without_access_token() {
+    runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_federated_sdl(SDL)
+            .with_mock_fetcher(MockFetch::default().with_responses(
+                "a",
+                [
+                    serde_json::json!({"data": {"me": "2"}}),
+                    serde_json::json!({"data": {"me": "1"}}),
+                ],
+            ))
+            .with_toml_config(
+                r#"
+                [response_caching]
+                enabled = true
+                "#,
+            )
+            .build()
+            .await;
+
+        let first = engine.execute("query { me }").await;
+        let second = engine.execute("query { me }").await;
+
+        assert!(first.headers.get("cache-control").is_none());
+        assert_eq!(first.into_data(), serde_json::json!({"me": "1"}));
+        assert_eq!(second.into_data(), serde_json::json!({"me": "2"}));
+    });
+}
+
+#[test]
+fn zero_max_age_responses_are_not_cached() {
+    runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_federated_sdl(SDL)
+            .with_mock_fetcher(MockFetch::default().with_responses(
+                "a",
+                [
+                    serde_json::json!({"data": {"fresh": "2"}}),
+                    serde_json::json!({"data": {"fresh": "1"}}),
+                ],
+            ))
+            .with_toml_config(
+                r#"
+                [response_caching]
+                enabled = true
+                "#,
+            )
+            .build()
+            .await;
+
+        let first = engine.execute("query { fresh }").await;
+        let second = engine.execute("query { fresh }").await;
+
+        assert_eq!(first.into_data(), serde_json::json!({"fresh": "1"}));
+        assert_eq!(second.into_data(), serde_json::json!({"fresh": "2"}));
+    });
+}
+
+#[test]
+fn cached_responses_include_the_estimated_cost() {
+    runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_federated_sdl(SDL)
+            .with_mock_fetcher(
+                MockFetch::default().with_responses("a", [serde_json::json!({"data": {"expensive": "yes"}})]),
+            )
+            .with_toml_config(
+                r#"
+                [response_caching]
+                enabled = true
+
+                [demand_control]
+                mode = "measure"
+                max_cost = 100
+                "#,
+            )
+            .build()
+            .await;
+
+        let first = engine.execute("query { expensive }").await;
+        let second = engine.execute("query { expensive }").await;
+
+        insta::assert_json_snapshot!(second, @r###"
+        {
+          "data": {
+            "expensive": "yes"
+          },
+          "extensions": {
+            "cost": {
+              "estimated": 20
+            }
+          }
+        }
+        "###);
+        assert_eq!(first.body, second.body);
+    });
+}
+
+#[test]
+fn streaming_responses_are_cached() {
+    runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_federated_sdl(SDL)
+            .with_mock_fetcher(
+                MockFetch::default().with_responses("a", [serde_json::json!({"data": {"hello": "Hello"}})]),
+            )
+            .with_toml_config(
+                r#"
+                [response_caching]
+                enabled = true
+                "#,
+            )
+            .build()
+            .await;
+
+        let first = engine
+            .execute("query { hello }")
+            .into_multipart_stream()
+            .collect::<Vec<_>>()
+            .await;
+        let second = engine
+            .execute("query { hello }")
+            .into_multipart_stream()
+            .collect::<Vec<_>>()
+            .await;
+
+        insta::assert_json_snapshot!(second, @r###"
+        [
+          {
+            "data": {
+              "hello": "Hello"
+            }
+          }
+        ]
+        "###);
+        assert_eq!(first, second);
+    });
+}
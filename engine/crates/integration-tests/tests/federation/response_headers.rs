@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use engine_v2::Engine;
+use headers::HeaderMapExt;
+use integration_tests::{
+    federation::EngineV2Ext,
+    fetch::{http_response, MockFetch},
+    runtime,
+};
+
+const TWO_SUBGRAPHS_SDL: &str = r###"
+    enum join__Graph {
+      A @join__graph(name: "a", url: "https://a/graphql")
+      B @join__graph(name: "b", url: "https://b/graphql")
+    }
+
+    type Query {
+      hello: String @join__field(graph: A)
+      world: String @join__field(graph: B)
+    }
+    "###;
+
+fn fetcher() -> MockFetch {
+    MockFetch::default()
+        .with_http_responses(
+            "a",
+            [http_response(
+                http::StatusCode::OK,
+                &[
+                    ("cache-control", "public, max-age=60"),
+                    ("x-version", "1.2.0"),
+                    ("x-served-by", "a"),
+                    ("x-internal", "secret"),
+                ],
+                r#"{"data":{"hello":"Hello"}}"#,
+            )],
+        )
+        .with_http_responses(
+            "b",
+            [http_response(
+                http::StatusCode::OK,
+                &[
+                    ("cache-control", "max-age=30, must-revalidate"),
+                    ("x-b-version", "3.0.1"),
+                    ("x-served-by", "b"),
+                ],
+                r#"{"data":{"world":"World"}}"#,
+            )],
+        )
+}
+
+#[test]
+fn forward_and_rename_response_headers() {
+    runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_federated_sdl(TWO_SUBGRAPHS_SDL)
+            .with_mock_fetcher(fetcher())
+            .with_toml_config(
+                r#"
+                [[subgraphs.a.response_headers]]
+                rule = "forward"
+                pattern = "^x-"
+
+                [[subgraphs.a.response_headers]]
+                rule = "remove"
+                name = "x-internal"
+
+                [[subgraphs.a.response_headers]]
+                rule = "insert"
+                name = "x-gateway"
+                value = "grafbase"
+
+                [[subgraphs.b.response_headers]]
+                rule = "rename"
+                name = "x-b-version"
+                rename = "x-world-version"
+                "#,
+            )
+            .build()
+            .await;
+
+        let response = engine.execute("query { hello world }").await;
+
+        insta::assert_json_snapshot!(response, @r###"
+        {
+          "data": {
+            "hello": "Hello",
+            "world": "World"
+          }
+        }
+        "###);
+
+        let headers = &response.headers;
+        assert_eq!(headers.get("x-version").unwrap(), "1.2.0");
+        assert_eq!(headers.get("x-served-by").unwrap(), "a");
+        assert_eq!(headers.get("x-gateway").unwrap(), "grafbase");
+        assert_eq!(headers.get("x-world-version").unwrap(), "3.0.1");
+        assert!(headers.get("x-internal").is_none());
+        assert!(headers.get("x-b-version").is_none());
+        assert!(headers.get("cache-control").is_none());
+    });
+}
+
+#[test]
+fn merge_response_headers_from_multiple_subgraphs() {
+    runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_federated_sdl(TWO_SUBGRAPHS_SDL)
+            .with_mock_fetcher(fetcher())
+            .with_toml_config(
+                r#"
+                [[subgraphs.a.response_headers]]
+                rule = "forward"
+                name = "cache-control"
+                merge = "most_restrictive_cache_control"
+
+                [[subgraphs.a.response_headers]]
+                rule = "forward"
+                name = "x-served-by"
+                merge = "append"
+
+                [[subgraphs.b.response_headers]]
+                rule = "forward"
+                name = "cache-control"
+                merge = "most_restrictive_cache_control"
+
+                [[subgraphs.b.response_headers]]
+                rule = "forward"
+                name = "x-served-by"
+                merge = "append"
+                "#,
+            )
+            .build()
+            .await;
+
+        let response = engine.execute("query { hello world }").await;
+
+        assert_eq!(
+            response.headers.typed_get::<headers::CacheControl>(),
+            Some(
+                headers::CacheControl::new()
+                    .with_must_revalidate()
+                    .with_max_age(Duration::from_secs(30))
+            )
+        );
+
+        let mut served_by = response
+            .headers
+            .get_all("x-served-by")
+            .iter()
+            .map(|value| value.to_str().unwrap())
+            .collect::<Vec<_>>();
+        served_by.sort_unstable();
+        assert_eq!(served_by, ["a", "b"]);
+    });
+}
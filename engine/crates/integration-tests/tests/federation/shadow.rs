@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use engine_v2::Engine;
+use integration_tests::{federation::EngineV2Ext, fetch::MockFetch, runtime};
+use serde_json::json;
+
+const SCHEMA: &str = r###"
+    enum join__Graph {
+      A @join__graph(name: "a", url: "https://a/graphql")
+    }
+
+    type Query {
+      hello: String @join__field(graph: A)
+    }
+
+    type Mutation {
+      greet: String @join__field(graph: A)
+    }
+    "###;
+
+const CONFIG: &str = r#"
+    [subgraphs.a.shadow]
+    url = "https://a-next/graphql"
+    sampling = 1.0
+"#;
+
+/// Shadow requests are sent in the background, after the client response.
+async fn wait_for_requests(fetcher: &MockFetch, count: usize) -> Vec<String> {
+    let mut hosts = Vec::new();
+    for _ in 0..100 {
+        hosts.extend(fetcher.drain_received_requests().map(|(host, _)| host));
+        if hosts.len() >= count {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    hosts.sort();
+    hosts
+}
+
+#[test]
+fn queries_are_mirrored() {
+    runtime().block_on(async move {
+        let fetcher = MockFetch::default()
+            .with_responses("a", [json!({"data": {"hello": "world"}})])
+            .with_responses("a-next", [json!({"data": {"hello": "other world"}})]);
+        let engine = Engine::builder()
+            .with_federated_sdl(SCHEMA)
+            .with_mock_fetcher(fetcher.clone())
+            .with_toml_config(CONFIG)
+            .build()
+            .await;
+
+        let response = engine.execute("query { hello }").await;
+        assert_eq!(response.to_string(), r#"{"data":{"hello":"world"}}"#);
+
+        insta::assert_json_snapshot!(wait_for_requests(&fetcher, 2).await, @r###"
+        [
+          "a",
+          "a-next"
+        ]
+        "###);
+    });
+}
+
+#[test]
+fn mutations_are_never_mirrored() {
+    runtime().block_on(async move {
+        let fetcher = MockFetch::default()
+            .with_responses("a", [json!({"data": {"greet": "hi"}})])
+            .with_responses("a-next", [json!({"data": {"greet": "hi"}})]);
+        let engine = Engine::builder()
+            .with_federated_sdl(SCHEMA)
+            .with_mock_fetcher(fetcher.clone())
+            .with_toml_config(CONFIG)
+            .build()
+            .await;
+
+        let response = engine.execute("mutation { greet }").await;
+        assert_eq!(response.to_string(), r#"{"data":{"greet":"hi"}}"#);
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        insta::assert_json_snapshot!(wait_for_requests(&fetcher, 1).await, @r###"
+        [
+          "a"
+        ]
+        "###);
+    });
+}
@@ -1,6 +1,10 @@
 use engine_v2::Engine;
 use graphql_mocks::{MockGraphQlServer, StateMutationSchema, Subgraph};
-use integration_tests::{federation::EngineV2Ext, runtime};
+use integration_tests::{
+    federation::EngineV2Ext,
+    fetch::{hello_world, http_response, MockFetch},
+    runtime,
+};
 
 struct Stateful;
 
@@ -95,3 +99,100 @@ fn subgraph_retries_mutations_enabled() {
         });
     });
 }
+
+const SINGLE_SUBGRAPH_SDL: &str = r###"
+    enum join__Graph {
+      A @join__graph(name: "a", url: "https://a/graphql")
+    }
+
+    type Query {
+      hello: String @join__field(graph: A)
+    }
+    "###;
+
+#[test]
+fn subgraph_retries_on_service_unavailable() {
+    runtime().block_on(async move {
+        let fetcher = MockFetch::default().with_http_responses(
+            "a",
+            [
+                http_response(
+                    http::StatusCode::SERVICE_UNAVAILABLE,
+                    &[("retry-after", "0")],
+                    "<html>Service Unavailable</html>",
+                ),
+                hello_world(),
+            ],
+        );
+        let engine = Engine::builder()
+            .with_federated_sdl(SINGLE_SUBGRAPH_SDL)
+            .with_mock_fetcher(fetcher.clone())
+            .with_toml_config(
+                r#"
+                [subgraphs.a.retry]
+                enabled = true
+                "#,
+            )
+            .build()
+            .await;
+
+        let response = engine.execute("query { hello }").await;
+
+        insta::assert_json_snapshot!(response, @r###"
+        {
+          "data": {
+            "hello": "world"
+          }
+        }
+        "###);
+
+        assert_eq!(fetcher.drain_received_requests().count(), 2);
+    });
+}
+
+#[test]
+fn subgraph_does_not_retry_client_errors() {
+    runtime().block_on(async move {
+        let fetcher = MockFetch::default().with_http_responses(
+            "a",
+            [
+                http_response(http::StatusCode::BAD_REQUEST, &[], "<html>Bad Request</html>"),
+                hello_world(),
+            ],
+        );
+        let engine = Engine::builder()
+            .with_federated_sdl(SINGLE_SUBGRAPH_SDL)
+            .with_mock_fetcher(fetcher.clone())
+            .with_toml_config(
+                r#"
+                [subgraphs.a.retry]
+                enabled = true
+                "#,
+            )
+            .build()
+            .await;
+
+        let response = engine.execute("query { hello }").await;
+
+        insta::assert_json_snapshot!(response, @r###"
+        {
+          "data": {
+            "hello": null
+          },
+          "errors": [
+            {
+              "message": "Subgraph 'a' responded with HTTP status 400 Bad Request",
+              "path": [
+                "hello"
+              ],
+              "extensions": {
+                "code": "SUBGRAPH_REQUEST_ERROR"
+              }
+            }
+          ]
+        }
+        "###);
+
+        assert_eq!(fetcher.drain_received_requests().count(), 1);
+    });
+}
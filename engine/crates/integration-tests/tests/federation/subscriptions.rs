@@ -1,8 +1,18 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
 use engine_v2::Engine;
+use futures::{stream::BoxStream, StreamExt};
 use graphql_mocks::{
     FederatedAccountsSchema, FederatedInventorySchema, FederatedProductsSchema, FederatedReviewsSchema,
 };
 use integration_tests::{federation::EngineV2Ext, runtime};
+use runtime::fetch::{FetchError, FetchRequest, FetchResponse, FetchResult, FetcherInner, GraphqlRequest};
 
 #[test]
 fn single_subgraph_subscription() {
@@ -54,6 +64,205 @@ fn single_subgraph_subscription() {
     "###);
 }
 
+#[test]
+fn single_subgraph_subscription_over_sse() {
+    let response = runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_subgraph(FederatedProductsSchema)
+            .with_toml_config(
+                r#"
+                [subgraphs.products]
+                subscription_protocol = "sse"
+                "#,
+            )
+            .build()
+            .await;
+
+        engine
+            .execute(
+                r"
+                subscription {
+                    newProducts {
+                        upc
+                        name
+                        price
+                    }
+                }
+                ",
+            )
+            .into_multipart_stream()
+            .collect::<Vec<_>>()
+            .await
+    });
+
+    insta::assert_json_snapshot!(response, @r###"
+    [
+      {
+        "data": {
+          "newProducts": {
+            "upc": "top-4",
+            "name": "Jeans",
+            "price": 44
+          }
+        }
+      },
+      {
+        "data": {
+          "newProducts": {
+            "upc": "top-5",
+            "name": "Pink Jeans",
+            "price": 55
+          }
+        }
+      }
+    ]
+    "###);
+}
+
+#[test]
+fn single_subgraph_subscription_over_multipart() {
+    let response = runtime().block_on(async move {
+        let engine = Engine::builder()
+            .with_subgraph(FederatedProductsSchema)
+            .with_toml_config(
+                r#"
+                [subgraphs.products]
+                subscription_protocol = "multipart"
+                "#,
+            )
+            .build()
+            .await;
+
+        engine
+            .execute(
+                r"
+                subscription {
+                    newProducts {
+                        upc
+                        name
+                        price
+                    }
+                }
+                ",
+            )
+            .into_multipart_stream()
+            .collect::<Vec<_>>()
+            .await
+    });
+
+    insta::assert_json_snapshot!(response, @r###"
+    [
+      {
+        "data": {
+          "newProducts": {
+            "upc": "top-4",
+            "name": "Jeans",
+            "price": 44
+          }
+        }
+      },
+      {
+        "data": {
+          "newProducts": {
+            "upc": "top-5",
+            "name": "Pink Jeans",
+            "price": 55
+          }
+        }
+      }
+    ]
+    "###);
+}
+
+#[test]
+fn identical_subscriptions_share_the_upstream_subscription() {
+    let fetcher = CountingSubscriptionFetcher::default();
+
+    let (first, second) = runtime().block_on({
+        let fetcher = fetcher.clone();
+        async move {
+            let engine = Engine::builder()
+                .with_subgraph(FederatedProductsSchema)
+                .with_toml_config(
+                    r#"
+                    [subgraphs.products]
+                    deduplicate_subscriptions = true
+                    "#,
+                )
+                .with_mock_fetcher(fetcher)
+                .build()
+                .await;
+
+            let query = r"
+                subscription {
+                    newProducts {
+                        upc
+                        name
+                    }
+                }
+            ";
+
+            futures::join!(
+                engine.execute(query).into_multipart_stream().collect::<Vec<_>>(),
+                engine.execute(query).into_multipart_stream().collect::<Vec<_>>()
+            )
+        }
+    });
+
+    assert_eq!(fetcher.subscriptions.load(Ordering::Relaxed), 1);
+    assert_eq!(first, second);
+    insta::assert_json_snapshot!(first, @r###"
+    [
+      {
+        "data": {
+          "newProducts": {
+            "upc": "top-4",
+            "name": "Jeans"
+          }
+        }
+      },
+      {
+        "data": {
+          "newProducts": {
+            "upc": "top-5",
+            "name": "Pink Jeans"
+          }
+        }
+      }
+    ]
+    "###);
+}
+
+/// Counts the upstream subscriptions, delaying the events so that all clients have the time to
+/// subscribe.
+#[derive(Clone, Default)]
+struct CountingSubscriptionFetcher {
+    subscriptions: Arc<AtomicUsize>,
+}
+
+#[async_trait::async_trait]
+impl FetcherInner for CountingSubscriptionFetcher {
+    async fn post(&self, _request: &FetchRequest<'_>) -> FetchResult<FetchResponse> {
+        unreachable!()
+    }
+
+    async fn stream(
+        &self,
+        _request: GraphqlRequest<'_>,
+    ) -> FetchResult<BoxStream<'static, Result<serde_json::Value, FetchError>>> {
+        self.subscriptions.fetch_add(1, Ordering::Relaxed);
+
+        let events = [
+            serde_json::json!({"data": {"newProducts": {"upc": "top-4", "name": "Jeans"}}}),
+            serde_json::json!({"data": {"newProducts": {"upc": "top-5", "name": "Pink Jeans"}}}),
+        ];
+
+        Ok(futures::stream::once(tokio::time::sleep(Duration::from_millis(100)))
+            .flat_map(move |_| futures::stream::iter(events.clone().map(Ok)))
+            .boxed())
+    }
+}
+
 #[test]
 fn actual_federated_subscription() {
     let response = runtime().block_on(async move {
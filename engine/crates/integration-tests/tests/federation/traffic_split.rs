@@ -0,0 +1,181 @@
+use engine_v2::Engine;
+use integration_tests::{
+    federation::EngineV2Ext,
+    fetch::{hello_world, MockFetch},
+    runtime,
+};
+use serde_json::json;
+
+const SCHEMA: &str = r###"
+    enum join__Graph {
+      A @join__graph(name: "a", url: "https://a/graphql")
+    }
+
+    type Query {
+      hello: String @join__field(graph: A)
+    }
+    "###;
+
+#[test]
+fn header_routes_to_variant() {
+    runtime().block_on(async move {
+        let fetcher = MockFetch::default()
+            .with_http_responses("a", [hello_world()])
+            .with_http_responses("a-canary", [hello_world()]);
+        let engine = Engine::builder()
+            .with_federated_sdl(SCHEMA)
+            .with_mock_fetcher(fetcher.clone())
+            .with_toml_config(
+                r#"
+                [[subgraphs.a.traffic_split.variants]]
+                name = "canary"
+                url = "https://a-canary/graphql"
+                header = { name = "x-version", value = "canary" }
+                "#,
+            )
+            .build()
+            .await;
+
+        let response = engine.execute("query { hello }").await;
+        assert_eq!(response.to_string(), r#"{"data":{"hello":"world"}}"#);
+
+        let response = engine.execute("query { hello }").header("x-version", "canary").await;
+        assert_eq!(response.to_string(), r#"{"data":{"hello":"world"}}"#);
+
+        insta::assert_json_snapshot!(fetcher.drain_requested_hosts(), @r###"
+        [
+          "a",
+          "a-canary"
+        ]
+        "###);
+    });
+}
+
+#[test]
+fn percentage_routes_to_variant() {
+    runtime().block_on(async move {
+        let fetcher = MockFetch::default().with_http_responses("a-canary", [hello_world(), hello_world()]);
+        let engine = Engine::builder()
+            .with_federated_sdl(SCHEMA)
+            .with_mock_fetcher(fetcher.clone())
+            .with_toml_config(
+                r#"
+                [[subgraphs.a.traffic_split.variants]]
+                name = "canary"
+                url = "https://a-canary/graphql"
+                percentage = 100
+                "#,
+            )
+            .build()
+            .await;
+
+        for _ in 0..2 {
+            let response = engine.execute("query { hello }").await;
+            assert_eq!(response.to_string(), r#"{"data":{"hello":"world"}}"#);
+        }
+
+        insta::assert_json_snapshot!(fetcher.drain_requested_hosts(), @r###"
+        [
+          "a-canary",
+          "a-canary"
+        ]
+        "###);
+    });
+}
+
+#[test]
+fn sticky_header_keeps_the_same_variant() {
+    runtime().block_on(async move {
+        let fetcher = MockFetch::default()
+            .with_http_responses("a", [hello_world(), hello_world(), hello_world(), hello_world()])
+            .with_http_responses("a-canary", [hello_world(), hello_world(), hello_world(), hello_world()]);
+        let engine = Engine::builder()
+            .with_federated_sdl(SCHEMA)
+            .with_mock_fetcher(fetcher.clone())
+            .with_toml_config(
+                r#"
+                [subgraphs.a.traffic_split]
+                sticky_header = "x-user-id"
+
+                [[subgraphs.a.traffic_split.variants]]
+                name = "canary"
+                url = "https://a-canary/graphql"
+                percentage = 50
+                "#,
+            )
+            .build()
+            .await;
+
+        for _ in 0..4 {
+            let response = engine.execute("query { hello }").header("x-user-id", "alice").await;
+            assert_eq!(response.to_string(), r#"{"data":{"hello":"world"}}"#);
+        }
+
+        let hosts = fetcher.drain_requested_hosts();
+        assert_eq!(hosts.len(), 4);
+        assert!(hosts.iter().all(|host| host == &hosts[0]), "{hosts:?}");
+    });
+}
+
+const ENTITIES_SCHEMA: &str = r###"
+    enum join__Graph {
+      A @join__graph(name: "a", url: "https://a/graphql")
+      B @join__graph(name: "b", url: "https://b/graphql")
+    }
+
+    type Product @join__type(graph: A, key: "upc") @join__type(graph: B, key: "upc") {
+      upc: String!
+      name: String @join__field(graph: B)
+    }
+
+    type Query @join__type(graph: A) @join__type(graph: B) {
+      products: [Product!]! @join__field(graph: A)
+    }
+    "###;
+
+#[test]
+fn entity_batches_are_routed_to_variant() {
+    runtime().block_on(async move {
+        let fetcher = MockFetch::default()
+            .with_responses("a", [json!({"data": {"products": [{"upc": "1"}, {"upc": "2"}]}})])
+            .with_responses(
+                "b-canary",
+                [
+                    json!({"data": {"_entities": [{"name": "Chair"}]}}),
+                    json!({"data": {"_entities": [{"name": "Chair"}]}}),
+                ],
+            );
+        let engine = Engine::builder()
+            .with_federated_sdl(ENTITIES_SCHEMA)
+            .with_mock_fetcher(fetcher.clone())
+            .with_toml_config(
+                r#"
+                [subgraphs.b]
+                max_entities_per_request = 1
+
+                [[subgraphs.b.traffic_split.variants]]
+                name = "canary"
+                url = "https://b-canary/graphql"
+                percentage = 100
+                "#,
+            )
+            .build()
+            .await;
+
+        let response = engine.execute("query { products { upc name } }").await;
+        assert_eq!(
+            response.to_string(),
+            r#"{"data":{"products":[{"upc":"1","name":"Chair"},{"upc":"2","name":"Chair"}]}}"#
+        );
+
+        let mut hosts = fetcher.drain_requested_hosts();
+        hosts.sort();
+        insta::assert_json_snapshot!(hosts, @r###"
+        [
+          "a",
+          "b-canary",
+          "b-canary"
+        ]
+        "###);
+    });
+}
@@ -6,7 +6,9 @@ use std::{collections::BTreeMap, path::PathBuf};
 use crate::{rules::auth_directive::v2::AuthV2Directive, GlobalCacheRules};
 use registry_v2::{ConnectorHeaderValue, OperationLimits};
 
-use self::header::{NameOrPattern, SubgraphHeaderForward, SubgraphHeaderInsert, SubgraphHeaderRule};
+use self::header::{
+    NameOrPattern, SubgraphHeaderForward, SubgraphHeaderInsert, SubgraphHeaderRule, SubgraphResponseHeaderRule,
+};
 
 /// Configuration for a federated graph
 #[derive(Clone, Debug, Default)]
@@ -20,6 +22,11 @@ pub struct FederatedGraphConfig {
     pub rate_limit: Option<RateLimitConfig>,
     pub timeout: Option<Duration>,
     pub entity_caching: EntityCachingConfig,
+    pub query_plan: QueryPlanConfig,
+    pub response_caching: ResponseCachingConfig,
+    pub error_masking: ErrorMaskingConfig,
+    pub demand_control: Option<DemandControlConfig>,
+    pub progressive_override: ProgressiveOverrideConfig,
 }
 
 /// Configuration for a subgraph of the current federated graph
@@ -39,9 +46,27 @@ pub struct SubgraphConfig {
     /// This will default to the normal URL if not present.
     pub websocket_url: Option<String>,
 
+    /// Transport used for subscriptions to this subgraph
+    pub subscription_protocol: SubscriptionProtocol,
+
+    /// Time without any message or heartbeat after which subscriptions reconnect
+    pub subscription_heartbeat_timeout: Option<Duration>,
+
+    /// Consecutive reconnection attempts of a subscription before giving up
+    pub subscription_max_reconnect_attempts: Option<u32>,
+
+    /// Whether identical subscriptions share a single upstream subscription
+    pub deduplicate_subscriptions: bool,
+
+    /// Whether identical in-flight queries share a single subgraph request
+    pub deduplicate_requests: bool,
+
     /// Rules for passing headers forward to the subgraph
     pub header_rules: Vec<SubgraphHeaderRule>,
 
+    /// Rules for passing subgraph response headers back to the client
+    pub response_header_rules: Vec<SubgraphResponseHeaderRule>,
+
     /// Configuration to enforce rate limiting on subgraph requests
     pub rate_limit: Option<GraphRateLimit>,
 
@@ -53,6 +78,230 @@ pub struct SubgraphConfig {
 
     /// Optional entity caching config for this subgraph.
     pub entity_caching: Option<EntityCachingConfig>,
+
+    /// Maximum number of entities in a single `_entities` request
+    pub max_entities_per_request: Option<usize>,
+
+    /// Error masking for this subgraph, replacing the global one
+    pub error_masking: Option<ErrorMaskingConfig>,
+
+    /// Circuit breaker protecting this subgraph
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+
+    /// Multiple URLs for this subgraph, replacing the one of the federated graph
+    pub load_balancing: Option<LoadBalancingConfig>,
+
+    /// Alternative versions of the subgraph receiving part of the requests
+    pub traffic_split: Option<TrafficSplitConfig>,
+
+    /// Candidate subgraph receiving a copy of a sample of the queries
+    pub shadow: Option<ShadowConfig>,
+
+    /// Compression of the requests and responses
+    pub compression: Option<CompressionConfig>,
+
+    /// Connection pool settings of the HTTP client
+    pub connection_pool: Option<ConnectionPoolConfig>,
+}
+
+/// Requests spread over multiple URLs of a subgraph
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub struct LoadBalancingConfig {
+    pub strategy: LoadBalancingStrategy,
+    pub urls: Vec<UpstreamUrl>,
+    /// Number of consecutive failures ejecting a URL
+    pub outlier_ejection_consecutive_failures: Option<u32>,
+    /// How long an URL stays ejected
+    pub outlier_ejection_duration: Option<Duration>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LoadBalancingStrategy {
+    #[default]
+    RoundRobin,
+    LeastOutstanding,
+    Weighted,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct UpstreamUrl {
+    pub url: String,
+    pub weight: u32,
+    pub websocket_url: Option<String>,
+}
+
+impl From<gateway_config::LoadBalancingConfig> for LoadBalancingConfig {
+    fn from(value: gateway_config::LoadBalancingConfig) -> Self {
+        Self {
+            strategy: match value.strategy {
+                gateway_config::LoadBalancingStrategy::RoundRobin => LoadBalancingStrategy::RoundRobin,
+                gateway_config::LoadBalancingStrategy::LeastOutstanding => LoadBalancingStrategy::LeastOutstanding,
+                gateway_config::LoadBalancingStrategy::Weighted => LoadBalancingStrategy::Weighted,
+            },
+            urls: value
+                .urls
+                .into_iter()
+                .map(|upstream| UpstreamUrl {
+                    url: upstream.url.to_string(),
+                    weight: upstream.weight.get(),
+                    websocket_url: upstream.websocket_url.map(|url| url.to_string()),
+                })
+                .collect(),
+            outlier_ejection_consecutive_failures: value.outlier_ejection.consecutive_failures.map(|count| count.get()),
+            outlier_ejection_duration: value.outlier_ejection.duration,
+        }
+    }
+}
+
+/// Requests routed to alternative versions of a subgraph
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub struct TrafficSplitConfig {
+    /// Header keying the percentage based assignment
+    pub sticky_header: Option<String>,
+    pub variants: Vec<TrafficVariant>,
+}
+
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub struct TrafficVariant {
+    pub name: String,
+    pub url: String,
+    /// Share of the requests, between 0 and 100
+    pub percentage: f32,
+    /// Header name and value routing requests to this variant
+    pub header: Option<(String, String)>,
+    /// Claim path and value routing requests to this variant
+    pub claim: Option<(String, String)>,
+}
+
+impl From<gateway_config::TrafficSplitConfig> for TrafficSplitConfig {
+    fn from(value: gateway_config::TrafficSplitConfig) -> Self {
+        Self {
+            sticky_header: value.sticky_header,
+            variants: value
+                .variants
+                .into_iter()
+                .map(|variant| TrafficVariant {
+                    name: variant.name,
+                    url: variant.url.to_string(),
+                    percentage: variant.percentage,
+                    header: variant.header.map(|header| (header.name, header.value)),
+                    claim: variant.claim.map(|claim| (claim.name, claim.value)),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Shadow traffic mirrored to a candidate subgraph
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub struct ShadowConfig {
+    pub url: String,
+    /// Fraction of the queries mirrored, between 0 and 1
+    pub sampling: f64,
+}
+
+impl From<gateway_config::ShadowConfig> for ShadowConfig {
+    fn from(value: gateway_config::ShadowConfig) -> Self {
+        Self {
+            url: value.url.to_string(),
+            sampling: value.sampling,
+        }
+    }
+}
+
+/// Compression of the requests sent to a subgraph and of its responses
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CompressionConfig {
+    pub request: Option<CompressionAlgorithm>,
+    pub response: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CompressionAlgorithm {
+    Gzip,
+    Brotli,
+    Zstd,
+}
+
+impl From<gateway_config::CompressionConfig> for CompressionConfig {
+    fn from(value: gateway_config::CompressionConfig) -> Self {
+        Self {
+            request: value.request.map(|algorithm| match algorithm {
+                gateway_config::CompressionAlgorithm::Gzip => CompressionAlgorithm::Gzip,
+                gateway_config::CompressionAlgorithm::Brotli => CompressionAlgorithm::Brotli,
+                gateway_config::CompressionAlgorithm::Zstd => CompressionAlgorithm::Zstd,
+            }),
+            response: value.response,
+        }
+    }
+}
+
+/// Connection pool of the HTTP client of a subgraph
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ConnectionPoolConfig {
+    pub max_idle_per_host: Option<usize>,
+    pub idle_timeout: Option<Duration>,
+    pub tcp_keepalive: Option<Duration>,
+    pub http2_prior_knowledge: bool,
+    pub connect_timeout: Option<Duration>,
+}
+
+impl From<gateway_config::ConnectionPoolConfig> for ConnectionPoolConfig {
+    fn from(value: gateway_config::ConnectionPoolConfig) -> Self {
+        Self {
+            max_idle_per_host: value.max_idle_per_host.map(|max| max.get()),
+            idle_timeout: value.idle_timeout,
+            tcp_keepalive: value.tcp_keepalive,
+            http2_prior_knowledge: value.http2_prior_knowledge,
+            connect_timeout: value.connect_timeout,
+        }
+    }
+}
+
+/// Thresholds and cooldown of a subgraph circuit breaker
+#[derive(Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct CircuitBreakerConfig {
+    /// Number of consecutive failures opening the circuit
+    pub consecutive_failures: Option<u32>,
+    /// Fraction of failed requests within the window opening the circuit
+    pub failure_rate: Option<f32>,
+    /// Window of the failure rate
+    pub window: Option<Duration>,
+    /// Minimum number of requests within the window for the failure rate to apply
+    pub minimum_requests: Option<u32>,
+    /// How long the circuit stays open
+    pub cooldown: Option<Duration>,
+}
+
+impl From<gateway_config::CircuitBreakerConfig> for CircuitBreakerConfig {
+    fn from(value: gateway_config::CircuitBreakerConfig) -> Self {
+        Self {
+            consecutive_failures: value.consecutive_failures.map(|count| count.get()),
+            failure_rate: value.failure_rate,
+            window: value.window,
+            minimum_requests: value.minimum_requests,
+            cooldown: value.cooldown,
+        }
+    }
+}
+
+/// Transport used for subscriptions to a subgraph
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SubscriptionProtocol {
+    #[default]
+    Websocket,
+    ServerSentEvents,
+    Multipart,
+}
+
+impl From<gateway_config::SubscriptionProtocol> for SubscriptionProtocol {
+    fn from(value: gateway_config::SubscriptionProtocol) -> Self {
+        match value {
+            gateway_config::SubscriptionProtocol::Websocket => SubscriptionProtocol::Websocket,
+            gateway_config::SubscriptionProtocol::ServerSentEvents => SubscriptionProtocol::ServerSentEvents,
+            gateway_config::SubscriptionProtocol::Multipart => SubscriptionProtocol::Multipart,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
@@ -130,6 +379,153 @@ impl From<(String, ConnectorHeaderValue)> for SubgraphHeaderRule {
     }
 }
 
+/// How subgraph errors are exposed to clients
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ErrorMaskingConfig {
+    pub mode: ErrorMaskingMode,
+    /// Message replacing the one of masked errors
+    pub message: Option<String>,
+    /// Error codes passed through in the allowlist mode
+    pub allowed_codes: Vec<String>,
+    /// Subgraph error codes mapped to the error codes sent to the client
+    pub code_mappings: BTreeMap<String, PublicErrorCode>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ErrorMaskingMode {
+    #[default]
+    PassThrough,
+    Mask,
+    Allowlist,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PublicErrorCode {
+    BadRequest,
+    Unauthenticated,
+    Unauthorized,
+    RateLimited,
+    GatewayTimeout,
+    InternalServerError,
+    SubgraphError,
+}
+
+impl From<gateway_config::ErrorMaskingConfig> for ErrorMaskingConfig {
+    fn from(value: gateway_config::ErrorMaskingConfig) -> Self {
+        Self {
+            mode: match value.mode {
+                gateway_config::ErrorMaskingMode::PassThrough => ErrorMaskingMode::PassThrough,
+                gateway_config::ErrorMaskingMode::Mask => ErrorMaskingMode::Mask,
+                gateway_config::ErrorMaskingMode::Allowlist => ErrorMaskingMode::Allowlist,
+            },
+            message: value.message,
+            allowed_codes: value.allowed_codes,
+            code_mappings: value
+                .code_mappings
+                .into_iter()
+                .map(|(code, public_code)| (code, public_code.into()))
+                .collect(),
+        }
+    }
+}
+
+impl From<gateway_config::PublicErrorCode> for PublicErrorCode {
+    fn from(value: gateway_config::PublicErrorCode) -> Self {
+        match value {
+            gateway_config::PublicErrorCode::BadRequest => PublicErrorCode::BadRequest,
+            gateway_config::PublicErrorCode::Unauthenticated => PublicErrorCode::Unauthenticated,
+            gateway_config::PublicErrorCode::Unauthorized => PublicErrorCode::Unauthorized,
+            gateway_config::PublicErrorCode::RateLimited => PublicErrorCode::RateLimited,
+            gateway_config::PublicErrorCode::GatewayTimeout => PublicErrorCode::GatewayTimeout,
+            gateway_config::PublicErrorCode::InternalServerError => PublicErrorCode::InternalServerError,
+            gateway_config::PublicErrorCode::SubgraphError => PublicErrorCode::SubgraphError,
+        }
+    }
+}
+
+/// Whether the query plan can be exposed in the response extensions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct QueryPlanConfig {
+    /// Clients may request the query plan with a header
+    pub enabled: bool,
+    /// The query plan is added to every response
+    pub always_include: bool,
+}
+
+impl From<gateway_config::QueryPlanConfig> for QueryPlanConfig {
+    fn from(value: gateway_config::QueryPlanConfig) -> Self {
+        Self {
+            enabled: value.enabled,
+            always_include: value.always_include,
+        }
+    }
+}
+
+/// Estimation of the cost of operations from the `@cost` and `@listSize` directives
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DemandControlConfig {
+    pub mode: DemandControlMode,
+    /// Operations with a higher estimated cost are rejected in the enforce mode
+    pub max_cost: Option<u64>,
+    /// Size of the lists without any `@listSize` directive
+    pub default_list_size: u32,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DemandControlMode {
+    #[default]
+    Enforce,
+    Measure,
+}
+
+impl From<gateway_config::DemandControlConfig> for DemandControlConfig {
+    fn from(value: gateway_config::DemandControlConfig) -> Self {
+        Self {
+            mode: match value.mode {
+                gateway_config::DemandControlMode::Enforce => DemandControlMode::Enforce,
+                gateway_config::DemandControlMode::Measure => DemandControlMode::Measure,
+            },
+            max_cost: value.max_cost,
+            default_list_size: value.default_list_size,
+        }
+    }
+}
+
+/// Custom labels of progressive `@override` migrations which are enabled
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ProgressiveOverrideConfig {
+    pub enabled_labels: Vec<String>,
+}
+
+impl From<gateway_config::ProgressiveOverrideConfig> for ProgressiveOverrideConfig {
+    fn from(value: gateway_config::ProgressiveOverrideConfig) -> Self {
+        Self {
+            enabled_labels: value.enabled_labels,
+        }
+    }
+}
+
+/// Caching of whole responses based on the `@cacheControl` directives
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ResponseCachingConfig {
+    /// Whether responses are cached at all
+    pub enabled: bool,
+    /// Request headers which are part of the cache key
+    pub vary_headers: Vec<String>,
+    /// JWT claims, as dot-separated paths, which are part of the cache key
+    pub vary_claims: Vec<String>,
+}
+
+impl From<gateway_config::ResponseCachingConfig> for ResponseCachingConfig {
+    fn from(value: gateway_config::ResponseCachingConfig) -> Self {
+        Self {
+            enabled: value.enabled,
+            vary_headers: value.vary_headers,
+            vary_claims: value.vary_claims,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct GraphRateLimit {
     pub limit: usize,
@@ -223,6 +619,8 @@ pub struct RetryConfig {
     pub retry_percent: Option<f32>,
     /// Whether mutations should be retried at all. False by default.
     pub retry_mutations: bool,
+    /// HTTP status codes of subgraph responses to retry. Defaults to 502, 503 and 504.
+    pub retry_on_status: Option<Vec<u16>>,
 }
 
 #[cfg(test)]
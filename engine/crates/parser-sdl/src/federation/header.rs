@@ -146,3 +146,112 @@ impl From<gateway_config::HeaderRemove> for SubgraphHeaderRemove {
         }
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SubgraphResponseHeaderRule {
+    /// Forward the header to the client.
+    Forward(SubgraphResponseHeaderForward),
+    /// Insert a new static header.
+    Insert(SubgraphResponseHeaderInsert),
+    /// Remove a header added by a previous rule.
+    Remove(SubgraphHeaderRemove),
+    /// Forward the header to the client with a new name.
+    Rename(SubgraphResponseHeaderRename),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SubgraphResponseHeaderForward {
+    /// Name or pattern of the header to be forwarded.
+    pub name: NameOrPattern,
+    /// If header is not present, insert this value.
+    pub default: Option<String>,
+    /// Use this name instead of the original when forwarding.
+    pub rename: Option<String>,
+    /// How to merge the header if multiple subgraphs set it.
+    pub merge: HeaderMergeStrategy,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SubgraphResponseHeaderInsert {
+    /// The name of the header.
+    pub name: String,
+    /// The value of the header.
+    pub value: String,
+    /// How to merge the header if multiple subgraphs set it.
+    pub merge: HeaderMergeStrategy,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SubgraphResponseHeaderRename {
+    /// The name of the header in the subgraph response.
+    pub name: String,
+    /// If header is not present, insert this value.
+    pub default: Option<String>,
+    /// The name of the header sent to the client.
+    pub rename: String,
+    /// How to merge the header if multiple subgraphs set it.
+    pub merge: HeaderMergeStrategy,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HeaderMergeStrategy {
+    First,
+    #[default]
+    Last,
+    Append,
+    MostRestrictiveCacheControl,
+}
+
+impl From<gateway_config::ResponseHeaderRule> for SubgraphResponseHeaderRule {
+    fn from(value: gateway_config::ResponseHeaderRule) -> Self {
+        match value {
+            gateway_config::ResponseHeaderRule::Forward(fwd) => Self::Forward(fwd.into()),
+            gateway_config::ResponseHeaderRule::Insert(insert) => Self::Insert(insert.into()),
+            gateway_config::ResponseHeaderRule::Remove(remove) => Self::Remove(remove.into()),
+            gateway_config::ResponseHeaderRule::Rename(rename) => Self::Rename(rename.into()),
+        }
+    }
+}
+
+impl From<gateway_config::ResponseHeaderForward> for SubgraphResponseHeaderForward {
+    fn from(value: gateway_config::ResponseHeaderForward) -> Self {
+        Self {
+            name: value.name.into(),
+            default: value.default.as_ref().map(ToString::to_string),
+            rename: value.rename.as_ref().map(ToString::to_string),
+            merge: value.merge.into(),
+        }
+    }
+}
+
+impl From<gateway_config::ResponseHeaderInsert> for SubgraphResponseHeaderInsert {
+    fn from(value: gateway_config::ResponseHeaderInsert) -> Self {
+        Self {
+            name: value.name.to_string(),
+            value: value.value.to_string(),
+            merge: value.merge.into(),
+        }
+    }
+}
+
+impl From<gateway_config::ResponseHeaderRename> for SubgraphResponseHeaderRename {
+    fn from(value: gateway_config::ResponseHeaderRename) -> Self {
+        Self {
+            name: value.name.to_string(),
+            default: value.default.as_ref().map(ToString::to_string),
+            rename: value.rename.to_string(),
+            merge: value.merge.into(),
+        }
+    }
+}
+
+impl From<gateway_config::HeaderMergeStrategy> for HeaderMergeStrategy {
+    fn from(value: gateway_config::HeaderMergeStrategy) -> Self {
+        match value {
+            gateway_config::HeaderMergeStrategy::First => Self::First,
+            gateway_config::HeaderMergeStrategy::Last => Self::Last,
+            gateway_config::HeaderMergeStrategy::Append => Self::Append,
+            gateway_config::HeaderMergeStrategy::MostRestrictiveCacheControl => Self::MostRestrictiveCacheControl,
+        }
+    }
+}
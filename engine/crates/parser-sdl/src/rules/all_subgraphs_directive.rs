@@ -116,6 +116,11 @@ mod tests {
                         name: "Products",
                         development_url: None,
                         websocket_url: None,
+                        subscription_protocol: Websocket,
+                        subscription_heartbeat_timeout: None,
+                        subscription_max_reconnect_attempts: None,
+                        deduplicate_subscriptions: false,
+                        deduplicate_requests: false,
                         header_rules: [
                             Insert(
                                 SubgraphHeaderInsert {
@@ -124,10 +129,19 @@ mod tests {
                                 },
                             ),
                         ],
+                        response_header_rules: [],
                         rate_limit: None,
                         timeout: None,
                         retry: None,
                         entity_caching: None,
+                        max_entities_per_request: None,
+                        error_masking: None,
+                        circuit_breaker: None,
+                        load_balancing: None,
+                        traffic_split: None,
+                        shadow: None,
+                        compression: None,
+                        connection_pool: None,
                     },
                 },
                 header_rules: [
@@ -174,6 +188,25 @@ mod tests {
                 rate_limit: None,
                 timeout: None,
                 entity_caching: Disabled,
+                query_plan: QueryPlanConfig {
+                    enabled: false,
+                    always_include: false,
+                },
+                response_caching: ResponseCachingConfig {
+                    enabled: false,
+                    vary_headers: [],
+                    vary_claims: [],
+                },
+                error_masking: ErrorMaskingConfig {
+                    mode: PassThrough,
+                    message: None,
+                    allowed_codes: [],
+                    code_mappings: {},
+                },
+                demand_control: None,
+                progressive_override: ProgressiveOverrideConfig {
+                    enabled_labels: [],
+                },
             },
         )
         "###);
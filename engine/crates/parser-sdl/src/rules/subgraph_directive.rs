@@ -72,6 +72,9 @@ pub struct RetryConfig {
     /// Whether mutations should be retried at all. False by default.
     #[serde(default)]
     pub retry_mutations: Option<bool>,
+    /// HTTP status codes of subgraph responses to retry. Defaults to 502, 503 and 504.
+    #[serde(default)]
+    pub retry_on_status: Option<Vec<u16>>,
 }
 
 impl Directive for SubgraphDirective {
@@ -129,6 +132,8 @@ impl Directive for SubgraphDirective {
             retryPercent: Float
             "Whether mutations should be retried at all. False by default."
             retryMutations: Boolean
+            "HTTP status codes of subgraph responses to retry. Defaults to 502, 503 and 504."
+            retryOnStatus: [Int!]
         }
         "#
         .to_string()
@@ -219,11 +224,13 @@ impl Visitor<'_> for SubgraphDirectiveVisitor {
                      ttl,
                      retry_percent,
                      retry_mutations,
+                     retry_on_status,
                  }| SubgraphRetryConfig {
                     min_per_second,
                     ttl,
                     retry_percent,
                     retry_mutations: retry_mutations.unwrap_or_default(),
+                    retry_on_status,
                 },
             );
         }
@@ -265,6 +272,11 @@ mod tests {
                         name: "Products",
                         development_url: None,
                         websocket_url: None,
+                        subscription_protocol: Websocket,
+                        subscription_heartbeat_timeout: None,
+                        subscription_max_reconnect_attempts: None,
+                        deduplicate_subscriptions: false,
+                        deduplicate_requests: false,
                         header_rules: [
                             Forward(
                                 SubgraphHeaderForward {
@@ -284,15 +296,29 @@ mod tests {
                                 },
                             ),
                         ],
+                        response_header_rules: [],
                         rate_limit: None,
                         timeout: None,
                         retry: None,
                         entity_caching: None,
+                        max_entities_per_request: None,
+                        error_masking: None,
+                        circuit_breaker: None,
+                        load_balancing: None,
+                        traffic_split: None,
+                        shadow: None,
+                        compression: None,
+                        connection_pool: None,
                     },
                     "Reviews": SubgraphConfig {
                         name: "Reviews",
                         development_url: None,
                         websocket_url: None,
+                        subscription_protocol: Websocket,
+                        subscription_heartbeat_timeout: None,
+                        subscription_max_reconnect_attempts: None,
+                        deduplicate_subscriptions: false,
+                        deduplicate_requests: false,
                         header_rules: [
                             Insert(
                                 SubgraphHeaderInsert {
@@ -301,10 +327,19 @@ mod tests {
                                 },
                             ),
                         ],
+                        response_header_rules: [],
                         rate_limit: None,
                         timeout: None,
                         retry: None,
                         entity_caching: None,
+                        max_entities_per_request: None,
+                        error_masking: None,
+                        circuit_breaker: None,
+                        load_balancing: None,
+                        traffic_split: None,
+                        shadow: None,
+                        compression: None,
+                        connection_pool: None,
                     },
                 },
                 header_rules: [],
@@ -323,6 +358,25 @@ mod tests {
                 rate_limit: None,
                 timeout: None,
                 entity_caching: Disabled,
+                query_plan: QueryPlanConfig {
+                    enabled: false,
+                    always_include: false,
+                },
+                response_caching: ResponseCachingConfig {
+                    enabled: false,
+                    vary_headers: [],
+                    vary_claims: [],
+                },
+                error_masking: ErrorMaskingConfig {
+                    mode: PassThrough,
+                    message: None,
+                    allowed_codes: [],
+                    code_mappings: {},
+                },
+                demand_control: None,
+                progressive_override: ProgressiveOverrideConfig {
+                    enabled_labels: [],
+                },
             },
         )
         "###);
@@ -1,88 +1,246 @@
+mod multipart;
+mod reconnect;
+mod sse;
 mod websockets;
 
-use std::collections::HashMap;
+use std::{collections::HashMap, io::Write, sync::Mutex};
 
+use bytes::Bytes;
 use futures_util::stream::BoxStream;
-use runtime::fetch::{FetchError, FetchRequest, FetchResponse, FetchResult, Fetcher, FetcherInner, GraphqlRequest};
-use serde_json::json;
+use runtime::fetch::{
+    Compression, FetchError, FetchRequest, FetchResponse, FetchResult, Fetcher, FetcherInner, GraphqlRequest,
+    HttpClientSettings, SubscriptionProtocol,
+};
 
-use self::websockets::StreamingRequest;
+use self::reconnect::{SubscriptionEvent, SubscriptionRequest};
 
 pub struct NativeFetcher {
     client: reqwest::Client,
+    /// Clients of the subgraphs with specific settings, each with its own connection pool.
+    clients: Mutex<HashMap<HttpClientSettings, reqwest::Client>>,
 }
 
 impl NativeFetcher {
     pub fn runtime_fetcher() -> Fetcher {
         Fetcher::new(Self {
-            client: reqwest::Client::new(),
+            client: Self::default_client(),
+            clients: Mutex::new(HashMap::new()),
         })
     }
+
+    /// A client matching the default `HttpClientSettings`. The reqwest features needed to decode
+    /// compressed subgraph responses would otherwise enable it for every client, advertising
+    /// `Accept-Encoding` and decoding response bodies behind the back of the caller.
+    fn default_client() -> reqwest::Client {
+        reqwest::Client::builder()
+            .gzip(false)
+            .brotli(false)
+            .zstd(false)
+            .build()
+            .expect("default client configuration to be valid")
+    }
+
+    fn client(&self, settings: HttpClientSettings) -> FetchResult<reqwest::Client> {
+        if settings == HttpClientSettings::default() {
+            return Ok(self.client.clone());
+        }
+
+        let mut clients = self.clients.lock().unwrap();
+        if let Some(client) = clients.get(&settings) {
+            return Ok(client.clone());
+        }
+
+        let mut builder = reqwest::Client::builder()
+            .gzip(settings.decompress_responses)
+            .brotli(settings.decompress_responses)
+            .zstd(settings.decompress_responses)
+            .tcp_keepalive(settings.tcp_keepalive);
+
+        if let Some(max) = settings.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+
+        if let Some(timeout) = settings.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+
+        if settings.http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge();
+        }
+
+        if let Some(timeout) = settings.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+
+        let client = builder.build().map_err(FetchError::any)?;
+        clients.insert(settings, client.clone());
+
+        Ok(client)
+    }
+}
+
+/// Favoring speed over size, the default quality being the slowest.
+const BROTLI_QUALITY: u32 = 5;
+const BROTLI_WINDOW: u32 = 22;
+
+fn compress(compression: Compression, body: &[u8]) -> std::io::Result<Bytes> {
+    let compressed = match compression {
+        Compression::Gzip => {
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(body)?;
+            encoder.finish()?
+        }
+        Compression::Brotli => {
+            let mut compressed = Vec::new();
+            {
+                let mut encoder = brotli::CompressorWriter::new(&mut compressed, 4096, BROTLI_QUALITY, BROTLI_WINDOW);
+                encoder.write_all(body)?;
+            }
+            compressed
+        }
+        Compression::Zstd => zstd::encode_all(body, zstd::DEFAULT_COMPRESSION_LEVEL)?,
+    };
+
+    Ok(compressed.into())
 }
 
 #[async_trait::async_trait]
 impl FetcherInner for NativeFetcher {
     async fn post(&self, request: &FetchRequest<'_>) -> FetchResult<FetchResponse> {
-        let n = request.json_body.len();
+        let body = match request.compression {
+            Some(compression) => compress(compression, &request.json_body).map_err(FetchError::any)?,
+            None => request.json_body.clone(),
+        };
+        let n = body.len();
 
-        let response = self
-            .client
+        let mut builder = self
+            .client(request.client_settings)?
             .post(request.url.clone())
-            .body(request.json_body.clone())
+            .body(body)
             .headers(request.headers.clone())
             .header("Content-Type", "application/json")
             .header("Content-Length", n)
-            .timeout(request.timeout)
-            .send()
-            .await
-            .map_err(|e| {
-                if e.is_timeout() {
-                    FetchError::Timeout
-                } else {
-                    FetchError::AnyError(e.to_string())
-                }
-            })?;
+            .timeout(request.timeout);
+
+        if let Some(compression) = request.compression {
+            builder = builder.header(http::header::CONTENT_ENCODING, compression.content_encoding());
+        }
+
+        let response = builder.send().await.map_err(|e| {
+            if e.is_timeout() {
+                FetchError::Timeout
+            } else {
+                FetchError::AnyError(e.to_string())
+            }
+        })?;
 
+        let status = response.status();
+        let headers = response.headers().clone();
         let bytes = response
             .bytes()
             .await
             .map_err(|e| FetchError::AnyError(e.to_string()))?;
 
-        Ok(FetchResponse { bytes })
+        Ok(FetchResponse { status, headers, bytes })
     }
 
     async fn stream(
         &self,
         request: GraphqlRequest<'_>,
     ) -> FetchResult<BoxStream<'static, Result<serde_json::Value, FetchError>>> {
+        let client = self.client(request.client_settings)?;
+        let request = SubscriptionRequest::from(request);
+        reconnect::subscribe(client, request).await
+    }
+}
+
+/// Opens a single connection for the subscription with the configured protocol.
+async fn connect(
+    client: &reqwest::Client,
+    request: &SubscriptionRequest,
+) -> FetchResult<BoxStream<'static, FetchResult<SubscriptionEvent>>> {
+    match request.protocol {
+        SubscriptionProtocol::Websocket => websockets::connect(request).await,
+        SubscriptionProtocol::ServerSentEvents => sse::connect(client, request).await,
+        SubscriptionProtocol::Multipart => multipart::connect(client, request).await,
+    }
+}
+
+enum HttpSubscription {
+    Stream(reqwest::Response),
+    /// The subgraph answered with a plain JSON response, for example if the request was invalid.
+    Single(serde_json::Value),
+}
+
+/// Sends the subscription request for the HTTP based protocols.
+async fn send_http_subscription(
+    client: &reqwest::Client,
+    request: &SubscriptionRequest,
+    accept: &'static str,
+) -> FetchResult<HttpSubscription> {
+    let response = client
+        .post(request.url.clone())
+        .headers(request.headers.clone())
+        .header(http::header::ACCEPT, accept)
+        .json(&request.body)
+        .send()
+        .await
+        .map_err(FetchError::any)?;
+
+    if !response.status().is_success() {
+        return Err(FetchError::any(format!(
+            "Subscription request failed with status {}",
+            response.status()
+        )));
+    }
+
+    let is_json = response
+        .headers()
+        .get(http::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.starts_with("application/json") || value.starts_with("application/graphql-response+json"))
+        .unwrap_or_default();
+
+    if is_json {
+        let value = response.json().await.map_err(FetchError::any)?;
+        Ok(HttpSubscription::Single(value))
+    } else {
+        Ok(HttpSubscription::Stream(response))
+    }
+}
+
+impl HttpSubscription {
+    fn single(value: serde_json::Value) -> BoxStream<'static, FetchResult<SubscriptionEvent>> {
         use futures_util::StreamExt;
-        use tungstenite::{client::IntoClientRequest, http::HeaderValue};
-
-        let (connection, _) = {
-            let mut request = request.url.into_client_request().unwrap();
-            request.headers_mut().insert(
-                "Sec-WebSocket-Protocol",
-                HeaderValue::from_str("graphql-transport-ws").unwrap(),
-            );
-
-            async_tungstenite::tokio::connect_async(request)
-                .await
-                .map_err(FetchError::any)?
-        };
 
-        let headers: HashMap<_, _> = request
-            .headers
-            .iter()
-            .flat_map(|(k, v)| v.to_str().map(|v| (k.as_str(), v)))
-            .collect();
+        futures_util::stream::iter([Ok(SubscriptionEvent::Next(value))]).boxed()
+    }
+}
 
-        Ok(graphql_ws_client::Client::build(connection)
-            .payload(json!({"headers": headers}))
-            .map_err(FetchError::any)?
-            .subscribe(StreamingRequest::from(request))
-            .await
-            .map_err(FetchError::any)?
-            .map(|item| item.map_err(FetchError::any))
-            .boxed())
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+
+    #[test]
+    fn compressed_bodies_round_trip() {
+        let body =
+            br#"{"query":"query($representations:[_Any!]!){_entities(representations:$representations){__typename}}"}"#;
+
+        let mut decoded = Vec::new();
+        flate2::read::GzDecoder::new(&compress(Compression::Gzip, body).unwrap()[..])
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, body);
+
+        let mut decoded = Vec::new();
+        brotli::Decompressor::new(&compress(Compression::Brotli, body).unwrap()[..], 4096)
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, body);
+
+        let decoded = zstd::decode_all(&compress(Compression::Zstd, body).unwrap()[..]).unwrap();
+        assert_eq!(decoded, body);
     }
 }
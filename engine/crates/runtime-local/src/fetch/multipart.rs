@@ -0,0 +1,156 @@
+//! Multipart HTTP subscriptions, each part holding a single JSON payload.
+
+use futures_util::{stream::BoxStream, StreamExt};
+use runtime::fetch::{FetchError, FetchResult};
+use serde_json::json;
+
+use super::{
+    reconnect::{SubscriptionEvent, SubscriptionRequest},
+    send_http_subscription, HttpSubscription,
+};
+
+const ACCEPT: &str = "multipart/mixed;subscriptionSpec=\"1.0\", application/json";
+
+pub(super) async fn connect(
+    client: &reqwest::Client,
+    request: &SubscriptionRequest,
+) -> FetchResult<BoxStream<'static, FetchResult<SubscriptionEvent>>> {
+    let response = match send_http_subscription(client, request, ACCEPT).await? {
+        HttpSubscription::Stream(response) => response,
+        HttpSubscription::Single(value) => return Ok(HttpSubscription::single(value)),
+    };
+
+    let boundary = response
+        .headers()
+        .get(http::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(boundary)
+        .unwrap_or_else(|| String::from("-"));
+
+    let state = (response.bytes_stream().boxed(), PartParser::new(&boundary));
+
+    Ok(futures_util::stream::unfold(Some(state), |state| async move {
+        let (mut bytes, mut parser) = state?;
+        loop {
+            match parser.next_part() {
+                Some(Part::Body(body)) => {
+                    let event = serde_json::from_slice(&body).map(into_event).map_err(FetchError::any);
+                    return Some((event, Some((bytes, parser))));
+                }
+                Some(Part::End) => return None,
+                None => {}
+            }
+
+            match bytes.next().await {
+                Some(Ok(chunk)) => parser.push(&chunk),
+                Some(Err(error)) => return Some((Err(FetchError::any(error)), None)),
+                None => {
+                    return Some((
+                        Err(FetchError::any("Subscription connection closed before completion")),
+                        None,
+                    ))
+                }
+            }
+        }
+    })
+    .boxed())
+}
+
+#[derive(serde::Deserialize)]
+struct Payload {
+    #[serde(default)]
+    payload: Option<serde_json::Value>,
+    /// Transport errors, the subscription is terminated by the subgraph afterwards.
+    #[serde(default)]
+    errors: Option<serde_json::Value>,
+}
+
+/// An empty object is a heartbeat.
+fn into_event(Payload { payload, errors }: Payload) -> SubscriptionEvent {
+    match (payload, errors) {
+        (Some(payload), _) => SubscriptionEvent::Next(payload),
+        (None, Some(errors)) => SubscriptionEvent::Next(json!({ "errors": errors })),
+        (None, None) => SubscriptionEvent::Heartbeat,
+    }
+}
+
+fn boundary(content_type: &str) -> Option<String> {
+    content_type.split(';').find_map(|parameter| {
+        let (name, value) = parameter.split_once('=')?;
+        (name.trim().eq_ignore_ascii_case("boundary")).then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+#[derive(Debug, PartialEq)]
+enum Part {
+    Body(Vec<u8>),
+    End,
+}
+
+struct PartParser {
+    // Starts with a line break so that the first delimiter matches like all the others.
+    buffer: Vec<u8>,
+    delimiter: Vec<u8>,
+}
+
+impl PartParser {
+    fn new(boundary: &str) -> Self {
+        PartParser {
+            buffer: b"\r\n".to_vec(),
+            delimiter: format!("\r\n--{boundary}").into_bytes(),
+        }
+    }
+
+    fn push(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    fn next_part(&mut self) -> Option<Part> {
+        let start = find(&self.buffer, &self.delimiter)? + self.delimiter.len();
+        if self.buffer.len() < start + 2 {
+            return None;
+        }
+        if &self.buffer[start..start + 2] == b"--" {
+            return Some(Part::End);
+        }
+
+        let end = start + find(&self.buffer[start..], &self.delimiter)?;
+        let part = self.buffer.drain(..end).skip(start).collect::<Vec<_>>();
+
+        // Part headers are separated from the body by an empty line.
+        let body = match find(&part, b"\r\n\r\n") {
+            Some(position) => &part[position + 4..],
+            None => &part[..],
+        };
+        Some(Part::Body(body.trim_ascii().to_vec()))
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_parts_across_chunks() {
+        assert_eq!(
+            boundary("multipart/mixed; boundary=\"graphql\"; subscriptionSpec=1.0").as_deref(),
+            Some("graphql")
+        );
+
+        let mut parser = PartParser::new("graphql");
+        parser.push(b"\r\n--graphql\r\ncontent-type: application/json\r\n\r\n{}\r\n--graphql\r\ncontent-type: appli");
+        assert_eq!(parser.next_part(), Some(Part::Body(b"{}".to_vec())));
+        assert_eq!(parser.next_part(), None);
+
+        parser.push(b"cation/json\r\n\r\n{\"payload\":{\"data\":1}}\r\n--graphql--\r\n");
+        assert_eq!(
+            parser.next_part(),
+            Some(Part::Body(b"{\"payload\":{\"data\":1}}".to_vec()))
+        );
+        assert_eq!(parser.next_part(), Some(Part::End));
+    }
+}
@@ -0,0 +1,146 @@
+//! Heartbeats and reconnection shared by all the subscription protocols.
+
+use std::time::Duration;
+
+use futures_util::{stream::BoxStream, StreamExt};
+use runtime::fetch::{FetchError, FetchResult, GraphqlRequest, SubscriptionProtocol};
+
+/// Interval of the websocket pings.
+pub(super) const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Consecutive reconnection attempts before giving up if the subgraph doesn't configure it, reset
+/// after every successful connection.
+const DEFAULT_MAX_RECONNECT_ATTEMPTS: u32 = 3;
+
+const RECONNECT_BASE_DELAY: Duration = Duration::from_millis(500);
+
+pub(super) enum SubscriptionEvent {
+    Next(serde_json::Value),
+    Heartbeat,
+}
+
+/// Owned copy of the request, kept to re-subscribe after a connection loss.
+pub(super) struct SubscriptionRequest {
+    pub url: url::Url,
+    pub headers: http::HeaderMap,
+    pub body: SubscriptionBody,
+    pub protocol: SubscriptionProtocol,
+    /// SSE and multipart connections without any message or heartbeat for this long are
+    /// considered dead.
+    pub heartbeat_timeout: Option<Duration>,
+    pub max_reconnect_attempts: u32,
+}
+
+#[derive(serde::Serialize)]
+pub(super) struct SubscriptionBody {
+    pub query: String,
+    pub variables: serde_json::Value,
+}
+
+impl From<GraphqlRequest<'_>> for SubscriptionRequest {
+    fn from(request: GraphqlRequest<'_>) -> Self {
+        SubscriptionRequest {
+            url: request.url.clone(),
+            headers: request.headers,
+            body: SubscriptionBody {
+                query: request.query.to_string(),
+                variables: request.variables,
+            },
+            protocol: request.protocol,
+            heartbeat_timeout: request.heartbeat_timeout,
+            max_reconnect_attempts: request.max_reconnect_attempts.unwrap_or(DEFAULT_MAX_RECONNECT_ATTEMPTS),
+        }
+    }
+}
+
+struct State {
+    client: reqwest::Client,
+    request: SubscriptionRequest,
+    connection: Option<BoxStream<'static, FetchResult<SubscriptionEvent>>>,
+    failed_attempts: u32,
+    done: bool,
+}
+
+/// Failing to establish the first connection is reported immediately. Afterwards connection
+/// errors and missed heartbeats trigger a new subscription with an exponential backoff. The
+/// stream ends once the subgraph completes the subscription.
+pub(super) async fn subscribe(
+    client: reqwest::Client,
+    request: SubscriptionRequest,
+) -> FetchResult<BoxStream<'static, FetchResult<serde_json::Value>>> {
+    let connection = super::connect(&client, &request).await?;
+
+    let state = State {
+        client,
+        request,
+        connection: Some(connection),
+        failed_attempts: 0,
+        done: false,
+    };
+
+    Ok(futures_util::stream::unfold(state, |mut state| async move {
+        loop {
+            if state.done {
+                return None;
+            }
+
+            if state.connection.is_none() {
+                match super::connect(&state.client, &state.request).await {
+                    Ok(connection) => {
+                        state.connection = Some(connection);
+                        state.failed_attempts = 0;
+                    }
+                    Err(error) => {
+                        if !state.wait_before_reconnecting().await {
+                            state.done = true;
+                            return Some((Err(error), state));
+                        }
+                        continue;
+                    }
+                }
+            }
+
+            let Some(connection) = state.connection.as_mut() else {
+                continue;
+            };
+
+            let event = match (state.request.protocol, state.request.heartbeat_timeout) {
+                // The websocket client handles its own pings.
+                (SubscriptionProtocol::Websocket, _) | (_, None) => Ok(connection.next().await),
+                (SubscriptionProtocol::ServerSentEvents | SubscriptionProtocol::Multipart, Some(timeout)) => {
+                    tokio::time::timeout(timeout, connection.next()).await
+                }
+            };
+
+            let error = match event {
+                Ok(Some(Ok(SubscriptionEvent::Next(value)))) => return Some((Ok(value), state)),
+                Ok(Some(Ok(SubscriptionEvent::Heartbeat))) => continue,
+                Ok(None) => return None,
+                Ok(Some(Err(error))) => error,
+                Err(_) => FetchError::any("Subscription heartbeat timeout"),
+            };
+
+            tracing::warn!("Subscription connection lost, reconnecting: {error}");
+            state.connection = None;
+            if !state.wait_before_reconnecting().await {
+                state.done = true;
+                return Some((Err(error), state));
+            }
+        }
+    })
+    .boxed())
+}
+
+impl State {
+    /// Returns false once all the reconnection attempts are exhausted.
+    async fn wait_before_reconnecting(&mut self) -> bool {
+        if self.failed_attempts >= self.request.max_reconnect_attempts {
+            return false;
+        }
+        // Capped at 32s, the number of attempts being configurable.
+        let delay = RECONNECT_BASE_DELAY * 2u32.pow(self.failed_attempts.min(6));
+        self.failed_attempts += 1;
+        tokio::time::sleep(delay).await;
+        true
+    }
+}
@@ -0,0 +1,126 @@
+//! GraphQL over server-sent events, in the distinct connections mode.
+
+use futures_util::{stream::BoxStream, StreamExt};
+use runtime::fetch::{FetchError, FetchResult};
+
+use super::{
+    reconnect::{SubscriptionEvent, SubscriptionRequest},
+    send_http_subscription, HttpSubscription,
+};
+
+pub(super) async fn connect(
+    client: &reqwest::Client,
+    request: &SubscriptionRequest,
+) -> FetchResult<BoxStream<'static, FetchResult<SubscriptionEvent>>> {
+    let response = match send_http_subscription(client, request, "text/event-stream").await? {
+        HttpSubscription::Stream(response) => response,
+        HttpSubscription::Single(value) => return Ok(HttpSubscription::single(value)),
+    };
+
+    let state = (response.bytes_stream().boxed(), EventParser::default());
+
+    Ok(futures_util::stream::unfold(Some(state), |state| async move {
+        let (mut bytes, mut parser) = state?;
+        loop {
+            match parser.next_event() {
+                Some(Event::Next(data)) => {
+                    let event = serde_json::from_str(&data)
+                        .map(SubscriptionEvent::Next)
+                        .map_err(FetchError::any);
+                    return Some((event, Some((bytes, parser))));
+                }
+                Some(Event::Heartbeat) => return Some((Ok(SubscriptionEvent::Heartbeat), Some((bytes, parser)))),
+                Some(Event::Complete) => return None,
+                None => {}
+            }
+
+            match bytes.next().await {
+                Some(Ok(chunk)) => parser.push(&chunk),
+                Some(Err(error)) => return Some((Err(FetchError::any(error)), None)),
+                None => {
+                    return Some((
+                        Err(FetchError::any("Subscription connection closed before completion")),
+                        None,
+                    ))
+                }
+            }
+        }
+    })
+    .boxed())
+}
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    Next(String),
+    /// Comments are used as keep-alive messages.
+    Heartbeat,
+    Complete,
+}
+
+#[derive(Default)]
+struct EventParser {
+    buffer: Vec<u8>,
+}
+
+impl EventParser {
+    fn push(&mut self, chunk: &[u8]) {
+        // Normalizing line endings, events are separated by an empty line.
+        self.buffer.extend(chunk.iter().copied().filter(|byte| *byte != b'\r'));
+    }
+
+    fn next_event(&mut self) -> Option<Event> {
+        loop {
+            let end = self.buffer.windows(2).position(|window| window == b"\n\n")?;
+            let block = self.buffer.drain(..end + 2).collect::<Vec<_>>();
+            let block = String::from_utf8_lossy(&block[..end]);
+
+            let mut event = None;
+            let mut data = Vec::new();
+            let mut is_comment = false;
+
+            for line in block.lines() {
+                if line.starts_with(':') {
+                    is_comment = true;
+                    continue;
+                }
+                let (field, value) = line.split_once(':').unwrap_or((line, ""));
+                let value = value.strip_prefix(' ').unwrap_or(value);
+                match field {
+                    "event" => event = Some(value),
+                    "data" => data.push(value),
+                    _ => {}
+                }
+            }
+
+            match event {
+                Some("complete") => return Some(Event::Complete),
+                None | Some("next") | Some("message") if !data.is_empty() => {
+                    return Some(Event::Next(data.join("\n")));
+                }
+                _ if is_comment => return Some(Event::Heartbeat),
+                // Unknown events are ignored.
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_events_across_chunks() {
+        let mut parser = EventParser::default();
+        parser.push(b":\n\nevent: next\r\ndata: {\"data\":");
+        assert_eq!(parser.next_event(), Some(Event::Heartbeat));
+        assert_eq!(parser.next_event(), None);
+
+        parser.push(b" {\"value\": 1}}\r\n\r\nevent: complete\n\n");
+        assert_eq!(
+            parser.next_event(),
+            Some(Event::Next(String::from("{\"data\": {\"value\": 1}}")))
+        );
+        assert_eq!(parser.next_event(), Some(Event::Complete));
+    }
+}
@@ -1,6 +1,49 @@
 //! graphql-ws-client <> engine glue code
 
-use runtime::fetch::{FetchError, GraphqlRequest};
+use std::collections::HashMap;
+
+use futures_util::{stream::BoxStream, StreamExt};
+use runtime::fetch::{FetchError, FetchResult};
+use serde_json::json;
+use tungstenite::{client::IntoClientRequest, http::HeaderValue};
+
+use super::reconnect::{SubscriptionEvent, SubscriptionRequest, HEARTBEAT_INTERVAL};
+
+pub(super) async fn connect(
+    request: &SubscriptionRequest,
+) -> FetchResult<BoxStream<'static, FetchResult<SubscriptionEvent>>> {
+    let (connection, _) = {
+        let mut client_request = (&request.url).into_client_request().map_err(FetchError::any)?;
+        client_request.headers_mut().insert(
+            "Sec-WebSocket-Protocol",
+            HeaderValue::from_str("graphql-transport-ws").unwrap(),
+        );
+
+        async_tungstenite::tokio::connect_async(client_request)
+            .await
+            .map_err(FetchError::any)?
+    };
+
+    let headers: HashMap<_, _> = request
+        .headers
+        .iter()
+        .flat_map(|(k, v)| v.to_str().map(|v| (k.as_str(), v)))
+        .collect();
+
+    // Pings are sent by the client, a connection without any pong is closed with an error.
+    Ok(graphql_ws_client::Client::build(connection)
+        .payload(json!({"headers": headers}))
+        .map_err(FetchError::any)?
+        .keep_alive_interval(HEARTBEAT_INTERVAL)
+        .subscribe(StreamingRequest {
+            query: request.body.query.clone(),
+            variables: request.body.variables.clone(),
+        })
+        .await
+        .map_err(FetchError::any)?
+        .map(|item| item.map(SubscriptionEvent::Next).map_err(FetchError::any))
+        .boxed())
+}
 
 #[derive(serde::Serialize)]
 pub struct StreamingRequest {
@@ -8,15 +51,6 @@ pub struct StreamingRequest {
     variables: serde_json::Value,
 }
 
-impl From<GraphqlRequest<'_>> for StreamingRequest {
-    fn from(value: GraphqlRequest<'_>) -> Self {
-        StreamingRequest {
-            query: value.query.to_string(),
-            variables: value.variables,
-        }
-    }
-}
-
 impl graphql_ws_client::graphql::GraphqlOperation for StreamingRequest {
     type Response = serde_json::Value;
 
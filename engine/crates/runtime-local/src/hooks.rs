@@ -1,25 +1,69 @@
 mod authorized;
+mod cache;
 mod pool;
 mod subgraph;
 
-use std::{collections::HashMap, sync::Arc};
+use std::sync::{Arc, RwLock};
 
+pub use cache::memory::InMemoryHooksCache;
+#[cfg(feature = "redis")]
+pub use cache::redis::RedisHooksCache;
+use grafbase_telemetry::{gql_response_status::GraphqlResponseStatus, metrics::OperationType};
 use pool::Pool;
 use runtime::{
     error::{PartialErrorCode, PartialGraphqlError},
-    hooks::{AuthorizedHooks, HeaderMap, Hooks, SubgraphHooks},
+    hooks::{AuthorizedHooks, ExecutedOperation, HeaderMap, Hooks, SubgraphHooks},
 };
 use tracing::instrument;
-use wasi_component_loader::{AuthorizationComponentInstance, GatewayComponentInstance, SubgraphComponentInstance};
-pub use wasi_component_loader::{ComponentLoader, Config as HooksWasiConfig};
+use wasi_component_loader::{
+    AccessToken, AuthorizationComponentInstance, ContextMap, GatewayComponentInstance,
+    GatewayResponseComponentInstance, SharedAccessToken, SharedContextMap, SubgraphComponentInstance,
+    SubgraphResponseComponentInstance,
+};
+pub use wasi_component_loader::{ComponentLoader, Config as HooksWasiConfig, SharedCache as SharedHooksCache};
 
 pub struct HooksWasi(Option<HooksWasiInner>);
-type Context = Arc<HashMap<String, String>>;
+
+/// The per-request context of the hooks. Entries can be recorded after a subgraph response, so
+/// the hooks get a snapshot of the current state.
+pub struct Context {
+    map: RwLock<SharedContextMap>,
+    access_token: SharedAccessToken,
+}
+
+impl Context {
+    fn new(context: ContextMap) -> Self {
+        Self {
+            map: RwLock::new(Arc::new(context)),
+            access_token: Arc::new(AccessToken::anonymous()),
+        }
+    }
+
+    fn snapshot(&self) -> SharedContextMap {
+        Arc::clone(&self.map.read().unwrap())
+    }
+
+    fn access_token(&self) -> SharedAccessToken {
+        Arc::clone(&self.access_token)
+    }
+
+    fn extend(&self, entries: Vec<(String, String)>) {
+        if entries.is_empty() {
+            return;
+        }
+
+        let mut context = self.map.write().unwrap();
+        Arc::make_mut(&mut *context).extend(entries);
+    }
+}
 
 struct HooksWasiInner {
     gateway: Pool<GatewayComponentInstance>,
+    gateway_response: Pool<GatewayResponseComponentInstance>,
     authorization: Pool<AuthorizationComponentInstance>,
     subgraph: Pool<SubgraphComponentInstance>,
+    subgraph_response: Pool<SubgraphResponseComponentInstance>,
+    subgraph_response_body: bool,
 }
 
 impl HooksWasi {
@@ -27,8 +71,11 @@ impl HooksWasi {
         match loader.map(Arc::new) {
             Some(loader) => Self(Some(HooksWasiInner {
                 gateway: Pool::new(&loader),
+                gateway_response: Pool::new(&loader),
                 authorization: Pool::new(&loader),
                 subgraph: Pool::new(&loader),
+                subgraph_response: Pool::new(&loader),
+                subgraph_response_body: loader.config().subgraph_response_body,
             })),
             None => Self(None),
         }
@@ -41,14 +88,14 @@ impl Hooks for HooksWasi {
     #[instrument(skip_all)]
     async fn on_gateway_request(&self, headers: HeaderMap) -> Result<(Self::Context, HeaderMap), PartialGraphqlError> {
         let Some(ref inner) = self.0 else {
-            return Ok((Arc::new(HashMap::new()), headers));
+            return Ok((Context::new(ContextMap::new()), headers));
         };
 
         let mut hook = inner.gateway.get().await;
 
-        hook.on_gateway_request(HashMap::new(), headers)
+        hook.on_gateway_request(ContextMap::new(), headers)
             .await
-            .map(|(ctx, headers)| (Arc::new(ctx), headers))
+            .map(|(ctx, headers)| (Context::new(ctx), headers))
             .map_err(|err| match err {
                 wasi_component_loader::Error::Internal(err) => {
                     tracing::error!("on_gateway_request error: {err}");
@@ -58,6 +105,68 @@ impl Hooks for HooksWasi {
             })
     }
 
+    fn authenticated(&self, context: &mut Self::Context, access_token: &runtime::auth::AccessToken) {
+        let claims = match access_token {
+            runtime::auth::AccessToken::Jwt(token) => serde_json::to_string(&token.claims).ok(),
+            _ => None,
+        };
+
+        context.access_token = Arc::new(AccessToken {
+            anonymous: access_token.is_anonymous(),
+            claims: claims.unwrap_or_else(|| String::from("{}")),
+        });
+    }
+
+    #[instrument(skip_all)]
+    async fn on_gateway_response(
+        &self,
+        context: &Self::Context,
+        operation: ExecutedOperation<'_>,
+        headers: HeaderMap,
+    ) -> Result<HeaderMap, PartialGraphqlError> {
+        let Some(ref inner) = self.0 else {
+            return Ok(headers);
+        };
+
+        let operation = wasi_component_loader::ExecutedOperation {
+            name: operation.name.map(str::to_string),
+            operation_type: operation.ty.map(|ty| match ty {
+                OperationType::Query => wasi_component_loader::OperationType::Query,
+                OperationType::Mutation => wasi_component_loader::OperationType::Mutation,
+                OperationType::Subscription => wasi_component_loader::OperationType::Subscription,
+            }),
+            status: match operation.status {
+                GraphqlResponseStatus::Success => wasi_component_loader::GraphqlResponseStatus::Success,
+                GraphqlResponseStatus::FieldError { count, data_is_null } => {
+                    wasi_component_loader::GraphqlResponseStatus::FieldError(wasi_component_loader::FieldError {
+                        count,
+                        data_is_null,
+                    })
+                }
+                GraphqlResponseStatus::RequestError { count } => {
+                    wasi_component_loader::GraphqlResponseStatus::RequestError(wasi_component_loader::RequestError {
+                        count,
+                    })
+                }
+            },
+            duration_ms: operation.duration.as_millis() as u64,
+        };
+
+        inner
+            .gateway_response
+            .get()
+            .await
+            .on_gateway_response(context.snapshot(), operation, headers)
+            .await
+            .map_err(|err| match err {
+                wasi_component_loader::Error::Internal(err) => {
+                    tracing::error!("on_gateway_response error: {err}");
+                    PartialGraphqlError::internal_hook_error()
+                }
+                wasi_component_loader::Error::Guest(err) => guest_error_as_gql(err, PartialErrorCode::HookError),
+            })
+    }
+
     fn authorized(&self) -> &impl AuthorizedHooks<Self::Context> {
         self
     }
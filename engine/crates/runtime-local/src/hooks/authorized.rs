@@ -1,5 +1,3 @@
-use std::sync::Arc;
-
 use runtime::{
     error::{PartialErrorCode, PartialGraphqlError},
     hooks::{Anything, AuthorizationVerdict, AuthorizationVerdicts, AuthorizedHooks, EdgeDefinition, NodeDefinition},
@@ -42,6 +40,34 @@ fn encode<'a>(
 }
 
 impl AuthorizedHooks<Context> for HooksWasi {
+    #[instrument(skip_all)]
+    async fn authorize_policies(
+        &self,
+        context: &Context,
+        policies: Vec<String>,
+    ) -> Result<Vec<String>, PartialGraphqlError> {
+        let Some(ref inner) = self.0 else {
+            return Err(PartialGraphqlError::new(
+                "@policy directive cannot be used, so access was denied",
+                PartialErrorCode::Unauthorized,
+            ));
+        };
+
+        inner
+            .authorization
+            .get()
+            .await
+            .authorize_policies(context.snapshot(), context.access_token(), policies)
+            .await
+            .map_err(|err| match err {
+                wasi_component_loader::Error::Internal(error) => {
+                    tracing::error!("authorize_policies error: {error}");
+                    PartialGraphqlError::internal_hook_error()
+                }
+                wasi_component_loader::Error::Guest(error) => guest_error_as_gql(error, PartialErrorCode::Unauthorized),
+            })
+    }
+
     #[instrument(skip_all)]
     async fn authorize_edge_pre_execution<'a>(
         &self,
@@ -62,7 +88,13 @@ impl AuthorizedHooks<Context> for HooksWasi {
         };
 
         instance
-            .authorize_edge_pre_execution(Arc::clone(context), definition, arguments, metadata)
+            .authorize_edge_pre_execution(
+                context.snapshot(),
+                context.access_token(),
+                definition,
+                arguments,
+                metadata,
+            )
             .await
             .map_err(|err| match err {
                 wasi_component_loader::Error::Internal(error) => {
@@ -92,7 +124,7 @@ impl AuthorizedHooks<Context> for HooksWasi {
         };
 
         instance
-            .authorize_node_pre_execution(Arc::clone(context), definition, metadata)
+            .authorize_node_pre_execution(context.snapshot(), context.access_token(), definition, metadata)
             .await
             .map_err(|err| match err {
                 wasi_component_loader::Error::Internal(error) => {
@@ -144,7 +176,13 @@ impl AuthorizedHooks<Context> for HooksWasi {
         };
 
         let results = instance
-            .authorize_parent_edge_post_execution(Arc::clone(context), definition, parents, metadata)
+            .authorize_parent_edge_post_execution(
+                context.snapshot(),
+                context.access_token(),
+                definition,
+                parents,
+                metadata,
+            )
             .await
             .map_err(|err| match err {
                 wasi_component_loader::Error::Internal(error) => {
@@ -182,7 +220,7 @@ impl AuthorizedHooks<Context> for HooksWasi {
         };
 
         let result = instance
-            .authorize_edge_node_post_execution(Arc::clone(context), definition, nodes, metadata)
+            .authorize_edge_node_post_execution(context.snapshot(), context.access_token(), definition, nodes, metadata)
             .await
             .map_err(|err| match err {
                 wasi_component_loader::Error::Internal(error) => {
@@ -246,7 +284,7 @@ impl AuthorizedHooks<Context> for HooksWasi {
         };
 
         let result = instance
-            .authorize_edge_post_execution(Arc::clone(context), definition, edges, metadata)
+            .authorize_edge_post_execution(context.snapshot(), context.access_token(), definition, edges, metadata)
             .await
             .map_err(|err| match err {
                 wasi_component_loader::Error::Internal(error) => {
@@ -0,0 +1,3 @@
+pub(crate) mod memory;
+#[cfg(feature = "redis")]
+pub(crate) mod redis;
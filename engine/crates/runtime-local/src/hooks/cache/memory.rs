@@ -0,0 +1,125 @@
+use std::time::{Duration, Instant};
+
+use wasi_component_loader::{BoxFuture, Cache};
+
+/// A hooks cache in the gateway memory, shared by all hook instances.
+pub struct InMemoryHooksCache {
+    inner: mini_moka::sync::Cache<String, CacheValue>,
+}
+
+#[derive(Clone)]
+struct CacheValue {
+    data: Vec<u8>,
+    expires_at: Option<Instant>,
+}
+
+impl InMemoryHooksCache {
+    pub fn new() -> Self {
+        InMemoryHooksCache {
+            inner: mini_moka::sync::Cache::new(4096),
+        }
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        let key = key.to_string();
+
+        let Some(value) = self.inner.get(&key) else {
+            return Ok(None);
+        };
+
+        match value.expires_at {
+            Some(expires_at) if expires_at < Instant::now() => {
+                self.inner.invalidate(&key);
+                Ok(None)
+            }
+            _ => Ok(Some(value.data)),
+        }
+    }
+
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Option<Duration>) -> anyhow::Result<()> {
+        // Expires immediately, same as with Redis.
+        if ttl.is_some_and(|ttl| ttl.as_millis() == 0) {
+            return self.delete(key).await;
+        }
+
+        self.inner.insert(
+            key.to_string(),
+            CacheValue {
+                data: value,
+                expires_at: ttl.map(|ttl| Instant::now() + ttl),
+            },
+        );
+
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        self.inner.invalidate(&key.to_string());
+        Ok(())
+    }
+}
+
+impl Default for InMemoryHooksCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cache for InMemoryHooksCache {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, anyhow::Result<Option<Vec<u8>>>> {
+        Box::pin(self.get(key))
+    }
+
+    fn set<'a>(&'a self, key: &'a str, value: Vec<u8>, ttl: Option<Duration>) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(self.set(key, value, ttl))
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(self.delete(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::InMemoryHooksCache;
+
+    #[tokio::test]
+    async fn values_expire_after_their_ttl() {
+        let cache = InMemoryHooksCache::new();
+
+        cache
+            .set("short", b"short".to_vec(), Some(Duration::from_millis(50)))
+            .await
+            .unwrap();
+        cache.set("forever", b"forever".to_vec(), None).await.unwrap();
+
+        assert_eq!(cache.get("short").await.unwrap(), Some(b"short".to_vec()));
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert_eq!(cache.get("short").await.unwrap(), None);
+        assert_eq!(cache.get("forever").await.unwrap(), Some(b"forever".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn zero_ttl_deletes_the_value() {
+        let cache = InMemoryHooksCache::new();
+
+        cache.set("key", b"value".to_vec(), None).await.unwrap();
+        cache.set("key", b"other".to_vec(), Some(Duration::ZERO)).await.unwrap();
+
+        assert_eq!(cache.get("key").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn delete() {
+        let cache = InMemoryHooksCache::new();
+
+        cache.set("key", b"value".to_vec(), None).await.unwrap();
+        cache.delete("key").await.unwrap();
+
+        assert_eq!(cache.get("key").await.unwrap(), None);
+    }
+}
@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use deadpool::managed::Object;
+use grafbase_telemetry::span::GRAFBASE_TARGET;
+use redis::{AsyncCommands, SetOptions};
+use wasi_component_loader::{BoxFuture, Cache};
+
+use crate::redis::{Manager, Pool};
+
+/// A hooks cache in Redis, shared by all hook instances and gateway replicas.
+pub struct RedisHooksCache {
+    pool: Pool,
+    key_prefix: String,
+}
+
+impl RedisHooksCache {
+    pub fn new(pool: Pool, key_prefix: &str) -> Self {
+        RedisHooksCache {
+            pool,
+            key_prefix: key_prefix.to_string(),
+        }
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        let mut connection = self.connection().await?;
+        Ok(connection.get(self.key(key)).await?)
+    }
+
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Option<Duration>) -> anyhow::Result<()> {
+        let mut connection = self.connection().await?;
+
+        match ttl {
+            // Redis rejects a zero expiry.
+            Some(ttl) if ttl.as_millis() == 0 => Ok(connection.del(self.key(key)).await?),
+            Some(ttl) => {
+                let options = SetOptions::default().with_expiration(redis::SetExpiry::PX(ttl.as_millis() as usize));
+                Ok(connection.set_options(self.key(key), value, options).await?)
+            }
+            None => Ok(connection.set(self.key(key), value).await?),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        let mut connection = self.connection().await?;
+        Ok(connection.del(self.key(key)).await?)
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}-hooks-{key}", self.key_prefix)
+    }
+
+    async fn connection(&self) -> Result<Object<Manager>, anyhow::Error> {
+        match self.pool.get().await {
+            Ok(conn) => Ok(conn),
+            Err(error) => {
+                tracing::error!(target: GRAFBASE_TARGET, "error fetching a Redis connection: {error}");
+                anyhow::bail!("error fetching a redis connection: {error}");
+            }
+        }
+    }
+}
+
+impl Cache for RedisHooksCache {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, anyhow::Result<Option<Vec<u8>>>> {
+        Box::pin(self.get(key))
+    }
+
+    fn set<'a>(&'a self, key: &'a str, value: Vec<u8>, ttl: Option<Duration>) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(self.set(key, value, ttl))
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(self.delete(key))
+    }
+}
@@ -1,7 +1,7 @@
 use http::HeaderMap;
 use runtime::{
     error::{PartialErrorCode, PartialGraphqlError},
-    hooks::SubgraphHooks,
+    hooks::{ExecutedSubgraphRequest, SubgraphHooks},
 };
 use tracing::instrument;
 use url::Url;
@@ -26,7 +26,14 @@ impl SubgraphHooks<Context> for HooksWasi {
             .subgraph
             .get()
             .await
-            .on_subgraph_request(context.clone(), subgraph_name, method, url, headers)
+            .on_subgraph_request(
+                context.snapshot(),
+                context.access_token(),
+                subgraph_name,
+                method,
+                url,
+                headers,
+            )
             .await
             .map_err(|err| match err {
                 wasi_component_loader::Error::Internal(err) => {
@@ -36,4 +43,41 @@ impl SubgraphHooks<Context> for HooksWasi {
                 wasi_component_loader::Error::Guest(err) => guest_error_as_gql(err, PartialErrorCode::HookError),
             })
     }
+
+    #[instrument(skip_all)]
+    async fn on_subgraph_response(
+        &self,
+        context: &Context,
+        request: ExecutedSubgraphRequest<'_>,
+    ) -> Result<(), PartialGraphqlError> {
+        let Some(ref hooks) = self.0 else {
+            return Ok(());
+        };
+
+        let headers = request.headers.clone();
+        let request = wasi_component_loader::ExecutedSubgraphRequest {
+            subgraph_name: request.subgraph_name.to_string(),
+            status: request.status.as_u16(),
+            duration_ms: request.duration.as_millis() as u64,
+            body: hooks.subgraph_response_body.then(|| request.body.to_vec()),
+        };
+
+        let entries = hooks
+            .subgraph_response
+            .get()
+            .await
+            .on_subgraph_response(context.snapshot(), context.access_token(), request, headers)
+            .await
+            .map_err(|err| match err {
+                wasi_component_loader::Error::Internal(err) => {
+                    tracing::error!("on_subgraph_response error: {err}");
+                    PartialGraphqlError::internal_hook_error()
+                }
+                wasi_component_loader::Error::Guest(err) => guest_error_as_gql(err, PartialErrorCode::HookError),
+            })?;
+
+        context.extend(entries);
+
+        Ok(())
+    }
 }
@@ -24,8 +24,10 @@ pub use kv::*;
 pub use pg::{LazyPgConnectionsPool, LocalPgTransportFactory};
 pub use ufd_invoker::UdfInvokerImpl;
 
+#[cfg(all(feature = "wasi", feature = "redis"))]
+pub use hooks::RedisHooksCache;
 #[cfg(feature = "wasi")]
-pub use hooks::{ComponentLoader, HooksWasi, HooksWasiConfig};
+pub use hooks::{ComponentLoader, HooksWasi, HooksWasiConfig, InMemoryHooksCache, SharedHooksCache};
 
 pub use crate::log::LogEventReceiverImpl;
 
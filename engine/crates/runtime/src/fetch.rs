@@ -4,7 +4,7 @@ use bytes::Bytes;
 use futures_util::stream::BoxStream;
 use serde_json::Value;
 
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum FetchError {
     #[error("{0}")]
     AnyError(String),
@@ -26,10 +26,47 @@ pub struct FetchRequest<'a> {
     pub headers: http::HeaderMap,
     pub json_body: Bytes,
     pub timeout: Duration,
+    /// Compression of the request body, sent uncompressed if `None`.
+    pub compression: Option<Compression>,
+    pub client_settings: HttpClientSettings,
+}
+
+/// Compression algorithm of a request body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Compression {
+    Gzip,
+    Brotli,
+    Zstd,
+}
+
+impl Compression {
+    /// Value of the `Content-Encoding` header.
+    pub fn content_encoding(&self) -> &'static str {
+        match self {
+            Compression::Gzip => "gzip",
+            Compression::Brotli => "br",
+            Compression::Zstd => "zstd",
+        }
+    }
+}
+
+/// Settings of the HTTP client sending a request. Requests with the same settings share their
+/// connection pool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct HttpClientSettings {
+    /// Advertises compressed encodings in `Accept-Encoding` and decodes the responses.
+    pub decompress_responses: bool,
+    pub pool_max_idle_per_host: Option<usize>,
+    pub pool_idle_timeout: Option<Duration>,
+    pub tcp_keepalive: Option<Duration>,
+    pub http2_prior_knowledge: bool,
+    pub connect_timeout: Option<Duration>,
 }
 
 #[derive(Clone)]
 pub struct FetchResponse {
+    pub status: http::StatusCode,
+    pub headers: http::HeaderMap,
     pub bytes: Bytes,
 }
 
@@ -38,6 +75,26 @@ pub struct GraphqlRequest<'a> {
     pub headers: http::HeaderMap,
     pub query: &'a str,
     pub variables: Value,
+    pub protocol: SubscriptionProtocol,
+    /// Reconnect if neither a message nor a heartbeat is received for this long. Only applies
+    /// to HTTP based protocols, websockets have their own pings.
+    pub heartbeat_timeout: Option<Duration>,
+    /// Consecutive reconnection attempts before giving up, the fetcher default if not set.
+    pub max_reconnect_attempts: Option<u32>,
+    /// Settings of the HTTP client used by the SSE and multipart protocols.
+    pub client_settings: HttpClientSettings,
+}
+
+/// Transport used for streaming requests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SubscriptionProtocol {
+    /// `graphql-transport-ws` over websockets
+    #[default]
+    Websocket,
+    /// GraphQL over server-sent events, in the distinct connections mode
+    ServerSentEvents,
+    /// Multipart HTTP subscriptions
+    Multipart,
 }
 
 #[async_trait::async_trait]
@@ -5,11 +5,15 @@ mod test_utils;
 pub use test_utils::*;
 use url::Url;
 
-use std::future::Future;
+use std::{future::Future, time::Duration};
 
+use grafbase_telemetry::{gql_response_status::GraphqlResponseStatus, metrics::OperationType};
 pub use http::HeaderMap;
 
-use crate::error::{PartialErrorCode, PartialGraphqlError};
+use crate::{
+    auth::AccessToken,
+    error::{PartialErrorCode, PartialGraphqlError},
+};
 
 pub struct NodeDefinition<'a> {
     pub type_name: &'a str,
@@ -32,6 +36,25 @@ impl std::fmt::Display for EdgeDefinition<'_> {
     }
 }
 
+/// Summary of an executed operation, provided to the gateway response hook.
+pub struct ExecutedOperation<'a> {
+    /// Not present if the request failed before the operation could be determined.
+    pub name: Option<&'a str>,
+    /// Not present if the request failed before the operation could be determined.
+    pub ty: Option<OperationType>,
+    pub status: GraphqlResponseStatus,
+    pub duration: Duration,
+}
+
+/// A subgraph response, provided to the subgraph response hook.
+pub struct ExecutedSubgraphRequest<'a> {
+    pub subgraph_name: &'a str,
+    pub status: http::StatusCode,
+    pub headers: &'a HeaderMap,
+    pub duration: Duration,
+    pub body: &'a [u8],
+}
+
 // Used as a sort of convenient type alias
 pub trait Anything<'a>: serde::Serialize + serde::de::Deserializer<'a> + Send {}
 impl<'a, T> Anything<'a> for T where T: serde::Serialize + serde::de::Deserializer<'a> + Send {}
@@ -47,6 +70,20 @@ pub trait Hooks: Send + Sync + 'static {
         headers: HeaderMap,
     ) -> impl Future<Output = Result<(Self::Context, HeaderMap), PartialGraphqlError>> + Send;
 
+    /// Called once the request is authenticated, making the access token available to the
+    /// authorization and subgraph hooks.
+    fn authenticated(&self, context: &mut Self::Context, access_token: &AccessToken);
+
+    /// Called with the final response headers, before the response is sent. For streaming
+    /// responses it's called before the first payload, so the operation status is the one of the
+    /// initial response.
+    fn on_gateway_response(
+        &self,
+        context: &Self::Context,
+        operation: ExecutedOperation<'_>,
+        headers: HeaderMap,
+    ) -> impl Future<Output = Result<HeaderMap, PartialGraphqlError>> + Send;
+
     fn authorized(&self) -> &impl AuthorizedHooks<Self::Context>;
 
     fn subgraph(&self) -> &impl SubgraphHooks<Self::Context>;
@@ -68,6 +105,12 @@ pub trait AuthorizedHooks<Context>: Send + Sync + 'static {
         metadata: Option<impl Anything<'a>>,
     ) -> impl Future<Output = AuthorizationVerdict> + Send;
 
+    fn authorize_policies(
+        &self,
+        context: &Context,
+        policies: Vec<String>,
+    ) -> impl Future<Output = Result<Vec<String>, PartialGraphqlError>> + Send;
+
     fn authorize_node_post_execution<'a>(
         &self,
         context: &Context,
@@ -113,6 +156,12 @@ pub trait SubgraphHooks<Context>: Send + Sync + 'static {
         url: &Url,
         headers: HeaderMap,
     ) -> impl Future<Output = Result<HeaderMap, PartialGraphqlError>> + Send;
+
+    fn on_subgraph_response(
+        &self,
+        context: &Context,
+        request: ExecutedSubgraphRequest<'_>,
+    ) -> impl Future<Output = Result<(), PartialGraphqlError>> + Send;
 }
 
 // ---------------------------//
@@ -125,6 +174,17 @@ impl Hooks for () {
         Ok(((), headers))
     }
 
+    fn authenticated(&self, _: &mut Self::Context, _: &AccessToken) {}
+
+    async fn on_gateway_response(
+        &self,
+        _: &Self::Context,
+        _: ExecutedOperation<'_>,
+        headers: HeaderMap,
+    ) -> Result<HeaderMap, PartialGraphqlError> {
+        Ok(headers)
+    }
+
     fn authorized(&self) -> &impl AuthorizedHooks<()> {
         self
     }
@@ -160,6 +220,13 @@ impl AuthorizedHooks<()> for () {
         ))
     }
 
+    async fn authorize_policies(&self, _: &(), _: Vec<String>) -> Result<Vec<String>, PartialGraphqlError> {
+        Err(PartialGraphqlError::new(
+            "@policy directive cannot be used, so access was denied",
+            PartialErrorCode::Unauthorized,
+        ))
+    }
+
     async fn authorize_node_post_execution<'a>(
         &self,
         _: &(),
@@ -228,4 +295,8 @@ impl SubgraphHooks<()> for () {
     ) -> Result<HeaderMap, PartialGraphqlError> {
         Ok(headers)
     }
+
+    async fn on_subgraph_response(&self, _: &(), _: ExecutedSubgraphRequest<'_>) -> Result<(), PartialGraphqlError> {
+        Ok(())
+    }
 }
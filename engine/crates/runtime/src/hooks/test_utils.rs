@@ -23,6 +23,19 @@ pub trait DynHooks: Send + Sync + 'static {
         Ok(headers)
     }
 
+    fn authenticated(&self, context: &mut DynHookContext, access_token: &AccessToken) {
+        context.typed_insert(access_token.clone());
+    }
+
+    async fn on_gateway_response(
+        &self,
+        context: &DynHookContext,
+        operation: ExecutedOperation<'_>,
+        headers: HeaderMap,
+    ) -> Result<HeaderMap, PartialGraphqlError> {
+        Ok(headers)
+    }
+
     async fn authorize_edge_pre_execution(
         &self,
         context: &DynHookContext,
@@ -48,6 +61,17 @@ pub trait DynHooks: Send + Sync + 'static {
         ))
     }
 
+    async fn authorize_policies(
+        &self,
+        context: &DynHookContext,
+        policies: Vec<String>,
+    ) -> Result<Vec<String>, PartialGraphqlError> {
+        Err(PartialGraphqlError::new(
+            "authorize_policies is not implemented",
+            PartialErrorCode::Unauthorized,
+        ))
+    }
+
     async fn authorize_node_post_execution(
         &self,
         context: &DynHookContext,
@@ -110,6 +134,14 @@ pub trait DynHooks: Send + Sync + 'static {
     ) -> Result<HeaderMap, PartialGraphqlError> {
         Ok(headers)
     }
+
+    async fn on_subgraph_response(
+        &self,
+        context: &DynHookContext,
+        request: ExecutedSubgraphRequest<'_>,
+    ) -> Result<(), PartialGraphqlError> {
+        Ok(())
+    }
 }
 
 #[derive(Default)]
@@ -128,6 +160,15 @@ impl DynHookContext {
             .and_then(|value| value.downcast_ref::<T>())
     }
 
+    pub fn typed_get_mut<T>(&mut self) -> Option<&mut T>
+    where
+        T: 'static + Send + Sync,
+    {
+        self.by_type
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_mut::<T>())
+    }
+
     pub fn typed_insert<T>(&mut self, value: T)
     where
         T: 'static + Send + Sync,
@@ -177,6 +218,19 @@ impl Hooks for DynamicHooks {
         Ok((context, headers))
     }
 
+    fn authenticated(&self, context: &mut Self::Context, access_token: &AccessToken) {
+        self.0.authenticated(context, access_token)
+    }
+
+    async fn on_gateway_response(
+        &self,
+        context: &Self::Context,
+        operation: ExecutedOperation<'_>,
+        headers: HeaderMap,
+    ) -> Result<HeaderMap, PartialGraphqlError> {
+        self.0.on_gateway_response(context, operation, headers).await
+    }
+
     fn authorized(&self) -> &impl AuthorizedHooks<Self::Context> {
         self
     }
@@ -204,6 +258,14 @@ impl AuthorizedHooks<DynHookContext> for DynamicHooks {
             .await
     }
 
+    async fn authorize_policies(
+        &self,
+        context: &DynHookContext,
+        policies: Vec<String>,
+    ) -> Result<Vec<String>, PartialGraphqlError> {
+        self.0.authorize_policies(context, policies).await
+    }
+
     async fn authorize_node_post_execution<'a>(
         &self,
         context: &DynHookContext,
@@ -321,6 +383,14 @@ impl SubgraphHooks<DynHookContext> for DynamicHooks {
             .on_subgraph_request(context, subgraph_name, method, url, headers)
             .await
     }
+
+    async fn on_subgraph_response(
+        &self,
+        context: &DynHookContext,
+        request: ExecutedSubgraphRequest<'_>,
+    ) -> Result<(), PartialGraphqlError> {
+        self.0.on_subgraph_response(context, request).await
+    }
 }
 
 pub struct DynWrapper<T>(T);
@@ -343,6 +413,26 @@ impl<H: Hooks> DynHooks for DynWrapper<H> {
         .boxed()
     }
 
+    fn authenticated(&self, context: &mut DynHookContext, access_token: &AccessToken) {
+        if let Some(context) = context.typed_get_mut() {
+            Hooks::authenticated(&self.0, context, access_token);
+        }
+    }
+
+    fn on_gateway_response<'a, 'b, 'c, 'fut>(
+        &'a self,
+        context: &'b DynHookContext,
+        operation: ExecutedOperation<'c>,
+        headers: HeaderMap,
+    ) -> BoxFuture<'fut, Result<HeaderMap, PartialGraphqlError>>
+    where
+        'a: 'fut,
+        'b: 'fut,
+        'c: 'fut,
+    {
+        Hooks::on_gateway_response(&self.0, context.typed_get().unwrap(), operation, headers).boxed()
+    }
+
     // FIXME: Had to write them explicitly because of: https://github.com/rust-lang/rust/issues/100013
     fn authorize_edge_pre_execution<'a, 'b, 'c, 'fut>(
         &'a self,
@@ -377,6 +467,20 @@ impl<H: Hooks> DynHooks for DynWrapper<H> {
             .boxed()
     }
 
+    fn authorize_policies<'a, 'b, 'fut>(
+        &'a self,
+        context: &'b DynHookContext,
+        policies: Vec<String>,
+    ) -> BoxFuture<'fut, Result<Vec<String>, PartialGraphqlError>>
+    where
+        'a: 'fut,
+        'b: 'fut,
+    {
+        Hooks::authorized(&self.0)
+            .authorize_policies(context.typed_get().unwrap(), policies)
+            .boxed()
+    }
+
     fn authorize_node_post_execution<'a, 'b, 'c, 'fut>(
         &'a self,
         context: &'b DynHookContext,
@@ -463,4 +567,19 @@ impl<H: Hooks> DynHooks for DynWrapper<H> {
             .on_subgraph_request(context.typed_get().unwrap(), subgraph_name, method, url, headers)
             .boxed()
     }
+
+    fn on_subgraph_response<'a, 'b, 'c, 'fut>(
+        &'a self,
+        context: &'b DynHookContext,
+        request: ExecutedSubgraphRequest<'c>,
+    ) -> BoxFuture<'fut, Result<(), PartialGraphqlError>>
+    where
+        'a: 'fut,
+        'b: 'fut,
+        'c: 'fut,
+    {
+        Hooks::subgraph(&self.0)
+            .on_subgraph_response(context.typed_get().unwrap(), request)
+            .boxed()
+    }
 }
@@ -0,0 +1,36 @@
+use opentelemetry::{
+    metrics::{Counter, Meter, UpDownCounter},
+    KeyValue,
+};
+
+/// Metrics of the subgraph circuit breakers.
+#[derive(Clone)]
+pub struct SubgraphCircuitBreakerMetrics {
+    open_circuits: UpDownCounter<i64>,
+    rejected_requests: Counter<u64>,
+}
+
+impl SubgraphCircuitBreakerMetrics {
+    pub fn build(meter: &Meter) -> Self {
+        Self {
+            open_circuits: meter.i64_up_down_counter("subgraph_circuit_breaker_open").init(),
+            rejected_requests: meter.u64_counter("subgraph_circuit_breaker_rejected_requests").init(),
+        }
+    }
+
+    pub fn record_opened(&self, subgraph_name: &str) {
+        self.open_circuits.add(1, &attributes(subgraph_name));
+    }
+
+    pub fn record_closed(&self, subgraph_name: &str) {
+        self.open_circuits.add(-1, &attributes(subgraph_name));
+    }
+
+    pub fn record_rejected_request(&self, subgraph_name: &str) {
+        self.rejected_requests.add(1, &attributes(subgraph_name));
+    }
+}
+
+fn attributes(subgraph_name: &str) -> [KeyValue; 1] {
+    [KeyValue::new("subgraph.name", subgraph_name.to_string())]
+}
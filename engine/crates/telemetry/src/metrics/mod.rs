@@ -1,11 +1,17 @@
+mod circuit_breaker;
 mod operation;
 mod request;
+mod shadow;
+mod subscription;
 
 use std::borrow::Cow;
 
+pub use circuit_breaker::*;
 use opentelemetry::metrics::{Meter, MeterProvider};
 pub use operation::*;
 pub use request::*;
+pub use shadow::*;
+pub use subscription::*;
 
 pub fn meter_from_global_provider() -> Meter {
     meter(&opentelemetry::global::meter_provider())
@@ -8,6 +8,7 @@ use crate::{gql_response_status::GraphqlResponseStatus, grafbase_client::Client}
 #[derive(Clone)]
 pub struct GraphqlOperationMetrics {
     latency: Histogram<u64>,
+    estimated_cost: Histogram<u64>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -72,9 +73,27 @@ impl GraphqlOperationMetrics {
     pub fn build(meter: &Meter) -> Self {
         Self {
             latency: meter.u64_histogram("gql_operation_latency").init(),
+            estimated_cost: meter.u64_histogram("gql_operation_estimated_cost").init(),
         }
     }
 
+    /// Records the cost estimated by demand control, whether the operation was rejected or not.
+    pub fn record_estimated_cost(&self, operation: &OperationMetricsAttributes, cost: u64, rejected: bool) {
+        use base64::{engine::general_purpose::STANDARD, Engine as _};
+        let mut attributes = vec![
+            KeyValue::new(
+                "gql.operation.query_hash",
+                STANDARD.encode(operation.sanitized_query_hash),
+            ),
+            KeyValue::new("gql.operation.type", operation.ty.as_str()),
+            KeyValue::new("gql.operation.cost.rejected", rejected),
+        ];
+        if let Some(name) = &operation.name {
+            attributes.push(KeyValue::new("gql.operation.name", name.clone()));
+        }
+        self.estimated_cost.record(cost, &attributes);
+    }
+
     pub fn record(
         &self,
         GraphqlRequestMetricsAttributes {
@@ -0,0 +1,49 @@
+use opentelemetry::{
+    metrics::{Counter, Meter},
+    KeyValue,
+};
+
+/// Outcome of a shadow request compared with the subgraph response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShadowComparison {
+    /// Both responses are identical.
+    Match,
+    /// The responses differ.
+    Mismatch,
+    /// The shadow request failed or its response couldn't be compared.
+    Error,
+}
+
+impl ShadowComparison {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShadowComparison::Match => "match",
+            ShadowComparison::Mismatch => "mismatch",
+            ShadowComparison::Error => "error",
+        }
+    }
+}
+
+/// Metrics of the shadow traffic mirrored to candidate subgraphs.
+#[derive(Clone)]
+pub struct SubgraphShadowMetrics {
+    comparisons: Counter<u64>,
+}
+
+impl SubgraphShadowMetrics {
+    pub fn build(meter: &Meter) -> Self {
+        Self {
+            comparisons: meter.u64_counter("subgraph_shadow_comparisons").init(),
+        }
+    }
+
+    pub fn record_comparison(&self, subgraph_name: &str, comparison: ShadowComparison) {
+        self.comparisons.add(
+            1,
+            &[
+                KeyValue::new("subgraph.name", subgraph_name.to_string()),
+                KeyValue::new("shadow.comparison", comparison.as_str()),
+            ],
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use opentelemetry::{
+    metrics::{Histogram, Meter, UpDownCounter},
+    KeyValue,
+};
+
+/// Metrics of the subgraph subscriptions shared between clients. The ratio between the
+/// subscribers and the upstream subscriptions gives the fan-out.
+#[derive(Clone)]
+pub struct SubgraphSubscriptionMetrics {
+    upstream_subscriptions: UpDownCounter<i64>,
+    subscribers: UpDownCounter<i64>,
+    fan_out: Histogram<u64>,
+}
+
+impl SubgraphSubscriptionMetrics {
+    pub fn build(meter: &Meter) -> Self {
+        Self {
+            upstream_subscriptions: meter.i64_up_down_counter("subgraph_subscription_upstreams").init(),
+            subscribers: meter.i64_up_down_counter("subgraph_subscription_subscribers").init(),
+            fan_out: meter.u64_histogram("subgraph_subscription_fan_out").init(),
+        }
+    }
+
+    pub fn record_upstream_opened(&self, subgraph_name: &str) {
+        self.upstream_subscriptions.add(1, &attributes(subgraph_name));
+    }
+
+    /// Records the maximum number of subscribers the upstream subscription had.
+    pub fn record_upstream_closed(&self, subgraph_name: &str, max_subscribers: usize) {
+        let attributes = attributes(subgraph_name);
+        self.upstream_subscriptions.add(-1, &attributes);
+        self.fan_out.record(max_subscribers as u64, &attributes);
+    }
+
+    pub fn record_subscriber_added(&self, subgraph_name: &str) {
+        self.subscribers.add(1, &attributes(subgraph_name));
+    }
+
+    pub fn record_subscriber_removed(&self, subgraph_name: &str) {
+        self.subscribers.add(-1, &attributes(subgraph_name));
+    }
+}
+
+fn attributes(subgraph_name: &str) -> [KeyValue; 1] {
+    [KeyValue::new("subgraph.name", subgraph_name.to_string())]
+}
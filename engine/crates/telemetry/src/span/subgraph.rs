@@ -10,6 +10,8 @@ pub struct SubgraphRequestSpan<'a> {
     pub operation_type: &'a str,
     pub sanitized_query: &'a str,
     pub url: &'a Url,
+    /// Traffic variant serving the request, if the subgraph has any.
+    pub variant: Option<&'a str>,
 }
 
 impl<'a> SubgraphRequestSpan<'a> {
@@ -20,6 +22,7 @@ impl<'a> SubgraphRequestSpan<'a> {
             "otel.name" = format!("{SUBGRAPH_SPAN_NAME}:{}", self.name),
             "subgraph.name" = self.name,
             "subgraph.url" = self.url.as_str(),
+            "subgraph.variant" = self.variant,
             "gql.operation.type" = self.operation_type,
             "gql.operation.query" = self.sanitized_query,
             "gql.response.status" = Empty,
@@ -27,6 +30,7 @@ impl<'a> SubgraphRequestSpan<'a> {
             "gql.response.data_is_null" = Empty,
             "gql.response.request_errors_count" = Empty,
             "gql.response.error" = Empty,
+            "http.response.status_code" = Empty,
         )
     }
 }
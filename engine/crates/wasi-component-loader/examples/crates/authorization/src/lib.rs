@@ -2,7 +2,7 @@
 mod bindings;
 
 use bindings::{
-    component::grafbase::types::{Context, EdgeDefinition, Error, Headers, NodeDefinition, SharedContext},
+    component::grafbase::types::{AccessToken, Context, EdgeDefinition, Error, Headers, NodeDefinition, SharedContext},
     exports::component::grafbase::{authorization, gateway_request},
 };
 
@@ -26,6 +26,7 @@ impl gateway_request::Guest for Component {
 impl authorization::Guest for Component {
     fn authorize_edge_pre_execution(
         context: SharedContext,
+        _: AccessToken,
         _: EdgeDefinition,
         arguments: String,
         _: String,
@@ -42,7 +43,12 @@ impl authorization::Guest for Component {
         Ok(())
     }
 
-    fn authorize_node_pre_execution(context: SharedContext, _: NodeDefinition, metadata: String) -> Result<(), Error> {
+    fn authorize_node_pre_execution(
+        context: SharedContext,
+        _: AccessToken,
+        _: NodeDefinition,
+        metadata: String,
+    ) -> Result<(), Error> {
         let auth_header = context.get("entitlement");
 
         if Some(metadata) != auth_header {
@@ -57,6 +63,7 @@ impl authorization::Guest for Component {
 
     fn authorize_parent_edge_post_execution(
         context: SharedContext,
+        _: AccessToken,
         _: EdgeDefinition,
         parents: Vec<String>,
         _: String,
@@ -83,6 +90,7 @@ impl authorization::Guest for Component {
 
     fn authorize_edge_node_post_execution(
         context: SharedContext,
+        _: AccessToken,
         _: EdgeDefinition,
         nodes: Vec<String>,
         _: String,
@@ -109,6 +117,7 @@ impl authorization::Guest for Component {
 
     fn authorize_edge_post_execution(
         context: SharedContext,
+        _: AccessToken,
         _: EdgeDefinition,
         edges: Vec<(String, Vec<String>)>,
         _: String,
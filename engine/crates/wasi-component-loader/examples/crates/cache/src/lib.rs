@@ -0,0 +1,37 @@
+#[allow(warnings)]
+mod bindings;
+
+use bindings::{
+    component::grafbase::{
+        cache,
+        types::{Context, Error, Headers},
+    },
+    exports::component::grafbase::gateway_request,
+};
+
+struct Component;
+
+impl gateway_request::Guest for Component {
+    fn on_gateway_request(context: Context, headers: Headers) -> Result<(), Error> {
+        let ttl_ms = headers.get("ttl-ms").map(|value| value.parse().unwrap());
+
+        context.set("before-set", &lookup("key"));
+
+        cache::set_with_ttl("key", b"value", ttl_ms);
+        context.set("after-set", &lookup("key"));
+
+        cache::delete("key");
+        context.set("after-delete", &lookup("key"));
+
+        Ok(())
+    }
+}
+
+fn lookup(key: &str) -> String {
+    match cache::get(key) {
+        Some(value) => String::from_utf8(value).unwrap(),
+        None => "miss".to_string(),
+    }
+}
+
+bindings::export!(Component with_types_in bindings);
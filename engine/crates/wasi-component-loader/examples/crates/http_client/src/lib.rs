@@ -0,0 +1,41 @@
+#[allow(warnings)]
+mod bindings;
+
+use bindings::{
+    component::grafbase::{
+        http_client::{self, HttpError, HttpMethod, HttpRequest},
+        types::{Context, Error, Headers},
+    },
+    exports::component::grafbase::gateway_request,
+};
+
+struct Component;
+
+impl gateway_request::Guest for Component {
+    fn on_gateway_request(context: Context, headers: Headers) -> Result<(), Error> {
+        let url = headers.get("url").unwrap();
+        let timeout_ms = headers.get("timeout-ms").map(|value| value.parse().unwrap());
+
+        let request = HttpRequest {
+            method: HttpMethod::Get,
+            url,
+            headers: vec![("x-guest".to_string(), "http-client".to_string())],
+            body: Vec::new(),
+            timeout_ms,
+        };
+
+        match http_client::execute(&request) {
+            Ok(response) => {
+                context.set("status", &response.status.to_string());
+                context.set("body", &String::from_utf8(response.body).unwrap());
+            }
+            Err(HttpError::NotAllowed) => context.set("error", "not-allowed"),
+            Err(HttpError::Timeout) => context.set("error", "timeout"),
+            Err(HttpError::Request(message)) => context.set("error", &format!("request: {message}")),
+        }
+
+        Ok(())
+    }
+}
+
+bindings::export!(Component with_types_in bindings);
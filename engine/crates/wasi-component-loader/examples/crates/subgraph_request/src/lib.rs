@@ -8,6 +8,7 @@ struct Component;
 impl subgraph_request::Guest for Component {
     fn on_subgraph_request(
         context: subgraph_request::SharedContext,
+        _: subgraph_request::AccessToken,
         subgraph_name: String,
         method: String,
         url: String,
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use wasmtime::{
+    component::{LinkerInstance, Resource, ResourceType},
+    StoreContextMut,
+};
+
+use crate::{
+    names::{ACCESS_TOKEN_CLAIMS_METHOD, ACCESS_TOKEN_IS_ANONYMOUS_METHOD, ACCESS_TOKEN_RESOURCE},
+    state::WasiState,
+};
+
+/// The authenticated access token of a request.
+#[derive(Debug, Clone)]
+pub struct AccessToken {
+    /// Whether the request was made without a token
+    pub anonymous: bool,
+    /// The token claims, serialized as a JSON object
+    pub claims: String,
+}
+
+impl AccessToken {
+    /// An access token for a request made without a token.
+    pub fn anonymous() -> Self {
+        Self {
+            anonymous: true,
+            claims: String::from("{}"),
+        }
+    }
+}
+
+/// The access token of a request, read-only.
+pub type SharedAccessToken = Arc<AccessToken>;
+
+/// Map read-only access token resource, with accessors to the guest component.
+///
+/// ```ignore
+/// interface types {
+///     resource access-token {
+///         is-anonymous: func() -> bool;
+///         claims: func() -> string;
+///     }
+/// }
+/// ```
+pub(crate) fn map(types: &mut LinkerInstance<'_, WasiState>) -> crate::Result<()> {
+    types.resource(
+        ACCESS_TOKEN_RESOURCE,
+        ResourceType::host::<SharedAccessToken>(),
+        |_, _| Ok(()),
+    )?;
+
+    types.func_wrap(ACCESS_TOKEN_IS_ANONYMOUS_METHOD, is_anonymous)?;
+    types.func_wrap(ACCESS_TOKEN_CLAIMS_METHOD, claims)?;
+
+    Ok(())
+}
+
+/// Whether the request was made without a token.
+///
+/// `is-anonymous: func() -> bool`
+fn is_anonymous(
+    store: StoreContextMut<'_, WasiState>,
+    (this,): (Resource<SharedAccessToken>,),
+) -> anyhow::Result<(bool,)> {
+    let access_token = store.data().get(&this).expect("must exist");
+
+    Ok((access_token.anonymous,))
+}
+
+/// The token claims, serialized as a JSON object.
+///
+/// `claims: func() -> string`
+fn claims(store: StoreContextMut<'_, WasiState>, (this,): (Resource<SharedAccessToken>,)) -> anyhow::Result<(String,)> {
+    let access_token = store.data().get(&this).expect("must exist");
+
+    Ok((access_token.claims.clone(),))
+}
@@ -0,0 +1,115 @@
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+
+use grafbase_telemetry::span::GRAFBASE_TARGET;
+use wasmtime::{component::Linker, StoreContextMut};
+
+use crate::{
+    names::{CACHE_DELETE_FUNCTION, CACHE_GET_FUNCTION, CACHE_INTERFACE, CACHE_SET_WITH_TTL_FUNCTION},
+    state::WasiState,
+};
+
+/// A boxed future returned by the cache methods.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// A key-value cache available to the guest. The same cache is shared by all component
+/// instances, and by all gateway replicas if backed by a shared storage.
+pub trait Cache: Send + Sync {
+    /// Fetches a value from the cache, if existing and not expired.
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, anyhow::Result<Option<Vec<u8>>>>;
+
+    /// Stores a value in the cache, with an optional expiry TTL. A TTL under a millisecond
+    /// expires the value immediately, deleting any existing one.
+    fn set<'a>(&'a self, key: &'a str, value: Vec<u8>, ttl: Option<Duration>) -> BoxFuture<'a, anyhow::Result<()>>;
+
+    /// Deletes a value from the cache.
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, anyhow::Result<()>>;
+}
+
+/// A cache shared by all component instances.
+pub type SharedCache = Arc<dyn Cache>;
+
+/// A cache which never stores anything.
+impl Cache for () {
+    fn get<'a>(&'a self, _: &'a str) -> BoxFuture<'a, anyhow::Result<Option<Vec<u8>>>> {
+        Box::pin(async { Ok(None) })
+    }
+
+    fn set<'a>(&'a self, _: &'a str, _: Vec<u8>, _: Option<Duration>) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn delete<'a>(&'a self, _: &'a str) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Map the shared cache to the guest component. Errors from the cache storage are logged,
+/// and a failed lookup is a cache miss for the guest.
+///
+/// ```ignore
+/// interface cache {
+///     get: func(key: string) -> option<list<u8>>;
+///     set-with-ttl: func(key: string, value: list<u8>, ttl-ms: option<u64>);
+///     delete: func(key: string);
+/// }
+/// ```
+pub(crate) fn map(linker: &mut Linker<WasiState>) -> crate::Result<()> {
+    let mut instance = linker.instance(CACHE_INTERFACE)?;
+
+    instance.func_wrap_async(CACHE_GET_FUNCTION, get)?;
+    instance.func_wrap_async(CACHE_SET_WITH_TTL_FUNCTION, set_with_ttl)?;
+    instance.func_wrap_async(CACHE_DELETE_FUNCTION, delete)?;
+
+    Ok(())
+}
+
+/// Fetches a value from the cache.
+fn get(
+    store: StoreContextMut<'_, WasiState>,
+    (key,): (String,),
+) -> Box<dyn Future<Output = anyhow::Result<(Option<Vec<u8>>,)>> + Send + '_> {
+    let cache = store.data().cache().clone();
+
+    Box::new(async move {
+        let value = cache.get(&key).await.unwrap_or_else(|error| {
+            tracing::error!(target: GRAFBASE_TARGET, "error reading the hooks cache: {error}");
+            None
+        });
+
+        Ok((value,))
+    })
+}
+
+/// Stores a value in the cache, with an optional TTL in milliseconds. Zero deletes the value.
+fn set_with_ttl(
+    store: StoreContextMut<'_, WasiState>,
+    (key, value, ttl_ms): (String, Vec<u8>, Option<u64>),
+) -> Box<dyn Future<Output = anyhow::Result<()>> + Send + '_> {
+    let cache = store.data().cache().clone();
+
+    Box::new(async move {
+        let ttl = ttl_ms.map(Duration::from_millis);
+
+        if let Err(error) = cache.set(&key, value, ttl).await {
+            tracing::error!(target: GRAFBASE_TARGET, "error writing to the hooks cache: {error}");
+        }
+
+        Ok(())
+    })
+}
+
+/// Deletes a value from the cache.
+fn delete(
+    store: StoreContextMut<'_, WasiState>,
+    (key,): (String,),
+) -> Box<dyn Future<Output = anyhow::Result<()>> + Send + '_> {
+    let cache = store.data().cache().clone();
+
+    Box::new(async move {
+        if let Err(error) = cache.delete(&key).await {
+            tracing::error!(target: GRAFBASE_TARGET, "error deleting from the hooks cache: {error}");
+        }
+
+        Ok(())
+    })
+}
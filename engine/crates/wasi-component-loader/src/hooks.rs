@@ -6,14 +6,16 @@ use anyhow::anyhow;
 use grafbase_telemetry::span::GRAFBASE_TARGET;
 use wasmtime::{
     component::{ComponentNamedList, Instance, Lift, Lower, Resource, TypedFunc},
-    Engine, Store,
+    Store,
 };
 
-use crate::{config::build_wasi_context, state::WasiState, ComponentLoader, Config, SharedContextMap};
+use crate::{config::build_wasi_context, state::WasiState, ComponentLoader, SharedAccessToken, SharedContextMap};
 
 pub(crate) mod authorization;
 pub(crate) mod gateway;
+pub(crate) mod gateway_response;
 pub(crate) mod subgraph;
+pub(crate) mod subgraph_response;
 
 /// A trait for components that can be recycled
 pub trait RecycleableComponentInstance: Sized + Send + 'static {
@@ -62,10 +64,14 @@ macro_rules! component_instance {
 pub(crate) use component_instance;
 
 /// Generic initialization of WASI components for all hooks.
-fn initialize_store(config: &Config, engine: &Engine) -> crate::Result<Store<WasiState>> {
-    let state = WasiState::new(build_wasi_context(config));
-
-    let mut store = Store::new(engine, state);
+fn initialize_store(loader: &ComponentLoader) -> crate::Result<Store<WasiState>> {
+    let state = WasiState::new(
+        build_wasi_context(loader.config()),
+        loader.http_client().clone(),
+        loader.cache().clone(),
+    );
+
+    let mut store = Store::new(loader.engine(), state);
     store.set_fuel(u64::MAX)?;
 
     // make this smaller to yield to the main thread more often
@@ -88,7 +94,7 @@ pub struct ComponentInstance {
 impl ComponentInstance {
     /// Creates a new instance of the authorization hook
     async fn new(loader: &ComponentLoader, interface_name: &'static str) -> crate::Result<Self> {
-        let mut store = initialize_store(loader.config(), loader.engine())?;
+        let mut store = initialize_store(loader)?;
 
         let instance = loader
             .linker()
@@ -104,24 +110,74 @@ impl ComponentInstance {
         })
     }
 
+    async fn call1<A1, R>(
+        &mut self,
+        name: &'static str,
+        context: SharedContextMap,
+        access_token: SharedAccessToken,
+        arg: A1,
+    ) -> crate::Result<Option<R>>
+    where
+        (Resource<SharedContextMap>, Resource<SharedAccessToken>, A1):
+            ComponentNamedList + Lower + Send + Sync + 'static,
+        (R,): ComponentNamedList + Lift + Send + Sync + 'static,
+    {
+        let Some(hook) = self.get_hook::<(Resource<SharedContextMap>, Resource<SharedAccessToken>, A1), (R,)>(name)
+        else {
+            return Ok(None);
+        };
+
+        let context = self.store.data_mut().push_resource(context)?;
+        let context_rep = context.rep();
+        let access_token = self.store.data_mut().push_resource(access_token)?;
+        let access_token_rep = access_token.rep();
+
+        let result = hook.call_async(&mut self.store, (context, access_token, arg)).await;
+
+        // We check if the hook call trapped, and if so we mark the instance poisoned.
+        //
+        // If no traps, we mark this hook so it can be called again.
+        if result.is_err() {
+            self.poisoned = true;
+        } else {
+            hook.post_return_async(&mut self.store).await?;
+        }
+
+        let result = result?.0;
+
+        // This is a bit ugly because we don't need it, but we need to clean the shared
+        // resources before exiting or this will leak RAM.
+        let _: SharedContextMap = self.store.data_mut().take_resource(context_rep)?;
+        let _: SharedAccessToken = self.store.data_mut().take_resource(access_token_rep)?;
+
+        Ok(Some(result))
+    }
+
     async fn call2<A1, A2, R>(
         &mut self,
         name: &'static str,
         context: SharedContextMap,
+        access_token: SharedAccessToken,
         args: (A1, A2),
     ) -> crate::Result<Option<R>>
     where
-        (Resource<SharedContextMap>, A1, A2): ComponentNamedList + Lower + Send + Sync + 'static,
+        (Resource<SharedContextMap>, Resource<SharedAccessToken>, A1, A2):
+            ComponentNamedList + Lower + Send + Sync + 'static,
         (R,): ComponentNamedList + Lift + Send + Sync + 'static,
     {
-        let Some(hook) = self.get_hook::<(Resource<SharedContextMap>, A1, A2), (R,)>(name) else {
+        let Some(hook) = self.get_hook::<(Resource<SharedContextMap>, Resource<SharedAccessToken>, A1, A2), (R,)>(name)
+        else {
             return Ok(None);
         };
 
         let context = self.store.data_mut().push_resource(context)?;
         let context_rep = context.rep();
+        let access_token = self.store.data_mut().push_resource(access_token)?;
+        let access_token_rep = access_token.rep();
 
-        let result = hook.call_async(&mut self.store, (context, args.0, args.1)).await;
+        let result = hook
+            .call_async(&mut self.store, (context, access_token, args.0, args.1))
+            .await;
 
         // We check if the hook call trapped, and if so we mark the instance poisoned.
         //
@@ -137,6 +193,7 @@ impl ComponentInstance {
         // This is a bit ugly because we don't need it, but we need to clean the shared
         // resources before exiting or this will leak RAM.
         let _: SharedContextMap = self.store.data_mut().take_resource(context_rep)?;
+        let _: SharedAccessToken = self.store.data_mut().take_resource(access_token_rep)?;
 
         Ok(Some(result))
     }
@@ -145,21 +202,27 @@ impl ComponentInstance {
         &mut self,
         name: &'static str,
         context: SharedContextMap,
+        access_token: SharedAccessToken,
         args: (A1, A2, A3),
     ) -> crate::Result<Option<R>>
     where
-        (Resource<SharedContextMap>, A1, A2, A3): ComponentNamedList + Lower + Send + Sync + 'static,
+        (Resource<SharedContextMap>, Resource<SharedAccessToken>, A1, A2, A3):
+            ComponentNamedList + Lower + Send + Sync + 'static,
         (R,): ComponentNamedList + Lift + Send + Sync + 'static,
     {
-        let Some(hook) = self.get_hook::<(Resource<SharedContextMap>, A1, A2, A3), (R,)>(name) else {
+        let Some(hook) =
+            self.get_hook::<(Resource<SharedContextMap>, Resource<SharedAccessToken>, A1, A2, A3), (R,)>(name)
+        else {
             return Ok(None);
         };
 
         let context = self.store.data_mut().push_resource(context)?;
         let context_rep = context.rep();
+        let access_token = self.store.data_mut().push_resource(access_token)?;
+        let access_token_rep = access_token.rep();
 
         let result = hook
-            .call_async(&mut self.store, (context, args.0, args.1, args.2))
+            .call_async(&mut self.store, (context, access_token, args.0, args.1, args.2))
             .await;
 
         // We check if the hook call trapped, and if so we mark the instance poisoned.
@@ -176,6 +239,7 @@ impl ComponentInstance {
         // This is a bit ugly because we don't need it, but we need to clean the shared
         // resources before exiting or this will leak RAM.
         let _: SharedContextMap = self.store.data_mut().take_resource(context_rep)?;
+        let _: SharedAccessToken = self.store.data_mut().take_resource(access_token_rep)?;
 
         Ok(Some(result))
     }
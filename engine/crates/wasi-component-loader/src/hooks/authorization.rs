@@ -1,11 +1,13 @@
 use wasmtime::component::{ComponentType, Lower};
 
 use crate::{
+    access_token::SharedAccessToken,
     context::SharedContextMap,
     names::{
         AUTHORIZATION_INTERFACE, AUTHORIZE_EDGE_NODE_POST_EXECUTION_HOOK_FUNCTION,
         AUTHORIZE_EDGE_POST_EXECUTION_HOOK_FUNCTION, AUTHORIZE_EDGE_PRE_EXECUTION_HOOK_FUNCTION,
         AUTHORIZE_NODE_PRE_EXECUTION_HOOK_FUNCTION, AUTHORIZE_PARENT_EDGE_POST_EXECUTION_HOOK_FUNCTION,
+        AUTHORIZE_POLICIES_HOOK_FUNCTION,
     },
     ComponentLoader, GuestResult,
 };
@@ -40,6 +42,7 @@ impl AuthorizationComponentInstance {
     pub async fn authorize_edge_pre_execution(
         &mut self,
         context: SharedContextMap,
+        access_token: SharedAccessToken,
         definition: EdgeDefinition,
         arguments: String,
         metadata: String,
@@ -47,6 +50,7 @@ impl AuthorizationComponentInstance {
         self.call3(
             AUTHORIZE_EDGE_PRE_EXECUTION_HOOK_FUNCTION,
             context,
+            access_token,
             (definition, arguments, metadata),
         )
         .await?
@@ -62,12 +66,14 @@ impl AuthorizationComponentInstance {
     pub async fn authorize_node_pre_execution(
         &mut self,
         context: SharedContextMap,
+        access_token: SharedAccessToken,
         definition: NodeDefinition,
         metadata: String,
     ) -> crate::Result<()> {
         self.call2(
             AUTHORIZE_NODE_PRE_EXECUTION_HOOK_FUNCTION,
             context,
+            access_token,
             (definition, metadata),
         )
         .await?
@@ -79,10 +85,28 @@ impl AuthorizationComponentInstance {
         })?
     }
 
+    /// Calls the authorize hook for all the policies of an operation, returning the granted ones
+    pub async fn authorize_policies(
+        &mut self,
+        context: SharedContextMap,
+        access_token: SharedAccessToken,
+        policies: Vec<String>,
+    ) -> crate::Result<Vec<String>> {
+        self.call1(AUTHORIZE_POLICIES_HOOK_FUNCTION, context, access_token, policies)
+            .await?
+            .map(|result: GuestResult<Vec<String>>| result.map_err(Into::into))
+            .ok_or_else(|| {
+                crate::Error::from(format!(
+                    "{AUTHORIZE_POLICIES_HOOK_FUNCTION} hook must be defined if using the @policy directive"
+                ))
+            })?
+    }
+
     /// Calls the post authorize hook for parent edge
     pub async fn authorize_parent_edge_post_execution(
         &mut self,
         context: SharedContextMap,
+        access_token: SharedAccessToken,
         definition: EdgeDefinition,
         parents: Vec<String>,
         metadata: String,
@@ -90,6 +114,7 @@ impl AuthorizationComponentInstance {
         self.call3(
             AUTHORIZE_PARENT_EDGE_POST_EXECUTION_HOOK_FUNCTION,
             context,
+            access_token,
             (definition, parents, metadata),
         )
         .await?
@@ -105,6 +130,7 @@ impl AuthorizationComponentInstance {
     pub async fn authorize_edge_node_post_execution(
         &mut self,
         context: SharedContextMap,
+        access_token: SharedAccessToken,
         definition: EdgeDefinition,
         nodes: Vec<String>,
         metadata: String,
@@ -112,6 +138,7 @@ impl AuthorizationComponentInstance {
         self.call3(
             AUTHORIZE_EDGE_NODE_POST_EXECUTION_HOOK_FUNCTION,
             context,
+            access_token,
             (definition, nodes, metadata),
         )
         .await?
@@ -127,6 +154,7 @@ impl AuthorizationComponentInstance {
     pub async fn authorize_edge_post_execution(
         &mut self,
         context: SharedContextMap,
+        access_token: SharedAccessToken,
         definition: EdgeDefinition,
         edges: Vec<(String, Vec<String>)>,
         metadata: String,
@@ -134,6 +162,7 @@ impl AuthorizationComponentInstance {
         self.call3(
             AUTHORIZE_EDGE_POST_EXECUTION_HOOK_FUNCTION,
             context,
+            access_token,
             (definition, edges, metadata),
         )
         .await?
@@ -0,0 +1,116 @@
+use http::HeaderMap;
+use wasmtime::component::{ComponentType, Lower};
+
+use crate::{
+    context::SharedContextMap,
+    names::{GATEWAY_RESPONSE_INTERFACE, ON_GATEWAY_RESPONSE_HOOK_FUNCTION},
+    ComponentLoader, GuestResult,
+};
+
+use super::{component_instance, ComponentInstance};
+
+/// The type of an executed operation.
+#[derive(Lower, ComponentType)]
+#[component(enum)]
+pub enum OperationType {
+    /// A query operation
+    #[component(name = "query")]
+    Query,
+    /// A mutation operation
+    #[component(name = "mutation")]
+    Mutation,
+    /// A subscription operation
+    #[component(name = "subscription")]
+    Subscription,
+}
+
+/// Status of a GraphQL response.
+#[derive(Lower, ComponentType)]
+#[component(variant)]
+pub enum GraphqlResponseStatus {
+    /// The operation was executed without errors
+    #[component(name = "success")]
+    Success,
+    /// Errors happened during the execution of the operation
+    #[component(name = "field-error")]
+    FieldError(FieldError),
+    /// The request failed before the execution, no data is present
+    #[component(name = "request-error")]
+    RequestError(RequestError),
+}
+
+/// Errors happening during the execution of an operation.
+#[derive(Lower, ComponentType)]
+#[component(record)]
+pub struct FieldError {
+    /// The number of errors
+    pub count: u64,
+    /// Whether the data is null
+    #[component(name = "data-is-null")]
+    pub data_is_null: bool,
+}
+
+/// Errors preventing the execution of an operation.
+#[derive(Lower, ComponentType)]
+#[component(record)]
+pub struct RequestError {
+    /// The number of errors
+    pub count: u64,
+}
+
+/// Summary of an executed operation.
+#[derive(Lower, ComponentType)]
+#[component(record)]
+pub struct ExecutedOperation {
+    /// The name of the operation, if any
+    pub name: Option<String>,
+    /// The type of the operation, if it could be determined
+    #[component(name = "operation-type")]
+    pub operation_type: Option<OperationType>,
+    /// The status of the response
+    pub status: GraphqlResponseStatus,
+    /// The time taken to execute the operation, in milliseconds
+    #[component(name = "duration-ms")]
+    pub duration_ms: u64,
+}
+
+component_instance!(GatewayResponseComponentInstance: GATEWAY_RESPONSE_INTERFACE);
+
+impl GatewayResponseComponentInstance {
+    /// Called once the operation is executed, before sending the response to the client.
+    pub async fn on_gateway_response(
+        &mut self,
+        context: SharedContextMap,
+        operation: ExecutedOperation,
+        headers: HeaderMap,
+    ) -> crate::Result<HeaderMap> {
+        let Some(hook) = self.get_hook::<_, (GuestResult<()>,)>(ON_GATEWAY_RESPONSE_HOOK_FUNCTION) else {
+            return Ok(headers);
+        };
+
+        // adds the data to the shared memory
+        let context = self.store.data_mut().push_resource(context)?;
+        let headers = self.store.data_mut().push_resource(headers)?;
+
+        // we need to take the pointers now, because a resource is not Copy and we need
+        // the pointers to get the data back from the shared memory.
+        let headers_rep = headers.rep();
+        let context_rep = context.rep();
+
+        let result = hook.call_async(&mut self.store, (context, operation, headers)).await;
+
+        if result.is_err() {
+            self.poisoned = true;
+        } else {
+            hook.post_return_async(&mut self.store).await?;
+        }
+
+        result?.0?;
+
+        // take the data back from the shared memory
+        self.store.data_mut().take_resource::<SharedContextMap>(context_rep)?;
+        let headers = self.store.data_mut().take_resource(headers_rep)?;
+
+        Ok(headers)
+    }
+}
@@ -1,6 +1,7 @@
 use url::Url;
 
 use crate::{
+    access_token::SharedAccessToken,
     context::SharedContextMap,
     names::{ON_SUBGRAGH_REQUEST_HOOK_FUNCTION, SUBGRAPH_REQUEST_INTERFACE},
     ComponentLoader, GuestResult,
@@ -15,6 +16,7 @@ impl SubgraphComponentInstance {
     pub async fn on_subgraph_request(
         &mut self,
         context: SharedContextMap,
+        access_token: SharedAccessToken,
         subgraph_name: &str,
         method: http::Method,
         url: &Url,
@@ -29,15 +31,20 @@ impl SubgraphComponentInstance {
         let method = method.to_string();
         // adds the data to the shared memory
         let context = self.store.data_mut().push_resource(context)?;
+        let access_token = self.store.data_mut().push_resource(access_token)?;
         let headers = self.store.data_mut().push_resource(headers)?;
 
         // we need to take the pointers now, because a resource is not Copy and we need
         // the pointers to get the data back from the shared memory.
         let headers_rep = headers.rep();
         let context_rep = context.rep();
+        let access_token_rep = access_token.rep();
 
         let result = hook
-            .call_async(&mut self.store, (context, subgraph_name, method, url, headers))
+            .call_async(
+                &mut self.store,
+                (context, access_token, subgraph_name, method, url, headers),
+            )
             .await;
 
         if result.is_err() {
@@ -50,6 +57,9 @@ impl SubgraphComponentInstance {
 
         // take the data back from the shared memory
         self.store.data_mut().take_resource::<SharedContextMap>(context_rep)?;
+        self.store
+            .data_mut()
+            .take_resource::<SharedAccessToken>(access_token_rep)?;
         let headers = self.store.data_mut().take_resource(headers_rep)?;
 
         Ok(headers)
@@ -0,0 +1,79 @@
+use http::HeaderMap;
+use wasmtime::component::{ComponentType, Lower};
+
+use crate::{
+    access_token::SharedAccessToken,
+    context::SharedContextMap,
+    names::{ON_SUBGRAPH_RESPONSE_HOOK_FUNCTION, SUBGRAPH_RESPONSE_INTERFACE},
+    ComponentLoader, GuestResult,
+};
+
+use super::{component_instance, ComponentInstance};
+
+/// Defines a subgraph response in the subgraph response hook.
+#[derive(Lower, ComponentType)]
+#[component(record)]
+pub struct ExecutedSubgraphRequest {
+    /// The name of the subgraph
+    #[component(name = "subgraph-name")]
+    pub subgraph_name: String,
+    /// The HTTP status code of the response
+    pub status: u16,
+    /// The time taken to get the response, in milliseconds
+    #[component(name = "duration-ms")]
+    pub duration_ms: u64,
+    /// The raw response body, if enabled in the configuration
+    pub body: Option<Vec<u8>>,
+}
+
+component_instance!(SubgraphResponseComponentInstance: SUBGRAPH_RESPONSE_INTERFACE);
+
+impl SubgraphResponseComponentInstance {
+    /// Called right after receiving a HTTP response from a subgraph, returning the entries to
+    /// record in the request context.
+    pub async fn on_subgraph_response(
+        &mut self,
+        context: SharedContextMap,
+        access_token: SharedAccessToken,
+        request: ExecutedSubgraphRequest,
+        headers: HeaderMap,
+    ) -> crate::Result<Vec<(String, String)>> {
+        let Some(hook) = self.get_hook::<_, (GuestResult<Vec<(String, String)>>,)>(ON_SUBGRAPH_RESPONSE_HOOK_FUNCTION)
+        else {
+            return Ok(Vec::new());
+        };
+
+        // adds the data to the shared memory
+        let context = self.store.data_mut().push_resource(context)?;
+        let access_token = self.store.data_mut().push_resource(access_token)?;
+        let headers = self.store.data_mut().push_resource(headers)?;
+
+        // we need to take the pointers now, because a resource is not Copy and we need
+        // the pointers to get the data back from the shared memory.
+        let headers_rep = headers.rep();
+        let context_rep = context.rep();
+        let access_token_rep = access_token.rep();
+
+        let result = hook
+            .call_async(&mut self.store, (context, access_token, request, headers))
+            .await;
+
+        if result.is_err() {
+            self.poisoned = true;
+        } else {
+            hook.post_return_async(&mut self.store).await?;
+        }
+
+        let entries = result?.0?;
+
+        // This is a bit ugly because we don't need it, but we need to clean the shared
+        // resources before exiting or this will leak RAM.
+        self.store.data_mut().take_resource::<SharedContextMap>(context_rep)?;
+        self.store
+            .data_mut()
+            .take_resource::<SharedAccessToken>(access_token_rep)?;
+        self.store.data_mut().take_resource::<HeaderMap>(headers_rep)?;
+
+        Ok(entries)
+    }
+}
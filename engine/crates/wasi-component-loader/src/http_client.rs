@@ -0,0 +1,223 @@
+use std::{future::Future, sync::Arc, time::Duration};
+
+use grafbase_telemetry::span::GRAFBASE_TARGET;
+use tracing::{field::Empty, Instrument, Span};
+use url::Url;
+use wasmtime::{
+    component::{ComponentType, Lift, Linker, Lower},
+    StoreContextMut,
+};
+
+use crate::{
+    names::{HTTP_CLIENT_EXECUTE_FUNCTION, HTTP_CLIENT_INTERFACE},
+    state::WasiState,
+};
+
+/// Redirects followed before giving up, same as the reqwest default.
+const MAX_REDIRECTS: usize = 10;
+
+/// The HTTP client of the host, shared by all component instances. Requests are
+/// restricted to the hosts allowed in the configuration, redirects included.
+#[derive(Clone)]
+pub(crate) struct HttpClient {
+    client: reqwest::Client,
+    allowed_hosts: Arc<[String]>,
+}
+
+impl HttpClient {
+    pub(crate) fn new(allowed_hosts: &[String]) -> crate::Result<Self> {
+        let allowed_hosts: Arc<[String]> = allowed_hosts.into();
+
+        // The client is not shared with the gateway: its redirect policy checks every hop
+        // against the allowed hosts.
+        let redirect_policy = {
+            let allowed_hosts = allowed_hosts.clone();
+
+            reqwest::redirect::Policy::custom(move |attempt| {
+                if attempt.previous().len() > MAX_REDIRECTS {
+                    attempt.error("too many redirects")
+                } else if is_allowed(&allowed_hosts, attempt.url()) {
+                    attempt.follow()
+                } else {
+                    attempt.error(format!(
+                        "redirect to a host not allowed: {}",
+                        attempt.url().host_str().unwrap_or_default()
+                    ))
+                }
+            })
+        };
+
+        let client = reqwest::Client::builder()
+            .redirect(redirect_policy)
+            .build()
+            .map_err(anyhow::Error::from)?;
+
+        Ok(Self { client, allowed_hosts })
+    }
+
+    async fn execute(&self, request: HttpRequest) -> Result<HttpResponse, HttpError> {
+        let url: Url = request
+            .url
+            .parse()
+            .map_err(|e| HttpError::Request(format!("invalid url: {e}")))?;
+
+        if !is_allowed(&self.allowed_hosts, &url) {
+            return Err(HttpError::NotAllowed);
+        }
+
+        let method = http::Method::from(request.method);
+
+        let span = tracing::info_span!(
+            target: GRAFBASE_TARGET,
+            "hook-http-request",
+            "http.request.method" = method.as_str(),
+            "url.full" = url.as_str(),
+            "http.response.status_code" = Empty,
+        );
+
+        async move {
+            let mut builder = self.client.request(method, url).body(request.body);
+
+            for (name, value) in request.headers {
+                builder = builder.header(name, value);
+            }
+
+            if let Some(timeout_ms) = request.timeout_ms {
+                builder = builder.timeout(Duration::from_millis(timeout_ms));
+            }
+
+            let response = builder.send().await.map_err(HttpError::from)?;
+            let status = response.status().as_u16();
+
+            Span::current().record("http.response.status_code", status);
+
+            let headers = response
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+                .collect();
+
+            let body = response.bytes().await.map_err(HttpError::from)?.to_vec();
+
+            Ok(HttpResponse { status, headers, body })
+        }
+        .instrument(span)
+        .await
+    }
+}
+
+fn is_allowed(allowed_hosts: &[String], url: &Url) -> bool {
+    url.host_str()
+        .is_some_and(|host| allowed_hosts.iter().any(|allowed| allowed.eq_ignore_ascii_case(host)))
+}
+
+/// The HTTP method of a request sent from the guest.
+#[derive(Clone, Copy, Debug, ComponentType, Lift)]
+#[component(enum)]
+enum HttpMethod {
+    #[component(name = "get")]
+    Get,
+    #[component(name = "post")]
+    Post,
+    #[component(name = "put")]
+    Put,
+    #[component(name = "delete")]
+    Delete,
+    #[component(name = "patch")]
+    Patch,
+    #[component(name = "head")]
+    Head,
+    #[component(name = "options")]
+    Options,
+}
+
+impl From<HttpMethod> for http::Method {
+    fn from(method: HttpMethod) -> Self {
+        match method {
+            HttpMethod::Get => http::Method::GET,
+            HttpMethod::Post => http::Method::POST,
+            HttpMethod::Put => http::Method::PUT,
+            HttpMethod::Delete => http::Method::DELETE,
+            HttpMethod::Patch => http::Method::PATCH,
+            HttpMethod::Head => http::Method::HEAD,
+            HttpMethod::Options => http::Method::OPTIONS,
+        }
+    }
+}
+
+/// An HTTP request sent from the guest.
+#[derive(Debug, ComponentType, Lift)]
+#[component(record)]
+struct HttpRequest {
+    #[component(name = "method")]
+    method: HttpMethod,
+    #[component(name = "url")]
+    url: String,
+    #[component(name = "headers")]
+    headers: Vec<(String, String)>,
+    #[component(name = "body")]
+    body: Vec<u8>,
+    #[component(name = "timeout-ms")]
+    timeout_ms: Option<u64>,
+}
+
+/// The HTTP response returned to the guest.
+#[derive(Debug, ComponentType, Lower)]
+#[component(record)]
+struct HttpResponse {
+    #[component(name = "status")]
+    status: u16,
+    #[component(name = "headers")]
+    headers: Vec<(String, String)>,
+    #[component(name = "body")]
+    body: Vec<u8>,
+}
+
+/// An error returned to the guest if the request could not be sent.
+#[derive(Debug, ComponentType, Lower)]
+#[component(variant)]
+enum HttpError {
+    /// The host of the url is not in the allowed hosts.
+    #[component(name = "not-allowed")]
+    NotAllowed,
+    /// The request timed out.
+    #[component(name = "timeout")]
+    Timeout,
+    /// The request could not be sent, or the response could not be read.
+    #[component(name = "request")]
+    Request(String),
+}
+
+impl From<reqwest::Error> for HttpError {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            HttpError::Timeout
+        } else {
+            HttpError::Request(error.to_string())
+        }
+    }
+}
+
+/// Map the host HTTP client to the guest component.
+///
+/// ```ignore
+/// interface http-client {
+///     execute: func(request: http-request) -> result<http-response, http-error>;
+/// }
+/// ```
+pub(crate) fn map(linker: &mut Linker<WasiState>) -> crate::Result<()> {
+    let mut instance = linker.instance(HTTP_CLIENT_INTERFACE)?;
+    instance.func_wrap_async(HTTP_CLIENT_EXECUTE_FUNCTION, execute)?;
+
+    Ok(())
+}
+
+/// Sends the request with the host HTTP client.
+fn execute(
+    store: StoreContextMut<'_, WasiState>,
+    (request,): (HttpRequest,),
+) -> Box<dyn Future<Output = anyhow::Result<(Result<HttpResponse, HttpError>,)>> + Send + '_> {
+    let client = store.data().http_client().clone();
+
+    Box::new(async move { Ok((client.execute(request).await,)) })
+}
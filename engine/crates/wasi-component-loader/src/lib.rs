@@ -8,24 +8,34 @@
 
 #![deny(missing_docs)]
 
+mod access_token;
+mod cache;
 mod config;
 mod context;
 mod error;
 mod headers;
 mod hooks;
+mod http_client;
 mod names;
 mod state;
 
 #[cfg(test)]
 mod tests;
 
+pub use access_token::{AccessToken, SharedAccessToken};
+pub use cache::{BoxFuture, Cache, SharedCache};
 pub use config::Config;
 pub use context::{ContextMap, SharedContextMap};
 pub use error::{guest::GuestError, Error};
 pub use hooks::{
     authorization::{AuthorizationComponentInstance, EdgeDefinition, NodeDefinition},
     gateway::GatewayComponentInstance,
+    gateway_response::{
+        ExecutedOperation, FieldError, GatewayResponseComponentInstance, GraphqlResponseStatus, OperationType,
+        RequestError,
+    },
     subgraph::*,
+    subgraph_response::{ExecutedSubgraphRequest, SubgraphResponseComponentInstance},
     RecycleableComponentInstance,
 };
 
@@ -34,7 +44,10 @@ pub type Result<T> = std::result::Result<T, Error>;
 /// The guest result type
 pub type GuestResult<T> = std::result::Result<T, GuestError>;
 
+use std::sync::Arc;
+
 use grafbase_telemetry::span::GRAFBASE_TARGET;
+use http_client::HttpClient;
 use state::WasiState;
 use wasmtime::{
     component::{Component, Linker},
@@ -50,6 +63,8 @@ pub struct ComponentLoader {
     linker: Linker<WasiState>,
     component: Component,
     config: Config,
+    http_client: HttpClient,
+    cache: SharedCache,
 }
 
 impl ComponentLoader {
@@ -94,12 +109,23 @@ impl ComponentLoader {
                 headers::map(&mut types)?;
                 context::map(&mut types)?;
                 context::map_shared(&mut types)?;
+                access_token::map(&mut types)?;
+
+                // adds the host http client, restricted to the allowed hosts
+                http_client::map(&mut linker)?;
+
+                // adds the cache shared between the instances
+                cache::map(&mut linker)?;
+
+                let http_client = HttpClient::new(&config.http_client_allowed_hosts)?;
 
                 Some(Self {
                     engine,
                     linker,
                     component,
                     config,
+                    http_client,
+                    cache: Arc::new(()),
                 })
             }
             Err(e) => {
@@ -117,7 +143,15 @@ impl ComponentLoader {
         Ok(this)
     }
 
-    pub(crate) fn config(&self) -> &Config {
+    /// Sets the cache shared by all instances of the component. Without a cache, the guest
+    /// lookups always miss.
+    pub fn with_cache(mut self, cache: SharedCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// The configuration of the loaded component.
+    pub fn config(&self) -> &Config {
         &self.config
     }
 
@@ -125,6 +159,14 @@ impl ComponentLoader {
         &self.engine
     }
 
+    pub(crate) fn http_client(&self) -> &HttpClient {
+        &self.http_client
+    }
+
+    pub(crate) fn cache(&self) -> &SharedCache {
+        &self.cache
+    }
+
     pub(crate) fn linker(&self) -> &Linker<WasiState> {
         &self.linker
     }
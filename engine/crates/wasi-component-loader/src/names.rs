@@ -1,15 +1,22 @@
 pub(crate) static COMPONENT_TYPES: &str = "component:grafbase/types";
 pub(crate) static GATEWAY_REQUEST_INTERFACE: &str = "component:grafbase/gateway-request";
+pub(crate) static GATEWAY_RESPONSE_INTERFACE: &str = "component:grafbase/gateway-response";
 pub(crate) static AUTHORIZATION_INTERFACE: &str = "component:grafbase/authorization";
 pub(crate) static SUBGRAPH_REQUEST_INTERFACE: &str = "component:grafbase/subgraph-request";
+pub(crate) static SUBGRAPH_RESPONSE_INTERFACE: &str = "component:grafbase/subgraph-response";
+pub(crate) static HTTP_CLIENT_INTERFACE: &str = "component:grafbase/http-client";
+pub(crate) static CACHE_INTERFACE: &str = "component:grafbase/cache";
 
 pub(crate) static GATEWAY_HOOK_FUNCTION: &str = "on-gateway-request";
+pub(crate) static ON_GATEWAY_RESPONSE_HOOK_FUNCTION: &str = "on-gateway-response";
 pub(crate) static AUTHORIZE_EDGE_PRE_EXECUTION_HOOK_FUNCTION: &str = "authorize-edge-pre-execution";
 pub(crate) static AUTHORIZE_NODE_PRE_EXECUTION_HOOK_FUNCTION: &str = "authorize-node-pre-execution";
 pub(crate) static AUTHORIZE_PARENT_EDGE_POST_EXECUTION_HOOK_FUNCTION: &str = "authorize-parent-edge-post-execution";
 pub(crate) static AUTHORIZE_EDGE_NODE_POST_EXECUTION_HOOK_FUNCTION: &str = "authorize-edge-node-post-execution";
 pub(crate) static AUTHORIZE_EDGE_POST_EXECUTION_HOOK_FUNCTION: &str = "authorize-edge-post-execution";
+pub(crate) static AUTHORIZE_POLICIES_HOOK_FUNCTION: &str = "authorize-policies";
 pub(crate) static ON_SUBGRAGH_REQUEST_HOOK_FUNCTION: &str = "on-subgraph-request";
+pub(crate) static ON_SUBGRAPH_RESPONSE_HOOK_FUNCTION: &str = "on-subgraph-response";
 
 pub(crate) static HEADERS_RESOURCE: &str = "headers";
 pub(crate) static HEADERS_SET_METHOD: &str = "[method]headers.set";
@@ -24,3 +31,13 @@ pub(crate) static CONTEXT_DELETE_METHOD: &str = "[method]context.delete";
 
 pub(crate) static SHARED_CONTEXT_RESOURCE: &str = "shared-context";
 pub(crate) static SHARED_CONTEXT_GET_METHOD: &str = "[method]shared-context.get";
+
+pub(crate) static ACCESS_TOKEN_RESOURCE: &str = "access-token";
+pub(crate) static ACCESS_TOKEN_IS_ANONYMOUS_METHOD: &str = "[method]access-token.is-anonymous";
+pub(crate) static ACCESS_TOKEN_CLAIMS_METHOD: &str = "[method]access-token.claims";
+
+pub(crate) static HTTP_CLIENT_EXECUTE_FUNCTION: &str = "execute";
+
+pub(crate) static CACHE_GET_FUNCTION: &str = "get";
+pub(crate) static CACHE_SET_WITH_TTL_FUNCTION: &str = "set-with-ttl";
+pub(crate) static CACHE_DELETE_FUNCTION: &str = "delete";
@@ -2,21 +2,37 @@ use wasmtime::component::Resource;
 use wasmtime_wasi::{ResourceTable, WasiCtx, WasiView};
 use wasmtime_wasi_http::{WasiHttpCtx, WasiHttpView};
 
+use crate::{http_client::HttpClient, SharedCache};
+
 pub(crate) struct WasiState {
     ctx: WasiCtx,
     http_ctx: WasiHttpCtx,
     table: ResourceTable,
+    http_client: HttpClient,
+    cache: SharedCache,
 }
 
 impl WasiState {
-    pub fn new(ctx: WasiCtx) -> Self {
+    pub fn new(ctx: WasiCtx, http_client: HttpClient, cache: SharedCache) -> Self {
         Self {
             ctx,
             http_ctx: WasiHttpCtx::new(),
             table: ResourceTable::new(),
+            http_client,
+            cache,
         }
     }
 
+    /// The host HTTP client available to the guest.
+    pub fn http_client(&self) -> &HttpClient {
+        &self.http_client
+    }
+
+    /// The cache shared by all component instances.
+    pub fn cache(&self) -> &SharedCache {
+        &self.cache
+    }
+
     /// Add a resource to the shared memory.
     pub fn push_resource<T: Send + 'static>(&mut self, entry: T) -> crate::Result<Resource<T>> {
         Ok(self.table.push(entry).map_err(anyhow::Error::from)?)
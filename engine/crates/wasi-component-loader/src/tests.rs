@@ -1,15 +1,24 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use crate::{
-    hooks::subgraph::SubgraphComponentInstance, AuthorizationComponentInstance, ComponentLoader, Config,
-    EdgeDefinition, GatewayComponentInstance, GuestError, NodeDefinition, RecycleableComponentInstance,
+    hooks::subgraph::SubgraphComponentInstance, AccessToken, AuthorizationComponentInstance, BoxFuture, Cache,
+    ComponentLoader, Config, EdgeDefinition, GatewayComponentInstance, GuestError, NodeDefinition,
+    RecycleableComponentInstance,
 };
 use expect_test::expect;
+use grafbase_telemetry::span::GRAFBASE_TARGET;
 use http::{HeaderMap, HeaderValue};
 use indoc::{formatdoc, indoc};
 use serde_json::json;
 use tempdir::TempDir;
-use wiremock::{matchers::method, ResponseTemplate};
+use wiremock::{
+    matchers::{header, method, path},
+    ResponseTemplate,
+};
 
 #[tokio::test]
 async fn missing_wasm() {
@@ -266,7 +275,13 @@ async fn authorize_edge_pre_execution_error() {
     };
 
     let error = hook
-        .authorize_edge_pre_execution(Arc::new(context), definition, String::new(), String::new())
+        .authorize_edge_pre_execution(
+            Arc::new(context),
+            Arc::new(AccessToken::anonymous()),
+            definition,
+            String::new(),
+            String::new(),
+        )
         .await
         .unwrap_err();
 
@@ -304,9 +319,15 @@ async fn authorize_edge_pre_execution_success() {
         field_name: String::new(),
     };
 
-    hook.authorize_edge_pre_execution(Arc::new(context), definition, String::from("kekw"), String::new())
-        .await
-        .unwrap();
+    hook.authorize_edge_pre_execution(
+        Arc::new(context),
+        Arc::new(AccessToken::anonymous()),
+        definition,
+        String::from("kekw"),
+        String::new(),
+    )
+    .await
+    .unwrap();
 }
 
 #[tokio::test]
@@ -335,7 +356,12 @@ async fn authorize_node_pre_execution_error() {
     };
 
     let error = hook
-        .authorize_node_pre_execution(Arc::new(context), definition, String::new())
+        .authorize_node_pre_execution(
+            Arc::new(context),
+            Arc::new(AccessToken::anonymous()),
+            definition,
+            String::new(),
+        )
         .await
         .unwrap_err();
 
@@ -372,9 +398,14 @@ async fn authorize_node_pre_execution_success() {
         type_name: String::new(),
     };
 
-    hook.authorize_node_pre_execution(Arc::new(context), definition, String::from("kekw"))
-        .await
-        .unwrap();
+    hook.authorize_node_pre_execution(
+        Arc::new(context),
+        Arc::new(AccessToken::anonymous()),
+        definition,
+        String::from("kekw"),
+    )
+    .await
+    .unwrap();
 }
 
 #[tokio::test]
@@ -409,7 +440,13 @@ async fn authorize_parent_edge_post_execution() {
     ];
 
     let result = hook
-        .authorize_parent_edge_post_execution(Arc::new(context), definition, parents, String::new())
+        .authorize_parent_edge_post_execution(
+            Arc::new(context),
+            Arc::new(AccessToken::anonymous()),
+            definition,
+            parents,
+            String::new(),
+        )
         .await
         .unwrap();
 
@@ -462,7 +499,13 @@ async fn authorize_edge_node_post_execution() {
     ];
 
     let result = hook
-        .authorize_edge_node_post_execution(Arc::new(context), definition, nodes, String::new())
+        .authorize_edge_node_post_execution(
+            Arc::new(context),
+            Arc::new(AccessToken::anonymous()),
+            definition,
+            nodes,
+            String::new(),
+        )
         .await
         .unwrap();
 
@@ -522,6 +565,7 @@ async fn authorize_edge_post_execution() {
     let result = hook
         .authorize_edge_post_execution(
             Arc::new(context),
+            Arc::new(AccessToken::anonymous()),
             definition,
             vec![(String::new(), nodes1), (String::new(), nodes2)],
             String::new(),
@@ -579,6 +623,7 @@ async fn on_subgraph_request() {
     let headers = hook
         .on_subgraph_request(
             Arc::new(context),
+            Arc::new(AccessToken::anonymous()),
             "dummy",
             http::Method::POST,
             &"http://example.com".parse().unwrap(),
@@ -610,6 +655,7 @@ async fn on_subgraph_request() {
     let error = hook
         .on_subgraph_request(
             Arc::new(context),
+            Arc::new(AccessToken::anonymous()),
             "dummy",
             http::Method::POST,
             &"http://example.com".parse().unwrap(),
@@ -627,3 +673,222 @@ async fn on_subgraph_request() {
     )
     "###);
 }
+
+/// Runs the gateway hook of examples/http_client/src/lib.rs, returning its context.
+async fn http_client_request(allowed_hosts: &[&str], url: &str, timeout_ms: Option<u64>) -> HashMap<String, String> {
+    let config = formatdoc! {r#"
+        location = "examples/target/wasm32-wasip1/debug/http_client.wasm"
+        http_client_allowed_hosts = {allowed_hosts:?}
+    "#};
+
+    let config: Config = toml::from_str(&config).unwrap();
+    assert!(config.location.exists());
+
+    let mut headers = HeaderMap::new();
+    headers.insert("url", HeaderValue::from_str(url).unwrap());
+
+    if let Some(timeout_ms) = timeout_ms {
+        headers.insert("timeout-ms", HeaderValue::from(timeout_ms));
+    }
+
+    let loader = ComponentLoader::new(config).unwrap().unwrap();
+    let mut hook = GatewayComponentInstance::new(&loader).await.unwrap();
+    let (context, _) = hook.on_gateway_request(HashMap::new(), headers).await.unwrap();
+
+    context
+}
+
+#[tokio::test]
+async fn http_client_allowed_host() {
+    let server = wiremock::MockServer::start().await;
+
+    wiremock::Mock::given(method("GET"))
+        .and(header("x-guest", "http-client"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("kekw"))
+        .mount(&server)
+        .await;
+
+    let context = http_client_request(&["127.0.0.1"], &server.uri(), None).await;
+
+    assert_eq!(Some("200"), context.get("status").map(|s| s.as_str()));
+    assert_eq!(Some("kekw"), context.get("body").map(|s| s.as_str()));
+}
+
+#[tokio::test]
+async fn http_client_denied_host() {
+    let server = wiremock::MockServer::start().await;
+
+    wiremock::Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("kekw"))
+        .mount(&server)
+        .await;
+
+    let context = http_client_request(&["example.com"], &server.uri(), None).await;
+
+    assert_eq!(Some("not-allowed"), context.get("error").map(|s| s.as_str()));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn http_client_redirect_to_denied_host() {
+    let server = wiremock::MockServer::start().await;
+
+    // Same server, but `localhost` is not in the allowed hosts.
+    let location = format!("http://localhost:{}/target", server.address().port());
+
+    wiremock::Mock::given(method("GET"))
+        .and(path("/"))
+        .respond_with(ResponseTemplate::new(302).insert_header("location", location.as_str()))
+        .mount(&server)
+        .await;
+
+    wiremock::Mock::given(method("GET"))
+        .and(path("/target"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("kekw"))
+        .mount(&server)
+        .await;
+
+    let context = http_client_request(&["127.0.0.1"], &server.uri(), None).await;
+
+    let error = context.get("error").unwrap();
+    assert!(error.starts_with("request: "), "{error}");
+    assert_eq!(1, server.received_requests().await.unwrap().len());
+}
+
+#[tokio::test]
+async fn http_client_redirect_to_allowed_host() {
+    let server = wiremock::MockServer::start().await;
+
+    wiremock::Mock::given(method("GET"))
+        .and(path("/"))
+        .respond_with(ResponseTemplate::new(302).insert_header("location", "/target"))
+        .mount(&server)
+        .await;
+
+    wiremock::Mock::given(method("GET"))
+        .and(path("/target"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("kekw"))
+        .mount(&server)
+        .await;
+
+    let context = http_client_request(&["127.0.0.1"], &server.uri(), None).await;
+
+    assert_eq!(Some("200"), context.get("status").map(|s| s.as_str()));
+    assert_eq!(Some("kekw"), context.get("body").map(|s| s.as_str()));
+}
+
+#[tokio::test]
+async fn http_client_timeout() {
+    let server = wiremock::MockServer::start().await;
+
+    wiremock::Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(5)))
+        .mount(&server)
+        .await;
+
+    let context = http_client_request(&["127.0.0.1"], &server.uri(), Some(100)).await;
+
+    assert_eq!(Some("timeout"), context.get("error").map(|s| s.as_str()));
+}
+
+#[tokio::test]
+async fn http_client_span() {
+    use tracing_mock::{expect, subscriber};
+
+    let server = wiremock::MockServer::start().await;
+
+    wiremock::Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let hook_span = expect::span().named("hook");
+    let url = format!("{}/", server.uri());
+
+    let (subscriber, handle) = subscriber::mock()
+        .with_filter(|meta| meta.is_span() && meta.target() == GRAFBASE_TARGET)
+        .new_span(hook_span.clone())
+        .enter(hook_span.clone())
+        .new_span(
+            expect::span()
+                .named("hook-http-request")
+                .with_field(
+                    expect::field("http.request.method")
+                        .with_value(&"GET")
+                        .and(expect::field("url.full").with_value(&url.as_str())),
+                )
+                .with_contextual_parent(Some("hook")),
+        )
+        .run_with_handle();
+
+    let _default = tracing::subscriber::set_default(subscriber);
+
+    // The test runs on a single thread, so the span stays the current one for the whole hook call.
+    let span = tracing::info_span!(target: GRAFBASE_TARGET, "hook");
+    let _entered = span.enter();
+
+    let context = http_client_request(&["127.0.0.1"], &server.uri(), None).await;
+
+    assert_eq!(Some("200"), context.get("status").map(|s| s.as_str()));
+    handle.assert_finished();
+}
+
+/// Records the TTLs the host passes to the storage.
+#[derive(Default)]
+struct TestCache {
+    values: Mutex<HashMap<String, Vec<u8>>>,
+    ttls: Mutex<Vec<Option<Duration>>>,
+}
+
+impl Cache for TestCache {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, anyhow::Result<Option<Vec<u8>>>> {
+        Box::pin(async move { Ok(self.values.lock().unwrap().get(key).cloned()) })
+    }
+
+    fn set<'a>(&'a self, key: &'a str, value: Vec<u8>, ttl: Option<Duration>) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            self.ttls.lock().unwrap().push(ttl);
+            self.values.lock().unwrap().insert(key.to_string(), value);
+            Ok(())
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            self.values.lock().unwrap().remove(key);
+            Ok(())
+        })
+    }
+}
+
+#[tokio::test]
+async fn cache_round_trip() {
+    // the guest code in examples/cache/src/lib.rs
+
+    let config = indoc! {r#"
+        location = "examples/target/wasm32-wasip1/debug/cache.wasm"
+    "#};
+
+    let config: Config = toml::from_str(config).unwrap();
+    assert!(config.location.exists());
+
+    let cache = Arc::new(TestCache::default());
+    let loader = ComponentLoader::new(config).unwrap().unwrap().with_cache(cache.clone());
+
+    for ttl_ms in [None, Some(60_000)] {
+        let mut headers = HeaderMap::new();
+
+        if let Some(ttl_ms) = ttl_ms {
+            headers.insert("ttl-ms", HeaderValue::from(ttl_ms));
+        }
+
+        let mut hook = GatewayComponentInstance::new(&loader).await.unwrap();
+        let (context, _) = hook.on_gateway_request(HashMap::new(), headers).await.unwrap();
+
+        assert_eq!(Some("miss"), context.get("before-set").map(|s| s.as_str()));
+        assert_eq!(Some("value"), context.get("after-set").map(|s| s.as_str()));
+        assert_eq!(Some("miss"), context.get("after-delete").map(|s| s.as_str()));
+    }
+
+    assert_eq!(*cache.ttls.lock().unwrap(), [None, Some(Duration::from_millis(60_000))]);
+}
@@ -0,0 +1,28 @@
+/// Demand control, estimating the cost of operations from the `@cost` and `@listSize` directives
+/// of the subgraphs before executing them.
+#[derive(Clone, Copy, Debug, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DemandControlConfig {
+    /// Whether operations above the maximum cost are rejected or only measured. Default: enforce.
+    #[serde(default)]
+    pub mode: DemandControlMode,
+    /// Maximum estimated cost of an operation. Default: no limit.
+    pub max_cost: Option<u64>,
+    /// Size assumed for lists without any `@listSize` directive or slicing argument. Default: 10.
+    #[serde(default = "default_list_size")]
+    pub default_list_size: u32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DemandControlMode {
+    /// Operations with an estimated cost above the maximum are rejected.
+    #[default]
+    Enforce,
+    /// The estimated cost is only recorded and exposed, operations are never rejected.
+    Measure,
+}
+
+fn default_list_size() -> u32 {
+    10
+}
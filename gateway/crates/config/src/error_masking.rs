@@ -0,0 +1,44 @@
+use std::collections::BTreeMap;
+
+/// Controls how errors returned by subgraphs are exposed to clients. Unmasked errors are always
+/// logged, with the correlation id sent to the client.
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ErrorMaskingConfig {
+    /// How subgraph errors are exposed. Default: pass_through.
+    #[serde(default)]
+    pub mode: ErrorMaskingMode,
+    /// Message replacing the one of masked errors.
+    pub message: Option<String>,
+    /// Error codes, from the `code` extension, of the errors passed through in the allowlist mode.
+    #[serde(default)]
+    pub allowed_codes: Vec<String>,
+    /// Maps error codes, from the `code` extension, to the error codes sent to the client.
+    #[serde(default)]
+    pub code_mappings: BTreeMap<String, PublicErrorCode>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorMaskingMode {
+    /// Errors are sent to the client as returned by the subgraph.
+    #[default]
+    PassThrough,
+    /// Errors are replaced with a generic message and a correlation id.
+    Mask,
+    /// Errors with an allowed code are passed through, all the others are masked.
+    Allowlist,
+}
+
+/// Error codes subgraph errors can be mapped to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PublicErrorCode {
+    BadRequest,
+    Unauthenticated,
+    Unauthorized,
+    RateLimited,
+    GatewayTimeout,
+    InternalServerError,
+    SubgraphError,
+}
@@ -76,3 +76,82 @@ pub struct HeaderRemove {
     #[serde(flatten)]
     pub name: NameOrPattern,
 }
+
+/// Defines a rule applied to the headers of a subgraph response. The resulting headers are
+/// merged with the ones of the other subgraphs and sent back to the client.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "rule")]
+pub enum ResponseHeaderRule {
+    /// Forward the header to the client.
+    #[serde(rename = "forward")]
+    Forward(ResponseHeaderForward),
+    /// Insert a new static header.
+    #[serde(rename = "insert")]
+    Insert(ResponseHeaderInsert),
+    /// Remove a header added by a previous rule.
+    #[serde(rename = "remove")]
+    Remove(HeaderRemove),
+    /// Forward the header to the client with a new name.
+    #[serde(rename = "rename")]
+    Rename(ResponseHeaderRename),
+}
+
+/// Response header forwarding rules.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ResponseHeaderForward {
+    /// Name or pattern of the header to be forwarded.
+    #[serde(flatten)]
+    pub name: NameOrPattern,
+    /// If header is not present, insert this value.
+    pub default: Option<DynamicString<AsciiString>>,
+    /// Use this name instead of the original when forwarding.
+    pub rename: Option<DynamicString<AsciiString>>,
+    /// How to merge the header if multiple subgraphs set it.
+    #[serde(default)]
+    pub merge: HeaderMergeStrategy,
+}
+
+/// Response header insertion rules.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ResponseHeaderInsert {
+    /// The name of the header.
+    pub name: DynamicString<AsciiString>,
+    /// The value of the header.
+    pub value: DynamicString<AsciiString>,
+    /// How to merge the header if multiple subgraphs set it.
+    #[serde(default)]
+    pub merge: HeaderMergeStrategy,
+}
+
+/// Response header renaming rules.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ResponseHeaderRename {
+    /// The name of the header in the subgraph response.
+    pub name: DynamicString<AsciiString>,
+    /// If header is not present, insert this value.
+    pub default: Option<DynamicString<AsciiString>>,
+    /// The name of the header sent to the client.
+    pub rename: DynamicString<AsciiString>,
+    /// How to merge the header if multiple subgraphs set it.
+    #[serde(default)]
+    pub merge: HeaderMergeStrategy,
+}
+
+/// Decides the value of a response header set by multiple subgraphs.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HeaderMergeStrategy {
+    /// Keep the value of the first subgraph response.
+    First,
+    /// Keep the value of the last subgraph response.
+    #[default]
+    Last,
+    /// Keep all the values.
+    Append,
+    /// Combine `cache-control` values, keeping the most restrictive directives and the
+    /// lowest max-age.
+    MostRestrictiveCacheControl,
+}
@@ -1,5 +1,7 @@
 use std::path::PathBuf;
 
+use crate::{EntityCachingRedisConfig, EntityCachingStorage};
+
 /// GraphQL WASI component configuration.
 #[derive(Clone, Default, Debug, serde::Deserialize)]
 pub struct HooksWasiConfig {
@@ -14,6 +16,26 @@ pub struct HooksWasiConfig {
     pub stderr: bool,
     #[serde(default)]
     pub preopened_directories: Vec<PreopenedDirectory>,
+    /// Provide the raw subgraph response body to the subgraph response hook
+    #[serde(default)]
+    pub subgraph_response_body: bool,
+    /// Hosts the hooks are allowed to call with the host HTTP client
+    #[serde(default)]
+    pub http_client_allowed_hosts: Vec<String>,
+    /// Storage of the cache shared by all hook instances
+    #[serde(default)]
+    pub cache: HooksCacheConfig,
+}
+
+/// Configuration of the cache available to the hooks. With Redis, the cache is also shared
+/// between gateway replicas.
+#[derive(Clone, Default, Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HooksCacheConfig {
+    #[serde(default)]
+    pub storage: EntityCachingStorage,
+    #[serde(default)]
+    pub redis: EntityCachingRedisConfig,
 }
 
 /// Configuration for allowing access to a certain directory from a WASI guest
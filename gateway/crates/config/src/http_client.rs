@@ -0,0 +1,44 @@
+use std::{num::NonZeroUsize, time::Duration};
+
+/// Compression of the requests sent to a subgraph and of its responses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CompressionConfig {
+    /// Compresses the request bodies with this algorithm. Default: no compression.
+    #[serde(default)]
+    pub request: Option<CompressionAlgorithm>,
+    /// Advertises gzip, brotli and zstd in `Accept-Encoding` and decodes compressed responses.
+    /// Default: false.
+    #[serde(default)]
+    pub response: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompressionAlgorithm {
+    Gzip,
+    Brotli,
+    Zstd,
+}
+
+/// Connection pool of the HTTP client of a subgraph. Subgraphs with the same settings share
+/// their client.
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConnectionPoolConfig {
+    /// Maximum number of idle connections kept per host. Default: no limit.
+    #[serde(default)]
+    pub max_idle_per_host: Option<NonZeroUsize>,
+    /// How long idle connections are kept. Default: 90 seconds.
+    #[serde(default, deserialize_with = "duration_str::deserialize_option_duration")]
+    pub idle_timeout: Option<Duration>,
+    /// Interval of the TCP keepalive probes. Default: disabled.
+    #[serde(default, deserialize_with = "duration_str::deserialize_option_duration")]
+    pub tcp_keepalive: Option<Duration>,
+    /// Uses HTTP/2 without negotiating it first, for subgraphs served over plain HTTP/2.
+    #[serde(default)]
+    pub http2_prior_knowledge: bool,
+    /// Timeout of the connection establishment. Default: no timeout besides the request one.
+    #[serde(default, deserialize_with = "duration_str::deserialize_option_duration")]
+    pub connect_timeout: Option<Duration>,
+}
@@ -1,24 +1,46 @@
 pub mod authentication;
 pub mod cors;
+pub mod demand_control;
 pub mod entity_caching;
+pub mod error_masking;
 pub mod header;
 pub mod health;
 pub mod hooks;
+pub mod http_client;
+pub mod load_balancing;
+pub mod progressive_override;
+pub mod query_plan;
 pub mod rate_limit;
+pub mod response_caching;
 pub mod telemetry;
+pub mod traffic_split;
 
-use std::{collections::BTreeMap, net::SocketAddr, path::PathBuf, time::Duration};
+use std::{
+    collections::BTreeMap,
+    net::SocketAddr,
+    num::{NonZeroU32, NonZeroUsize},
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use ascii::AsciiString;
 pub use authentication::*;
 pub use cors::*;
+pub use demand_control::*;
 pub use entity_caching::*;
+pub use error_masking::*;
 pub use header::*;
 pub use health::*;
 pub use hooks::*;
+pub use http_client::*;
+pub use load_balancing::*;
+pub use progressive_override::*;
+pub use query_plan::*;
 pub use rate_limit::*;
+pub use response_caching::*;
 use serde_dynamic_string::DynamicString;
 pub use telemetry::*;
+pub use traffic_split::*;
 use url::Url;
 
 #[derive(Clone, Debug, Default, serde::Deserialize)]
@@ -66,6 +88,21 @@ pub struct Config {
     /// Global configuration for entity caching
     #[serde(default)]
     pub entity_caching: EntityCachingConfig,
+    /// Query plan exposure in the response extensions
+    #[serde(default)]
+    pub query_plan: QueryPlanConfig,
+    /// Full-response caching settings
+    #[serde(default)]
+    pub response_caching: ResponseCachingConfig,
+    /// How subgraph errors are exposed to clients
+    #[serde(default)]
+    pub error_masking: ErrorMaskingConfig,
+    /// Estimation and limit of the cost of operations
+    #[serde(default)]
+    pub demand_control: Option<DemandControlConfig>,
+    /// Custom labels of progressive `@override` migrations
+    #[serde(default)]
+    pub progressive_override: ProgressiveOverrideConfig,
 }
 
 impl Config {
@@ -109,8 +146,30 @@ pub struct SubgraphConfig {
     /// Header bypass configuration
     #[serde(default)]
     pub headers: Vec<HeaderRule>,
+    /// Rules applied to the subgraph response headers, forwarding them to the client
+    #[serde(default)]
+    pub response_headers: Vec<ResponseHeaderRule>,
     /// The URL to use for GraphQL websocket calls.
     pub websocket_url: Option<Url>,
+    /// Protocol used for subscriptions to this subgraph. Default: websocket.
+    #[serde(default)]
+    pub subscription_protocol: SubscriptionProtocol,
+    /// Reconnect SSE and multipart subscriptions receiving neither a message nor a heartbeat for
+    /// this long. Default: disabled, as not all subgraphs send heartbeats.
+    #[serde(deserialize_with = "duration_str::deserialize_option_duration", default)]
+    pub subscription_heartbeat_timeout: Option<Duration>,
+    /// Consecutive reconnection attempts of SSE and multipart subscriptions before giving up.
+    /// Default: 3.
+    #[serde(default)]
+    pub subscription_max_reconnect_attempts: Option<u32>,
+    /// Share a single upstream subscription between all clients sending identical subscriptions
+    /// to this subgraph. Default: false.
+    #[serde(default)]
+    pub deduplicate_subscriptions: bool,
+    /// Share a single subgraph request between identical queries in flight at the same time.
+    /// Mutations are never deduplicated. Default: false.
+    #[serde(default)]
+    pub deduplicate_requests: bool,
     /// Rate limiting configuration specifically for this Subgraph
     #[serde(default)]
     pub rate_limit: Option<GraphRateLimit>,
@@ -123,9 +182,112 @@ pub struct SubgraphConfig {
     /// Subgraph specific entity caching config  this overrides the global config if there
     /// is any
     pub entity_caching: Option<EntityCachingConfig>,
+    /// Maximum number of entities sent in a single `_entities` request, larger batches are split
+    /// into multiple requests sent in parallel. Default: no limit.
+    #[serde(default)]
+    pub max_entities_per_request: Option<NonZeroUsize>,
+    /// Subgraph specific error masking, replacing the global configuration.
+    #[serde(default)]
+    pub error_masking: Option<ErrorMaskingConfig>,
+    /// Stops sending requests to the subgraph for a while once it keeps failing.
+    #[serde(default)]
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+    /// Multiple URLs for this subgraph. With retries enabled, a failed request is retried on
+    /// another URL.
+    #[serde(default)]
+    pub load_balancing: Option<LoadBalancingConfig>,
+    /// Routes a share of the requests, or those matching a header or claim, to other versions
+    /// of the subgraph.
+    #[serde(default)]
+    pub traffic_split: Option<TrafficSplitConfig>,
+    /// Mirrors a sample of the queries sent to this subgraph to a candidate version, comparing
+    /// both responses.
+    #[serde(default)]
+    pub shadow: Option<ShadowConfig>,
+    /// Compression of the requests and responses.
+    #[serde(default)]
+    pub compression: Option<CompressionConfig>,
+    /// Connection pool settings of the HTTP client.
+    #[serde(default)]
+    pub connection_pool: Option<ConnectionPoolConfig>,
+}
+
+/// A circuit breaker opens once the subgraph reaches one of the failure thresholds. Requests
+/// then fail immediately until the cooldown is over, after which a single probe request decides
+/// whether the circuit closes again. Connection errors and 5xx responses are failures.
+#[derive(Debug, serde::Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CircuitBreakerConfig {
+    /// Opens the circuit after this many consecutive failures. Default: 5 if no failure rate is
+    /// defined.
+    #[serde(default)]
+    pub consecutive_failures: Option<NonZeroU32>,
+    /// Opens the circuit once the fraction of failed requests within the window reaches this
+    /// value, between 0 and 1.
+    #[serde(default)]
+    pub failure_rate: Option<f32>,
+    /// The window over which the failure rate is computed. Default: 10 seconds.
+    #[serde(default, deserialize_with = "duration_str::deserialize_option_duration")]
+    pub window: Option<Duration>,
+    /// Minimum number of requests within the window for the failure rate to apply. Default: 10.
+    #[serde(default)]
+    pub minimum_requests: Option<u32>,
+    /// How long the circuit stays open before a probe request is let through. Default: 30 seconds.
+    #[serde(default, deserialize_with = "duration_str::deserialize_option_duration")]
+    pub cooldown: Option<Duration>,
 }
 
-#[derive(Debug, serde::Deserialize, Clone, Copy, Default, PartialEq)]
+/// Shadow traffic: a sample of the queries is also sent to a candidate URL in the background.
+/// Its responses never reach the client, they're only compared with the subgraph ones. Mutations
+/// are never mirrored.
+#[derive(Debug, serde::Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ShadowConfig {
+    /// URL of the candidate subgraph.
+    pub url: Url,
+    /// The fraction of the queries mirrored, between 0.0 and 1.0. Default: 0.1.
+    #[serde(
+        default = "default_shadow_sampling",
+        deserialize_with = "deserialize_shadow_sampling"
+    )]
+    pub sampling: f64,
+}
+
+const DEFAULT_SHADOW_SAMPLING: f64 = 0.1;
+
+fn default_shadow_sampling() -> f64 {
+    DEFAULT_SHADOW_SAMPLING
+}
+
+fn deserialize_shadow_sampling<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let input = <f64 as serde::Deserialize>::deserialize(deserializer)?;
+
+    if !(0.0..=1.0).contains(&input) {
+        return Err(serde::de::Error::custom("sampling should be between 0.0 and 1.0"));
+    }
+
+    Ok(input)
+}
+
+/// Transport of the subscriptions sent to a subgraph.
+#[derive(Debug, serde::Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SubscriptionProtocol {
+    /// The `graphql-transport-ws` protocol over websockets.
+    #[default]
+    #[serde(rename = "websocket")]
+    Websocket,
+    /// GraphQL over server-sent events, in the distinct connections mode.
+    #[serde(rename = "sse")]
+    ServerSentEvents,
+    /// The multipart HTTP subscription protocol.
+    #[serde(rename = "multipart")]
+    Multipart,
+}
+
+#[derive(Debug, serde::Deserialize, Clone, Default, PartialEq)]
 pub struct RetryConfig {
     /// Should we retry or not.
     pub enabled: bool,
@@ -141,6 +303,56 @@ pub struct RetryConfig {
     /// Whether mutations should be retried at all. False by default.
     #[serde(default)]
     pub retry_mutations: bool,
+    /// HTTP status codes of subgraph responses that should be retried, connection errors
+    /// are always retried. Default: 502, 503 and 504.
+    #[serde(default)]
+    pub retry_on_status: Option<Vec<RetryStatus>>,
+}
+
+/// A single HTTP status code like `503` or a whole class of status codes like `"5xx"`.
+#[derive(Debug, serde::Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(try_from = "RawRetryStatus")]
+pub enum RetryStatus {
+    Code(u16),
+    /// The hundreds digit of the status codes, so `5` for `5xx`.
+    Class(u16),
+}
+
+impl RetryStatus {
+    /// All the status codes matched.
+    pub fn codes(self) -> std::ops::RangeInclusive<u16> {
+        match self {
+            RetryStatus::Code(code) => code..=code,
+            RetryStatus::Class(class) => (class * 100)..=(class * 100 + 99),
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum RawRetryStatus {
+    Code(u16),
+    Pattern(String),
+}
+
+impl TryFrom<RawRetryStatus> for RetryStatus {
+    type Error = String;
+
+    fn try_from(value: RawRetryStatus) -> Result<Self, Self::Error> {
+        match value {
+            RawRetryStatus::Code(code @ 100..=599) => Ok(RetryStatus::Code(code)),
+            RawRetryStatus::Code(code) => Err(format!("invalid HTTP status code: {code}")),
+            RawRetryStatus::Pattern(pattern) => match pattern.as_bytes() {
+                [class @ b'1'..=b'5', b'x' | b'X', b'x' | b'X'] => Ok(RetryStatus::Class(u16::from(class - b'0'))),
+                _ => pattern
+                    .parse::<u16>()
+                    .ok()
+                    .filter(|code| (100..=599).contains(code))
+                    .map(RetryStatus::Code)
+                    .ok_or_else(|| format!("invalid HTTP status code or class: {pattern}")),
+            },
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, serde::Deserialize)]
@@ -180,6 +392,30 @@ pub struct TrustedDocumentsConfig {
     /// See [BypassHeader]
     #[serde(flatten)]
     pub bypass_header: BypassHeader,
+    /// Local persisted query manifests, used when the gateway runs without a connection to the Grafbase API.
+    #[serde(default)]
+    pub manifests: Vec<TrustedDocumentsManifest>,
+}
+
+impl TrustedDocumentsConfig {
+    /// Resolves the relative manifest paths against the directory of the configuration file.
+    pub fn resolve_manifest_paths(&mut self, config_dir: &Path) {
+        for manifest in &mut self.manifests {
+            if manifest.path.is_relative() {
+                manifest.path = config_dir.join(&manifest.path);
+            }
+        }
+    }
+}
+
+/// A persisted query manifest on the local file system, registered for one client.
+#[derive(Debug, serde::Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TrustedDocumentsManifest {
+    /// The name of the client sending the documents, as given in the `x-grafbase-client-name` header.
+    pub client_name: String,
+    /// Path to the manifest file, either in the Apollo (`apollo-persisted-query-manifest`) or in the Relay (`{"<id>": "<document>"}`) format.
+    pub path: PathBuf,
 }
 
 /// An optional header that can be passed by clients to bypass trusted documents enforcement, allowing arbitrary queries.
@@ -650,6 +886,7 @@ mod tests {
                 bypass_header_name: None,
                 bypass_header_value: None,
             },
+            manifests: [],
         }
         "###)
     }
@@ -670,6 +907,7 @@ mod tests {
                 bypass_header_name: None,
                 bypass_header_value: None,
             },
+            manifests: [],
         }
         "###)
     }
@@ -718,10 +956,73 @@ mod tests {
                     ),
                 ),
             },
+            manifests: [],
         }
         "###);
     }
 
+    #[test]
+    fn trusted_documents_manifests() {
+        let input = indoc! {r#"
+            [trusted_documents]
+            enabled = true
+
+            [[trusted_documents.manifests]]
+            client_name = "ios-app"
+            path = "./manifests/ios-app.json"
+
+            [[trusted_documents.manifests]]
+            client_name = "web"
+            path = "/etc/grafbase/web.json"
+        "#};
+
+        let config = toml::from_str::<Config>(input).unwrap();
+
+        insta::assert_debug_snapshot!(config.trusted_documents.manifests, @r###"
+        [
+            TrustedDocumentsManifest {
+                client_name: "ios-app",
+                path: "./manifests/ios-app.json",
+            },
+            TrustedDocumentsManifest {
+                client_name: "web",
+                path: "/etc/grafbase/web.json",
+            },
+        ]
+        "###);
+    }
+
+    #[test]
+    fn trusted_documents_manifest_paths_are_resolved() {
+        let input = indoc! {r#"
+            [[trusted_documents.manifests]]
+            client_name = "ios-app"
+            path = "./manifests/ios-app.json"
+
+            [[trusted_documents.manifests]]
+            client_name = "web"
+            path = "/etc/grafbase/web.json"
+        "#};
+
+        let mut config = toml::from_str::<Config>(input).unwrap();
+        config
+            .trusted_documents
+            .resolve_manifest_paths(Path::new("/srv/gateway"));
+
+        insta::assert_debug_snapshot!(config.trusted_documents.manifests, @r###"
+        [
+            TrustedDocumentsManifest {
+                client_name: "ios-app",
+                path: "/srv/gateway/./manifests/ios-app.json",
+            },
+            TrustedDocumentsManifest {
+                client_name: "web",
+                path: "/etc/grafbase/web.json",
+            },
+        ]
+        "###);
+    }
+
     #[test]
     fn trusted_documents_unknown_setting() {
         let input = indoc! {r#"
@@ -1324,16 +1625,584 @@ mod tests {
                         },
                     ),
                 ],
+                response_headers: [],
                 websocket_url: None,
+                subscription_protocol: Websocket,
+                subscription_heartbeat_timeout: None,
+                subscription_max_reconnect_attempts: None,
+                deduplicate_subscriptions: false,
+                deduplicate_requests: false,
                 rate_limit: None,
                 timeout: None,
                 retry: None,
                 entity_caching: None,
+                max_entities_per_request: None,
+                error_masking: None,
+                circuit_breaker: None,
+                load_balancing: None,
+                traffic_split: None,
+                shadow: None,
+                compression: None,
+                connection_pool: None,
+            },
+        }
+        "###);
+    }
+
+    #[test]
+    fn subgraph_subscription_protocol() {
+        let input = indoc! {r#"
+            [subgraphs.products]
+            subscription_protocol = "sse"
+
+            [subgraphs.reviews]
+            subscription_protocol = "multipart"
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        assert_eq!(
+            config.subgraphs["products"].subscription_protocol,
+            SubscriptionProtocol::ServerSentEvents
+        );
+        assert_eq!(
+            config.subgraphs["reviews"].subscription_protocol,
+            SubscriptionProtocol::Multipart
+        );
+    }
+
+    #[test]
+    fn subgraph_subscription_heartbeat_timeout() {
+        let input = indoc! {r#"
+            [subgraphs.products]
+            subscription_protocol = "sse"
+            subscription_heartbeat_timeout = "30s"
+
+            [subgraphs.reviews]
+            subscription_protocol = "multipart"
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        assert_eq!(
+            config.subgraphs["products"].subscription_heartbeat_timeout,
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(config.subgraphs["reviews"].subscription_heartbeat_timeout, None);
+    }
+
+    #[test]
+    fn subgraph_subscription_max_reconnect_attempts() {
+        let input = indoc! {r#"
+            [subgraphs.products]
+            subscription_protocol = "sse"
+            subscription_max_reconnect_attempts = 10
+
+            [subgraphs.reviews]
+            subscription_protocol = "multipart"
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        assert_eq!(
+            config.subgraphs["products"].subscription_max_reconnect_attempts,
+            Some(10)
+        );
+        assert_eq!(config.subgraphs["reviews"].subscription_max_reconnect_attempts, None);
+    }
+
+    #[test]
+    fn subgraph_subscription_deduplication() {
+        let input = indoc! {r#"
+            [subgraphs.products]
+            deduplicate_subscriptions = true
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        assert!(config.subgraphs["products"].deduplicate_subscriptions);
+    }
+
+    #[test]
+    fn subgraph_request_deduplication() {
+        let input = indoc! {r#"
+            [subgraphs.products]
+            deduplicate_requests = true
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        assert!(config.subgraphs["products"].deduplicate_requests);
+        assert!(!config.subgraphs["products"].deduplicate_subscriptions);
+    }
+
+    #[test]
+    fn subgraph_max_entities_per_request() {
+        let input = indoc! {r#"
+            [subgraphs.products]
+            max_entities_per_request = 100
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        assert_eq!(
+            config.subgraphs["products"].max_entities_per_request,
+            NonZeroUsize::new(100)
+        );
+
+        let input = indoc! {r#"
+            [subgraphs.products]
+            max_entities_per_request = 0
+        "#};
+
+        assert!(toml::from_str::<Config>(input).is_err());
+    }
+
+    #[test]
+    fn error_masking() {
+        let input = indoc! {r#"
+            [error_masking]
+            mode = "mask"
+            message = "Something went wrong"
+
+            [subgraphs.products.error_masking]
+            mode = "allowlist"
+            allowed_codes = ["NOT_FOUND"]
+
+            [subgraphs.products.error_masking.code_mappings]
+            FORBIDDEN = "UNAUTHORIZED"
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        insta::assert_debug_snapshot!(&config.error_masking, @r###"
+        ErrorMaskingConfig {
+            mode: Mask,
+            message: Some(
+                "Something went wrong",
+            ),
+            allowed_codes: [],
+            code_mappings: {},
+        }
+        "###);
+
+        insta::assert_debug_snapshot!(&config.subgraphs["products"].error_masking, @r###"
+        Some(
+            ErrorMaskingConfig {
+                mode: Allowlist,
+                message: None,
+                allowed_codes: [
+                    "NOT_FOUND",
+                ],
+                code_mappings: {
+                    "FORBIDDEN": Unauthorized,
+                },
+            },
+        )
+        "###);
+    }
+
+    #[test]
+    fn subgraph_circuit_breaker() {
+        let input = indoc! {r#"
+            [subgraphs.products.circuit_breaker]
+            consecutive_failures = 3
+            failure_rate = 0.5
+            window = "30s"
+            minimum_requests = 20
+            cooldown = "1m"
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        insta::assert_debug_snapshot!(&config.subgraphs["products"].circuit_breaker, @r###"
+        Some(
+            CircuitBreakerConfig {
+                consecutive_failures: Some(
+                    3,
+                ),
+                failure_rate: Some(
+                    0.5,
+                ),
+                window: Some(
+                    30s,
+                ),
+                minimum_requests: Some(
+                    20,
+                ),
+                cooldown: Some(
+                    60s,
+                ),
+            },
+        )
+        "###);
+    }
+
+    #[test]
+    fn subgraph_load_balancing() {
+        let input = indoc! {r#"
+            [subgraphs.products.load_balancing]
+            strategy = "weighted"
+            urls = [
+                "http://products-1:4000/graphql",
+                { url = "http://products-2:4000/graphql", weight = 3, websocket_url = "ws://products-2:4000/ws" },
+            ]
+
+            [subgraphs.products.load_balancing.outlier_ejection]
+            consecutive_failures = 2
+            duration = "10s"
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        insta::assert_debug_snapshot!(&config.subgraphs["products"].load_balancing, @r###"
+        Some(
+            LoadBalancingConfig {
+                strategy: Weighted,
+                urls: [
+                    UpstreamUrlConfig {
+                        url: Url {
+                            scheme: "http",
+                            cannot_be_a_base: false,
+                            username: "",
+                            password: None,
+                            host: Some(
+                                Domain(
+                                    "products-1",
+                                ),
+                            ),
+                            port: Some(
+                                4000,
+                            ),
+                            path: "/graphql",
+                            query: None,
+                            fragment: None,
+                        },
+                        weight: 1,
+                        websocket_url: None,
+                    },
+                    UpstreamUrlConfig {
+                        url: Url {
+                            scheme: "http",
+                            cannot_be_a_base: false,
+                            username: "",
+                            password: None,
+                            host: Some(
+                                Domain(
+                                    "products-2",
+                                ),
+                            ),
+                            port: Some(
+                                4000,
+                            ),
+                            path: "/graphql",
+                            query: None,
+                            fragment: None,
+                        },
+                        weight: 3,
+                        websocket_url: Some(
+                            Url {
+                                scheme: "ws",
+                                cannot_be_a_base: false,
+                                username: "",
+                                password: None,
+                                host: Some(
+                                    Domain(
+                                        "products-2",
+                                    ),
+                                ),
+                                port: Some(
+                                    4000,
+                                ),
+                                path: "/ws",
+                                query: None,
+                                fragment: None,
+                            },
+                        ),
+                    },
+                ],
+                outlier_ejection: OutlierEjectionConfig {
+                    consecutive_failures: Some(
+                        2,
+                    ),
+                    duration: Some(
+                        10s,
+                    ),
+                },
             },
+        )
+        "###);
+    }
+
+    #[test]
+    fn subgraph_traffic_split() {
+        let input = indoc! {r#"
+            [subgraphs.products.traffic_split]
+            sticky_header = "x-user-id"
+
+            [[subgraphs.products.traffic_split.variants]]
+            name = "canary"
+            url = "http://products-canary:4000/graphql"
+            percentage = 10
+            header = { name = "x-products-version", value = "canary" }
+            claim = { name = "plan", value = "beta" }
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        insta::assert_debug_snapshot!(&config.subgraphs["products"].traffic_split, @r###"
+        Some(
+            TrafficSplitConfig {
+                sticky_header: Some(
+                    "x-user-id",
+                ),
+                variants: [
+                    TrafficVariantConfig {
+                        name: "canary",
+                        url: Url {
+                            scheme: "http",
+                            cannot_be_a_base: false,
+                            username: "",
+                            password: None,
+                            host: Some(
+                                Domain(
+                                    "products-canary",
+                                ),
+                            ),
+                            port: Some(
+                                4000,
+                            ),
+                            path: "/graphql",
+                            query: None,
+                            fragment: None,
+                        },
+                        percentage: 10.0,
+                        header: Some(
+                            TrafficMatchConfig {
+                                name: "x-products-version",
+                                value: "canary",
+                            },
+                        ),
+                        claim: Some(
+                            TrafficMatchConfig {
+                                name: "plan",
+                                value: "beta",
+                            },
+                        ),
+                    },
+                ],
+            },
+        )
+        "###);
+    }
+
+    #[test]
+    fn subgraph_shadow() {
+        let input = indoc! {r#"
+            [subgraphs.products.shadow]
+            url = "http://products-next:4000/graphql"
+            sampling = 0.5
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        let shadow = config.subgraphs["products"].shadow.as_ref().unwrap();
+        assert_eq!(shadow.url.as_str(), "http://products-next:4000/graphql");
+        assert_eq!(shadow.sampling, 0.5);
+
+        let input = indoc! {r#"
+            [subgraphs.products.shadow]
+            url = "http://products-next:4000/graphql"
+            sampling = 2.0
+        "#};
+
+        let error = toml::from_str::<Config>(input).unwrap_err();
+        assert!(
+            error.to_string().contains("sampling should be between 0.0 and 1.0"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn subgraph_compression_and_connection_pool() {
+        let input = indoc! {r#"
+            [subgraphs.products.compression]
+            request = "zstd"
+            response = true
+
+            [subgraphs.products.connection_pool]
+            max_idle_per_host = 16
+            idle_timeout = "30s"
+            tcp_keepalive = "60s"
+            http2_prior_knowledge = true
+            connect_timeout = "2s"
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+        let subgraph = &config.subgraphs["products"];
+
+        insta::assert_debug_snapshot!((&subgraph.compression, &subgraph.connection_pool), @r###"
+        (
+            Some(
+                CompressionConfig {
+                    request: Some(
+                        Zstd,
+                    ),
+                    response: true,
+                },
+            ),
+            Some(
+                ConnectionPoolConfig {
+                    max_idle_per_host: Some(
+                        16,
+                    ),
+                    idle_timeout: Some(
+                        30s,
+                    ),
+                    tcp_keepalive: Some(
+                        60s,
+                    ),
+                    http2_prior_knowledge: true,
+                    connect_timeout: Some(
+                        2s,
+                    ),
+                },
+            ),
+        )
+        "###);
+    }
+
+    #[test]
+    fn demand_control() {
+        let input = indoc! {r#"
+            [demand_control]
+            mode = "measure"
+            max_cost = 1000
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        insta::assert_debug_snapshot!(&config.demand_control, @r###"
+        Some(
+            DemandControlConfig {
+                mode: Measure,
+                max_cost: Some(
+                    1000,
+                ),
+                default_list_size: 10,
+            },
+        )
+        "###);
+    }
+
+    #[test]
+    fn progressive_override() {
+        let input = indoc! {r#"
+            [progressive_override]
+            enabled_labels = ["use-new-inventory"]
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        insta::assert_debug_snapshot!(&config.progressive_override, @r###"
+        ProgressiveOverrideConfig {
+            enabled_labels: [
+                "use-new-inventory",
+            ],
         }
         "###);
     }
 
+    #[test]
+    fn hooks_cache_redis() {
+        let input = indoc! {r#"
+            [hooks]
+            location = "hooks.wasm"
+
+            [hooks.cache]
+            storage = "redis"
+
+            [hooks.cache.redis]
+            url = "redis://localhost:6380"
+            key_prefix = "my-hooks"
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        insta::assert_debug_snapshot!(&config.hooks.unwrap().cache, @r###"
+        HooksCacheConfig {
+            storage: Redis,
+            redis: EntityCachingRedisConfig {
+                url: Url {
+                    scheme: "redis",
+                    cannot_be_a_base: false,
+                    username: "",
+                    password: None,
+                    host: Some(
+                        Domain(
+                            "localhost",
+                        ),
+                    ),
+                    port: Some(
+                        6380,
+                    ),
+                    path: "",
+                    query: None,
+                    fragment: None,
+                },
+                key_prefix: "my-hooks",
+                tls: None,
+            },
+        }
+        "###);
+    }
+
+    #[test]
+    fn subgraph_response_headers() {
+        let input = indoc! {r#"
+            [[subgraphs.products.response_headers]]
+            rule = "forward"
+            name = "cache-control"
+            merge = "most_restrictive_cache_control"
+
+            [[subgraphs.products.response_headers]]
+            rule = "rename"
+            name = "x-products-version"
+            rename = "x-version"
+        "#};
+
+        let result: Config = toml::from_str(input).unwrap();
+
+        insta::assert_debug_snapshot!(&result.subgraphs["products"].response_headers, @r###"
+        [
+            Forward(
+                ResponseHeaderForward {
+                    name: Name(
+                        DynamicString(
+                            "cache-control",
+                        ),
+                    ),
+                    default: None,
+                    rename: None,
+                    merge: MostRestrictiveCacheControl,
+                },
+            ),
+            Rename(
+                ResponseHeaderRename {
+                    name: DynamicString(
+                        "x-products-version",
+                    ),
+                    default: None,
+                    rename: DynamicString(
+                        "x-version",
+                    ),
+                    merge: Last,
+                },
+            ),
+        ]
+        "###);
+    }
+
     #[test]
     fn subgraph_ws_valid_url() {
         let input = indoc! {r#"
@@ -1394,6 +2263,7 @@ mod tests {
                 ttl: None,
                 retry_percent: None,
                 retry_mutations: false,
+                retry_on_status: None,
             },
         }
         "###);
@@ -1734,6 +2604,7 @@ mod tests {
             ttl: None,
             retry_percent: None,
             retry_mutations: false,
+            retry_on_status: None,
         }
         "###);
     }
@@ -1751,7 +2622,13 @@ mod tests {
         {
             "products": SubgraphConfig {
                 headers: [],
+                response_headers: [],
                 websocket_url: None,
+                subscription_protocol: Websocket,
+                subscription_heartbeat_timeout: None,
+                subscription_max_reconnect_attempts: None,
+                deduplicate_subscriptions: false,
+                deduplicate_requests: false,
                 rate_limit: None,
                 timeout: None,
                 retry: Some(
@@ -1761,11 +2638,114 @@ mod tests {
                         ttl: None,
                         retry_percent: None,
                         retry_mutations: false,
+                        retry_on_status: None,
                     },
                 ),
                 entity_caching: None,
+                max_entities_per_request: None,
+                error_masking: None,
+                circuit_breaker: None,
+                load_balancing: None,
+                traffic_split: None,
+                shadow: None,
+                compression: None,
+                connection_pool: None,
             },
         }
         "###);
     }
+
+    #[test]
+    fn query_plan() {
+        let input = indoc! {r#"
+            [query_plan]
+            enabled = true
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        insta::assert_debug_snapshot!(&config.query_plan, @r###"
+        QueryPlanConfig {
+            enabled: true,
+            always_include: false,
+        }
+        "###);
+    }
+
+    #[test]
+    fn response_caching() {
+        let input = indoc! {r#"
+            [response_caching]
+            enabled = true
+            vary_headers = ["accept-language"]
+            vary_claims = ["sub", "org.id"]
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        insta::assert_debug_snapshot!(&config.response_caching, @r###"
+        ResponseCachingConfig {
+            enabled: true,
+            vary_headers: [
+                "accept-language",
+            ],
+            vary_claims: [
+                "sub",
+                "org.id",
+            ],
+        }
+        "###);
+    }
+
+    #[test]
+    fn subgraph_retry_on_status() {
+        let input = indoc! {r#"
+            [subgraphs.products.retry]
+            enabled = true
+            retry_on_status = [429, "5xx"]
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        insta::assert_debug_snapshot!(&config.subgraphs["products"].retry, @r###"
+        Some(
+            RetryConfig {
+                enabled: true,
+                min_per_second: None,
+                ttl: None,
+                retry_percent: None,
+                retry_mutations: false,
+                retry_on_status: Some(
+                    [
+                        Code(
+                            429,
+                        ),
+                        Class(
+                            5,
+                        ),
+                    ],
+                ),
+            },
+        )
+        "###);
+    }
+
+    #[test]
+    fn subgraph_retry_on_invalid_status() {
+        let input = indoc! {r#"
+            [subgraphs.products.retry]
+            enabled = true
+            retry_on_status = ["6xx"]
+        "#};
+
+        let error = toml::from_str::<Config>(input).unwrap_err();
+
+        insta::assert_snapshot!(&error.to_string(), @r###"
+        TOML parse error at line 3, column 20
+          |
+        3 | retry_on_status = ["6xx"]
+          |                    ^^^^^
+        invalid HTTP status code or class: 6xx
+        "###);
+    }
 }
@@ -0,0 +1,87 @@
+use std::{num::NonZeroU32, time::Duration};
+
+use url::Url;
+
+/// Spreads the requests sent to a subgraph over several URLs, replacing the one defined in the
+/// federated graph.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LoadBalancingConfig {
+    /// How the URL of each request is chosen. Default: round_robin.
+    #[serde(default)]
+    pub strategy: LoadBalancingStrategy,
+    /// The subgraph URLs, either as a string or with a weight and websocket URL.
+    pub urls: Vec<UpstreamUrlConfig>,
+    /// Temporarily removes URLs which keep failing.
+    #[serde(default)]
+    pub outlier_ejection: OutlierEjectionConfig,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadBalancingStrategy {
+    /// Each URL in turn.
+    #[default]
+    RoundRobin,
+    /// The URL with the fewest requests in flight.
+    LeastOutstanding,
+    /// A random URL, proportionally to its weight.
+    Weighted,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(from = "RawUpstreamUrlConfig")]
+pub struct UpstreamUrlConfig {
+    pub url: Url,
+    /// Relative share of the requests with the weighted strategy. Default: 1.
+    pub weight: NonZeroU32,
+    /// The URL to use for GraphQL websocket calls, defaults to `url`.
+    pub websocket_url: Option<Url>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum RawUpstreamUrlConfig {
+    Url(Url),
+    Detailed {
+        url: Url,
+        #[serde(default)]
+        weight: Option<NonZeroU32>,
+        #[serde(default)]
+        websocket_url: Option<Url>,
+    },
+}
+
+impl From<RawUpstreamUrlConfig> for UpstreamUrlConfig {
+    fn from(value: RawUpstreamUrlConfig) -> Self {
+        match value {
+            RawUpstreamUrlConfig::Url(url) => UpstreamUrlConfig {
+                url,
+                weight: NonZeroU32::MIN,
+                websocket_url: None,
+            },
+            RawUpstreamUrlConfig::Detailed {
+                url,
+                weight,
+                websocket_url,
+            } => UpstreamUrlConfig {
+                url,
+                weight: weight.unwrap_or(NonZeroU32::MIN),
+                websocket_url,
+            },
+        }
+    }
+}
+
+/// Passive outlier ejection: a URL failing too many times in a row doesn't receive any request
+/// for a while. Connection errors and 5xx responses are failures.
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutlierEjectionConfig {
+    /// Number of consecutive failures ejecting a URL. Default: 5.
+    #[serde(default)]
+    pub consecutive_failures: Option<NonZeroU32>,
+    /// How long a URL stays ejected. Default: 30 seconds.
+    #[serde(default, deserialize_with = "duration_str::deserialize_option_duration")]
+    pub duration: Option<Duration>,
+}
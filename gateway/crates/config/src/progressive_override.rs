@@ -0,0 +1,9 @@
+/// Progressive migrations of fields between subgraphs with `@override(label: ...)`.
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProgressiveOverrideConfig {
+    /// Custom override labels which are active for all requests. Labels of the form
+    /// `percent(<number>)` are always activated for the given percentage of requests.
+    #[serde(default)]
+    pub enabled_labels: Vec<String>,
+}
@@ -0,0 +1,12 @@
+/// Query plan exposure configuration.
+#[derive(Clone, Copy, Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QueryPlanConfig {
+    /// Allows clients to request the query plan in the response extensions with the
+    /// `x-grafbase-query-plan` header.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Adds the query plan to the extensions of every response, without any header.
+    #[serde(default)]
+    pub always_include: bool,
+}
@@ -0,0 +1,18 @@
+/// Full-response caching configuration, driven by the `@cacheControl` directives of the
+/// fields an operation touches. Responses are stored in the same backend as the entity cache.
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResponseCachingConfig {
+    /// Enables caching of whole query responses.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Request headers whose values are part of the cache key. Responses to requests with
+    /// headers forwarded to subgraphs are only cached if all of them are listed here, and
+    /// only privately by clients.
+    #[serde(default)]
+    pub vary_headers: Vec<String>,
+    /// JWT claims whose values are part of the cache key, as dot-separated paths.
+    /// Responses varying on claims are only cached privately by clients.
+    #[serde(default)]
+    pub vary_claims: Vec<String>,
+}
@@ -0,0 +1,39 @@
+use url::Url;
+
+/// Routes part of the requests sent to a subgraph to alternative versions of it, for canary
+/// releases. Requests not assigned to any variant use the subgraph URL.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TrafficSplitConfig {
+    /// Header keying the percentage based assignment, so that the same value is always routed to
+    /// the same variant. Without it, or if absent from the request, requests are assigned randomly.
+    #[serde(default)]
+    pub sticky_header: Option<String>,
+    /// Variants are evaluated in order, the first one matching the request is used.
+    pub variants: Vec<TrafficVariantConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TrafficVariantConfig {
+    /// Name of the variant, recorded in the subgraph request telemetry.
+    pub name: String,
+    pub url: Url,
+    /// Share of the requests routed to this variant, between 0 and 100.
+    #[serde(default)]
+    pub percentage: f32,
+    /// Routes requests with this header value to the variant, regardless of the percentage.
+    #[serde(default)]
+    pub header: Option<TrafficMatchConfig>,
+    /// Routes requests whose access token has this claim value to the variant, regardless of the
+    /// percentage. The name is a dot-separated path into the claims.
+    #[serde(default)]
+    pub claim: Option<TrafficMatchConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TrafficMatchConfig {
+    pub name: String,
+    pub value: String,
+}
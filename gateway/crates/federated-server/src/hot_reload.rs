@@ -1,4 +1,4 @@
-use std::{fs, path::PathBuf, sync::OnceLock, time::Duration};
+use std::{collections::HashSet, fs, path::PathBuf, sync::OnceLock, time::Duration};
 
 use gateway_config::Config;
 use grafbase_telemetry::span::GRAFBASE_TARGET;
@@ -14,24 +14,37 @@ impl ConfigWatcher {
     pub fn init(config: Config, hot_reload_config_path: Option<PathBuf>) -> crate::Result<watch::Receiver<Config>> {
         let (sender, receiver) = watch::channel(config);
         if let Some(path) = hot_reload_config_path {
-            Self { path, sender }.start()?
+            Self { path, sender }.start(receiver.clone())?
         }
         Ok(receiver)
     }
 
-    fn start(self) -> crate::Result<()> {
-        static WATCHER: OnceLock<PollWatcher> = OnceLock::new();
+    fn start(self, mut receiver: watch::Receiver<Config>) -> crate::Result<()> {
+        static STARTED: OnceLock<()> = OnceLock::new();
 
-        WATCHER.get_or_init(|| {
-            let config = notify::Config::default().with_poll_interval(Duration::from_secs(1));
-            let path = self.path.clone();
-            let mut watcher = PollWatcher::new(self, config).expect("config watch init failed");
+        if STARTED.set(()).is_err() {
+            return Ok(());
+        }
+
+        let config = notify::Config::default().with_poll_interval(Duration::from_secs(1));
+        let path = self.path.clone();
+        let mut watcher = PollWatcher::new(self, config).expect("config watch init failed");
 
-            watcher
-                .watch(&path, notify::RecursiveMode::NonRecursive)
-                .expect("config watch failed");
+        watcher
+            .watch(&path, notify::RecursiveMode::NonRecursive)
+            .expect("config watch failed");
 
-            watcher
+        // Changes to the trusted documents manifests trigger a configuration reload too. The
+        // watched manifests follow the manifests of the latest configuration.
+        let mut watched = HashSet::new();
+        let paths = manifest_paths(&receiver.borrow_and_update());
+        watch_manifests(&mut watcher, &mut watched, paths);
+
+        tokio::spawn(async move {
+            while receiver.changed().await.is_ok() {
+                let paths = manifest_paths(&receiver.borrow_and_update());
+                watch_manifests(&mut watcher, &mut watched, paths);
+            }
         });
 
         Ok(())
@@ -47,7 +60,7 @@ impl ConfigWatcher {
             }
         };
 
-        let config: Config = match toml::from_str(&config) {
+        let mut config: Config = match toml::from_str(&config) {
             Ok(config) => config,
             Err(e) => {
                 tracing::error!(target: GRAFBASE_TARGET, "error parsing gateway config: {e}");
@@ -56,6 +69,10 @@ impl ConfigWatcher {
             }
         };
 
+        if let Some(config_dir) = self.path.parent() {
+            config.trusted_documents.resolve_manifest_paths(config_dir);
+        }
+
         self.sender.send(config)?;
 
         Ok(())
@@ -79,3 +96,29 @@ impl EventHandler for ConfigWatcher {
         }
     }
 }
+
+fn manifest_paths(config: &Config) -> HashSet<PathBuf> {
+    config
+        .trusted_documents
+        .manifests
+        .iter()
+        .map(|manifest| manifest.path.clone())
+        .collect()
+}
+
+/// Watches the given manifests, and stops watching the ones no longer in the configuration.
+fn watch_manifests(watcher: &mut PollWatcher, watched: &mut HashSet<PathBuf>, manifest_paths: HashSet<PathBuf>) {
+    for path in watched.difference(&manifest_paths) {
+        if let Err(e) = watcher.unwatch(path) {
+            tracing::debug!(target: GRAFBASE_TARGET, "error unwatching trusted documents manifest {}: {e}", path.display());
+        }
+    }
+
+    for path in manifest_paths.difference(watched) {
+        if let Err(e) = watcher.watch(path, notify::RecursiveMode::NonRecursive) {
+            tracing::error!(target: GRAFBASE_TARGET, "error watching trusted documents manifest {}: {e}", path.display());
+        }
+    }
+
+    *watched = manifest_paths;
+}
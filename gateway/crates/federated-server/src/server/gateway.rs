@@ -9,10 +9,13 @@ use tokio::sync::watch;
 
 use engine_v2::Engine;
 use graphql_composition::FederatedGraph;
-use runtime_local::{ComponentLoader, HooksWasi, InMemoryEntityCache, InMemoryKvStore, RedisEntityCache};
+use runtime_local::{
+    ComponentLoader, HooksWasi, InMemoryEntityCache, InMemoryHooksCache, InMemoryKvStore, RedisEntityCache,
+    RedisHooksCache, SharedHooksCache,
+};
 use runtime_noop::trusted_documents::NoopTrustedDocuments;
 
-use gateway_config::{Config, EntityCachingRedisConfig};
+use gateway_config::{Config, EntityCachingRedisConfig, EntityCachingStorage, HooksCacheConfig};
 
 use crate::hot_reload::ConfigWatcher;
 
@@ -37,39 +40,45 @@ pub(super) async fn generate(
         FederatedGraph::from_sdl(federated_schema).map_err(|e| crate::Error::SchemaValidationError(e.to_string()))?;
     let config = engine_config_builder::build_with_toml_config(gateway_config, graph).into_latest();
 
-    // TODO: https://linear.app/grafbase/issue/GB-6168/support-trusted-documents-in-air-gapped-mode
+    let watcher = ConfigWatcher::init(gateway_config.clone(), hot_reload_config_path)?;
+
     let trusted_documents = if gateway_config.trusted_documents.enabled {
-        let Some(branch_id) = branch_id else {
-            return Err(crate::Error::InternalError(
-                "Trusted documents are not implemented yet in airgapped mode".into(),
-            ));
-        };
-
-        runtime::trusted_documents_client::Client::new(super::trusted_documents_client::TrustedDocumentsClient {
-            http_client: Default::default(),
-            bypass_header: gateway_config
-                .trusted_documents
-                .bypass_header
-                .bypass_header_name
-                .as_ref()
-                .zip(
-                    gateway_config
-                        .trusted_documents
-                        .bypass_header
-                        .bypass_header_value
-                        .as_ref(),
-                )
-                .map(|(name, value)| (name.clone().into(), String::from(value.as_ref()))),
-            branch_id,
-        })
+        let bypass_header = gateway_config
+            .trusted_documents
+            .bypass_header
+            .bypass_header_name
+            .as_ref()
+            .zip(
+                gateway_config
+                    .trusted_documents
+                    .bypass_header
+                    .bypass_header_value
+                    .as_ref(),
+            )
+            .map(|(name, value)| (name.clone().into(), String::from(value.as_ref())));
+
+        match branch_id {
+            Some(branch_id) => runtime::trusted_documents_client::Client::new(
+                super::trusted_documents_client::TrustedDocumentsClient {
+                    http_client: Default::default(),
+                    bypass_header,
+                    branch_id,
+                },
+            ),
+            None => runtime::trusted_documents_client::Client::new(
+                super::trusted_documents_client::LocalTrustedDocumentsClient::new(
+                    &gateway_config.trusted_documents.manifests,
+                    bypass_header,
+                    watcher.clone(),
+                )?,
+            ),
+        }
     } else {
         runtime::trusted_documents_client::Client::new(NoopTrustedDocuments)
     };
 
     let mut redis_factory = RedisPoolFactory::default();
 
-    let watcher = ConfigWatcher::init(gateway_config.clone(), hot_reload_config_path)?;
-
     let rate_limiter = match config.rate_limit_config() {
         Some(config) if config.storage.is_redis() => {
             let tls = config.redis.tls.map(|tls| RedisTlsConfig {
@@ -111,6 +120,26 @@ pub(super) async fn generate(
         }
     };
 
+    let hooks_cache: SharedHooksCache = match gateway_config.hooks.as_ref().map(|hooks| &hooks.cache) {
+        Some(HooksCacheConfig {
+            storage: EntityCachingStorage::Redis,
+            redis: EntityCachingRedisConfig { url, key_prefix, tls },
+        }) => {
+            let tls = tls.as_ref().map(|tls| RedisTlsConfig {
+                cert: tls.cert.as_deref(),
+                key: tls.key.as_deref(),
+                ca: tls.ca.as_deref(),
+            });
+
+            let pool = redis_factory
+                .pool(url.as_str(), tls)
+                .map_err(|e| crate::Error::InternalError(e.to_string()))?;
+
+            Arc::new(RedisHooksCache::new(pool, key_prefix))
+        }
+        _ => Arc::new(InMemoryHooksCache::default()),
+    };
+
     let runtime = GatewayRuntime {
         fetcher: runtime_local::NativeFetcher::runtime_fetcher(),
         kv: InMemoryKvStore::runtime(),
@@ -123,7 +152,8 @@ pub(super) async fn generate(
                 .map(ComponentLoader::new)
                 .transpose()
                 .map_err(|e| crate::Error::InternalError(e.to_string()))?
-                .flatten(),
+                .flatten()
+                .map(|loader| loader.with_cache(hooks_cache)),
         ),
         rate_limiter,
         entity_cache,
@@ -1,24 +1,41 @@
-use std::net::SocketAddr;
+use std::{collections::BTreeMap, net::SocketAddr};
 
 use gateway_config::{HealthConfig, TlsConfig};
 
 use super::state::ServerState;
 use axum::{extract::State, routing::get, Json, Router};
+use engine_v2::CircuitBreakerState;
 use grafbase_telemetry::span::GRAFBASE_TARGET;
 use http::StatusCode;
 
 #[derive(Debug, serde::Serialize)]
 #[serde(tag = "status", rename_all = "lowercase")]
 pub(crate) enum HealthState {
-    Healthy,
+    Healthy {
+        /// Subgraphs with a circuit breaker. An open circuit doesn't make the gateway unhealthy,
+        /// it still serves partial data.
+        #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+        subgraphs: BTreeMap<String, SubgraphHealth>,
+    },
     Unhealthy,
 }
 
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct SubgraphHealth {
+    circuit_breaker: CircuitBreakerState,
+}
+
 pub(crate) async fn health(State(state): State<ServerState>) -> (StatusCode, Json<HealthState>) {
-    if state.gateway().borrow().is_some() {
-        (StatusCode::OK, Json(HealthState::Healthy))
-    } else {
-        (StatusCode::SERVICE_UNAVAILABLE, Json(HealthState::Unhealthy))
+    match state.gateway().borrow().as_ref() {
+        Some(engine) => {
+            let subgraphs = engine
+                .subgraph_circuit_breakers()
+                .map(|(name, circuit_breaker)| (name.to_string(), SubgraphHealth { circuit_breaker }))
+                .collect();
+
+            (StatusCode::OK, Json(HealthState::Healthy { subgraphs }))
+        }
+        None => (StatusCode::SERVICE_UNAVAILABLE, Json(HealthState::Unhealthy)),
     }
 }
 
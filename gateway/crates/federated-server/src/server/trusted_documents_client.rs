@@ -1,3 +1,7 @@
+mod local;
+
+pub(crate) use local::LocalTrustedDocumentsClient;
+
 const GRAFBASE_PRODUCTION_TRUSTED_DOCUMENTS_BUCKET: &str = "https://pub-72f3517515a34104921bb714721a885a.r2.dev";
 
 pub(crate) struct TrustedDocumentsClient {
@@ -0,0 +1,251 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+    sync::{Arc, RwLock},
+};
+
+use gateway_config::{Config, TrustedDocumentsManifest};
+use grafbase_telemetry::span::GRAFBASE_TARGET;
+use runtime::trusted_documents_client::{TrustedDocumentsError, TrustedDocumentsResult};
+use tokio::sync::watch;
+
+/// Documents per client name, per document id.
+type Documents = HashMap<String, HashMap<String, String>>;
+
+/// Trusted documents client for the air-gapped mode, serving the documents from persisted
+/// query manifests on the local file system. The manifests are reloaded every time the
+/// configuration is hot-reloaded.
+pub(crate) struct LocalTrustedDocumentsClient {
+    documents: Arc<RwLock<Documents>>,
+    bypass_header: Option<(String, String)>,
+}
+
+impl LocalTrustedDocumentsClient {
+    pub(crate) fn new(
+        manifests: &[TrustedDocumentsManifest],
+        bypass_header: Option<(String, String)>,
+        mut config_watcher: watch::Receiver<Config>,
+    ) -> crate::Result<Self> {
+        let documents = load_manifests(manifests).map_err(crate::Error::InternalError)?;
+        let documents = Arc::new(RwLock::new(documents));
+
+        tokio::spawn({
+            let documents = documents.clone();
+
+            async move {
+                while config_watcher.changed().await.is_ok() {
+                    let manifests = config_watcher.borrow_and_update().trusted_documents.manifests.clone();
+
+                    match load_manifests(&manifests) {
+                        Ok(reloaded) => {
+                            tracing::debug!(target: GRAFBASE_TARGET, "reloaded trusted documents manifests");
+                            *documents.write().unwrap() = reloaded;
+                        }
+                        Err(e) => {
+                            tracing::error!(target: GRAFBASE_TARGET, "error reloading trusted documents: {e}");
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            documents,
+            bypass_header,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl runtime::trusted_documents_client::TrustedDocumentsClient for LocalTrustedDocumentsClient {
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    fn bypass_header(&self) -> Option<(&str, &str)> {
+        self.bypass_header
+            .as_ref()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    async fn fetch(&self, client_name: &str, document_id: &str) -> TrustedDocumentsResult<String> {
+        self.documents
+            .read()
+            .unwrap()
+            .get(client_name)
+            .and_then(|documents| documents.get(document_id))
+            .cloned()
+            .ok_or(TrustedDocumentsError::DocumentNotFound)
+    }
+}
+
+/// The persisted query manifest formats we understand.
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum Manifest {
+    /// `{ "format": "apollo-persisted-query-manifest", "version": 1, "operations": [{ "id": "..", "body": ".." }] }`
+    Apollo { operations: Vec<ApolloOperation> },
+    /// `{ "<id>": "<document>" }`
+    Relay(HashMap<String, String>),
+}
+
+#[derive(serde::Deserialize)]
+struct ApolloOperation {
+    id: String,
+    body: String,
+}
+
+fn load_manifests(manifests: &[TrustedDocumentsManifest]) -> Result<Documents, String> {
+    let mut documents = Documents::new();
+
+    for manifest in manifests {
+        let client_documents = documents.entry(manifest.client_name.clone()).or_default();
+
+        match load_manifest(&manifest.path)? {
+            Manifest::Apollo { operations } => {
+                client_documents.extend(operations.into_iter().map(|operation| (operation.id, operation.body)));
+            }
+            Manifest::Relay(operations) => client_documents.extend(operations),
+        }
+    }
+
+    Ok(documents)
+}
+
+fn load_manifest(path: &Path) -> Result<Manifest, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("could not read trusted documents manifest {}: {e}", path.display()))?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| format!("could not parse trusted documents manifest {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, time::Duration};
+
+    use gateway_config::{Config, TrustedDocumentsManifest};
+    use runtime::trusted_documents_client::{TrustedDocumentsClient, TrustedDocumentsError};
+    use serde_json::json;
+    use tempfile::TempDir;
+    use tokio::sync::watch;
+
+    use super::LocalTrustedDocumentsClient;
+
+    fn write_manifest(dir: &TempDir, name: &str, manifest: serde_json::Value) -> PathBuf {
+        let path = dir.path().join(name);
+        std::fs::write(&path, manifest.to_string()).unwrap();
+        path
+    }
+
+    fn manifest(client_name: &str, path: PathBuf) -> TrustedDocumentsManifest {
+        TrustedDocumentsManifest {
+            client_name: client_name.to_string(),
+            path,
+        }
+    }
+
+    fn config(manifests: Vec<TrustedDocumentsManifest>) -> Config {
+        let mut config = Config::default();
+        config.trusted_documents.manifests = manifests;
+        config
+    }
+
+    fn apollo_manifest() -> serde_json::Value {
+        json!({
+            "format": "apollo-persisted-query-manifest",
+            "version": 1,
+            "operations": [{ "id": "apollo-1", "name": "Me", "type": "query", "body": "query Me { me { id } }" }]
+        })
+    }
+
+    #[tokio::test]
+    async fn apollo_and_relay_manifests() {
+        let dir = TempDir::new().unwrap();
+        let manifests = vec![
+            manifest("web", write_manifest(&dir, "apollo.json", apollo_manifest())),
+            manifest(
+                "ios",
+                write_manifest(&dir, "relay.json", json!({ "relay-1": "query { hello }" })),
+            ),
+        ];
+
+        let (_sender, receiver) = watch::channel(config(manifests.clone()));
+        let client = LocalTrustedDocumentsClient::new(&manifests, None, receiver).unwrap();
+
+        assert!(client.is_enabled());
+        assert_eq!(client.fetch("web", "apollo-1").await.unwrap(), "query Me { me { id } }");
+        assert_eq!(client.fetch("ios", "relay-1").await.unwrap(), "query { hello }");
+
+        // documents are only available to the client of their manifest
+        assert!(matches!(
+            client.fetch("ios", "apollo-1").await,
+            Err(TrustedDocumentsError::DocumentNotFound)
+        ));
+        assert!(matches!(
+            client.fetch("android", "relay-1").await,
+            Err(TrustedDocumentsError::DocumentNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn invalid_manifest_is_an_error() {
+        let dir = TempDir::new().unwrap();
+        let manifests = vec![manifest("web", write_manifest(&dir, "invalid.json", json!([1, 2, 3])))];
+
+        let (_sender, receiver) = watch::channel(config(manifests.clone()));
+        assert!(LocalTrustedDocumentsClient::new(&manifests, None, receiver).is_err());
+
+        let manifests = vec![manifest("web", dir.path().join("missing.json"))];
+
+        let (_sender, receiver) = watch::channel(config(manifests.clone()));
+        assert!(LocalTrustedDocumentsClient::new(&manifests, None, receiver).is_err());
+    }
+
+    #[tokio::test]
+    async fn bypass_header() {
+        let (_sender, receiver) = watch::channel(Config::default());
+        let client = LocalTrustedDocumentsClient::new(
+            &[],
+            Some((String::from("x-bypass"), String::from("secret"))),
+            receiver.clone(),
+        )
+        .unwrap();
+
+        assert_eq!(client.bypass_header(), Some(("x-bypass", "secret")));
+
+        let client = LocalTrustedDocumentsClient::new(&[], None, receiver).unwrap();
+        assert_eq!(client.bypass_header(), None);
+    }
+
+    #[tokio::test]
+    async fn manifests_are_reloaded_with_the_config() {
+        let dir = TempDir::new().unwrap();
+        let manifests = vec![manifest("web", write_manifest(&dir, "apollo.json", apollo_manifest()))];
+
+        let (sender, receiver) = watch::channel(config(manifests.clone()));
+        let client = LocalTrustedDocumentsClient::new(&manifests, None, receiver).unwrap();
+
+        assert!(client.fetch("web", "apollo-1").await.is_ok());
+
+        // a manifest added by a later configuration
+        let relay = write_manifest(&dir, "relay.json", json!({ "relay-1": "query { hello }" }));
+        sender.send(config(vec![manifest("web", relay)])).unwrap();
+
+        let mut reloaded = None;
+        for _ in 0..50 {
+            if let Ok(document) = client.fetch("web", "relay-1").await {
+                reloaded = Some(document);
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(reloaded.as_deref(), Some("query { hello }"));
+        assert!(matches!(
+            client.fetch("web", "apollo-1").await,
+            Err(TrustedDocumentsError::DocumentNotFound)
+        ));
+    }
+}
@@ -46,7 +46,15 @@ impl super::Args for Args {
     /// The gateway configuration
     fn config(&self) -> anyhow::Result<Config> {
         match fs::read_to_string(&self.config) {
-            Ok(config) => Ok(toml::from_str(&config)?),
+            Ok(config) => {
+                let mut config: Config = toml::from_str(&config)?;
+
+                if let Some(config_dir) = self.config.parent() {
+                    config.trusted_documents.resolve_manifest_paths(config_dir);
+                }
+
+                Ok(config)
+            }
             Err(e) => match e.kind() {
                 ErrorKind::NotFound => Ok(Config::default()),
                 _ => Err(anyhow::anyhow!("error loading config file: {e}")),
@@ -98,7 +98,13 @@ impl super::Args for Args {
         let mut config = match self.config.as_ref() {
             Some(path) => {
                 let config = fs::read_to_string(path).context("could not read config file")?;
-                toml::from_str(&config)?
+                let mut config: Config = toml::from_str(&config)?;
+
+                if let Some(config_dir) = path.parent() {
+                    config.trusted_documents.resolve_manifest_paths(config_dir);
+                }
+
+                config
             }
             None => Config::default(),
         };